@@ -1,27 +1,35 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::{
-	decl_module, decl_storage, decl_error, decl_event,
-	traits::Get, traits::FindAuthor, weights::Weight,
+	decl_module, decl_storage, decl_error, decl_event, ensure,
+	traits::{Currency, ExistenceRequirement, Get, FindAuthor}, weights::Weight,
 	dispatch::{DispatchResult, DispatchResultWithPostInfo},
 };
-use sp_std::prelude::*;
-use frame_system::ensure_none;
+use sp_std::{convert::TryInto, prelude::*};
+use sp_core::ecdsa;
+use frame_system::{ensure_none, ensure_signed};
 use ethereum_types::{H160, H64, H256, U256, Bloom, BloomInput};
 use sp_runtime::{
 	transaction_validity::{
-		TransactionValidity, TransactionSource, InvalidTransaction, ValidTransactionBuilder,
+		TransactionValidity, TransactionValidityError, TransactionSource, InvalidTransaction,
+		ValidTransactionBuilder,
 	},
-	traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize},
+	traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize, Saturating},
 	generic::DigestItem, traits::UniqueSaturatedInto, DispatchError, RuntimeDebug
 };
 use evm::ExitReason;
 use fp_evm::{CallInfo, CallOrCreateInfo};
-use clover_evm::{Runner, GasToWeight};
+use clover_evm::{Runner, GasToWeight, AddressMapping};
+use orml_traits::Happened;
+use orml_utilities::with_transaction_result;
 use sha3::{Digest, Keccak256};
 use codec::{Decode, Encode};
 use fp_consensus::{FRONTIER_ENGINE_ID, ConsensusLog};
 
+mod default_weight;
+mod mock;
+mod tests;
+
 pub use fp_rpc::TransactionStatus;
 pub use ethereum::{Transaction, Log, Block, Receipt, TransactionAction, TransactionMessage};
 
@@ -34,12 +42,34 @@ pub enum ReturnValue {
 /// A type alias for the balance type from this pallet's point of view.
 pub type BalanceOf<T> = <T as pallet_balances::Trait>::Balance;
 
+/// Limits of the fees a sponsor pays for its sponsored accounts.
+#[derive(Encode, Decode, Eq, PartialEq, Clone, Default, RuntimeDebug)]
+pub struct SponsorLimit {
+	/// Max gas price of a sponsored transaction.
+	pub max_gas_price: U256,
+	/// Max gas limit of a sponsored transaction.
+	pub max_gas_limit: U256,
+	/// Total fees the sponsor is still willing to pay.
+	pub remaining_fee: U256,
+}
+
+pub trait WeightInfo {
+	fn set_sponsor_limit() -> Weight;
+	fn remove_sponsor(a: u32, ) -> Weight;
+	fn add_sponsored_accounts(a: u32, ) -> Weight;
+	fn remove_sponsored_accounts(a: u32, ) -> Weight;
+}
+
 /// Trait for Ethereum pallet.
 pub trait Trait: frame_system::Trait<Hash=H256> + pallet_balances::Trait + pallet_timestamp::Trait + clover_evm::Trait {
 	/// The overarching event type.
 	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
 	/// Find author for Ethereum.
 	type FindAuthor: FindAuthor<H160>;
+	/// Handler of the gas used by the executed transactions, with (sender account, used gas).
+	type OnTransactionExecuted: Happened<(Self::AccountId, u64)>;
+	/// Weight information for the extrinsics in this module.
+	type WeightInfo: WeightInfo;
 }
 
 /// An abstraction of EVM for EVMBridge
@@ -77,6 +107,8 @@ decl_storage! {
 	trait Store for Module<T: Trait> as Ethereum {
 		/// Current building block's transactions and receipts.
 		Pending: Vec<(ethereum::Transaction, TransactionStatus, ethereum::Receipt)>;
+		/// Exit reasons of the current building block's transactions, with the revert data of the reverted ones.
+		PendingExitReasons: Vec<(ExitReason, Vec<u8>)>;
 
 		/// The current Ethereum block.
 		CurrentBlock: Option<ethereum::Block>;
@@ -84,6 +116,16 @@ decl_storage! {
 		CurrentReceipts: Option<Vec<ethereum::Receipt>>;
 		/// The current transaction statuses.
 		CurrentTransactionStatuses: Option<Vec<TransactionStatus>>;
+		/// The current exit reasons of the transactions.
+		CurrentExitReasons: Option<Vec<(ExitReason, Vec<u8>)>>;
+
+		/// Fee limits of the sponsors.
+		pub SponsorLimits get(fn sponsor_limits): map hasher(twox_64_concat) H160 => Option<SponsorLimit>;
+		/// Accounts whose transactions are paid by a sponsor. [sponsor, account]
+		pub SponsoredAccounts get(fn sponsored_accounts):
+			double_map hasher(twox_64_concat) H160, hasher(twox_64_concat) H160 => bool;
+		/// Number of the sponsored accounts of the sponsors.
+		pub SponsoredAccountCounts get(fn sponsored_account_counts): map hasher(twox_64_concat) H160 => u32;
 	}
 	add_extra_genesis {
 		build(|_config: &GenesisConfig| {
@@ -97,8 +139,21 @@ decl_event!(
 	pub enum Event {
 		/// An clover-ethereum transaction was successfully executed. [from, transaction_hash]
 		Executed(H160, H256, ExitReason),
+		/// An clover-ethereum transaction failed, the data is the revert data of a call or a create.
+		/// [from, transaction_hash, exit_reason, data]
+		ExecutionFailed(H160, H256, ExitReason, Vec<u8>),
 		TransferExecuted(H160),
 		TransferFailed(H160, ExitReason, Vec<u8>),
+		/// Sponsor fee limits updated. [sponsor, limit]
+		SponsorLimitUpdated(H160, SponsorLimit),
+		/// Sponsor removed with all its sponsored accounts. [sponsor]
+		SponsorRemoved(H160),
+		/// Account added to a sponsor's whitelist. [sponsor, account]
+		SponsoredAccountAdded(H160, H160),
+		/// Account removed from a sponsor's whitelist. [sponsor, account]
+		SponsoredAccountRemoved(H160, H160),
+		/// A sponsored transaction was executed. [sponsor, from, transaction_hash, fee]
+		SponsoredExecuted(H160, H160, H256, U256),
 	}
 );
 
@@ -108,6 +163,20 @@ decl_error! {
 	pub enum Error for Module<T: Trait> {
 		/// Signature is invalid.
 		InvalidSignature,
+		/// Sponsor signature is invalid.
+		InvalidSponsorSignature,
+		/// Account has no evm address.
+		NoEvmAddress,
+		/// Sponsor does not exist.
+		SponsorNotFound,
+		/// Account is not sponsored by the sponsor.
+		NotSponsored,
+		/// Transaction exceeds the sponsor's limits.
+		SponsorLimitExceeded,
+		/// The account count is below the number of the sponsored accounts.
+		InvalidAccountCount,
+		/// Calculating transaction fee overflowed.
+		FeeOverflow,
 	}
 }
 
@@ -125,87 +194,120 @@ decl_module! {
 			let source = Self::recover_signer(&transaction)
 				.ok_or_else(|| Error::<T>::InvalidSignature)?;
 
-			let transaction_hash = H256::from_slice(
-				Keccak256::digest(&rlp::encode(&transaction)).as_slice()
-			);
-			let transaction_index = Pending::get().len() as u32;
-
-			let (to, info) = Self::execute(
-				source,
-				transaction.input.clone(),
-				transaction.value,
-				transaction.gas_limit,
-				Some(transaction.gas_price),
-				Some(transaction.nonce),
-				transaction.action,
-				None,
-			)?;
-
-			let (reason, status, used_gas) = match info {
-				CallOrCreateInfo::Call(info) => {
-					(info.exit_reason, TransactionStatus {
-						transaction_hash,
-						transaction_index,
-						from: source,
-						to,
-						contract_address: None,
-						logs: info.logs.clone(),
-						logs_bloom: {
-							let mut bloom: Bloom = Bloom::default();
-							Self::logs_bloom(
-								info.logs,
-								&mut bloom
-							);
-							bloom
-						},
-						internal_transactions: info.internal_txs,
-					}, info.used_gas)
-				},
-				CallOrCreateInfo::Create(info) => {
-					(info.exit_reason, TransactionStatus {
-						transaction_hash,
-						transaction_index,
-						from: source,
-						to,
-						contract_address: Some(info.value),
-						logs: info.logs.clone(),
-						logs_bloom: {
-							let mut bloom: Bloom = Bloom::default();
-							Self::logs_bloom(
-								info.logs,
-								&mut bloom
-							);
-							bloom
-						},
-						internal_transactions: Vec::new(),
-					}, info.used_gas)
-				},
-			};
-
-			let receipt = ethereum::Receipt {
-				state_root: match reason {
-					ExitReason::Succeed(_) => H256::from_low_u64_be(1),
-					ExitReason::Error(_) => H256::from_low_u64_le(0),
-					ExitReason::Revert(_) => H256::from_low_u64_le(0),
-					ExitReason::Fatal(_) => H256::from_low_u64_le(0),
-				},
-				used_gas,
-				logs_bloom: status.clone().logs_bloom,
-				logs: status.clone().logs,
-			};
-
-			Pending::append((transaction, status, receipt));
+			let (_, used_gas) = Self::apply_transaction(source, transaction)?;
+			Ok(Some(T::GasToWeight::gas_to_weight(used_gas.low_u32())).into())
+		}
+
+		/// Transact an Ethereum transaction with fees paid by a sponsor.
+		///
+		/// The transaction is executed as its signer, the sponsor signs the transaction hash
+		/// with `personal_sign` and is charged the fees from its mapped account. The sponsor also
+		/// tops the signer up to the existential deposit, so the signer's nonce is kept and the
+		/// sponsored transaction can't be replayed.
+		#[weight = <T as clover_evm::Trait>::GasToWeight::gas_to_weight(transaction.gas_limit.low_u32())]
+		fn transact_sponsored(
+			origin,
+			transaction: ethereum::Transaction,
+			sponsor_signature: ecdsa::Signature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let source = Self::recover_signer(&transaction)
+				.ok_or_else(|| Error::<T>::InvalidSignature)?;
+			let transaction_hash = Self::transaction_hash(&transaction);
+			let sponsor = Self::recover_sponsor(&transaction_hash, &sponsor_signature)
+				.ok_or_else(|| Error::<T>::InvalidSponsorSignature)?;
+			let max_fee = Self::check_sponsor(&sponsor, &source, &transaction)?;
+
+			let sponsor_account = <T as clover_evm::Trait>::AddressMapping::into_account_id(&sponsor);
+			let source_account = <T as clover_evm::Trait>::AddressMapping::into_account_id(&source);
+			let gas_price = transaction.gas_price;
+
+			let used_gas = with_transaction_result(|| {
+				// prefund the sender with max fee, the unused part is paid back after execution
+				let keep_alive = <T as clover_evm::Trait>::Currency::minimum_balance()
+					.saturating_sub(<T as clover_evm::Trait>::Currency::free_balance(&source_account));
+				<T as clover_evm::Trait>::Currency::transfer(
+					&sponsor_account,
+					&source_account,
+					Self::fee_balance(max_fee)?.saturating_add(keep_alive),
+					ExistenceRequirement::AllowDeath,
+				)?;
+
+				let (_, used_gas) = Self::apply_transaction(source, transaction)?;
+
+				let fee = used_gas.saturating_mul(gas_price);
+				<T as clover_evm::Trait>::Currency::transfer(
+					&source_account,
+					&sponsor_account,
+					Self::fee_balance(max_fee.saturating_sub(fee))?,
+					ExistenceRequirement::AllowDeath,
+				)?;
+				SponsorLimits::mutate(&sponsor, |limit| if let Some(limit) = limit {
+					limit.remaining_fee = limit.remaining_fee.saturating_sub(fee);
+				});
+
+				Self::deposit_event(Event::SponsoredExecuted(sponsor, source, transaction_hash, fee));
+				Ok(used_gas)
+			})?;
 
-			Self::deposit_event(Event::Executed(source, transaction_hash, reason));
 			Ok(Some(T::GasToWeight::gas_to_weight(used_gas.low_u32())).into())
 		}
 
+		/// Set the fee limits of the sponsor, the sponsor is the evm address of origin.
+		#[weight = <T as Trait>::WeightInfo::set_sponsor_limit()]
+		fn set_sponsor_limit(origin, limit: SponsorLimit) {
+			let sponsor = Self::ensure_evm_origin(origin)?;
+			SponsorLimits::insert(&sponsor, &limit);
+			Self::deposit_event(Event::SponsorLimitUpdated(sponsor, limit));
+		}
+
+		/// Stop sponsoring, removes the limits and all sponsored accounts. `account_count` is at least
+		/// the number of the sponsored accounts, the weight is charged for it.
+		#[weight = <T as Trait>::WeightInfo::remove_sponsor(*account_count)]
+		fn remove_sponsor(origin, account_count: u32) {
+			let sponsor = Self::ensure_evm_origin(origin)?;
+			ensure!(SponsorLimits::contains_key(&sponsor), Error::<T>::SponsorNotFound);
+			ensure!(Self::sponsored_account_counts(&sponsor) <= account_count, Error::<T>::InvalidAccountCount);
+			SponsorLimits::remove(&sponsor);
+			SponsoredAccounts::remove_prefix(&sponsor);
+			SponsoredAccountCounts::remove(&sponsor);
+			Self::deposit_event(Event::SponsorRemoved(sponsor));
+		}
+
+		/// Add accounts to the whitelist of the sponsor.
+		#[weight = <T as Trait>::WeightInfo::add_sponsored_accounts(accounts.len() as u32)]
+		fn add_sponsored_accounts(origin, accounts: Vec<H160>) {
+			let sponsor = Self::ensure_evm_origin(origin)?;
+			ensure!(SponsorLimits::contains_key(&sponsor), Error::<T>::SponsorNotFound);
+			for account in accounts {
+				if !SponsoredAccounts::contains_key(&sponsor, &account) {
+					SponsoredAccounts::insert(&sponsor, &account, true);
+					SponsoredAccountCounts::mutate(&sponsor, |count| *count = count.saturating_add(1));
+				}
+				Self::deposit_event(Event::SponsoredAccountAdded(sponsor, account));
+			}
+		}
+
+		/// Remove accounts from the whitelist of the sponsor.
+		#[weight = <T as Trait>::WeightInfo::remove_sponsored_accounts(accounts.len() as u32)]
+		fn remove_sponsored_accounts(origin, accounts: Vec<H160>) {
+			let sponsor = Self::ensure_evm_origin(origin)?;
+			for account in accounts {
+				if SponsoredAccounts::take(&sponsor, &account) {
+					SponsoredAccountCounts::mutate(&sponsor, |count| *count = count.saturating_sub(1));
+				}
+				Self::deposit_event(Event::SponsoredAccountRemoved(sponsor, account));
+			}
+		}
+
 		fn on_finalize(n: T::BlockNumber) {
 			<Module<T>>::store_block();
 		}
 
 		fn on_initialize(n: T::BlockNumber) -> Weight {
 			Pending::kill();
+			PendingExitReasons::kill();
 			0
 		}
 	}
@@ -217,49 +319,71 @@ enum TransactionValidationError {
 	UnknownError,
 	InvalidChainId,
 	InvalidSignature,
+	InvalidSponsorSignature,
+	SponsorNotAllowed,
 }
 
 impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
 	type Call = Call<T>;
 
 	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-		if let Call::transact(transaction) = call {
-			if transaction.signature.chain_id().unwrap_or_default() != T::ChainId::get() {
-				return InvalidTransaction::Custom(TransactionValidationError::InvalidChainId as u8).into();
-			}
+		match call {
+			Call::transact(transaction) => {
+				let origin = Self::validate_transaction_signer(transaction)?;
+				let fee = transaction.gas_price.saturating_mul(transaction.gas_limit);
+				Self::validate_transaction(transaction, origin, fee)
+			},
+			Call::transact_sponsored(transaction, sponsor_signature) => {
+				let origin = Self::validate_transaction_signer(transaction)?;
+				let sponsor = Self::recover_sponsor(&Self::transaction_hash(transaction), sponsor_signature)
+					.ok_or_else(|| InvalidTransaction::Custom(TransactionValidationError::InvalidSponsorSignature as u8))?;
+				let fee = Self::check_sponsor(&sponsor, &origin, transaction)
+					.map_err(|_| InvalidTransaction::Custom(TransactionValidationError::SponsorNotAllowed as u8))?;
+
+				if clover_evm::Module::<T>::account_basic(&sponsor).balance < fee {
+					return InvalidTransaction::Payment.into();
+				}
+				// the sender pays no fee, the sponsor's balance is checked above
+				Self::validate_transaction(transaction, origin, U256::zero())
+			},
+			_ => Err(InvalidTransaction::Call.into()),
+		}
+	}
+}
 
-			let origin = Self::recover_signer(&transaction)
-				.ok_or_else(|| InvalidTransaction::Custom(TransactionValidationError::InvalidSignature as u8))?;
+impl<T: Trait> Module<T> {
+	fn validate_transaction_signer(transaction: &ethereum::Transaction) -> Result<H160, TransactionValidityError> {
+		if transaction.signature.chain_id().unwrap_or_default() != T::ChainId::get() {
+			return Err(InvalidTransaction::Custom(TransactionValidationError::InvalidChainId as u8).into());
+		}
 
-			let account_data = clover_evm::Module::<T>::account_basic(&origin);
+		Self::recover_signer(&transaction)
+			.ok_or_else(|| InvalidTransaction::Custom(TransactionValidationError::InvalidSignature as u8).into())
+	}
 
-			if transaction.nonce < account_data.nonce {
-				return InvalidTransaction::Stale.into();
-			}
+	fn validate_transaction(transaction: &ethereum::Transaction, origin: H160, fee: U256) -> TransactionValidity {
+		let account_data = clover_evm::Module::<T>::account_basic(&origin);
 
-			let fee = transaction.gas_price.saturating_mul(transaction.gas_limit);
+		if transaction.nonce < account_data.nonce {
+			return InvalidTransaction::Stale.into();
+		}
 
-			if account_data.balance < fee {
-				return InvalidTransaction::Payment.into();
-			}
+		if account_data.balance < fee {
+			return InvalidTransaction::Payment.into();
+		}
 
-			let mut builder = ValidTransactionBuilder::default()
-				.and_provides((origin, transaction.nonce));
+		let mut builder = ValidTransactionBuilder::default()
+			.and_provides((origin, transaction.nonce));
 
-			if transaction.nonce > account_data.nonce {
-				if let Some(prev_nonce) = transaction.nonce.checked_sub(1.into()) {
-					builder = builder.and_requires((origin, prev_nonce))
-				}
+		if transaction.nonce > account_data.nonce {
+			if let Some(prev_nonce) = transaction.nonce.checked_sub(1.into()) {
+				builder = builder.and_requires((origin, prev_nonce))
 			}
-
-			builder.build()
-		} else {
-			Err(InvalidTransaction::Call.into())
 		}
+
+		builder.build()
 	}
-}
 
-impl<T: Trait> Module<T> {
 	fn recover_signer(transaction: &ethereum::Transaction) -> Option<H160> {
 		let mut sig = [0u8; 65];
 		let mut msg = [0u8; 32];
@@ -272,6 +396,143 @@ impl<T: Trait> Module<T> {
 		Some(H160::from(H256::from_slice(Keccak256::digest(&pubkey).as_slice())))
 	}
 
+	/// Execute a transaction from `source` and append it to the pending block.
+	fn apply_transaction(
+		source: H160,
+		transaction: ethereum::Transaction,
+	) -> Result<(H256, U256), DispatchError> {
+		let transaction_hash = Self::transaction_hash(&transaction);
+		let transaction_index = Pending::get().len() as u32;
+
+		let (to, info) = Self::execute(
+			source,
+			transaction.input.clone(),
+			transaction.value,
+			transaction.gas_limit,
+			Some(transaction.gas_price),
+			Some(transaction.nonce),
+			transaction.action,
+			None,
+		)?;
+
+		let (reason, status, used_gas, output) = match info {
+			CallOrCreateInfo::Call(info) => {
+				(info.exit_reason, TransactionStatus {
+					transaction_hash,
+					transaction_index,
+					from: source,
+					to,
+					contract_address: None,
+					logs: info.logs.clone(),
+					logs_bloom: {
+						let mut bloom: Bloom = Bloom::default();
+						Self::logs_bloom(
+							info.logs,
+							&mut bloom
+						);
+						bloom
+					},
+					internal_transactions: info.internal_txs,
+				}, info.used_gas, info.value)
+			},
+			CallOrCreateInfo::Create(info, output) => {
+				(info.exit_reason, TransactionStatus {
+					transaction_hash,
+					transaction_index,
+					from: source,
+					to,
+					contract_address: Some(info.value),
+					logs: info.logs.clone(),
+					logs_bloom: {
+						let mut bloom: Bloom = Bloom::default();
+						Self::logs_bloom(
+							info.logs,
+							&mut bloom
+						);
+						bloom
+					},
+					internal_transactions: Vec::new(),
+				}, info.used_gas, output)
+			},
+		};
+
+		let receipt = ethereum::Receipt {
+			state_root: match reason {
+				ExitReason::Succeed(_) => H256::from_low_u64_be(1),
+				ExitReason::Error(_) => H256::from_low_u64_le(0),
+				ExitReason::Revert(_) => H256::from_low_u64_le(0),
+				ExitReason::Fatal(_) => H256::from_low_u64_le(0),
+			},
+			used_gas,
+			logs_bloom: status.clone().logs_bloom,
+			logs: status.clone().logs,
+		};
+
+		Pending::append((transaction, status, receipt));
+		PendingExitReasons::append((reason.clone(), match reason {
+			ExitReason::Revert(_) => output.clone(),
+			_ => Vec::new(),
+		}));
+		T::OnTransactionExecuted::happened(&(
+			<T as clover_evm::Trait>::AddressMapping::into_account_id(&source),
+			used_gas.low_u64(),
+		));
+
+		if reason.is_succeed() {
+			Self::deposit_event(Event::Executed(source, transaction_hash, reason));
+		} else {
+			Self::deposit_event(Event::ExecutionFailed(source, transaction_hash, reason, output));
+		}
+		Ok((transaction_hash, used_gas))
+	}
+
+	fn transaction_hash(transaction: &ethereum::Transaction) -> H256 {
+		H256::from_slice(Keccak256::digest(&rlp::encode(transaction)).as_slice())
+	}
+
+	/// Recover the sponsor address from its `personal_sign` signature of the transaction hash.
+	fn recover_sponsor(transaction_hash: &H256, signature: &ecdsa::Signature) -> Option<H160> {
+		let mut prefixed = b"\x19Ethereum Signed Message:\n32".to_vec();
+		prefixed.extend_from_slice(&transaction_hash[..]);
+		let mut msg = [0u8; 32];
+		msg.copy_from_slice(Keccak256::digest(&prefixed).as_slice());
+
+		let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(signature.as_ref(), &msg).ok()?;
+		Some(H160::from(H256::from_slice(Keccak256::digest(&pubkey).as_slice())))
+	}
+
+	/// Ensure the sponsor pays for `source` and the transaction is within its limits,
+	/// returns the max fee of the transaction.
+	fn check_sponsor(
+		sponsor: &H160,
+		source: &H160,
+		transaction: &ethereum::Transaction,
+	) -> Result<U256, Error<T>> {
+		let limit = Self::sponsor_limits(sponsor).ok_or(Error::<T>::SponsorNotFound)?;
+		ensure!(Self::sponsored_accounts(sponsor, source), Error::<T>::NotSponsored);
+		ensure!(
+			transaction.gas_price <= limit.max_gas_price && transaction.gas_limit <= limit.max_gas_limit,
+			Error::<T>::SponsorLimitExceeded
+		);
+
+		let max_fee = transaction.gas_price.checked_mul(transaction.gas_limit).ok_or(Error::<T>::FeeOverflow)?;
+		ensure!(max_fee <= limit.remaining_fee, Error::<T>::SponsorLimitExceeded);
+		Self::fee_balance(max_fee)?;
+		Ok(max_fee)
+	}
+
+	/// Convert a fee to the balance type, fees which don't fit are rejected instead of truncated.
+	fn fee_balance(fee: U256) -> Result<clover_evm::BalanceOf<T>, Error<T>> {
+		ensure!(fee <= U256::from(u128::max_value()), Error::<T>::FeeOverflow);
+		fee.as_u128().try_into().map_err(|_| Error::<T>::FeeOverflow)
+	}
+
+	fn ensure_evm_origin(origin: T::Origin) -> Result<H160, DispatchError> {
+		let who = ensure_signed(origin)?;
+		<T as clover_evm::Trait>::AddressMapping::to_evm_address(&who)
+			.ok_or_else(|| Error::<T>::NoEvmAddress.into())
+	}
+
 	fn store_block() {
 		let mut transactions = Vec::new();
 		let mut statuses = Vec::new();
@@ -332,6 +593,7 @@ impl<T: Trait> Module<T> {
 		CurrentBlock::put(block.clone());
 		CurrentReceipts::put(receipts.clone());
 		CurrentTransactionStatuses::put(statuses.clone());
+		CurrentExitReasons::put(PendingExitReasons::get());
 
 		let digest = DigestItem::<T::Hash>::Consensus(
 			FRONTIER_ENGINE_ID,
@@ -365,6 +627,11 @@ impl<T: Trait> Module<T> {
 		CurrentTransactionStatuses::get()
 	}
 
+	/// Get the exit reasons of the current transactions, with the revert data of the reverted ones.
+	pub fn current_exit_reasons() -> Option<Vec<(ExitReason, Vec<u8>)>> {
+		CurrentExitReasons::get()
+	}
+
 	/// Get current block.
 	pub fn current_block() -> Option<ethereum::Block> {
 		CurrentBlock::get()
@@ -405,7 +672,7 @@ impl<T: Trait> Module<T> {
 				).map_err(Into::into)?)))
 			},
 			ethereum::TransactionAction::Create => {
-				Ok((None, CallOrCreateInfo::Create(T::Runner::create(
+				let (info, output) = T::Runner::create(
 					from,
 					input.clone(),
 					value,
@@ -413,7 +680,8 @@ impl<T: Trait> Module<T> {
 					gas_price,
 					nonce,
 					config.as_ref().unwrap_or(T::config()),
-				).map_err(Into::into)?)))
+				).map_err(Into::into)?;
+				Ok((None, CallOrCreateInfo::Create(info, output)))
 			},
 		}
 	}
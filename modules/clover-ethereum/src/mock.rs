@@ -0,0 +1,202 @@
+//! Test utilities
+
+#![cfg(test)]
+
+use super::*;
+use crate::{Module, Trait};
+use ethereum::{TransactionAction, TransactionSignature};
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_system::EnsureRoot;
+use sp_core::{crypto::AccountId32, H160, H256, U256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1000;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+/// Maps an address to the account with the address as its first 20 bytes.
+pub struct TruncatedAddressMapping;
+
+impl AddressMapping<AccountId32> for TruncatedAddressMapping {
+	fn into_account_id(address: &H160) -> AccountId32 {
+		let mut data = [0u8; 32];
+		data[0..20].copy_from_slice(&address[..]);
+		AccountId32::from(data)
+	}
+
+	fn to_evm_address(account: &AccountId32) -> Option<H160> {
+		Some(H160::from_slice(&AsRef::<[u8; 32]>::as_ref(account)[0..20]))
+	}
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+	pub const DepositPerStorageSlot: u64 = 0;
+	pub const DepositPerCodeByte: u64 = 0;
+}
+
+impl clover_evm::Trait for Test {
+	type FeeCalculator = ();
+	type GasToWeight = ();
+	type CallOrigin = clover_evm::EnsureAddressTruncated;
+	type WithdrawOrigin = clover_evm::EnsureAddressTruncated;
+	type AddressMapping = TruncatedAddressMapping;
+	type MergeAccount = ();
+	type Currency = Balances;
+	type DefaultDepositPerStorageSlot = DepositPerStorageSlot;
+	type DefaultDepositPerCodeByte = DepositPerCodeByte;
+	type DepositOrigin = EnsureRoot<AccountId32>;
+	type PermissionOrigin = EnsureRoot<AccountId32>;
+	type Event = ();
+	type Precompiles = (clover_evm::precompiles::ECRecover,);
+	type ChainId = ChainId;
+	type Runner = clover_evm::runner::stack::Runner<Self>;
+}
+
+impl Trait for Test {
+	type Event = ();
+	type FindAuthor = ();
+	type OnTransactionExecuted = ();
+	type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Ethereum = Module<Test>;
+
+/// An ethereum key pair of the tests.
+pub struct AccountInfo {
+	pub address: H160,
+	pub account_id: AccountId32,
+	pub private_key: secp256k1::SecretKey,
+}
+
+pub fn account(seed: u8) -> AccountInfo {
+	let private_key = secp256k1::SecretKey::parse(&[seed; 32]).expect("valid secret key");
+	let public = secp256k1::PublicKey::from_secret_key(&private_key).serialize();
+	let address = H160::from(H256::from_slice(Keccak256::digest(&public[1..65]).as_slice()));
+	AccountInfo {
+		address,
+		account_id: TruncatedAddressMapping::into_account_id(&address),
+		private_key,
+	}
+}
+
+/// Sign a 32 bytes hash, the recovery id is the last byte.
+pub fn sign_hash(hash: &H256, key: &secp256k1::SecretKey) -> [u8; 65] {
+	let (signature, recovery_id) = secp256k1::sign(&secp256k1::Message::parse(hash.as_fixed_bytes()), key);
+	let mut result = [0u8; 65];
+	result[0..64].copy_from_slice(&signature.serialize());
+	result[64] = recovery_id.serialize();
+	result
+}
+
+/// A call to `to` without value and input, signed by `from`.
+pub fn transfer_transaction(from: &AccountInfo, to: H160, nonce: u64, gas_price: U256, gas_limit: u64) -> Transaction {
+	let message = TransactionMessage {
+		nonce: U256::from(nonce),
+		gas_price,
+		gas_limit: U256::from(gas_limit),
+		action: TransactionAction::Call(to),
+		value: U256::zero(),
+		input: Vec::new(),
+		chain_id: Some(ChainId::get()),
+	};
+	let signature = sign_hash(&message.hash(), &from.private_key);
+	Transaction {
+		nonce: message.nonce,
+		gas_price: message.gas_price,
+		gas_limit: message.gas_limit,
+		action: message.action,
+		value: message.value,
+		input: message.input,
+		signature: TransactionSignature::new(
+			signature[64] as u64 + ChainId::get() * 2 + 35,
+			H256::from_slice(&signature[0..32]),
+			H256::from_slice(&signature[32..64]),
+		).expect("valid signature"),
+	}
+}
+
+/// The `personal_sign` signature of the sponsor over the transaction hash.
+pub fn sponsor_signature(transaction: &Transaction, sponsor: &AccountInfo) -> ecdsa::Signature {
+	let mut prefixed = b"\x19Ethereum Signed Message:\n32".to_vec();
+	prefixed.extend_from_slice(&Ethereum::transaction_hash(transaction)[..]);
+	let hash = H256::from_slice(Keccak256::digest(&prefixed).as_slice());
+	ecdsa::Signature::from_raw(sign_hash(&hash, &sponsor.private_key))
+}
+
+pub fn new_test_ext(balances: Vec<(AccountId32, u64)>) -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> { balances }.assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
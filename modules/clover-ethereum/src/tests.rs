@@ -0,0 +1,225 @@
+//! Unit tests for the ethereum module.
+
+#![cfg(test)]
+
+use super::*;
+use mock::*;
+use frame_support::{assert_noop, assert_ok, unsigned::ValidateUnsigned};
+
+const GAS_LIMIT: u64 = 21_000;
+
+fn limit(max_gas_price: u64, remaining_fee: u64) -> SponsorLimit {
+	SponsorLimit {
+		max_gas_price: U256::from(max_gas_price),
+		max_gas_limit: U256::from(GAS_LIMIT),
+		remaining_fee: U256::from(remaining_fee),
+	}
+}
+
+fn check_sponsor(sponsor: &AccountInfo, source: &AccountInfo, transaction: &Transaction) -> Result<U256, DispatchError> {
+	Ethereum::check_sponsor(&sponsor.address, &source.address, transaction).map_err(Into::into)
+}
+
+fn setup_sponsor(sponsor: &AccountInfo, account: &AccountInfo, limit: SponsorLimit) {
+	assert_ok!(Ethereum::set_sponsor_limit(Origin::signed(sponsor.account_id.clone()), limit));
+	assert_ok!(Ethereum::add_sponsored_accounts(Origin::signed(sponsor.account_id.clone()), vec![account.address]));
+}
+
+#[test]
+fn transact_sponsored_works() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![(sponsor.account_id.clone(), 1_000_000)]).execute_with(|| {
+		setup_sponsor(&sponsor, &alice, limit(1, 100_000));
+
+		let transaction = transfer_transaction(&alice, bob.address, 0, U256::one(), GAS_LIMIT);
+		let signature = sponsor_signature(&transaction, &sponsor);
+		assert_ok!(Ethereum::transact_sponsored(Origin::none(), transaction, signature));
+
+		// the sponsor pays the fee and keeps the signer alive
+		assert_eq!(Balances::free_balance(&sponsor.account_id), 1_000_000 - GAS_LIMIT - ExistentialDeposit::get());
+		assert_eq!(Balances::free_balance(&alice.account_id), ExistentialDeposit::get());
+		assert_eq!(clover_evm::Module::<Test>::account_basic(&alice.address).nonce, U256::one());
+		assert_eq!(Ethereum::sponsor_limits(&sponsor.address).unwrap().remaining_fee, U256::from(100_000 - GAS_LIMIT));
+	});
+}
+
+#[test]
+fn transact_sponsored_rejects_replay() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![(sponsor.account_id.clone(), 1_000_000)]).execute_with(|| {
+		setup_sponsor(&sponsor, &alice, limit(1, 100_000));
+
+		let transaction = transfer_transaction(&alice, bob.address, 0, U256::one(), GAS_LIMIT);
+		let signature = sponsor_signature(&transaction, &sponsor);
+		assert_ok!(Ethereum::transact_sponsored(Origin::none(), transaction.clone(), signature.clone()));
+
+		assert_noop!(
+			Ethereum::transact_sponsored(Origin::none(), transaction, signature),
+			clover_evm::Error::<Test>::InvalidNonce
+		);
+		assert_eq!(Balances::free_balance(&sponsor.account_id), 1_000_000 - GAS_LIMIT - ExistentialDeposit::get());
+		assert_eq!(Ethereum::sponsor_limits(&sponsor.address).unwrap().remaining_fee, U256::from(100_000 - GAS_LIMIT));
+	});
+}
+
+#[test]
+fn check_sponsor_enforces_limits() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![(sponsor.account_id.clone(), 1_000_000)]).execute_with(|| {
+		let transaction = transfer_transaction(&alice, bob.address, 0, U256::from(2), GAS_LIMIT);
+		assert_noop!(
+			check_sponsor(&sponsor, &alice, &transaction),
+			Error::<Test>::SponsorNotFound
+		);
+
+		assert_ok!(Ethereum::set_sponsor_limit(Origin::signed(sponsor.account_id.clone()), limit(1, 100_000)));
+		assert_noop!(
+			check_sponsor(&sponsor, &alice, &transaction),
+			Error::<Test>::NotSponsored
+		);
+
+		assert_ok!(Ethereum::add_sponsored_accounts(Origin::signed(sponsor.account_id.clone()), vec![alice.address]));
+		// gas price above the max gas price
+		assert_noop!(
+			check_sponsor(&sponsor, &alice, &transaction),
+			Error::<Test>::SponsorLimitExceeded
+		);
+
+		// max fee above the remaining fee
+		assert_ok!(Ethereum::set_sponsor_limit(Origin::signed(sponsor.account_id.clone()), limit(2, 30_000)));
+		assert_noop!(
+			check_sponsor(&sponsor, &alice, &transaction),
+			Error::<Test>::SponsorLimitExceeded
+		);
+
+		assert_ok!(Ethereum::set_sponsor_limit(Origin::signed(sponsor.account_id.clone()), limit(2, 42_000)));
+		assert_eq!(check_sponsor(&sponsor, &alice, &transaction), Ok(U256::from(42_000)));
+
+		assert_noop!(
+			Ethereum::remove_sponsor(Origin::signed(sponsor.account_id.clone()), 0),
+			Error::<Test>::InvalidAccountCount
+		);
+		assert_ok!(Ethereum::remove_sponsor(Origin::signed(sponsor.account_id.clone()), 1));
+		assert!(!Ethereum::sponsored_accounts(&sponsor.address, &alice.address));
+		assert_eq!(Ethereum::sponsored_account_counts(&sponsor.address), 0);
+	});
+}
+
+#[test]
+fn check_sponsor_rejects_fee_overflow() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![(sponsor.account_id.clone(), 1_000_000)]).execute_with(|| {
+		let gas_price = U256::from(u128::max_value()) + 1;
+		setup_sponsor(&sponsor, &alice, SponsorLimit {
+			max_gas_price: U256::max_value(),
+			max_gas_limit: U256::from(GAS_LIMIT),
+			remaining_fee: U256::max_value(),
+		});
+
+		let transaction = transfer_transaction(&alice, bob.address, 0, gas_price, GAS_LIMIT);
+		assert_noop!(
+			check_sponsor(&sponsor, &alice, &transaction),
+			Error::<Test>::FeeOverflow
+		);
+
+		// fits into u128 but not into the balance type
+		let transaction = transfer_transaction(&alice, bob.address, 0, U256::from(u64::max_value()), GAS_LIMIT);
+		assert_noop!(
+			check_sponsor(&sponsor, &alice, &transaction),
+			Error::<Test>::FeeOverflow
+		);
+	});
+}
+
+#[test]
+fn recover_sponsor_works() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![]).execute_with(|| {
+		let transaction = transfer_transaction(&alice, bob.address, 0, U256::one(), GAS_LIMIT);
+		let transaction_hash = Ethereum::transaction_hash(&transaction);
+		let signature = sponsor_signature(&transaction, &sponsor);
+		assert_eq!(Ethereum::recover_sponsor(&transaction_hash, &signature), Some(sponsor.address));
+
+		// a signature of another transaction recovers another address
+		let other = transfer_transaction(&alice, bob.address, 1, U256::one(), GAS_LIMIT);
+		assert_ne!(Ethereum::recover_sponsor(&Ethereum::transaction_hash(&other), &signature), Some(sponsor.address));
+	});
+}
+
+#[test]
+fn transact_sponsored_rejects_mismatched_signature() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![(sponsor.account_id.clone(), 1_000_000)]).execute_with(|| {
+		setup_sponsor(&sponsor, &alice, limit(1, 100_000));
+
+		let transaction = transfer_transaction(&alice, bob.address, 0, U256::one(), GAS_LIMIT);
+		let other = transfer_transaction(&alice, bob.address, 1, U256::one(), GAS_LIMIT);
+		let signature = sponsor_signature(&other, &sponsor);
+
+		assert_eq!(
+			Ethereum::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact_sponsored(transaction.clone(), signature.clone()),
+			),
+			Err(InvalidTransaction::Custom(TransactionValidationError::SponsorNotAllowed as u8).into())
+		);
+		assert_noop!(
+			Ethereum::transact_sponsored(Origin::none(), transaction, signature),
+			Error::<Test>::SponsorNotFound
+		);
+		assert_eq!(Balances::free_balance(&sponsor.account_id), 1_000_000);
+	});
+}
+
+#[test]
+fn validate_sponsored_checks_sponsor_balance() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![(sponsor.account_id.clone(), 1_000)]).execute_with(|| {
+		setup_sponsor(&sponsor, &alice, limit(1, 100_000));
+
+		let transaction = transfer_transaction(&alice, bob.address, 0, U256::one(), GAS_LIMIT);
+		let signature = sponsor_signature(&transaction, &sponsor);
+		assert_eq!(
+			Ethereum::validate_unsigned(
+				TransactionSource::External,
+				&Call::transact_sponsored(transaction, signature),
+			),
+			Err(InvalidTransaction::Payment.into())
+		);
+	});
+}
+
+#[test]
+fn sponsored_accounts_are_counted() {
+	let sponsor = account(1);
+	let alice = account(2);
+	let bob = account(3);
+	new_test_ext(vec![(sponsor.account_id.clone(), 1_000_000)]).execute_with(|| {
+		let origin = || Origin::signed(sponsor.account_id.clone());
+		assert_ok!(Ethereum::set_sponsor_limit(origin(), limit(1, 100_000)));
+		assert_ok!(Ethereum::add_sponsored_accounts(origin(), vec![alice.address, bob.address, alice.address]));
+		assert_eq!(Ethereum::sponsored_account_counts(&sponsor.address), 2);
+
+		assert_ok!(Ethereum::remove_sponsored_accounts(origin(), vec![bob.address, bob.address]));
+		assert_eq!(Ethereum::sponsored_account_counts(&sponsor.address), 1);
+
+		assert_noop!(Ethereum::remove_sponsor(origin(), 0), Error::<Test>::InvalidAccountCount);
+		assert_ok!(Ethereum::remove_sponsor(origin(), 1));
+		assert_eq!(Ethereum::sponsor_limits(&sponsor.address), None);
+		assert!(!Ethereum::sponsored_accounts(&sponsor.address, &alice.address));
+	});
+}
@@ -3,25 +3,30 @@
 pub mod runner;
 pub mod precompiles;
 
+mod mock;
+mod tests;
+
 pub use crate::precompiles::{Precompile, Precompiles};
 pub use crate::runner::Runner;
 pub use fp_evm::{Account, Log, Vicinity, ExecutionInfo, CallInfo, CreateInfo};
 pub use evm::{ExitReason, ExitSucceed, ExitError, ExitRevert, ExitFatal};
 
-use sp_std::{marker::PhantomData, vec::Vec};
-#[cfg(feature = "std")]
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, vec::Vec};
 use codec::{Encode, Decode};
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
 use frame_support::{decl_module, decl_storage, decl_event, decl_error};
-use frame_support::weights::{Weight, Pays, PostDispatchInfo};
-use frame_support::traits::{Currency, ExistenceRequirement, Get, OnKilledAccount};
+use frame_support::storage::{IterableStorageDoubleMap, StoragePrefixedMap};
+use frame_support::weights::{DispatchClass, Weight, Pays, PostDispatchInfo};
+use frame_support::traits::{Currency, EnsureOrigin, ExistenceRequirement, Get, OnKilledAccount, ReservableCurrency};
 use frame_support::dispatch::DispatchResultWithPostInfo;
 use frame_system::RawOrigin;
 use sp_core::{U256, H256, H160};
-use sp_runtime::{AccountId32, traits::{UniqueSaturatedInto, BadOrigin}};
+use sp_runtime::{AccountId32, RuntimeDebug, traits::{UniqueSaturatedInto, BadOrigin, Saturating, Zero}};
+use evm::backend::Apply;
 use evm::Config;
-use fp_evm::AddressMapping;
+use evm::executor::PolicyCheck;
+pub use fp_evm::AddressMapping;
 use orml_traits::account::MergeAccount;
 
 /// Type alias for currency balance.
@@ -152,6 +157,9 @@ impl Get<u64> for SystemChainId {
 
 static ISTANBUL_CONFIG: Config = Config::istanbul();
 
+/// Contracts migrated to the storage deposits in a block.
+const CONTRACT_INFO_MIGRATION_LIMIT: u32 = 100;
+
 /// EVM module trait
 pub trait Trait: frame_system::Trait + pallet_timestamp::Trait {
 	/// Calculator for current gas price.
@@ -171,8 +179,17 @@ pub trait Trait: frame_system::Trait + pallet_timestamp::Trait {
 	/// Merge free balance from source to dest.
 	type MergeAccount: MergeAccount<Self::AccountId>;
 
-	/// Currency type for withdraw and balance storage.
-	type Currency: Currency<Self::AccountId>;
+	/// Currency type for withdraw, balance storage and storage deposits.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// Deposit reserved for each storage slot of a contract, unless set on chain.
+	type DefaultDepositPerStorageSlot: Get<BalanceOf<Self>>;
+	/// Deposit reserved for each byte of contract code, unless set on chain.
+	type DefaultDepositPerCodeByte: Get<BalanceOf<Self>>;
+	/// Origin allowed to set the storage deposit params.
+	type DepositOrigin: EnsureOrigin<Self::Origin>;
+	/// Origin allowed to manage the deployers, paused contracts and contract callers.
+	type PermissionOrigin: EnsureOrigin<Self::Origin>;
 
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
@@ -203,12 +220,91 @@ pub struct GenesisAccount {
 	pub code: Vec<u8>,
 }
 
+/// Prices of the contract state, reserved from the accounts which write it.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StorageDepositParams<Balance> {
+	/// Deposit of a storage slot.
+	pub per_storage_slot: Balance,
+	/// Deposit of a byte of code.
+	pub per_code_byte: Balance,
+}
+
+/// State of a contract covered by storage deposits.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct ContractInfo<AccountId> {
+	/// Account which created the contract.
+	pub deployer: AccountId,
+	/// Bytes of code.
+	pub code_size: u32,
+}
+
+/// Storage slots and code of a contract changed by an EVM execution.
+#[derive(Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct StorageChange {
+	/// The contract.
+	pub address: H160,
+	/// Storage slots written from empty.
+	pub slots_added: Vec<H256>,
+	/// Storage slots cleared.
+	pub slots_removed: Vec<H256>,
+	/// Whether all the storage slots were cleared before writing the new ones.
+	pub reset: bool,
+	/// Size of the new code, if the code was set.
+	pub code_size: Option<u32>,
+	/// Whether the contract self-destructed.
+	pub deleted: bool,
+}
+
+// storage layout releases of the module
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V1_0_0,
+	V2_0_0,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1_0_0
+	}
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as EVM {
+		/// Deployers of the contracts, the source of the transaction which created them.
 		pub AccountConnection get(fn account_connection): map hasher(blake2_128_concat) H160 => H160;
 		AccountCodes get(fn account_codes): map hasher(blake2_128_concat) H160 => Vec<u8>;
 		AccountStorages get(fn account_storages):
 			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H256 => H256;
+
+		/// Deployer and code size of the contracts.
+		pub ContractInfos get(fn contract_infos): map hasher(blake2_128_concat) H160 => Option<ContractInfo<T::AccountId>>;
+		/// Deposits reserved from the accounts for the state of the contracts.
+		pub StorageDeposits get(fn storage_deposits):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+		/// Account which wrote a storage slot and the deposit reserved from it, refunded when the slot is cleared.
+		pub SlotDeposits get(fn slot_deposits):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H256 => Option<(T::AccountId, BalanceOf<T>)>;
+		/// Raw key of the last contract code migrated to `ContractInfos`, None if the migration is done.
+		ContractInfoMigration get(fn contract_info_migration): Option<Vec<u8>>;
+		/// Storage deposit prices, the defaults are used if not set.
+		pub DepositParams get(fn deposit_params): Option<StorageDepositParams<BalanceOf<T>>>;
+
+		/// Whether only the allowed deployers can create contracts, the contracts created by
+		/// contracts included.
+		pub DeployerRestricted get(fn deployer_restricted): bool;
+		/// Accounts allowed to create contracts when deployment is restricted.
+		pub AllowedDeployers get(fn allowed_deployers): map hasher(blake2_128_concat) H160 => bool;
+		/// Contracts which can't be called, by transactions or by other contracts.
+		pub PausedContracts get(fn paused_contracts): map hasher(blake2_128_concat) H160 => bool;
+		/// Contracts which can only be called by their allowed callers.
+		pub CallerRestricted get(fn caller_restricted): map hasher(blake2_128_concat) H160 => bool;
+		/// Accounts allowed to call the caller restricted contracts, the caller of a delegate call
+		/// is the original sender.
+		pub AllowedCallers get(fn allowed_callers):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H160 => bool;
+
+		StorageVersion build(|_: &GenesisConfig| Releases::V2_0_0): Releases;
 	}
 
 	add_extra_genesis {
@@ -233,6 +329,13 @@ decl_storage! {
 				for (index, value) in &account.storage {
 					AccountStorages::insert(address, index, value);
 				}
+
+				if !account.code.is_empty() {
+					ContractInfos::<T>::insert(address, ContractInfo {
+						deployer: account_id,
+						code_size: account.code.len() as u32,
+					});
+				}
 			}
 		});
 	}
@@ -242,6 +345,7 @@ decl_event! {
 	/// EVM events
 	pub enum Event<T> where
 		<T as frame_system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
 	{
 		/// Ethereum events from contracts.
 		Log(Log),
@@ -257,6 +361,22 @@ decl_event! {
 		BalanceDeposit(AccountId, H160, U256),
 		/// A withdrawal has been made from a given address. \[sender, address, value\]
 		BalanceWithdraw(AccountId, H160, U256),
+		/// Storage deposit reserved for the state of a contract. \[who, contract, amount\]
+		StorageDepositReserved(AccountId, H160, Balance),
+		/// Storage deposit refunded for the cleared state of a contract. \[who, contract, amount\]
+		StorageDepositRefunded(AccountId, H160, Balance),
+		/// Storage deposit prices set, None means the defaults. \[params\]
+		DepositParamsUpdated(Option<StorageDepositParams<Balance>>),
+		/// Contract deployment restricted to the allowed deployers or not. \[restricted\]
+		DeployerRestrictionSet(bool),
+		/// Deployer allowed or disallowed. \[deployer, allowed\]
+		DeployerAllowed(H160, bool),
+		/// Contract paused or resumed. \[contract, paused\]
+		ContractPaused(H160, bool),
+		/// Calls of a contract restricted to the allowed callers or not. \[contract, restricted\]
+		CallerRestrictionSet(H160, bool),
+		/// Caller of a contract allowed or disallowed. \[contract, caller, allowed\]
+		CallerAllowed(H160, H160, bool),
 	}
 }
 
@@ -274,6 +394,8 @@ decl_error! {
 		GasPriceTooLow,
 		/// Nonce is invalid
 		InvalidNonce,
+		/// Not enough free balance to reserve the storage deposit
+		StorageDepositLow,
 	}
 }
 
@@ -283,6 +405,91 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V2_0_0 {
+				return 0;
+			}
+			// the contracts are migrated in the next blocks
+			ContractInfoMigration::put(AccountCodes::final_prefix().to_vec());
+			StorageVersion::put(Releases::V2_0_0);
+			T::DbWeight::get().reads_writes(1, 2)
+		}
+
+		fn on_initialize() -> Weight {
+			match Self::contract_info_migration() {
+				Some(cursor) => Self::migrate_contract_infos(cursor, CONTRACT_INFO_MIGRATION_LIMIT),
+				None => T::DbWeight::get().reads(1),
+			}
+		}
+
+		/// Set the storage deposit prices, `None` uses the defaults.
+		/// Deposits already reserved are not changed.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		fn set_deposit_params(origin, params: Option<StorageDepositParams<BalanceOf<T>>>) {
+			T::DepositOrigin::ensure_origin(origin)?;
+			match params {
+				Some(params) => DepositParams::<T>::put(params),
+				None => DepositParams::<T>::kill(),
+			}
+			Self::deposit_event(RawEvent::DepositParamsUpdated(params));
+		}
+
+		/// Restrict the contract deployment to the allowed deployers.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		fn set_deployer_restricted(origin, restricted: bool) {
+			T::PermissionOrigin::ensure_origin(origin)?;
+			DeployerRestricted::put(restricted);
+			Self::deposit_event(RawEvent::DeployerRestrictionSet(restricted));
+		}
+
+		/// Allow or disallow an account to deploy contracts.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		fn set_deployer_allowed(origin, deployer: H160, allowed: bool) {
+			T::PermissionOrigin::ensure_origin(origin)?;
+			if allowed {
+				AllowedDeployers::insert(deployer, true);
+			} else {
+				AllowedDeployers::remove(deployer);
+			}
+			Self::deposit_event(RawEvent::DeployerAllowed(deployer, allowed));
+		}
+
+		/// Pause or resume the calls of a contract.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		fn set_contract_paused(origin, contract: H160, paused: bool) {
+			T::PermissionOrigin::ensure_origin(origin)?;
+			if paused {
+				PausedContracts::insert(contract, true);
+			} else {
+				PausedContracts::remove(contract);
+			}
+			Self::deposit_event(RawEvent::ContractPaused(contract, paused));
+		}
+
+		/// Restrict the calls of a contract to its allowed callers.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		fn set_caller_restricted(origin, contract: H160, restricted: bool) {
+			T::PermissionOrigin::ensure_origin(origin)?;
+			if restricted {
+				CallerRestricted::insert(contract, true);
+			} else {
+				CallerRestricted::remove(contract);
+			}
+			Self::deposit_event(RawEvent::CallerRestrictionSet(contract, restricted));
+		}
+
+		/// Allow or disallow an account to call a contract.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		fn set_caller_allowed(origin, contract: H160, caller: H160, allowed: bool) {
+			T::PermissionOrigin::ensure_origin(origin)?;
+			if allowed {
+				AllowedCallers::insert(contract, caller, true);
+			} else {
+				AllowedCallers::remove(contract, caller);
+			}
+			Self::deposit_event(RawEvent::CallerAllowed(contract, caller, allowed));
+		}
+
 		/// Withdraw balance from EVM into currency/balances module.
 		#[weight = 0]
 		fn withdraw(origin, address: H160, value: BalanceOf<T>) {
@@ -351,7 +558,7 @@ decl_module! {
 		) -> DispatchResultWithPostInfo {
 			T::CallOrigin::ensure_address_origin(&source, origin)?;
 
-			let info = T::Runner::create(
+			let (info, _) = T::Runner::create(
 				source,
 				init,
 				value,
@@ -398,7 +605,7 @@ decl_module! {
 		) -> DispatchResultWithPostInfo {
 			T::CallOrigin::ensure_address_origin(&source, origin)?;
 
-			let info = T::Runner::create2(
+			let (info, _) = T::Runner::create2(
 				source,
 				init,
 				salt,
@@ -452,10 +659,202 @@ impl<T: Trait> Module<T> {
 		}
 	}
 
-	/// Remove an account.
+	/// Remove an account, its storage deposits are refunded.
 	pub fn remove_account(address: &H160) {
 		AccountCodes::remove(address);
 		AccountStorages::remove_prefix(address);
+		Self::release_storage_deposits(address);
+	}
+
+	/// Deployer and code size of a contract, the contracts not migrated yet are read from their code.
+	pub fn contract_info(address: &H160) -> Option<ContractInfo<T::AccountId>> {
+		Self::contract_infos(address).or_else(|| {
+			let code_size = AccountCodes::decode_len(address).unwrap_or(0);
+			if code_size == 0 {
+				return None;
+			}
+			Some(ContractInfo {
+				deployer: T::AddressMapping::into_account_id(&Self::account_connection(address)),
+				code_size: code_size as u32,
+			})
+		})
+	}
+
+	/// Check that `deployer` can create contracts.
+	pub fn ensure_deploy_permitted(deployer: &H160) -> Result<(), ExitError> {
+		if Self::deployer_restricted() && !Self::allowed_deployers(deployer) {
+			return Err(ExitError::DeployerNotAllowed);
+		}
+		Ok(())
+	}
+
+	/// Check that `caller` can call `contract`.
+	pub fn ensure_call_permitted(caller: &H160, contract: &H160) -> Result<(), ExitError> {
+		if Self::paused_contracts(contract) {
+			return Err(ExitError::ContractPaused);
+		}
+		if Self::caller_restricted(contract) && !Self::allowed_callers(contract, caller) {
+			return Err(ExitError::CallerNotAllowed);
+		}
+		Ok(())
+	}
+
+	/// Policy of the executors, the transactions and their internal calls and creates are checked.
+	pub fn execution_policy(check: PolicyCheck) -> Result<(), ExitError> {
+		match check {
+			PolicyCheck::Create { caller } => Self::ensure_deploy_permitted(&caller),
+			PolicyCheck::Call { caller, address } => Self::ensure_call_permitted(&caller, &address),
+		}
+	}
+
+	/// Storage deposit prices.
+	pub fn get_deposit_params() -> StorageDepositParams<BalanceOf<T>> {
+		Self::deposit_params().unwrap_or_else(|| StorageDepositParams {
+			per_storage_slot: T::DefaultDepositPerStorageSlot::get(),
+			per_code_byte: T::DefaultDepositPerCodeByte::get(),
+		})
+	}
+
+	/// Storage slots and code changed by the applies, to be called before they are applied.
+	pub fn storage_changes(values: &[Apply<Vec<(H256, H256)>>]) -> Vec<StorageChange> {
+		values.iter().map(|apply| match apply {
+			Apply::Modify { address, code, storage, reset_storage, .. } => {
+				let mut change = StorageChange {
+					address: *address,
+					reset: *reset_storage,
+					code_size: code.as_ref().map(|code| code.len() as u32),
+					..Default::default()
+				};
+				for (index, value) in storage {
+					let existed = !reset_storage && AccountStorages::contains_key(address, index);
+					if value == &H256::default() && existed {
+						change.slots_removed.push(*index);
+					} else if value != &H256::default() && !existed {
+						change.slots_added.push(*index);
+					}
+				}
+				change
+			},
+			Apply::Delete { address } => StorageChange {
+				address: *address,
+				deleted: true,
+				..Default::default()
+			},
+		}).collect()
+	}
+
+	/// Reserve the deposits of the new state from `payer`, the deposits of the cleared slots are
+	/// refunded to the accounts which wrote them. `payer` is recorded as the deployer of the
+	/// contracts created by the transaction.
+	pub fn update_storage_deposits(payer: &H160, changes: Vec<StorageChange>) -> Result<(), Error<T>> {
+		let who = T::AddressMapping::into_account_id(payer);
+		let params = Self::get_deposit_params();
+
+		for change in changes {
+			if change.deleted {
+				continue;
+			}
+			let mut info = match Self::contract_infos(&change.address) {
+				Some(info) => info,
+				// created by the transaction, either by itself or by a contract
+				None if change.code_size.is_some() => {
+					AccountConnection::insert(&change.address, payer);
+					ContractInfo { deployer: who.clone(), code_size: 0 }
+				},
+				None => match Self::contract_info(&change.address) {
+					Some(info) => info,
+					// not a contract
+					None => continue,
+				},
+			};
+
+			let cleared: Vec<(T::AccountId, BalanceOf<T>)> = if change.reset {
+				SlotDeposits::<T>::drain_prefix(&change.address).map(|(_, deposit)| deposit).collect()
+			} else {
+				change.slots_removed.iter()
+					.filter_map(|index| SlotDeposits::<T>::take(&change.address, index))
+					.collect()
+			};
+			let mut refunds = BTreeMap::<T::AccountId, BalanceOf<T>>::new();
+			for (owner, amount) in cleared {
+				let refund = refunds.entry(owner).or_insert_with(Zero::zero);
+				*refund = refund.saturating_add(amount);
+			}
+			for (owner, amount) in refunds {
+				Self::refund_storage_deposit(&change.address, owner, amount);
+			}
+
+			let mut charge = params.per_storage_slot.saturating_mul((change.slots_added.len() as u32).into());
+			if let Some(code_size) = change.code_size {
+				info.code_size = code_size;
+				charge = charge.saturating_add(params.per_code_byte.saturating_mul(code_size.into()));
+			}
+			if !charge.is_zero() {
+				T::Currency::reserve(&who, charge).map_err(|_| Error::<T>::StorageDepositLow)?;
+				StorageDeposits::<T>::mutate(&change.address, &who, |deposit| *deposit = deposit.saturating_add(charge));
+				Self::deposit_event(RawEvent::StorageDepositReserved(who.clone(), change.address, charge));
+			}
+			for index in change.slots_added {
+				SlotDeposits::<T>::insert(&change.address, index, (who.clone(), params.per_storage_slot));
+			}
+			ContractInfos::<T>::insert(&change.address, info);
+		}
+		Ok(())
+	}
+
+	/// Refund a part of the storage deposits of `who` for the contract.
+	fn refund_storage_deposit(address: &H160, who: T::AccountId, amount: BalanceOf<T>) {
+		if amount.is_zero() {
+			return;
+		}
+		T::Currency::unreserve(&who, amount);
+		let deposit = Self::storage_deposits(address, &who).saturating_sub(amount);
+		if deposit.is_zero() {
+			StorageDeposits::<T>::remove(address, &who);
+		} else {
+			StorageDeposits::<T>::insert(address, &who, deposit);
+		}
+		Self::deposit_event(RawEvent::StorageDepositRefunded(who, *address, amount));
+	}
+
+	/// Refund all the storage deposits of the contract.
+	fn release_storage_deposits(address: &H160) {
+		for (who, deposit) in StorageDeposits::<T>::drain_prefix(address) {
+			T::Currency::unreserve(&who, deposit);
+			Self::deposit_event(RawEvent::StorageDepositRefunded(who, *address, deposit));
+		}
+		SlotDeposits::<T>::remove_prefix(address);
+		ContractInfos::<T>::remove(address);
+	}
+
+	/// Track up to `limit` existing contracts after `cursor`, no deposit is reserved for their state.
+	fn migrate_contract_infos(cursor: Vec<u8>, limit: u32) -> Weight {
+		let prefix = AccountCodes::final_prefix();
+		let mut cursor = cursor;
+		let mut migrated: Weight = 0;
+		loop {
+			if migrated >= limit as Weight {
+				ContractInfoMigration::put(cursor);
+				break;
+			}
+			let next = match sp_io::storage::next_key(&cursor).filter(|key| key.starts_with(&prefix)) {
+				Some(key) => key,
+				None => {
+					ContractInfoMigration::kill();
+					break;
+				},
+			};
+			// the keys end with the address, hashed with blake2_128_concat
+			let address = H160::from_slice(&next[next.len() - 20..]);
+			if !ContractInfos::<T>::contains_key(&address) {
+				if let Some(info) = Self::contract_info(&address) {
+					ContractInfos::<T>::insert(&address, info);
+				}
+			}
+			cursor = next;
+			migrated += 1;
+		}
+		T::DbWeight::get().reads_writes(migrated.saturating_mul(4).saturating_add(1), migrated.saturating_add(1))
 	}
 
 	/// Get the account basic in EVM format.
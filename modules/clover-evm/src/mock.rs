@@ -0,0 +1,143 @@
+//! Test utilities
+
+#![cfg(test)]
+
+use super::*;
+use crate::{Module, Trait};
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_system::EnsureRoot;
+use sp_core::{crypto::AccountId32, H160, H256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = ();
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId32;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1000;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+/// Maps an address to the account with the address as its first 20 bytes.
+pub struct TruncatedAddressMapping;
+
+impl AddressMapping<AccountId32> for TruncatedAddressMapping {
+	fn into_account_id(address: &H160) -> AccountId32 {
+		let mut data = [0u8; 32];
+		data[0..20].copy_from_slice(&address[..]);
+		AccountId32::from(data)
+	}
+
+	fn to_evm_address(account: &AccountId32) -> Option<H160> {
+		Some(H160::from_slice(&AsRef::<[u8; 32]>::as_ref(account)[0..20]))
+	}
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+	pub const DepositPerStorageSlot: u64 = 10;
+	pub const DepositPerCodeByte: u64 = 1;
+}
+
+impl Trait for Test {
+	type FeeCalculator = ();
+	type GasToWeight = ();
+	type CallOrigin = EnsureAddressTruncated;
+	type WithdrawOrigin = EnsureAddressTruncated;
+	type AddressMapping = TruncatedAddressMapping;
+	type MergeAccount = ();
+	type Currency = Balances;
+	type DefaultDepositPerStorageSlot = DepositPerStorageSlot;
+	type DefaultDepositPerCodeByte = DepositPerCodeByte;
+	type DepositOrigin = EnsureRoot<AccountId32>;
+	type PermissionOrigin = EnsureRoot<AccountId32>;
+	type Event = ();
+	type Precompiles = ();
+	type ChainId = ChainId;
+	type Runner = runner::stack::Runner<Self>;
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type EVM = Module<Test>;
+pub type EvmRunner = runner::stack::Runner<Test>;
+
+pub const ALICE: H160 = H160([1u8; 20]);
+pub const BOB: H160 = H160([2u8; 20]);
+
+pub fn account_id(address: &H160) -> AccountId32 {
+	TruncatedAddressMapping::into_account_id(address)
+}
+
+pub fn new_test_ext(balances: Vec<(H160, u64)>) -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: balances.iter().map(|(address, balance)| (account_id(address), *balance)).collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	GenesisConfig::default().assimilate_storage::<Test>(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -20,6 +20,7 @@ pub trait Runner<T: Trait> {
 		config: &evm::Config,
 	) -> Result<CallInfo, Self::Error>;
 
+	/// Create a contract, the revert data of a reverted init code is returned with the info.
 	fn create(
 		source: H160,
 		init: Vec<u8>,
@@ -28,8 +29,10 @@ pub trait Runner<T: Trait> {
 		gas_price: Option<U256>,
 		nonce: Option<U256>,
 		config: &evm::Config,
-	) -> Result<CreateInfo, Self::Error>;
+	) -> Result<(CreateInfo, Vec<u8>), Self::Error>;
 
+	/// Create a contract at a salted address, the revert data of a reverted init code is returned
+	/// with the info.
 	fn create2(
 		source: H160,
 		init: Vec<u8>,
@@ -39,5 +42,5 @@ pub trait Runner<T: Trait> {
 		gas_price: Option<U256>,
 		nonce: Option<U256>,
 		config: &evm::Config,
-	) -> Result<CreateInfo, Self::Error>;
+	) -> Result<(CreateInfo, Vec<u8>), Self::Error>;
 }
@@ -2,10 +2,11 @@ use sp_std::marker::PhantomData;
 use sp_std::vec::Vec;
 use sp_core::{U256, H256, H160};
 use sp_runtime::traits::UniqueSaturatedInto;
-use frame_support::{debug, ensure, traits::{Get, Currency}, storage::{StorageMap, StorageDoubleMap}};
+use frame_support::{debug, ensure, traits::{Get, Currency}, storage::{StorageMap, StorageDoubleMap, with_transaction}};
+use sp_runtime::TransactionOutcome;
 use sha3::{Keccak256, Digest};
 use fp_evm::{ExecutionInfo, CallInfo, CreateInfo, Account, Log, Vicinity};
-use evm::ExitReason;
+use evm::{ExitReason, ExitError};
 use evm::backend::{Backend as BackendT, ApplyBackend, Apply};
 use evm::executor::StackExecutor;
 use crate::{Trait, AccountStorages, FeeCalculator, AccountCodes, Module, Event, Error, AddressMapping};
@@ -54,7 +55,7 @@ impl<T: Trait> Runner<T> {
 			gas_limit as usize,
 			config,
 			T::Precompiles::execute,
-		);
+		).with_policy(Module::<T>::execution_policy);
 
 		let total_fee = gas_price.checked_mul(U256::from(gas_limit))
 			.ok_or(Error::<T>::FeeOverflow)?;
@@ -70,7 +71,8 @@ impl<T: Trait> Runner<T> {
 		let (reason, retv) = f(&mut executor);
 
 		let used_gas = U256::from(executor.used_gas());
-		let actual_fee = executor.fee(gas_price);		
+		let actual_fee = executor.fee(gas_price);
+		let source_nonce = executor.nonce(source);
 
 		executor.deposit(source, total_fee.saturating_sub(actual_fee));
 
@@ -95,8 +97,42 @@ impl<T: Trait> Runner<T> {
 
 		let (values, logs, _call_graph) = executor.deconstruct();
 
-		let logs_data = logs.into_iter().map(|x| x).collect::<Vec<_>>();
-		backend.apply(values, logs_data.clone(), true);
+		let values = values.into_iter().map(|apply| match apply {
+			Apply::Modify { address, basic, code, storage, reset_storage } => Apply::Modify {
+				address, basic, code, reset_storage,
+				storage: storage.into_iter().collect::<Vec<_>>(),
+			},
+			Apply::Delete { address } => Apply::Delete { address },
+		}).collect::<Vec<_>>();
+		let storage_changes = Module::<T>::storage_changes(&values);
+
+		let mut logs_data = logs.into_iter().map(|x| x).collect::<Vec<_>>();
+		// the state is only applied if the storage deposits can be reserved
+		let applied = with_transaction(|| {
+			backend.apply(values, logs_data.clone(), true);
+			match Module::<T>::update_storage_deposits(&source, storage_changes) {
+				Ok(()) => TransactionOutcome::Commit(Ok(())),
+				Err(e) => TransactionOutcome::Rollback(Err(e)),
+			}
+		});
+
+		let reason = match applied {
+			Ok(()) => reason,
+			Err(e) => {
+				debug::info!("CLOVER EVM storage deposit of {:?} failed: {:?}", source, e);
+				// the execution fails, the source still pays the fee and its nonce is used
+				backend.mutate_account_basic(&source, Account {
+					nonce: source_nonce,
+					balance: source_account.balance.saturating_sub(actual_fee),
+				});
+				logs_data.clear();
+				for item in internal_transactions.iter_mut() {
+					item.developer = None;
+					item.developer_reward = None;
+				}
+				ExitReason::Error(ExitError::OutOfFund)
+			},
+		};
 
 		Ok(ExecutionInfo {
 			value: retv,
@@ -106,6 +142,12 @@ impl<T: Trait> Runner<T> {
 			internal_txs: internal_transactions,
 		})
 	}
+
+	/// Split the revert data of the init code from the info of a create.
+	fn split_create_output(info: ExecutionInfo<(H160, Vec<u8>)>) -> (CreateInfo, Vec<u8>) {
+		let ExecutionInfo { exit_reason, value: (address, output), used_gas, logs, internal_txs } = info;
+		(CreateInfo { exit_reason, value: address, used_gas, logs, internal_txs }, output)
+	}
 }
 
 impl<T: Trait> RunnerT<T> for Runner<T> {
@@ -150,7 +192,7 @@ impl<T: Trait> RunnerT<T> for Runner<T> {
 		gas_price: Option<U256>,
 		nonce: Option<U256>,
 		config: &evm::Config,
-	) -> Result<CreateInfo, Self::Error> {
+	) -> Result<(CreateInfo, Vec<u8>), Self::Error> {
 
 		Self::execute(
 			source,
@@ -165,16 +207,15 @@ impl<T: Trait> RunnerT<T> for Runner<T> {
 				);
 
 				debug::info!("CLOVER EVM CREATE [deployer: {:?}, address: {:?}, code: {:02x}]", source, address, init.as_hex());
-				AccountConnection::insert(address, source);
-
-				(executor.transact_create(
+				let (reason, output) = executor.transact_create(
 					source,
 					value,
 					init,
 					gas_limit as usize,
-				), address)
+				);
+				(reason, (address, output))
 			},
-		)
+		).map(Self::split_create_output)
 	}
 
 	fn create2(
@@ -186,7 +227,7 @@ impl<T: Trait> RunnerT<T> for Runner<T> {
 		gas_price: Option<U256>,
 		nonce: Option<U256>,
 		config: &evm::Config,
-	) -> Result<CreateInfo, Self::Error> {
+	) -> Result<(CreateInfo, Vec<u8>), Self::Error> {
 		let code_hash = H256::from_slice(Keccak256::digest(&init).as_slice());
 		Self::execute(
 			source,
@@ -199,15 +240,16 @@ impl<T: Trait> RunnerT<T> for Runner<T> {
 				let address = executor.create_address(
 					evm::CreateScheme::Create2 { caller: source, code_hash, salt },
 				);
-				(executor.transact_create2(
+				let (reason, output) = executor.transact_create2(
 					source,
 					value,
 					init,
 					salt,
 					gas_limit as usize,
-				), address)
+				);
+				(reason, (address, output))
 			},
-		)
+		).map(Self::split_create_output)
 	}
 }
 
@@ -0,0 +1,253 @@
+//! Unit tests for the evm module.
+
+#![cfg(test)]
+
+use super::*;
+use mock::*;
+use frame_support::{assert_ok, storage::IterableStorageMap, traits::{OnInitialize, OnRuntimeUpgrade}};
+
+const GAS_LIMIT: u32 = 200_000;
+
+/// Init code of a contract which stores the second word of the input at the slot of the first word.
+const STORE_CONTRACT: [u8; 20] = [
+	0x60, 0x08, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x08, 0x60, 0x00, 0xf3,
+	0x60, 0x20, 0x35, 0x60, 0x00, 0x35, 0x55, 0x00,
+];
+const STORE_CODE_SIZE: u64 = 8;
+/// Init code of a contract which calls the address of the input and stores the result at slot 0.
+const PROXY_CONTRACT: [u8; 31] = [
+	0x60, 0x13, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x13, 0x60, 0x00, 0xf3,
+	0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x35, 0x5a, 0xf1,
+	0x60, 0x00, 0x55, 0x00,
+];
+/// Init code of a contract which creates a contract with the input as init code and stores its
+/// address at slot 0.
+const FACTORY_CONTRACT: [u8; 28] = [
+	0x60, 0x10, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x10, 0x60, 0x00, 0xf3,
+	0x36, 0x60, 0x00, 0x60, 0x00, 0x37, 0x36, 0x60, 0x00, 0x60, 0x00, 0xf0,
+	0x60, 0x00, 0x55, 0x00,
+];
+
+fn create(source: H160, init: &[u8], gas_price: U256) -> CreateInfo {
+	EvmRunner::create(source, init.to_vec(), U256::zero(), GAS_LIMIT, Some(gas_price), None, <Test as Trait>::config())
+		.expect("create never fails after the fee is withdrawn")
+		.0
+}
+
+fn deploy(source: H160, gas_price: U256) -> CreateInfo {
+	create(source, &STORE_CONTRACT, gas_price)
+}
+
+fn call(source: H160, contract: H160, input: Vec<u8>) -> CallInfo {
+	EvmRunner::call(source, contract, input, U256::zero(), GAS_LIMIT, Some(U256::zero()), None, <Test as Trait>::config())
+		.expect("call never fails after the fee is withdrawn")
+}
+
+fn store(source: H160, contract: H160, index: u8, value: u8) -> CallInfo {
+	let mut input = H256::from_low_u64_be(index as u64).as_bytes().to_vec();
+	input.extend_from_slice(H256::from_low_u64_be(value as u64).as_bytes());
+	call(source, contract, input)
+}
+
+fn reserved(address: &H160) -> u64 {
+	Balances::reserved_balance(&account_id(address))
+}
+
+#[test]
+fn storage_deposits_are_reserved_from_the_writers() {
+	new_test_ext(vec![(ALICE, 1_000), (BOB, 1_000)]).execute_with(|| {
+		let info = deploy(ALICE, U256::zero());
+		assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+		let contract = info.value;
+		assert_eq!(reserved(&ALICE), STORE_CODE_SIZE);
+		assert_eq!(EVM::contract_infos(&contract), Some(ContractInfo {
+			deployer: account_id(&ALICE),
+			code_size: STORE_CODE_SIZE as u32,
+		}));
+
+		assert!(store(ALICE, contract, 1, 1).exit_reason.is_succeed());
+		assert!(store(BOB, contract, 2, 1).exit_reason.is_succeed());
+		assert_eq!(reserved(&ALICE), STORE_CODE_SIZE + 10);
+		assert_eq!(reserved(&BOB), 10);
+		assert_eq!(EVM::storage_deposits(&contract, &account_id(&BOB)), 10);
+		assert_eq!(EVM::slot_deposits(&contract, H256::from_low_u64_be(1)), Some((account_id(&ALICE), 10)));
+
+		// overwriting a slot doesn't change the deposits
+		assert!(store(BOB, contract, 1, 2).exit_reason.is_succeed());
+		assert_eq!((reserved(&ALICE), reserved(&BOB)), (STORE_CODE_SIZE + 10, 10));
+	});
+}
+
+#[test]
+fn cleared_slots_are_refunded_to_their_writers() {
+	new_test_ext(vec![(ALICE, 1_000), (BOB, 1_000)]).execute_with(|| {
+		let contract = deploy(ALICE, U256::zero()).value;
+		assert!(store(ALICE, contract, 1, 1).exit_reason.is_succeed());
+		assert!(store(BOB, contract, 2, 1).exit_reason.is_succeed());
+
+		// bob clears the slot written by alice
+		assert!(store(BOB, contract, 1, 0).exit_reason.is_succeed());
+		assert_eq!(reserved(&ALICE), STORE_CODE_SIZE);
+		assert_eq!(reserved(&BOB), 10);
+		assert_eq!(EVM::slot_deposits(&contract, H256::from_low_u64_be(1)), None);
+
+		// the deposit params at the time of the write are refunded
+		assert_ok!(EVM::set_deposit_params(Origin::root(), Some(StorageDepositParams {
+			per_storage_slot: 100,
+			per_code_byte: 1,
+		})));
+		assert!(store(ALICE, contract, 2, 0).exit_reason.is_succeed());
+		assert_eq!(reserved(&BOB), 0);
+		assert_eq!(EVM::storage_deposits(&contract, &account_id(&BOB)), 0);
+	});
+}
+
+#[test]
+fn removed_contracts_refund_all_the_deposits() {
+	new_test_ext(vec![(ALICE, 1_000), (BOB, 1_000)]).execute_with(|| {
+		let contract = deploy(ALICE, U256::zero()).value;
+		assert!(store(ALICE, contract, 1, 1).exit_reason.is_succeed());
+		assert!(store(BOB, contract, 2, 1).exit_reason.is_succeed());
+
+		EVM::remove_account(&contract);
+		assert_eq!((reserved(&ALICE), reserved(&BOB)), (0, 0));
+		assert_eq!(EVM::contract_infos(&contract), None);
+		assert_eq!(EVM::slot_deposits(&contract, H256::from_low_u64_be(1)), None);
+	});
+}
+
+#[test]
+fn failed_storage_deposit_fails_the_execution() {
+	new_test_ext(vec![(ALICE, 300_000)]).execute_with(|| {
+		assert_ok!(EVM::set_deposit_params(Origin::root(), Some(StorageDepositParams {
+			per_storage_slot: 10,
+			per_code_byte: 100_000,
+		})));
+
+		let info = deploy(ALICE, U256::one());
+		assert_eq!(info.exit_reason, ExitReason::Error(ExitError::OutOfFund));
+		assert!(info.logs.is_empty());
+		assert_eq!(EVM::account_codes(&info.value), Vec::<u8>::new());
+		assert_eq!(EVM::contract_infos(&info.value), None);
+
+		// the fee is paid and the nonce is used
+		let alice = EVM::account_basic(&ALICE);
+		assert_eq!(alice.balance, U256::from(300_000) - info.used_gas);
+		assert_eq!(alice.nonce, U256::one());
+		assert_eq!(reserved(&ALICE), 0);
+	});
+}
+
+#[test]
+fn contract_infos_are_migrated_over_blocks() {
+	new_test_ext(vec![]).execute_with(|| {
+		let contracts = CONTRACT_INFO_MIGRATION_LIMIT + 1;
+		for i in 0..contracts {
+			let address = H160::from_low_u64_be(i as u64 + 1);
+			AccountCodes::insert(&address, vec![0u8; 4]);
+			AccountConnection::insert(&address, ALICE);
+		}
+		StorageVersion::put(Releases::V1_0_0);
+
+		<EVM as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+		assert_eq!(ContractInfos::<Test>::iter().count(), 0);
+
+		EVM::on_initialize(2);
+		assert_eq!(ContractInfos::<Test>::iter().count() as u32, CONTRACT_INFO_MIGRATION_LIMIT);
+		assert!(EVM::contract_info_migration().is_some());
+
+		EVM::on_initialize(3);
+		assert_eq!(ContractInfos::<Test>::iter().count() as u32, contracts);
+		assert_eq!(EVM::contract_info_migration(), None);
+		assert_eq!(EVM::contract_infos(&H160::from_low_u64_be(1)), Some(ContractInfo {
+			deployer: account_id(&ALICE),
+			code_size: 4,
+		}));
+	});
+}
+
+#[test]
+fn deployers_are_checked_for_transactions_and_contracts() {
+	new_test_ext(vec![(ALICE, 1_000), (BOB, 1_000)]).execute_with(|| {
+		assert_ok!(EVM::set_deployer_restricted(Origin::root(), true));
+		assert_ok!(EVM::set_deployer_allowed(Origin::root(), ALICE, true));
+
+		let info = deploy(BOB, U256::zero());
+		assert_eq!(info.exit_reason, ExitReason::Error(ExitError::DeployerNotAllowed));
+		assert_eq!(EVM::account_codes(&info.value), Vec::<u8>::new());
+		// the denied transaction can't be replayed
+		assert_eq!(EVM::account_basic(&BOB).nonce, U256::one());
+
+		let factory = create(ALICE, &FACTORY_CONTRACT, U256::zero());
+		assert!(factory.exit_reason.is_succeed());
+		assert!(call(BOB, factory.value, STORE_CONTRACT.to_vec()).exit_reason.is_succeed());
+		assert_eq!(EVM::account_storages(factory.value, H256::zero()), H256::zero());
+
+		assert_ok!(EVM::set_deployer_allowed(Origin::root(), factory.value, true));
+		assert!(call(BOB, factory.value, STORE_CONTRACT.to_vec()).exit_reason.is_succeed());
+		let created = H160::from(EVM::account_storages(factory.value, H256::zero()));
+		assert_eq!(EVM::account_codes(&created).len() as u64, STORE_CODE_SIZE);
+	});
+}
+
+#[test]
+fn deployers_are_recorded_for_all_creates() {
+	new_test_ext(vec![(ALICE, 1_000), (BOB, 1_000)]).execute_with(|| {
+		let contract = EvmRunner::create2(ALICE, STORE_CONTRACT.to_vec(), H256::repeat_byte(1), U256::zero(),
+			GAS_LIMIT, Some(U256::zero()), None, <Test as Trait>::config())
+			.expect("create2 never fails after the fee is withdrawn")
+			.0.value;
+		assert_eq!(EVM::account_connection(&contract), ALICE);
+
+		// the source of the transaction deploys the contracts created by a factory
+		let factory = create(ALICE, &FACTORY_CONTRACT, U256::zero()).value;
+		assert!(call(BOB, factory, STORE_CONTRACT.to_vec()).exit_reason.is_succeed());
+		let created = H160::from(EVM::account_storages(factory, H256::zero()));
+		assert_eq!(EVM::account_connection(&created), BOB);
+		assert_eq!(EVM::contract_infos(&created), Some(ContractInfo {
+			deployer: account_id(&BOB),
+			code_size: STORE_CODE_SIZE as u32,
+		}));
+		// the code of the created contract and the slot of the factory
+		assert_eq!(reserved(&BOB), STORE_CODE_SIZE + 10);
+	});
+}
+
+#[test]
+fn paused_contracts_cant_be_called_by_transactions_and_contracts() {
+	new_test_ext(vec![(ALICE, 1_000)]).execute_with(|| {
+		let contract = deploy(ALICE, U256::zero()).value;
+		let proxy = create(ALICE, &PROXY_CONTRACT, U256::zero()).value;
+		assert_ok!(EVM::set_contract_paused(Origin::root(), contract, true));
+
+		assert_eq!(store(ALICE, contract, 1, 1).exit_reason, ExitReason::Error(ExitError::ContractPaused));
+		assert!(call(ALICE, proxy, H256::from(contract).as_bytes().to_vec()).exit_reason.is_succeed());
+		assert_eq!(EVM::account_storages(proxy, H256::zero()), H256::zero());
+
+		assert_ok!(EVM::set_contract_paused(Origin::root(), contract, false));
+		assert!(call(ALICE, proxy, H256::from(contract).as_bytes().to_vec()).exit_reason.is_succeed());
+		assert_eq!(EVM::account_storages(proxy, H256::zero()), H256::from_low_u64_be(1));
+	});
+}
+
+#[test]
+fn callers_are_checked_for_transactions_and_contracts() {
+	new_test_ext(vec![(ALICE, 1_000), (BOB, 1_000)]).execute_with(|| {
+		let contract = deploy(ALICE, U256::zero()).value;
+		let proxy = create(ALICE, &PROXY_CONTRACT, U256::zero()).value;
+		assert_ok!(EVM::set_caller_restricted(Origin::root(), contract, true));
+		assert_ok!(EVM::set_caller_allowed(Origin::root(), contract, ALICE, true));
+
+		assert!(store(ALICE, contract, 1, 1).exit_reason.is_succeed());
+		assert_eq!(store(BOB, contract, 1, 1).exit_reason, ExitReason::Error(ExitError::CallerNotAllowed));
+
+		// the caller of an internal call is the calling contract
+		assert!(call(ALICE, proxy, H256::from(contract).as_bytes().to_vec()).exit_reason.is_succeed());
+		assert_eq!(EVM::account_storages(proxy, H256::zero()), H256::zero());
+
+		assert_ok!(EVM::set_caller_allowed(Origin::root(), contract, proxy, true));
+		assert!(call(BOB, proxy, H256::from(contract).as_bytes().to_vec()).exit_reason.is_succeed());
+		assert_eq!(EVM::account_storages(proxy, H256::zero()), H256::from_low_u64_be(1));
+	});
+}
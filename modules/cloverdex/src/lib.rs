@@ -7,44 +7,58 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use byteorder::{ByteOrder, LittleEndian};
+use codec::{Decode, Encode};
 
 use frame_support::{
   decl_error, decl_event, decl_module, decl_storage, ensure,
   debug,
-  dispatch::{PaysFee, WeighData},
   traits::{Get, Happened},
-  weights::constants::WEIGHT_PER_MICROS,
   Parameter,
-  weights::{ClassifyDispatch, DispatchClass, Pays, Weight},
+  weights::{DispatchClass, Pays, Weight},
 };
-use frame_support::storage::IterableStorageMap;
+use frame_support::storage::{IterableStorageDoubleMap, IterableStorageMap};
 
 use frame_system::{self as system, ensure_signed, ensure_root};
 
-use num_traits::FromPrimitive;
-
-use orml_traits::{MultiCurrency, MultiCurrencyExtended};
+use orml_traits::{LockIdentifier, MultiCurrency, MultiCurrencyExtended, MultiLockableCurrency};
 use orml_utilities::with_transaction_result;
-use primitives::{Balance, CurrencyId, Price, Rate, Ratio};
+use primitives::{Balance, CurrencyId, PoolId, Price, Rate, Ratio};
 
 use sp_runtime::{
   traits::{
-    AccountIdConversion, AtLeast32Bit, CheckedAdd, CheckedSub, MaybeSerializeDeserialize, Member,
+    AccountIdConversion, AtLeast32Bit, CheckedAdd, CheckedDiv, CheckedSub, MaybeSerializeDeserialize, Member,
     Saturating, UniqueSaturatedInto, Zero, One,
   },
-  DispatchError, DispatchResult, FixedPointNumber, FixedPointOperand, ModuleId,
+  DispatchError, DispatchResult, FixedPointNumber, FixedPointOperand, ModuleId, RuntimeDebug,
 };
 
 use sp_std::vec;
 use sp_std::vec::Vec;
 use sp_std::collections::btree_map;
-use clover_traits::IncentiveOps;
+use clover_traits::{IncentiveOps, TwapProvider};
 
-mod simple_graph;
+pub mod simple_graph;
 
+mod default_weight;
 mod mock;
 mod tests;
 
+pub trait WeightInfo {
+  fn set_exchange_rate() -> Weight;
+  fn set_max_route_hops() -> Weight;
+  fn add_liquidity() -> Weight;
+  fn withdraw_liquidity() -> Weight;
+  fn swap_currency(r: u32, ) -> Weight;
+  fn stake_pool_shares() -> Weight;
+  fn unstake_pool_shares() -> Weight;
+  fn withdraw_rewards() -> Weight;
+  fn lock_pool_shares() -> Weight;
+  fn unlock_pool_shares() -> Weight;
+  fn release_pool_share_locks() -> Weight;
+  fn get_supply_amount_needed(p: u32, ) -> Weight;
+  fn accumulate_prices(p: u32, ) -> Weight;
+}
+
 pub trait Trait: system::Trait {
   type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
@@ -52,8 +66,9 @@ pub trait Trait: system::Trait {
   /// pairs
   type Share: Parameter + Member + AtLeast32Bit + Default + Copy + MaybeSerializeDeserialize + FixedPointOperand;
 
-  /// Currency for transfer currencies
-  type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
+  /// Currency for transfer currencies, also mints and locks the lp tokens
+  type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>
+    + MultiLockableCurrency<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
 
   /// Trading fee rate
   type GetExchangeFee: Get<Rate>;
@@ -62,17 +77,99 @@ pub trait Trait: system::Trait {
   type ModuleId: Get<ModuleId>;
 
   /// incentive ops
-  type IncentiveOps:  IncentiveOps<Self::AccountId, CurrencyId, Self::Share, Balance>;
+  type IncentiveOps:  IncentiveOps<Self::AccountId, PoolId, CurrencyId, Self::Share, Balance>;
   /// Event handler which calls when add liquidity.
   type OnAddLiquidity: Happened<(Self::AccountId, CurrencyId, CurrencyId, Self::Share)>;
 
   /// Event handler which calls when remove liquidity.
   type OnRemoveLiquidity: Happened<(Self::AccountId, CurrencyId, CurrencyId, Self::Share)>;
+
+  /// Max number of blocks staked shares can be locked for.
+  type MaxStakeLockDuration: Get<Self::BlockNumber>;
+
+  /// Reward multiplier of staked shares locked for `MaxStakeLockDuration`,
+  /// shorter locks get a linearly smaller multiplier.
+  type MaxStakeBoost: Get<Rate>;
+
+  /// Part of the locked shares paid to the treasury when a lock is removed right after it was created,
+  /// it decreases linearly to zero at the end of the lock.
+  type EarlyUnlockPenalty: Get<Ratio>;
+
+  /// Max number of stake locks of an account in a pool.
+  type MaxStakeLocks: Get<u32>;
+
+  /// Account receiving the early unlock penalties.
+  type TreasuryAccount: Get<Self::AccountId>;
+
+  /// Min number of blocks the time weighted average prices of the pools are averaged over,
+  /// the averaging period is between one and two windows.
+  type TwapWindow: Get<Self::BlockNumber>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
 }
 
 pub type PairKey = u64;
 pub type PoolInfo = (Balance, Balance);
 
+/// Max number of candidate routes compared when looking for the best route.
+///
+/// The candidates are the routes with the least hops, they are all priced and the one with the best
+/// amount is used. A longer route is not considered if there are more shorter routes than this limit.
+pub const MAX_CANDIDATE_ROUTES: usize = 16;
+/// Upper bound of the governance set max route hops.
+pub const MAX_ROUTE_HOPS_LIMIT: u32 = 8;
+/// Lock of the lp tokens staked in the reward pool.
+pub const LP_STAKING_LOCK_ID: LockIdentifier = *b"clv/dexs";
+/// Holders of the deprecated shares migrated to lp tokens in a block.
+const SHARE_MIGRATION_LIMIT: u32 = 500;
+/// Accounts whose expired stake locks are released in a block, the rest are released in the next blocks.
+const STAKE_LOCK_EXPIRY_LIMIT: usize = 100;
+
+// storage layout releases of the module
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+  V1_0_0,
+  /// the currency graph is cached and the shares are lp token balances
+  V2_0_0,
+}
+
+impl Default for Releases {
+  fn default() -> Self {
+    Releases::V1_0_0
+  }
+}
+
+/// Cumulative price of the left currency of a pool in the right currency, sampled every block,
+/// and two observations of it for the time weighted average price.
+/// The cumulative price wraps around on overflow, only the difference of two observations is meaningful.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PriceAccumulator<BlockNumber> {
+  /// sum of the price of every block, modulo the range of the inner value of `Price`
+  pub cumulative: Price,
+  /// block the cumulative price was last updated at
+  pub updated_at: BlockNumber,
+  /// (cumulative, block) the average price is calculated from, between one and two windows old
+  pub older: (Price, BlockNumber),
+  /// (cumulative, block) which replaces `older` once it's a window old
+  pub newer: (Price, BlockNumber),
+}
+
+/// Staked shares locked until `unlock_at` for a reward multiplier.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct StakeLock<Share, BlockNumber> {
+  /// locked staked shares
+  pub amount: Share,
+  /// extra reward pool shares of the lock, `amount * (multiplier - 1)`
+  pub boost: Share,
+  /// reward multiplier of the locked shares
+  pub multiplier: Rate,
+  /// block the lock was created at
+  pub start: BlockNumber,
+  /// block the shares are unlocked at
+  pub unlock_at: BlockNumber,
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
 pub enum RouteType {
   TargetToSupply = 0,
@@ -82,6 +179,7 @@ pub enum RouteType {
 decl_event!(
   pub enum Event<T> where
     <T as system::Trait>::AccountId,
+    <T as system::Trait>::BlockNumber,
     <T as Trait>::Share,
     Balance = Balance,
     CurrencyId = CurrencyId,
@@ -102,7 +200,20 @@ decl_event!(
     /// Share unstaked
     /// account, currency left/right, amount unstaked, total staked amount
     UnStakeShare(AccountId, CurrencyId, CurrencyId, Share, Share),
-    RewardsClaimed(AccountId, CurrencyId, CurrencyId, Balance),
+    /// Rewards claimed, one event for each reward currency
+    /// account, currency left/right, reward currency, amount claimed
+    RewardsClaimed(AccountId, CurrencyId, CurrencyId, CurrencyId, Balance),
+    /// Max route hops updated. [max_route_hops]
+    MaxRouteHopsUpdated(u32),
+    /// Staked shares locked for a reward multiplier.
+    /// account, currency left/right, amount locked, multiplier, unlock block
+    StakeLocked(AccountId, CurrencyId, CurrencyId, Share, Rate, BlockNumber),
+    /// Stake lock removed before the unlock block.
+    /// account, currency left/right, amount unlocked, penalty paid to the treasury
+    StakeUnlocked(AccountId, CurrencyId, CurrencyId, Share, Share),
+    /// Stake lock expired and its boost was removed.
+    /// account, currency left/right, amount unlocked
+    StakeLockExpired(AccountId, CurrencyId, CurrencyId, Share),
   }
 );
 
@@ -125,6 +236,18 @@ decl_error! {
     InvalidRoute,
     InvalidExchangeRate,
     InvalidAmount,
+    /// The max route hops is zero or too large
+    InvalidMaxRouteHops,
+    /// The shares to unstake are locked
+    StakeLocked,
+    /// The lock duration is zero or too long
+    InvalidLockDuration,
+    /// The account has too many stake locks in the pool
+    TooManyStakeLocks,
+    /// The stake lock doesn't exist
+    StakeLockNotFound,
+    /// The shares are still migrated to lp tokens
+    SharesMigrating,
   }
 }
 
@@ -134,16 +257,41 @@ decl_storage! {
     /// CurrencyType -> (OtherCurrencyAmount, BaseCurrencyAmount)
     LiquidityPool get(fn liquidity_pool): map hasher(blake2_128_concat) PairKey => (Balance, Balance);
 
-    /// Total shares amount of liquidity pool specified by currency type
-    /// CurrencyType -> TotalSharesAmount
-    TotalShares get(fn total_shares): map hasher(blake2_128_concat) PairKey => T::Share;
+    /// Deprecated, shares are `CurrencyId::LpToken` balances now. Emptied by the share migration.
+    TotalShares: map hasher(blake2_128_concat) PairKey => T::Share;
 
-    /// Shares records indexed by currency type and account id
-    /// CurrencyType -> Owner -> ShareAmount
-    Shares get(fn shares): double_map hasher(blake2_128_concat) PairKey, hasher(twox_64_concat) T::AccountId => T::Share;
+    /// Deprecated, shares are `CurrencyId::LpToken` balances now. Emptied by the share migration.
+    Shares: double_map hasher(blake2_128_concat) PairKey, hasher(twox_64_concat) T::AccountId => T::Share;
 
     /// Exchange fee for governance
     ExchangeFee get(fn exchange_fee): Rate;
+
+    /// Max number of hops of a swap route, set by governance
+    MaxRouteHops get(fn max_route_hops): u32 = 6;
+
+    /// Locks of staked shares, indexed by currency type and account id
+    /// CurrencyType -> Owner -> Locks
+    StakeLocks get(fn stake_locks): double_map hasher(blake2_128_concat) PairKey, hasher(twox_64_concat) T::AccountId => Vec<StakeLock<T::Share, T::BlockNumber>>;
+
+    /// Accounts with stake locks expiring at a block, their boost is removed at the start of the block.
+    /// BlockNumber -> [(CurrencyType, Owner)]
+    StakeLockExpiries get(fn stake_lock_expiries): map hasher(twox_64_concat) T::BlockNumber => Vec<(PairKey, T::AccountId)>;
+
+    /// Adjacency of currencies which have a liquidity pool, used for route finding.
+    /// Updated only when a liquidity pool is created.
+    /// CurrencyType -> Currencies paired with it
+    CurrencyGraph get(fn currency_graph): btree_map::BTreeMap<CurrencyId, Vec<CurrencyId>>;
+
+    /// Price accumulators of the liquidity pools for time weighted average prices.
+    /// CurrencyType -> Accumulator
+    PriceAccumulators get(fn price_accumulators): map hasher(blake2_128_concat) PairKey => Option<PriceAccumulator<T::BlockNumber>>;
+
+    /// Whether the deprecated shares are still migrated to lp tokens, `SHARE_MIGRATION_LIMIT` holders a block.
+    /// Liquidity can't be added or withdrawn until the lp token issuance is complete.
+    SharesMigrating get(fn shares_migrating): bool;
+
+    /// Storage version of the module, new networks start with the latest version.
+    StorageVersion build(|_: &GenesisConfig| Releases::V2_0_0): Releases;
   }
 
   add_extra_genesis {
@@ -159,36 +307,6 @@ decl_storage! {
   }
 }
 
-// Transaction weight caculation for routed swap
-// transaction weight relates to the number of routes going through
-pub struct SwapCurrencyUsingRoute(u64, u64);
-
-impl WeighData<(&CurrencyId, &Balance, &CurrencyId,
-                &Balance, &vec::Vec<CurrencyId>)> for SwapCurrencyUsingRoute {
-  fn weigh_data(
-    &self,
-    (_, _, _, _, routes): (&CurrencyId, &Balance, &CurrencyId, &Balance, &vec::Vec<CurrencyId>))
-    -> Weight {
-    let len = routes.len() as u64;
-
-    (200 * WEIGHT_PER_MICROS)
-      .saturating_add(self.0.saturating_mul(len).saturating_mul(9).into())
-      .saturating_add(self.1.saturating_mul(len).saturating_mul(6).into()).into()
-  }
-}
-
-impl<T> PaysFee<T> for SwapCurrencyUsingRoute {
-  fn pays_fee(&self, _: T) -> Pays {
-    Pays::Yes
-  }
-}
-
-impl<T> ClassifyDispatch<T> for SwapCurrencyUsingRoute {
-  fn classify_dispatch(&self, _: T) -> DispatchClass {
-    Default::default()
-  }
-}
-
 decl_module! {
   pub struct Module<T: Trait> for enum Call where origin: T::Origin {
     type Error = Error<T>;
@@ -198,7 +316,47 @@ decl_module! {
     /// The DEX's module id, keep all assets in DEX.
     const ModuleId: ModuleId = T::ModuleId::get();
 
-    #[weight = (0, DispatchClass::Operational, Pays::No)]
+    /// Max number of blocks staked shares can be locked for.
+    const MaxStakeLockDuration: T::BlockNumber = T::MaxStakeLockDuration::get();
+
+    /// Reward multiplier of the longest stake lock.
+    const MaxStakeBoost: Rate = T::MaxStakeBoost::get();
+
+    /// Part of the locked shares paid to the treasury when unlocking right after locking.
+    const EarlyUnlockPenalty: Ratio = T::EarlyUnlockPenalty::get();
+
+    /// Max number of stake locks of an account in a pool.
+    const MaxStakeLocks: u32 = T::MaxStakeLocks::get();
+
+    /// Min number of blocks the time weighted average prices are averaged over.
+    const TwapWindow: T::BlockNumber = T::TwapWindow::get();
+
+    fn on_initialize(now: T::BlockNumber) -> Weight {
+      let pools = Self::accumulate_prices(now);
+      let expiries = Self::release_stake_locks_expiring_at(now);
+      let migration = if Self::shares_migrating() {
+        Self::migrate_shares_to_lp_tokens(SHARE_MIGRATION_LIMIT)
+      } else {
+        T::DbWeight::get().reads(1)
+      };
+      T::WeightInfo::accumulate_prices(pools)
+        .saturating_add(expiries)
+        .saturating_add(migration)
+    }
+
+    fn on_runtime_upgrade() -> Weight {
+      if StorageVersion::get() == Releases::V2_0_0 {
+        return T::DbWeight::get().reads(1);
+      }
+      // pools created before the currency graph existed
+      let pools = Self::rebuild_currency_graph();
+      // the shares are migrated in the next blocks
+      SharesMigrating::put(true);
+      StorageVersion::put(Releases::V2_0_0);
+      T::DbWeight::get().reads_writes(1 + pools as Weight, 3)
+    }
+
+    #[weight = (T::WeightInfo::set_exchange_rate(), DispatchClass::Operational, Pays::No)]
     fn set_exchange_rate(origin, fee: Rate) {
       with_transaction_result(|| {
         ensure_root(origin)?;
@@ -208,7 +366,15 @@ decl_module! {
       })?;
     }
 
-    #[weight = 206 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(10, 9)]
+    #[weight = (T::WeightInfo::set_max_route_hops(), DispatchClass::Operational, Pays::No)]
+    fn set_max_route_hops(origin, max_route_hops: u32) {
+      ensure_root(origin)?;
+      ensure!(max_route_hops > 0 && max_route_hops <= MAX_ROUTE_HOPS_LIMIT, Error::<T>::InvalidMaxRouteHops);
+      MaxRouteHops::put(max_route_hops);
+      Self::deposit_event(RawEvent::MaxRouteHopsUpdated(max_route_hops));
+    }
+
+    #[weight = T::WeightInfo::add_liquidity()]
     pub fn add_liquidity(
       origin,
       currency_id_first: CurrencyId,
@@ -217,6 +383,9 @@ decl_module! {
       #[compact] max_second_currency_amount: Balance,
     ) {
       ensure!(currency_id_first != currency_id_second, Error::<T>::InvalidCurrencyPair);
+      ensure!(!currency_id_first.is_lp_token() && !currency_id_second.is_lp_token(), Error::<T>::CurrencyIdNotAllowed);
+
+      ensure!(!Self::shares_migrating(), Error::<T>::SharesMigrating);
 
       with_transaction_result(|| {
         let who = ensure_signed(origin)?;
@@ -224,6 +393,7 @@ decl_module! {
 
         if !LiquidityPool::contains_key(pair_id) {
           LiquidityPool::insert(pair_id, (0, 0));
+          Self::add_currency_graph_edge(currency_id_first, currency_id_second);
         }
 
         //
@@ -276,13 +446,8 @@ decl_module! {
         T::Currency::transfer(currency_id_left, &who, &sub_account, left_currency_increment)?;
         T::Currency::transfer(currency_id_right, &who, &sub_account, right_currency_increment)?;
 
-        <TotalShares<T>>::try_mutate(pair_id, |total_shares| -> DispatchResult {
-          *total_shares = total_shares.checked_add(&share_increment).ok_or(Error::<T>::SharesOverflow)?;
-          Ok(())
-        })?;
-        <Shares<T>>::mutate(pair_id, &who, |share|
-          *share = share.checked_add(&share_increment).expect("share cannot overflow if `total_shares` doesn't; qed")
-        );
+        total_shares.checked_add(&share_increment).ok_or(Error::<T>::SharesOverflow)?;
+        T::Currency::deposit(Self::lp_token(pair_id), &who, share_increment.unique_saturated_into())?;
         LiquidityPool::mutate(pair_id, |(left, right)| {
           *left = left.saturating_add(left_currency_increment);
           *right = right.saturating_add(right_currency_increment);
@@ -301,12 +466,13 @@ decl_module! {
       })?;
     }
 
-    #[weight = 248 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(11, 10)]
+    #[weight = T::WeightInfo::withdraw_liquidity()]
     pub fn withdraw_liquidity(origin,
                               currency_id_first: CurrencyId,
                               currency_id_second: CurrencyId,
                               #[compact] remove_share: T::Share) {
       ensure!(currency_id_first != currency_id_second, Error::<T>::InvalidCurrencyPair);
+      ensure!(!Self::shares_migrating(), Error::<T>::SharesMigrating);
       with_transaction_result(|| {
         let who = ensure_signed(origin)?;
         if remove_share.is_zero() { return Ok(()); }
@@ -335,22 +501,15 @@ decl_module! {
         T::Currency::transfer(currency_id_left, &sub_account, &who, withdraw_other_currency_amount)?;
         T::Currency::transfer(currency_id_right, &sub_account, &who, withdraw_base_currency_amount)?;
 
-        <Shares<T>>::try_mutate(pair_id, &who, |share| -> DispatchResult{
-          let new_shares = share.checked_sub(&remove_share).ok_or(Error::<T>::ShareNotEnough)?;
-          // should check the free shares before removing liquidity
-          // remaining shares amount should >= locked shares amount
-          let locked_shares = T::IncentiveOps::get_account_shares(&who, &currency_id_first, &currency_id_right);
-          debug::info!("new_shares: {:?}, locked_shares: {:?}", new_shares, locked_shares);
-          if !locked_shares.is_zero() && locked_shares > new_shares {
-            return Err(Error::<T>::ShareNotEnough.into());
-          }
-
-          *share = new_shares;
-          Ok(())
-        })?;
-        <TotalShares<T>>::mutate(pair_id, |share|
-                                 *share = share.checked_sub(&remove_share).expect("total share cannot underflow if share doesn't; qed")
-        );
+        let new_shares = Self::shares(pair_id, &who).checked_sub(&remove_share).ok_or(Error::<T>::ShareNotEnough)?;
+        // should check the free shares before removing liquidity
+        // remaining shares amount should >= locked shares amount
+        let locked_shares = Self::get_staked_shares(&who, currency_id_left, currency_id_right);
+        debug::info!("new_shares: {:?}, locked_shares: {:?}", new_shares, locked_shares);
+        if !locked_shares.is_zero() && locked_shares > new_shares {
+          return Err(Error::<T>::ShareNotEnough.into());
+        }
+        T::Currency::withdraw(Self::lp_token(pair_id), &who, remove_share.unique_saturated_into())?;
         LiquidityPool::mutate(pair_id, |(other, base)| {
           *other = other.saturating_sub(withdraw_other_currency_amount);
           *base = base.saturating_sub(withdraw_base_currency_amount);
@@ -386,7 +545,7 @@ decl_module! {
     //}
 
     /// swap currencies using specified routes
-    #[weight = SwapCurrencyUsingRoute(T::DbWeight::get().reads(1), T::DbWeight::get().writes(1))]
+    #[weight = T::WeightInfo::swap_currency(route.len() as u32)]
     pub fn swap_currency(
       origin,
       supply_currency_id: CurrencyId,
@@ -407,7 +566,7 @@ decl_module! {
       })?;
     }
 
-    #[weight = 206 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(8, 5)]
+    #[weight = T::WeightInfo::stake_pool_shares()]
     pub fn stake_pool_shares(
       origin,
       currency_id_first: CurrencyId,
@@ -424,7 +583,7 @@ decl_module! {
       })?;
     }
 
-    #[weight = 206 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(8, 5)]
+    #[weight = T::WeightInfo::unstake_pool_shares()]
     pub fn unstake_pool_shares(
       origin,
       currency_id_first: CurrencyId,
@@ -441,15 +600,108 @@ decl_module! {
       })?;
     }
 
+    /// Lock staked shares for `duration` blocks, the locked shares earn rewards with a multiplier
+    /// growing with the duration up to `MaxStakeBoost`.
+    /// The shares can't be unstaked until the lock expires or is removed with `unlock_pool_shares`.
+    #[weight = T::WeightInfo::lock_pool_shares()]
+    pub fn lock_pool_shares(
+      origin,
+      currency_id_first: CurrencyId,
+      currency_id_second: CurrencyId,
+      amount: T::Share,
+      duration: T::BlockNumber,
+    ) {
+      let who = ensure_signed(origin)?;
+      ensure!(amount > T::Share::zero(), Error::<T>::InvalidAmount);
+      ensure!(!duration.is_zero() && duration <= T::MaxStakeLockDuration::get(), Error::<T>::InvalidLockDuration);
+      with_transaction_result(|| {
+        Self::release_expired_stake_locks(&who, currency_id_first, currency_id_second)?;
+
+        let pair_id = Self::get_pair_key(&currency_id_first, &currency_id_second);
+        let mut locks = Self::stake_locks(pair_id, &who);
+        ensure!((locks.len() as u32) < T::MaxStakeLocks::get(), Error::<T>::TooManyStakeLocks);
+        let unlocked_shares = Self::get_staked_shares(&who, currency_id_first, currency_id_second)
+          .saturating_sub(Self::locked_stake(&locks));
+        ensure!(amount <= unlocked_shares, Error::<T>::ShareNotEnough);
+
+        let multiplier = Self::stake_lock_multiplier(duration);
+        let boost = multiplier.saturating_sub(Rate::one()).saturating_mul_int(amount);
+        if !boost.is_zero() {
+          T::IncentiveOps::add_share(&who, &Self::incentive_pool_id(currency_id_first, currency_id_second)?, &boost)?;
+        }
+
+        let now = <system::Module<T>>::block_number();
+        let unlock_at = now.saturating_add(duration);
+        locks.push(StakeLock { amount, boost, multiplier, start: now, unlock_at });
+        <StakeLocks<T>>::insert(pair_id, &who, locks);
+        <StakeLockExpiries<T>>::append(unlock_at, (pair_id, who.clone()));
+
+        Self::deposit_event(RawEvent::StakeLocked(who, currency_id_first, currency_id_second, amount, multiplier, unlock_at));
+        Ok(())
+      })?;
+    }
+
+    /// Remove the stake lock at `lock_index`, the shares stay staked without the multiplier.
+    /// Removing a lock before it expires pays part of the locked shares to the treasury,
+    /// the part is `EarlyUnlockPenalty` scaled by the remaining time of the lock.
+    #[weight = T::WeightInfo::unlock_pool_shares()]
+    pub fn unlock_pool_shares(
+      origin,
+      currency_id_first: CurrencyId,
+      currency_id_second: CurrencyId,
+      lock_index: u32,
+    ) {
+      let who = ensure_signed(origin)?;
+      with_transaction_result(|| {
+        let pair_id = Self::get_pair_key(&currency_id_first, &currency_id_second);
+        let mut locks = Self::stake_locks(pair_id, &who);
+        ensure!((lock_index as usize) < locks.len(), Error::<T>::StakeLockNotFound);
+        let lock = locks.remove(lock_index as usize);
+
+        let penalty = Self::early_unlock_penalty(&lock, <system::Module<T>>::block_number());
+        let removed_shares = lock.boost.saturating_add(penalty);
+        if !removed_shares.is_zero() {
+          T::IncentiveOps::remove_share(&who, &Self::incentive_pool_id(currency_id_first, currency_id_second)?, &removed_shares)?;
+        }
+        Self::set_stake_locks(pair_id, &who, locks);
+
+        let staked_shares = Self::get_staked_shares(&who, currency_id_first, currency_id_second);
+        Self::update_staking_lock(pair_id, &who, staked_shares)?;
+        if !penalty.is_zero() {
+          T::Currency::transfer(Self::lp_token(pair_id), &who, &T::TreasuryAccount::get(), penalty.unique_saturated_into())?;
+        }
+
+        Self::deposit_event(RawEvent::StakeUnlocked(who, currency_id_first, currency_id_second, lock.amount, penalty));
+        Ok(())
+      })?;
+    }
+
+    /// Remove the boost of the expired stake locks of an account.
+    /// The boost is removed when the locks expire, anyone can call it for locks created before that.
+    #[weight = T::WeightInfo::release_pool_share_locks()]
+    pub fn release_pool_share_locks(
+      origin,
+      who: T::AccountId,
+      currency_id_first: CurrencyId,
+      currency_id_second: CurrencyId,
+    ) {
+      ensure_signed(origin)?;
+      with_transaction_result(|| {
+        Self::release_expired_stake_locks(&who, currency_id_first, currency_id_second)
+      })?;
+    }
+
     /// withdraw all rewards from reward pool
-    #[weight = 206 * WEIGHT_PER_MICROS + T::DbWeight::get().reads_writes(8, 5)]
+    #[weight = T::WeightInfo::withdraw_rewards()]
     pub fn withdraw_rewards(origin,
                             currency_id_first: CurrencyId,
                             currency_id_second: CurrencyId) {
       with_transaction_result(|| {
         let who = ensure_signed(origin)?;
-        let reward = T::IncentiveOps::claim_rewards(&who, &currency_id_first, &currency_id_second)?;
-        Self::deposit_event(RawEvent::RewardsClaimed(who, currency_id_first, currency_id_second, reward));
+        let rewards = T::IncentiveOps::claim_rewards(&who, &Self::incentive_pool_id(currency_id_first, currency_id_second)?)?;
+        for (reward_currency_id, reward) in rewards {
+          Self::deposit_event(RawEvent::RewardsClaimed(who.clone(), currency_id_first, currency_id_second, reward_currency_id, reward));
+        }
         Ok(())
       })?;
     }
@@ -541,10 +793,10 @@ impl<T: Trait> Module<T> {
                   }
 
                   Some(aid) => {
-                      if <Shares<T>>::contains_key(pk, aid.clone()) {
+                      let self_share = Self::shares(pk, &aid);
+                      if !self_share.is_zero() {
                         if let Some((id_left, id_right)) = Self::pair_key_to_ids(pk) {
                           let (other_currency_pool, base_currency_pool): (Balance, Balance) = info;
-                          let self_share = <Shares<T>>::get(pk, aid.clone());
                           let staked_share = Self::get_staked_shares(&aid, id_left, id_right);
                           let proportion = Ratio::checked_from_rational(self_share, Self::total_shares(pk)).unwrap_or_default();
                           let other_currency_amount = proportion.saturating_mul_int(other_currency_pool);
@@ -562,11 +814,13 @@ impl<T: Trait> Module<T> {
   /// currency ids are sorted by asc order.
   /// the pair key was a u64 number, whose first 32bits is smaller currency id.
   /// the second 32bits is the greater currency id.
+  /// lp tokens use an id which is never a valid token, so there is no pool of lp tokens.
   pub fn get_pair_key(first: &CurrencyId, second: &CurrencyId) -> PairKey {
+    let id = |c: &CurrencyId| c.token_id().unwrap_or(u32::max_value());
     let (left, right) = if first < second {
-       (*first as u32, *second as u32)
+       (id(first), id(second))
     } else {
-       (*second as u32, *first as u32)
+       (id(second), id(first))
     };
     let mut bytes = [0; 8];
     let numbers = [left, right];
@@ -582,7 +836,7 @@ impl<T: Trait> Module<T> {
     LittleEndian::write_u64_into(&numbers, &mut bytes);
     let left_id = LittleEndian::read_u32(&bytes[0 .. 4]);
     let right_id = LittleEndian::read_u32(&bytes[4 .. 8]);
-    match (FromPrimitive::from_u32(left_id), FromPrimitive::from_u32(right_id)) {
+    match (CurrencyId::from_token_id(left_id), CurrencyId::from_token_id(right_id)) {
       (Some(left), Some(right)) => Some((left, right)),
        _ => {
          debug::warn!("invalid pair ids: {:?}", pair_key);
@@ -591,6 +845,21 @@ impl<T: Trait> Module<T> {
     }
   }
 
+  /// the lp token of the liquidity pool
+  pub fn lp_token(pair_id: PairKey) -> CurrencyId {
+    CurrencyId::LpToken(pair_id)
+  }
+
+  /// shares of the liquidity pool owned by the account, including the staked shares
+  pub fn shares(pair_id: PairKey, who: &T::AccountId) -> T::Share {
+    T::Currency::total_balance(Self::lp_token(pair_id), who).unique_saturated_into()
+  }
+
+  /// total shares of the liquidity pool
+  pub fn total_shares(pair_id: PairKey) -> T::Share {
+    T::Currency::total_issuance(Self::lp_token(pair_id)).unique_saturated_into()
+  }
+
   /// generate the sub account id from two currencies pair.
   /// the sub account is generated from the pair key.
   pub fn sub_account_id(first: CurrencyId, right: CurrencyId) -> T::AccountId {
@@ -755,6 +1024,8 @@ impl<T: Trait> Module<T> {
     ensure!(*(route.last().unwrap()) == target_currency_id, Error::<T>::InvalidRoute);
     // route should not contains the from currency
     ensure!(!route.contains(&from_currency_id), Error::<T>::InvalidRoute);
+    // route should not be longer than the max route hops
+    ensure!(route.len() as u32 <= Self::max_route_hops(), Error::<T>::InvalidRoute);
 
     let fee_rate = Self::get_exchange_fee();
 
@@ -815,6 +1086,45 @@ impl<T: Trait> Module<T> {
     currency_data
   }
 
+  /// Rebuild the cached currency graph from the liquidity pools,
+  /// returns the number of liquidity pools.
+  pub fn rebuild_currency_graph() -> usize {
+    let (currency_pairs, _) = Self::get_existing_currency_pairs();
+    CurrencyGraph::put(Self::build_currency_map(&currency_pairs));
+    currency_pairs.len()
+  }
+
+  /// Add a newly created pool to the cached currency graph.
+  fn add_currency_graph_edge(currency_id_first: CurrencyId, currency_id_second: CurrencyId) {
+    CurrencyGraph::mutate(|graph| {
+      graph.entry(currency_id_first).or_insert_with(Vec::new).push(currency_id_second);
+      graph.entry(currency_id_second).or_insert_with(Vec::new).push(currency_id_first);
+    });
+  }
+
+  /// Find the best `MAX_CANDIDATE_ROUTES` routes with the least hops from start to target,
+  /// and the info of the pools used by them.
+  fn find_routes(start: &CurrencyId, target: &CurrencyId)
+    -> (vec::Vec<simple_graph::Routes<CurrencyId>>, btree_map::BTreeMap<PairKey, PoolInfo>) {
+    let currency_map = Self::currency_graph();
+    let routes = simple_graph::find_shortest_routes(
+      start, target,
+      |currency| currency_map.get(currency).cloned().unwrap_or_default(),
+      Self::max_route_hops() as usize, MAX_CANDIDATE_ROUTES);
+
+    let mut pool_info = btree_map::BTreeMap::new();
+    for route in &routes {
+      let mut cur_currency = start;
+      for currency in route {
+        let pair_key = Self::get_pair_key(cur_currency, currency);
+        pool_info.entry(pair_key).or_insert_with(|| Self::liquidity_pool(pair_key));
+        cur_currency = currency;
+      }
+    }
+
+    (routes, pool_info)
+  }
+
   // get the minimum amount of supply currency needed for the target currency
   // amount return 0 means cannot exchange
   // and the route info for the exchange
@@ -841,13 +1151,9 @@ impl<T: Trait> Module<T> {
       return (amount, vec![target_currency_id]);
     }
 
-    let (currency_pair, pool_info) = Self::get_existing_currency_pairs();
-    let currency_map = Self::build_currency_map(&currency_pair);
     // find a reverse route from target to supply
     // as we need to caculate the cost reversely
-    let routes = simple_graph::find_all_routes(
-      &target_currency_id, &supply_currency_id,
-      |currency| currency_map.get(&currency).unwrap_or(&vec![]).to_vec(), 6);
+    let (routes, pool_info) = Self::find_routes(&target_currency_id, &supply_currency_id);
 
     debug::info!("got {:?} routes for currency: {:?}, target: {:?}", routes.len(), supply_currency_id, target_currency_id);
 
@@ -859,6 +1165,76 @@ impl<T: Trait> Module<T> {
       .unwrap_or((Zero::zero(), vec![]))
   }
 
+  /// Weight of `swap_for_exact_target`, the route is searched among all the pools
+  /// and has at most `MaxRouteHops` hops.
+  pub fn swap_for_exact_target_weight() -> Weight {
+    let pools = Self::currency_graph().values().map(|paired| paired.len() as u32).sum::<u32>() / 2;
+    T::WeightInfo::get_supply_amount_needed(pools)
+      .saturating_add(T::WeightInfo::swap_currency(Self::max_route_hops()))
+      .saturating_add(T::DbWeight::get().reads(2))
+  }
+
+  /// Swap supply currency for exactly `target_amount` of target currency using the best route.
+  /// Fails if the supply amount exceeds the amount at current pool prices by more than `max_slippage`.
+  pub fn swap_for_exact_target(
+    who: &T::AccountId,
+    supply_currency_id: CurrencyId,
+    target_currency_id: CurrencyId,
+    target_amount: Balance,
+    max_slippage: Ratio,
+  ) -> sp_std::result::Result<Balance, DispatchError> {
+    let (supply_amount, route) = Self::get_supply_amount_needed(supply_currency_id, target_currency_id, target_amount);
+    ensure!(!supply_amount.is_zero(), Error::<T>::InvalidRoute);
+
+    // the route starts from the target currency, reverse it to swap from the supply currency
+    let route: Vec<CurrencyId> = route.into_iter().rev().skip(1)
+      .chain(sp_std::iter::once(target_currency_id))
+      .collect();
+
+    // supply amount at current pool prices, without fees and price impact
+    let mut spot_amount = target_amount;
+    let mut currency = target_currency_id;
+    for previous in route.iter().rev().skip(1).chain(sp_std::iter::once(&supply_currency_id)) {
+      let (previous_pool, pool) = Self::get_pool_info(*previous, currency)?;
+      spot_amount = Ratio::checked_from_rational(previous_pool, pool)
+        .and_then(|n| n.checked_mul_int(spot_amount))
+        .ok_or(Error::<T>::InvalidAmount)?;
+      currency = *previous;
+    }
+    let max_supply_amount = Ratio::one().saturating_add(max_slippage).saturating_mul_int(spot_amount);
+    ensure!(supply_amount <= max_supply_amount, Error::<T>::UnacceptablePrice);
+
+    Self::swap_currencies_using_route(who, supply_currency_id, supply_amount, target_currency_id, target_amount, route)
+  }
+
+  /// Swap exactly `supply_amount` of supply currency for target currency using the best route.
+  /// Fails if the target amount is less than the amount at current pool prices by more than `max_slippage`.
+  pub fn swap_exact_supply(
+    who: &T::AccountId,
+    supply_currency_id: CurrencyId,
+    target_currency_id: CurrencyId,
+    supply_amount: Balance,
+    max_slippage: Ratio,
+  ) -> sp_std::result::Result<Balance, DispatchError> {
+    let (target_amount, route) = Self::get_target_amount_available(supply_currency_id, target_currency_id, supply_amount);
+    ensure!(!target_amount.is_zero(), Error::<T>::InvalidRoute);
+
+    // target amount at current pool prices, without fees and price impact
+    let mut spot_amount = supply_amount;
+    let mut currency = supply_currency_id;
+    for next in route.iter() {
+      let (pool, next_pool) = Self::get_pool_info(currency, *next)?;
+      spot_amount = Ratio::checked_from_rational(next_pool, pool)
+        .and_then(|n| n.checked_mul_int(spot_amount))
+        .ok_or(Error::<T>::InvalidAmount)?;
+      currency = *next;
+    }
+    let min_target_amount = Ratio::one().saturating_sub(max_slippage).saturating_mul_int(spot_amount);
+    ensure!(target_amount >= min_target_amount, Error::<T>::UnacceptablePrice);
+
+    Self::swap_currencies_using_route(who, supply_currency_id, supply_amount, target_currency_id, target_amount, route)
+  }
+
   // get the maximum amount of target currency you can get for the supply currency
   // amount return 0 means cannot exchange
   pub fn get_target_amount_available(
@@ -884,12 +1260,8 @@ impl<T: Trait> Module<T> {
       return (amount, vec![target_currency_id]);
     }
 
-    let (currency_pair, pool_info) = Self::get_existing_currency_pairs();
-    let currency_map = Self::build_currency_map(&currency_pair);
     // find a route from supply to target
-    let routes = simple_graph::find_all_routes(
-      &supply_currency_id, &target_currency_id,
-      |currency| currency_map.get(&currency).unwrap_or(&vec![]).to_vec(), 6);
+    let (routes, pool_info) = Self::find_routes(&supply_currency_id, &target_currency_id);
 
     debug::info!("got {:?} routes for currency: {:?}, target: {:?}, routes: {:?}", routes.len(), supply_currency_id, target_currency_id, routes);
 
@@ -917,7 +1289,7 @@ impl<T: Trait> Module<T> {
       } else if route_type == RouteType::TargetToSupply {
         new_amount < best_amount
       } else {
-        best_amount > new_amount
+        new_amount > best_amount
       }
     };
 
@@ -952,15 +1324,17 @@ impl<T: Trait> Module<T> {
                                   currency_id_first: CurrencyId,
                                   currency_id_second: CurrencyId,
                                   amount: T::Share) -> Result<T::Share, DispatchError>{
-    let locked_shares = T::IncentiveOps::add_share(&who,
-                                                   &currency_id_first,
-                                                   &currency_id_second,
-                                                   &amount)?;
+    Self::release_expired_stake_locks(who, currency_id_first, currency_id_second)?;
+    T::IncentiveOps::add_share(&who,
+                               &Self::incentive_pool_id(currency_id_first, currency_id_second)?,
+                               &amount)?;
+    let locked_shares = Self::get_staked_shares(who, currency_id_first, currency_id_second);
     // should check we have enough shares to add to the pool
     let pair_id = Self::get_pair_key(&currency_id_first, &currency_id_second);
-    let total_shares = Self::shares(&pair_id, who);
+    let total_shares = Self::shares(pair_id, who);
     debug::info!("add stake: totals shares: {:?}, locked shares: {:?}", total_shares, locked_shares);
     ensure!(locked_shares <= total_shares, Error::<T>::ShareNotEnough);
+    Self::update_staking_lock(pair_id, who, locked_shares)?;
     Ok(locked_shares)
   }
 
@@ -968,18 +1342,252 @@ impl<T: Trait> Module<T> {
                                        currency_id_first: CurrencyId,
                                        currency_id_second: CurrencyId,
                                        amount: T::Share) -> Result<T::Share, DispatchError>{
+    Self::release_expired_stake_locks(who, currency_id_first, currency_id_second)?;
+    let pair_id = Self::get_pair_key(&currency_id_first, &currency_id_second);
+    // the shares in stake locks can't be unstaked
+    let locked_shares = Self::get_staked_shares(who, currency_id_first, currency_id_second)
+      .checked_sub(&amount)
+      .ok_or(Error::<T>::ShareNotEnough)?;
+    ensure!(locked_shares >= Self::locked_stake(&Self::stake_locks(pair_id, who)), Error::<T>::StakeLocked);
 
     T::IncentiveOps::remove_share(&who,
-                                  &currency_id_first,
-                                  &currency_id_second,
-                                  &amount)
+                                  &Self::incentive_pool_id(currency_id_first, currency_id_second)?,
+                                  &amount)?;
+    Self::update_staking_lock(pair_id, who, locked_shares)?;
+    Ok(locked_shares)
+  }
+
+  /// reward multiplier of shares locked for `duration` blocks
+  pub fn stake_lock_multiplier(duration: T::BlockNumber) -> Rate {
+    let duration: u128 = duration.unique_saturated_into();
+    let max_duration: u128 = T::MaxStakeLockDuration::get().unique_saturated_into();
+    let extra = T::MaxStakeBoost::get().saturating_sub(Rate::one());
+    Rate::checked_from_rational(sp_std::cmp::min(duration, max_duration), max_duration)
+      .map(|n| Rate::one().saturating_add(n.saturating_mul(extra)))
+      .unwrap_or_else(Rate::one)
   }
 
+  /// shares paid to the treasury if the lock is removed at `now`
+  fn early_unlock_penalty(lock: &StakeLock<T::Share, T::BlockNumber>, now: T::BlockNumber) -> T::Share {
+    if lock.unlock_at <= now {
+      return Zero::zero();
+    }
+    let remaining: u128 = (lock.unlock_at - now).unique_saturated_into();
+    let duration: u128 = lock.unlock_at.saturating_sub(lock.start).unique_saturated_into();
+    Ratio::checked_from_rational(remaining, duration)
+      .map(|n| sp_std::cmp::min(n, Ratio::one()).saturating_mul(T::EarlyUnlockPenalty::get()))
+      .unwrap_or_default()
+      .saturating_mul_int(lock.amount)
+  }
+
+  /// sum of the shares in the locks
+  fn locked_stake(locks: &[StakeLock<T::Share, T::BlockNumber>]) -> T::Share {
+    locks.iter().fold(Zero::zero(), |acc: T::Share, lock| acc.saturating_add(lock.amount))
+  }
+
+  fn set_stake_locks(pair_id: PairKey, who: &T::AccountId, locks: Vec<StakeLock<T::Share, T::BlockNumber>>) {
+    if locks.is_empty() {
+      <StakeLocks<T>>::remove(pair_id, who);
+    } else {
+      <StakeLocks<T>>::insert(pair_id, who, locks);
+    }
+  }
+
+  /// remove the expired stake locks and their boost from the reward pool
+  fn release_expired_stake_locks(who: &T::AccountId,
+                                 currency_id_first: CurrencyId,
+                                 currency_id_second: CurrencyId) -> DispatchResult {
+    let pair_id = Self::get_pair_key(&currency_id_first, &currency_id_second);
+    let now = <system::Module<T>>::block_number();
+    let (expired, active): (Vec<_>, Vec<_>) = Self::stake_locks(pair_id, who)
+      .into_iter()
+      .partition(|lock| lock.unlock_at <= now);
+    if expired.is_empty() {
+      return Ok(());
+    }
+
+    let boost = expired.iter().fold(T::Share::zero(), |acc, lock| acc.saturating_add(lock.boost));
+    if !boost.is_zero() {
+      T::IncentiveOps::remove_share(who, &Self::incentive_pool_id(currency_id_first, currency_id_second)?, &boost)?;
+    }
+    Self::set_stake_locks(pair_id, who, active);
+    for lock in expired {
+      Self::deposit_event(RawEvent::StakeLockExpired(who.clone(), currency_id_first, currency_id_second, lock.amount));
+    }
+    Ok(())
+  }
+
+  /// remove the boost of the stake locks expiring at `now`, so that they don't earn boosted rewards after it.
+  /// up to `STAKE_LOCK_EXPIRY_LIMIT` accounts are released, the others are postponed to the next block.
+  fn release_stake_locks_expiring_at(now: T::BlockNumber) -> Weight {
+    let mut expiries = <StakeLockExpiries<T>>::take(now);
+    if expiries.is_empty() {
+      return T::DbWeight::get().reads(1);
+    }
+    let postponed = expiries.split_off(sp_std::cmp::min(expiries.len(), STAKE_LOCK_EXPIRY_LIMIT));
+    if !postponed.is_empty() {
+      <StakeLockExpiries<T>>::mutate(now.saturating_add(One::one()), |next| next.extend(postponed));
+    }
+
+    let count = expiries.len() as Weight;
+    for (pair_id, who) in expiries {
+      if let Some((left, right)) = Self::pair_key_to_ids(pair_id) {
+        let result = with_transaction_result(|| Self::release_expired_stake_locks(&who, left, right));
+        if let Err(e) = result {
+          debug::warn!("failed to release the stake locks of {:?} in pair {:?}: {:?}", who, pair_id, e);
+        }
+      }
+    }
+    T::WeightInfo::release_pool_share_locks().saturating_mul(count)
+      .saturating_add(T::DbWeight::get().reads_writes(2, 2))
+  }
+
+  /// staked shares and stake locks of the account in every pool,
+  /// returns (currency_left, currency_right, staked shares, effective reward multiplier, locks)
+  pub fn get_stake_locks(who: &T::AccountId) -> Vec<(CurrencyId, CurrencyId, T::Share, Rate, Vec<StakeLock<T::Share, T::BlockNumber>>)> {
+    LiquidityPool::iter()
+      .filter_map(|(pair_id, _)| Self::pair_key_to_ids(pair_id).map(|(left, right)| (pair_id, left, right)))
+      .filter_map(|(pair_id, left, right)| {
+        let staked_shares = Self::get_staked_shares(who, left, right);
+        if staked_shares.is_zero() {
+          return None;
+        }
+        let reward_shares = Self::incentive_pool_id(left, right)
+          .map(|pool_id| T::IncentiveOps::get_account_shares(who, &pool_id))
+          .unwrap_or_else(|_| Zero::zero());
+        let multiplier = Rate::checked_from_rational(reward_shares, staked_shares).unwrap_or_else(Rate::one);
+        Some((left, right, staked_shares, multiplier, Self::stake_locks(pair_id, who)))
+      })
+      .collect()
+  }
+
+  /// lock the staked lp tokens, so they can't be transferred or burned
+  fn update_staking_lock(pair_id: PairKey, who: &T::AccountId, locked_shares: T::Share) -> DispatchResult {
+    if locked_shares.is_zero() {
+      T::Currency::remove_lock(LP_STAKING_LOCK_ID, Self::lp_token(pair_id), who)
+    } else {
+      T::Currency::set_lock(LP_STAKING_LOCK_ID, Self::lp_token(pair_id), who, locked_shares.unique_saturated_into())
+    }
+  }
+
+  /// mint lp tokens for the shares stored before lp tokens existed of up to `limit` holders,
+  /// and lock the shares already staked in the reward pool.
+  /// the migration ends when all holders are migrated.
+  fn migrate_shares_to_lp_tokens(limit: u32) -> Weight {
+    let mut migrated: Weight = 0;
+    for (pair_id, who, share) in <Shares<T>>::drain().take(limit as usize) {
+      migrated += 1;
+      if share.is_zero() {
+        continue;
+      }
+      if let Err(e) = T::Currency::deposit(Self::lp_token(pair_id), &who, share.unique_saturated_into()) {
+        debug::warn!("failed to migrate shares of pair {:?}: {:?}", pair_id, e);
+        continue;
+      }
+      if let Some((left, right)) = Self::pair_key_to_ids(pair_id) {
+        let staked_shares = Self::get_staked_shares(&who, left, right);
+        if !staked_shares.is_zero() {
+          let _ = Self::update_staking_lock(pair_id, &who, staked_shares);
+        }
+      }
+    }
+
+    let mut pools: Weight = 0;
+    if migrated < limit as Weight {
+      pools = <TotalShares<T>>::drain().count() as Weight;
+      SharesMigrating::kill();
+    }
+    T::DbWeight::get().reads_writes(
+      migrated.saturating_mul(6).saturating_add(pools).saturating_add(2),
+      migrated.saturating_mul(4).saturating_add(pools).saturating_add(1),
+    )
+  }
+
+  /// staked shares of the account, which are its reward pool shares without the boost of stake locks
   pub fn get_staked_shares(who: &T::AccountId,
                            currency_id_first: CurrencyId,
                            currency_id_second: CurrencyId) -> T::Share {
-    T::IncentiveOps::get_account_shares(who,
-                                        &currency_id_first,
-                                        &currency_id_second)
+    let pair_id = Self::get_pair_key(&currency_id_first, &currency_id_second);
+    let boost = Self::stake_locks(pair_id, who)
+      .iter()
+      .fold(T::Share::zero(), |acc, lock| acc.saturating_add(lock.boost));
+    Self::incentive_pool_id(currency_id_first, currency_id_second)
+      .map(|pool_id| T::IncentiveOps::get_account_shares(who, &pool_id))
+      .unwrap_or_else(|_| Zero::zero())
+      .saturating_sub(boost)
+  }
+
+  /// sum of the boost of the stake locks in the pool, the reward pool shares which aren't staked lp tokens
+  pub fn get_total_stake_boost(currency_id_first: CurrencyId, currency_id_second: CurrencyId) -> T::Share {
+    let pair_id = Self::get_pair_key(&currency_id_first, &currency_id_second);
+    <StakeLocks<T>>::iter_prefix_values(pair_id)
+      .flatten()
+      .fold(T::Share::zero(), |acc, lock| acc.saturating_add(lock.boost))
+  }
+
+  /// add the prices of the blocks since the last update to the price accumulators of the pools,
+  /// returns the number of pools
+  pub fn accumulate_prices(now: T::BlockNumber) -> u32 {
+    let window = T::TwapWindow::get();
+    let mut count: u32 = 0;
+    for (pair_id, (balance_left, balance_right)) in LiquidityPool::iter() {
+      count += 1;
+      let price = match Price::checked_from_rational(balance_right, balance_left) {
+        Some(price) if !price.is_zero() => price,
+        // an empty pool has no price, the average starts over once it has liquidity again
+        _ => {
+          PriceAccumulators::<T>::remove(pair_id);
+          continue;
+        },
+      };
+
+      PriceAccumulators::<T>::mutate(pair_id, |maybe_accumulator| {
+        let accumulator = maybe_accumulator.get_or_insert_with(|| PriceAccumulator {
+          cumulative: Zero::zero(),
+          updated_at: now,
+          older: (Zero::zero(), now),
+          newer: (Zero::zero(), now),
+        });
+        let blocks: u128 = now.saturating_sub(accumulator.updated_at).unique_saturated_into();
+        // wrapping, the difference of two observations is right as long as it fits
+        let added = price.into_inner().wrapping_mul(blocks);
+        accumulator.cumulative = Price::from_inner(accumulator.cumulative.into_inner().wrapping_add(added));
+        accumulator.updated_at = now;
+        if now.saturating_sub(accumulator.newer.1) >= window {
+          accumulator.older = accumulator.newer;
+          accumulator.newer = (accumulator.cumulative, now);
+        }
+      });
+    }
+    count
+  }
+
+  /// reward pool of the liquidity pool of the currency pair
+  pub fn incentive_pool_id(currency_id_first: CurrencyId, currency_id_second: CurrencyId) -> Result<PoolId, DispatchError> {
+    primitives::PairKey::try_from(currency_id_first, currency_id_second)
+      .map(PoolId::Dex)
+      .ok_or_else(|| Error::<T>::InvalidCurrencyPair.into())
+  }
+}
+
+impl<T: Trait> TwapProvider<CurrencyId, Price> for Module<T> {
+  fn get_twap(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
+    if base == quote {
+      return None;
+    }
+    let accumulator = Self::price_accumulators(Self::get_pair_key(&base, &quote))?;
+    let blocks = accumulator.updated_at.saturating_sub(accumulator.older.1);
+    if blocks.is_zero() || blocks < T::TwapWindow::get() {
+      return None;
+    }
+    let blocks: u128 = blocks.unique_saturated_into();
+    // average price of the left currency in the right currency
+    let twap = Price::from_inner(accumulator.cumulative.into_inner().wrapping_sub(accumulator.older.0.into_inner()))
+      .checked_div(&Price::saturating_from_integer(blocks))?;
+    if base < quote {
+      Some(twap)
+    } else {
+      Price::one().checked_div(&twap)
+    }
   }
 }
@@ -124,6 +124,12 @@ pub type Currencies = orml_currencies::Module<TestRuntime>;
 parameter_types! {
   pub GetExchangeFee: Rate = Rate::saturating_from_rational(1, 100);
   pub const CloverdexModuleId: ModuleId = ModuleId(*b"clv/dexm");
+  pub const MaxStakeLockDuration: BlockNumber = 1000;
+  pub MaxStakeBoost: Rate = Rate::saturating_from_integer(3);
+  pub EarlyUnlockPenalty: Ratio = Ratio::saturating_from_rational(1, 2);
+  pub const MaxStakeLocks: u32 = 3;
+  pub TreasuryAccount: AccountId = AccountId::from(TREASURY);
+  pub const TwapWindow: u64 = 10;
 }
 
 impl Trait for TestRuntime {
@@ -134,13 +140,21 @@ impl Trait for TestRuntime {
   type ModuleId = CloverdexModuleId;
   type OnAddLiquidity = ();
   type OnRemoveLiquidity = ();
+  type MaxStakeLockDuration = MaxStakeLockDuration;
+  type MaxStakeBoost = MaxStakeBoost;
+  type EarlyUnlockPenalty = EarlyUnlockPenalty;
+  type MaxStakeLocks = MaxStakeLocks;
+  type TreasuryAccount = TreasuryAccount;
+  type TwapWindow = TwapWindow;
   type IncentiveOps = IncentiveOpsHandler;
+  type WeightInfo = ();
 }
 
 pub type CloverdexModule = Module<TestRuntime>;
 
 pub const ALICE: [u8; 32] = [0u8; 32];
 pub const BOB: [u8; 32] = [1u8; 32];
+pub const TREASURY: [u8; 32] = [2u8; 32];
 pub const CLV: CurrencyId = CurrencyId::CLV;
 pub const CUSDT: CurrencyId = CurrencyId::CUSDT;
 pub const DOT: CurrencyId = CurrencyId::DOT;
@@ -152,12 +166,17 @@ thread_local! {
 
 pub struct IncentiveOpsHandler;
 
-impl IncentiveOps<AccountId, CurrencyId, Share, Balance> for IncentiveOpsHandler {
-  fn add_share(who: &AccountId, left: &CurrencyId, right: &CurrencyId, amount: &Share) -> Result<Share, DispatchError> {
+fn staked_key(pool: &PoolId) -> PairKey {
+  let (left, right) = pool.currency_pair().expect("cloverdex only stakes to dex pools");
+  CloverdexModule::get_pair_key(&left, &right)
+}
+
+impl IncentiveOps<AccountId, PoolId, CurrencyId, Share, Balance> for IncentiveOpsHandler {
+  fn add_share(who: &AccountId, pool: &PoolId, amount: &Share) -> Result<Share, DispatchError> {
     let t = SHARES_STAKED.with(|v| {
       let total;
       let mut old_map = v.borrow().clone();
-      let key = CloverdexModule::get_pair_key(left, right);
+      let key = staked_key(pool);
       if let Some(before) = old_map.get_mut(&(who.clone(), key)) {
         *before += amount;
         total = before.clone();
@@ -171,11 +190,11 @@ impl IncentiveOps<AccountId, CurrencyId, Share, Balance> for IncentiveOpsHandler
     Ok(t)
   }
 
-  fn remove_share(who: &AccountId, left: &CurrencyId, right: &CurrencyId, amount: &Share) -> Result<Share, DispatchError> {
+  fn remove_share(who: &AccountId, pool: &PoolId, amount: &Share) -> Result<Share, DispatchError> {
     let total = SHARES_STAKED.with(|v| {
       let total;
       let mut old_map = v.borrow().clone();
-      let key = CloverdexModule::get_pair_key(left, right);
+      let key = staked_key(pool);
       if let Some(before) = old_map.get_mut(&(who.clone(), key)) {
         *before -= amount;
         total = before.clone();
@@ -189,27 +208,27 @@ impl IncentiveOps<AccountId, CurrencyId, Share, Balance> for IncentiveOpsHandler
     Ok(total)
   }
 
-  fn get_account_shares(who: &AccountId, left: &CurrencyId, right: &CurrencyId) -> Share {
+  fn get_account_shares(who: &AccountId, pool: &PoolId) -> Share {
     SHARES_STAKED.with(|v| {
-      let key = CloverdexModule::get_pair_key(left, right);
+      let key = staked_key(pool);
       v.borrow().get(&(who.clone(), key)).unwrap_or(&0).clone()
     })
   }
 
   // todo implement it
-  fn get_accumlated_rewards(_who: &AccountId, _left: &CurrencyId, _right: &CurrencyId) -> Balance {
-    0
+  fn get_accumlated_rewards(_who: &AccountId, _pool: &PoolId) -> Vec<(CurrencyId, Balance)> {
+    vec![]
   }
 
-  fn get_account_info(_who: &AccountId, _left: &CurrencyId, _right: &CurrencyId) -> IncentivePoolAccountInfo<Share, Balance> {
-    IncentivePoolAccountInfo { shares: 0, accumlated_rewards: 0 }
+  fn get_account_info(_who: &AccountId, _pool: &PoolId) -> IncentivePoolAccountInfo<CurrencyId, Share, Balance> {
+    IncentivePoolAccountInfo { shares: 0, accumlated_rewards: vec![] }
   }
 
-  fn claim_rewards(_who: &AccountId, _left: &CurrencyId, _right: &CurrencyId) -> Result<Balance, DispatchError> {
-    Ok(Zero::zero())
+  fn claim_rewards(_who: &AccountId, _pool: &PoolId) -> Result<Vec<(CurrencyId, Balance)>, DispatchError> {
+    Ok(vec![])
   }
 
-  fn get_all_incentive_pools() -> Vec<(CurrencyId, CurrencyId, Share, Balance)> {
+  fn get_all_incentive_pools() -> Vec<(PoolId, Share, Vec<(CurrencyId, Balance)>)> {
     vec![]
   }
 }
@@ -6,7 +6,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use sp_std::{ clone::*, collections::btree_set, vec};
+use sp_std::{ clone::*, collections::{btree_map, btree_set, vec_deque}, vec};
 
 pub type Routes<N> = vec::Vec<N>;
 
@@ -62,6 +62,130 @@ where
   }).filter(|r| !r.is_empty()).flatten().collect()
 }
 
+/// Find the route with the least hops from start to target using breadth first search,
+/// nodes in `excluded_nodes` and edges in `excluded_edges` are not used.
+pub fn find_shortest_route<N, FN, IN>(
+  start: &N, target: &N,
+  successors: &mut FN,
+  max_depth: usize,
+  excluded_nodes: &btree_set::BTreeSet<N>,
+  excluded_edges: &btree_set::BTreeSet<(N, N)>) -> Option<Routes<N>>
+where
+  N: Eq + Ord + Clone,
+  FN: FnMut(&N) -> IN,
+  IN: IntoIterator<Item = N>,
+{
+  if start == target || max_depth == 0 {
+    return None;
+  }
+
+  let mut visited = btree_set::BTreeSet::new();
+  visited.insert(start.clone());
+  let mut parents = btree_map::BTreeMap::<N, N>::new();
+  let mut queue = vec_deque::VecDeque::new();
+  queue.push_back((start.clone(), 0));
+
+  while let Some((node, depth)) = queue.pop_front() {
+    if depth >= max_depth {
+      continue;
+    }
+
+    for next in successors(&node) {
+      if visited.contains(&next) || excluded_nodes.contains(&next) ||
+        excluded_edges.contains(&(node.clone(), next.clone())) {
+        continue;
+      }
+      visited.insert(next.clone());
+      parents.insert(next.clone(), node.clone());
+
+      if &next == target {
+        // walk back to the start node
+        let mut route = vec![next.clone()];
+        let mut current = next;
+        while let Some(parent) = parents.get(&current) {
+          if parent == start {
+            break;
+          }
+          route.push(parent.clone());
+          current = parent.clone();
+        }
+        route.reverse();
+        return Some(route);
+      }
+      queue.push_back((next, depth + 1));
+    }
+  }
+
+  None
+}
+
+/// Find at most `k` routes with the least hops from start to target, ordered by the number of hops.
+///
+/// Implements Yen's k shortest paths algorithm, it runs at most `k * max_depth` breadth first
+/// searches so the cost grows polynomially with the size of the graph, unlike `find_all_routes`.
+pub fn find_shortest_routes<N, FN, IN>(
+  start: &N, target: &N,
+  mut successors: FN,
+  max_depth: usize,
+  k: usize) -> vec::Vec<Routes<N>>
+where
+  N: Eq + Ord + Clone,
+  FN: FnMut(&N) -> IN,
+  IN: IntoIterator<Item = N>,
+{
+  let no_nodes = btree_set::BTreeSet::new();
+  let no_edges = btree_set::BTreeSet::new();
+  let first = match find_shortest_route(start, target, &mut successors, max_depth, &no_nodes, &no_edges) {
+    Some(route) if k > 0 => route,
+    _ => return vec![],
+  };
+
+  // paths include the start node while searching
+  let with_start = |route: Routes<N>| {
+    let mut path = vec![start.clone()];
+    path.extend(route);
+    path
+  };
+  let mut found = vec![with_start(first)];
+  let mut candidates: vec::Vec<vec::Vec<N>> = vec![];
+
+  while found.len() < k {
+    let last = found[found.len() - 1].clone();
+    for i in 0..last.len() - 1 {
+      let root = &last[..=i];
+      // don't reuse the next edge of any found path sharing the same root
+      let excluded_edges = found.iter()
+        .filter(|path| path.len() > i + 1 && &path[..=i] == root)
+        .map(|path| (path[i].clone(), path[i + 1].clone()))
+        .collect();
+      let excluded_nodes = root[..i].iter().cloned().collect();
+
+      if let Some(spur) = find_shortest_route(&last[i], target, &mut successors, max_depth - i,
+                                              &excluded_nodes, &excluded_edges) {
+        let mut path = root.to_vec();
+        path.extend(spur);
+        if !found.contains(&path) && !candidates.contains(&path) {
+          candidates.push(path);
+        }
+      }
+    }
+
+    // take the shortest candidate, the earliest found one if there are several
+    let shortest = candidates.iter().enumerate()
+      .min_by_key(|(_, path)| path.len())
+      .map(|(index, _)| index);
+    match shortest {
+      Some(index) => found.push(candidates.remove(index)),
+      None => break,
+    }
+  }
+
+  found.into_iter().map(|mut path| {
+    path.remove(0);
+    path
+  }).collect()
+}
+
 #[cfg(test)]
 mod tests {
   use sp_std::collections::btree_map::*;
@@ -159,4 +283,59 @@ mod tests {
     let routes = find_all_routes(&(0, 0), &(3, 2), |p| get_succssors(p, &edges), 1);
     assert_eq!(routes.len(), 0);
   }
+
+  #[test]
+  fn test_shortest_route() {
+    let mut edges = BTreeMap::new();
+    edges.insert((0, 0), vec![(0, 1), (1, 0)]);
+    edges.insert((0, 1), vec![(1, 1)]);
+    edges.insert((1, 0), vec![(1, 1)]);
+    edges.insert((1, 1), vec![(2, 1)]);
+    edges.insert((2, 1), vec![(2, 2)]);
+    edges.insert((2, 2), vec![(3, 2)]);
+
+    let none = btree_set::BTreeSet::new();
+    let no_edges = btree_set::BTreeSet::new();
+    let route = find_shortest_route(&(0, 0), &(2, 2), &mut |p: &Pos| get_succssors(p, &edges), 5, &none, &no_edges);
+    assert_eq!(format_routes(&route.unwrap()), "(0, 1),(1, 1),(2, 1),(2, 2),");
+
+    // exclude the first hop
+    let mut excluded_nodes = btree_set::BTreeSet::new();
+    excluded_nodes.insert((0, 1));
+    let route = find_shortest_route(&(0, 0), &(2, 2), &mut |p: &Pos| get_succssors(p, &edges), 5, &excluded_nodes, &no_edges);
+    assert_eq!(format_routes(&route.unwrap()), "(1, 0),(1, 1),(2, 1),(2, 2),");
+
+    // limit the max depth
+    let route = find_shortest_route(&(0, 0), &(2, 2), &mut |p: &Pos| get_succssors(p, &edges), 3, &none, &no_edges);
+    assert!(route.is_none());
+  }
+
+  #[test]
+  fn test_k_shortest_routes() {
+    let mut edges = BTreeMap::new();
+    edges.insert((0, 0), vec![(0, 1), (1, 0), (2, 2)]);
+    edges.insert((0, 1), vec![(1, 1)]);
+    edges.insert((1, 0), vec![(1, 1)]);
+    edges.insert((1, 1), vec![(2, 1)]);
+    edges.insert((2, 1), vec![(2, 2)]);
+    edges.insert((2, 2), vec![(3, 2)]);
+
+    let routes = find_shortest_routes(&(0, 0), &(3, 2), |p| get_succssors(p, &edges), 5, 3);
+    assert_eq!(routes.len(), 3);
+    assert_eq!(format_routes(&routes[0]), "(2, 2),(3, 2),");
+    assert_eq!(format_routes(&routes[1]), "(0, 1),(1, 1),(2, 1),(2, 2),(3, 2),");
+    assert_eq!(format_routes(&routes[2]), "(1, 0),(1, 1),(2, 1),(2, 2),(3, 2),");
+
+    // only the best k routes
+    let routes = find_shortest_routes(&(0, 0), &(3, 2), |p| get_succssors(p, &edges), 5, 1);
+    assert_eq!(routes.len(), 1);
+    assert_eq!(format_routes(&routes[0]), "(2, 2),(3, 2),");
+
+    // limit max depth
+    let routes = find_shortest_routes(&(0, 0), &(3, 2), |p| get_succssors(p, &edges), 4, 3);
+    assert_eq!(routes.len(), 1);
+
+    let routes = find_shortest_routes(&(0, 0), &(4, 4), |p| get_succssors(p, &edges), 5, 3);
+    assert_eq!(routes.len(), 0);
+  }
 }
@@ -1,9 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::assert_ok;
+use frame_support::{assert_noop, assert_ok, traits::{OnInitialize, OnRuntimeUpgrade}, StorageDoubleMap, StorageMap, StorageValue};
 use mock::{
-  CloverdexModule, ExtBuilder, Origin, CLV, ALICE, CUSDT, BOB, DOT, CETH,
+  CloverdexModule, Currencies, ExtBuilder, IncentiveOpsHandler, Origin, System, TestRuntime, CLV, ALICE, CUSDT, BOB, DOT,
+  CETH, TREASURY,
 };
 
 pub use primitives::{ AccountId, currency::*, };
@@ -46,6 +47,84 @@ fn test_compound_rote() {
   });
 }
 
+#[test]
+fn max_route_hops_limits_routes() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_ok!(BDM::add_liquidity(
+      Origin::signed(AccountId::from(ALICE)),
+      CLV,
+      CETH,
+      500000000000,
+      100000000000000000
+    ));
+    assert_ok!(BDM::add_liquidity(
+      Origin::signed(AccountId::from(BOB)),
+      CETH,
+      DOT,
+      80000000000,
+      4000000000000
+    ));
+
+    // currency graph is cached when pools are created
+    assert_eq!(BDM::currency_graph().get(&CETH), Some(&vec![CLV, DOT]));
+
+    let (_, route) = BDM::get_target_amount_available(CLV, DOT, 90000000);
+    assert_eq!(route, [CETH, DOT]);
+
+    assert_eq!(BDM::set_max_route_hops(Origin::root(), 0), Err(Error::<TestRuntime>::InvalidMaxRouteHops.into()));
+    assert_eq!(
+      BDM::set_max_route_hops(Origin::root(), MAX_ROUTE_HOPS_LIMIT + 1),
+      Err(Error::<TestRuntime>::InvalidMaxRouteHops.into())
+    );
+    assert_ok!(BDM::set_max_route_hops(Origin::root(), 1));
+    assert_eq!(BDM::max_route_hops(), 1);
+
+    assert_eq!(BDM::get_target_amount_available(CLV, DOT, 90000000), (0, vec![]));
+    assert_eq!(
+      BDM::swap_currency(Origin::signed(AccountId::from(ALICE)), CLV, 90000000, DOT, 0, vec![CETH, DOT]),
+      Err(Error::<TestRuntime>::InvalidRoute.into())
+    );
+  });
+}
+
+#[test]
+fn best_route_has_the_best_amount() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = Origin::signed(AccountId::from(ALICE));
+    assert_ok!(BDM::add_liquidity(alice.clone(), CLV, CUSDT, 1_000_000_000_000_000, 1_000_000_000_000_000));
+    assert_ok!(BDM::add_liquidity(alice.clone(), CUSDT, DOT, 1_000_000_000_000_000, 1_000_000_000_000_000));
+    assert_ok!(BDM::add_liquidity(alice.clone(), CLV, CETH, 1_000_000_000_000_000, 1_000_000_000_000_000));
+    // the route through CETH gets a thousandth of the DOT
+    assert_ok!(BDM::add_liquidity(alice, CETH, DOT, 1_000_000_000_000_000, 1_000_000_000_000));
+
+    let (target_amount, route) = BDM::get_target_amount_available(CLV, DOT, 1_000_000_000);
+    assert_eq!(route, [CUSDT, DOT]);
+    let (ceth_amount, _) = BDM::get_target_amount_available(CLV, CETH, 1_000_000_000);
+    let (ceth_route_amount, _) = BDM::get_target_amount_available(CETH, DOT, ceth_amount);
+    assert!(target_amount > ceth_route_amount);
+
+    let (_, route) = BDM::get_supply_amount_needed(CLV, DOT, 1_000_000);
+    assert_eq!(route, [CUSDT, DOT]);
+  });
+}
+
+#[test]
+fn best_route_can_be_longer_than_the_shortest() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = Origin::signed(AccountId::from(ALICE));
+    // the shortest route starts with a shallow pool
+    assert_ok!(BDM::add_liquidity(alice.clone(), CLV, CUSDT, 1_000_000_000_000, 1_000_000_000_000));
+    assert_ok!(BDM::add_liquidity(alice.clone(), CUSDT, DOT, 100_000_000_000_000_000, 100_000_000_000_000_000));
+    assert_ok!(BDM::add_liquidity(alice.clone(), CLV, CETH, 100_000_000_000_000_000, 100_000_000_000_000_000));
+    assert_ok!(BDM::add_liquidity(alice, CETH, CUSDT, 100_000_000_000_000_000, 100_000_000_000_000_000));
+
+    let (_, route) = BDM::get_target_amount_available(CLV, DOT, 100_000_000_000);
+    assert_eq!(route, [CETH, CUSDT, DOT]);
+    let (_, route) = BDM::get_supply_amount_needed(CLV, DOT, 100_000_000_000);
+    assert_eq!(route, [CETH, CUSDT, DOT]);
+  });
+}
+
 #[test]
 fn test_exchange_rate() {
   ExtBuilder::default().build().execute_with(|| {
@@ -257,6 +336,38 @@ fn make_sure_get_supply_amount_needed_can_affort_target() {
   });
 }
 
+#[test]
+fn swap_for_exact_target_within_slippage() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_ok!(BDM::add_liquidity(
+      Origin::signed(AccountId::from(ALICE)),
+      CLV,
+      CETH,
+      500000000000,
+      100000000000000000
+    ));
+    assert_ok!(BDM::add_liquidity(
+      Origin::signed(AccountId::from(ALICE)),
+      CUSDT,
+      CETH,
+      500000000000,
+      100000000000000000
+    ));
+
+    let bob = AccountId::from(BOB);
+    let target_amount = 1000000;
+    let clv_balance = <Currencies as MultiCurrency<_>>::free_balance(CLV, &bob);
+
+    // two hops with 1% exchange fee each exceed 1% slippage
+    assert_eq!(
+      BDM::swap_for_exact_target(&bob, CUSDT, CLV, target_amount, Ratio::saturating_from_rational(1, 100)),
+      Err(Error::<TestRuntime>::UnacceptablePrice.into())
+    );
+    assert_ok!(BDM::swap_for_exact_target(&bob, CUSDT, CLV, target_amount, Ratio::saturating_from_rational(5, 100)));
+    assert!(<Currencies as MultiCurrency<_>>::free_balance(CLV, &bob) >= clv_balance + target_amount);
+  });
+}
+
 #[test]
 fn test_supply_target_calculation() {
   let supply_amount = 5 * DOLLARS;
@@ -306,3 +417,256 @@ fn test_lp_staking() {
     assert_eq!(BDM::get_staked_shares(&alice, CLV, CETH), 0);
   });
 }
+
+#[test]
+fn lp_tokens_minted_transferred_and_locked() {
+  let alice = AccountId::from(ALICE);
+  let bob = AccountId::from(BOB);
+  ExtBuilder::default().build().execute_with(|| {
+    assert_eq!(
+      BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CurrencyId::LpToken(0), DOLLARS, DOLLARS),
+      Err(Error::<TestRuntime>::CurrencyIdNotAllowed.into())
+    );
+
+    assert_ok!(BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CETH, 1000 * DOLLARS, 1000 * DOLLARS));
+    let pair_id = BDM::get_pair_key(&CLV, &CETH);
+    let lp_token = BDM::lp_token(pair_id);
+    assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(lp_token, &alice), 1000 * DOLLARS);
+    assert_eq!(BDM::total_shares(pair_id), 1000 * DOLLARS);
+
+    // staked shares are locked
+    assert_ok!(BDM::stake_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 600 * DOLLARS));
+    assert!(<Currencies as MultiCurrency<_>>::transfer(lp_token, &alice, &bob, 500 * DOLLARS).is_err());
+    assert_ok!(<Currencies as MultiCurrency<_>>::transfer(lp_token, &alice, &bob, 400 * DOLLARS));
+    assert_eq!(BDM::shares(pair_id, &bob), 400 * DOLLARS);
+
+    // the new owner can withdraw the liquidity, which burns the lp tokens
+    assert_ok!(BDM::withdraw_liquidity(Origin::signed(bob.clone()), CLV, CETH, 400 * DOLLARS));
+    assert_eq!(BDM::shares(pair_id, &bob), 0);
+    assert_eq!(BDM::total_shares(pair_id), 600 * DOLLARS);
+    assert_eq!(BDM::get_pool_info(CLV, CETH), Ok((600 * DOLLARS, 600 * DOLLARS)));
+
+    assert_eq!(
+      BDM::withdraw_liquidity(Origin::signed(alice.clone()), CLV, CETH, 100 * DOLLARS),
+      Err(Error::<TestRuntime>::ShareNotEnough.into())
+    );
+    assert_ok!(BDM::unstake_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 600 * DOLLARS));
+    assert_ok!(<Currencies as MultiCurrency<_>>::transfer(lp_token, &alice, &bob, 600 * DOLLARS));
+  });
+}
+
+#[test]
+fn stake_locks_boost_rewards_and_pay_penalty() {
+  let alice = AccountId::from(ALICE);
+  let bob = AccountId::from(BOB);
+  let treasury = AccountId::from(TREASURY);
+  ExtBuilder::default().build().execute_with(|| {
+    System::set_block_number(1);
+    assert_ok!(BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CETH, 1000 * DOLLARS, 1000 * DOLLARS));
+    assert_ok!(BDM::stake_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 600 * DOLLARS));
+
+    assert_eq!(
+      BDM::lock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 300 * DOLLARS, 0),
+      Err(Error::<TestRuntime>::InvalidLockDuration.into())
+    );
+    assert_eq!(
+      BDM::lock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 300 * DOLLARS, 1001),
+      Err(Error::<TestRuntime>::InvalidLockDuration.into())
+    );
+    assert_eq!(
+      BDM::lock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 700 * DOLLARS, 500),
+      Err(Error::<TestRuntime>::ShareNotEnough.into())
+    );
+
+    // half of the max duration, multiplier is 1 + (3 - 1) / 2
+    assert_ok!(BDM::lock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 300 * DOLLARS, 500));
+    assert_eq!(BDM::stake_lock_multiplier(500), Rate::saturating_from_integer(2));
+    assert_eq!(BDM::get_staked_shares(&alice, CLV, CETH), 600 * DOLLARS);
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&alice, &CloverdexModule::incentive_pool_id(CLV, CETH).unwrap()), 900 * DOLLARS);
+
+    assert_eq!(
+      BDM::unstake_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 400 * DOLLARS),
+      Err(Error::<TestRuntime>::StakeLocked.into())
+    );
+    assert_ok!(BDM::unstake_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 300 * DOLLARS));
+    assert_eq!(BDM::get_staked_shares(&alice, CLV, CETH), 300 * DOLLARS);
+
+    // half of the lock remains, half of the penalty rate is paid
+    System::set_block_number(251);
+    assert_ok!(BDM::unlock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 0));
+    assert_eq!(BDM::get_staked_shares(&alice, CLV, CETH), 225 * DOLLARS);
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&alice, &CloverdexModule::incentive_pool_id(CLV, CETH).unwrap()), 225 * DOLLARS);
+    let lp_token = BDM::lp_token(BDM::get_pair_key(&CLV, &CETH));
+    assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(lp_token, &treasury), 75 * DOLLARS);
+    assert_eq!(BDM::shares(BDM::get_pair_key(&CLV, &CETH), &alice), 925 * DOLLARS);
+    assert_eq!(
+      BDM::unlock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 0),
+      Err(Error::<TestRuntime>::StakeLockNotFound.into())
+    );
+
+    // expired locks lose the boost, anyone can release them
+    assert_ok!(BDM::lock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 100 * DOLLARS, 10));
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&alice, &CloverdexModule::incentive_pool_id(CLV, CETH).unwrap()), 227 * DOLLARS);
+    let locks = BDM::get_stake_locks(&alice);
+    assert_eq!(locks.len(), 1);
+    assert_eq!(locks[0].2, 225 * DOLLARS);
+    assert_eq!(locks[0].4[0].unlock_at, 261);
+
+    System::set_block_number(261);
+    assert_ok!(BDM::release_pool_share_locks(Origin::signed(bob.clone()), alice.clone(), CLV, CETH));
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&alice, &CloverdexModule::incentive_pool_id(CLV, CETH).unwrap()), 225 * DOLLARS);
+    assert!(BDM::stake_locks(BDM::get_pair_key(&CLV, &CETH), &alice).is_empty());
+    assert_ok!(BDM::unstake_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 225 * DOLLARS));
+  });
+}
+
+#[test]
+fn stake_locks_lose_the_boost_at_expiry() {
+  let alice = AccountId::from(ALICE);
+  let bob = AccountId::from(BOB);
+  ExtBuilder::default().build().execute_with(|| {
+    System::set_block_number(1);
+    let pool_id = CloverdexModule::incentive_pool_id(CLV, CETH).unwrap();
+    assert_ok!(BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CETH, 1000 * DOLLARS, 1000 * DOLLARS));
+    assert_ok!(<Currencies as MultiCurrency<_>>::transfer(BDM::lp_token(BDM::get_pair_key(&CLV, &CETH)), &alice, &bob, 500 * DOLLARS));
+    assert_ok!(BDM::stake_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 300 * DOLLARS));
+    assert_ok!(BDM::stake_pool_shares(Origin::signed(bob.clone()), CLV, CETH, 300 * DOLLARS));
+    assert_ok!(BDM::lock_pool_shares(Origin::signed(alice.clone()), CLV, CETH, 100 * DOLLARS, 500));
+    assert_ok!(BDM::lock_pool_shares(Origin::signed(bob.clone()), CLV, CETH, 100 * DOLLARS, 500));
+    assert_eq!(BDM::stake_lock_expiries(501), vec![(BDM::get_pair_key(&CLV, &CETH), alice.clone()), (BDM::get_pair_key(&CLV, &CETH), bob.clone())]);
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&alice, &pool_id), 400 * DOLLARS);
+
+    BDM::on_initialize(500);
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&alice, &pool_id), 400 * DOLLARS);
+
+    // released without anyone calling `release_pool_share_locks`
+    System::set_block_number(501);
+    BDM::on_initialize(501);
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&alice, &pool_id), 300 * DOLLARS);
+    assert_eq!(IncentiveOpsHandler::get_account_shares(&bob, &pool_id), 300 * DOLLARS);
+    assert!(BDM::stake_locks(BDM::get_pair_key(&CLV, &CETH), &alice).is_empty());
+    assert!(BDM::stake_lock_expiries(501).is_empty());
+  });
+}
+
+#[test]
+fn twap_averages_pool_prices() {
+  let alice = AccountId::from(ALICE);
+  ExtBuilder::default().build().execute_with(|| {
+    System::set_block_number(1);
+    assert_ok!(BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CUSDT, 100 * DOLLARS, 200 * DOLLARS));
+    for n in 1..11 {
+      BDM::on_initialize(n);
+    }
+    // less than a window of prices
+    assert_eq!(BDM::get_twap(CLV, CUSDT), None);
+    BDM::on_initialize(11);
+    assert_eq!(BDM::get_twap(CLV, CUSDT), Some(Price::saturating_from_integer(2)));
+    assert_eq!(BDM::get_twap(CLV, CLV), None);
+    assert_eq!(BDM::get_twap(CLV, DOT), None);
+
+    // the price doubles, the older observation moves to the end of the first window
+    LiquidityPool::insert(BDM::get_pair_key(&CLV, &CUSDT), (100 * DOLLARS, 400 * DOLLARS));
+    for n in 12..21 {
+      BDM::on_initialize(n);
+    }
+    assert!(BDM::get_twap(CLV, CUSDT).unwrap() < Price::saturating_from_integer(4));
+    BDM::on_initialize(21);
+    assert_eq!(BDM::get_twap(CLV, CUSDT), Some(Price::saturating_from_integer(4)));
+    assert_eq!(BDM::get_twap(CUSDT, CLV), Some(Price::saturating_from_rational(1, 4)));
+  });
+}
+
+#[test]
+fn twap_survives_the_accumulator_wrapping_around() {
+  let alice = AccountId::from(ALICE);
+  ExtBuilder::default().build().execute_with(|| {
+    System::set_block_number(1);
+    assert_ok!(BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CUSDT, 100 * DOLLARS, 200 * DOLLARS));
+    BDM::on_initialize(1);
+    let pair_key = BDM::get_pair_key(&CLV, &CUSDT);
+    PriceAccumulators::<TestRuntime>::mutate(pair_key, |maybe_accumulator| {
+      let accumulator = maybe_accumulator.as_mut().unwrap();
+      let cumulative = Price::from_inner(u128::max_value() - Price::accuracy());
+      accumulator.cumulative = cumulative;
+      accumulator.older.0 = cumulative;
+      accumulator.newer.0 = cumulative;
+    });
+    for n in 2..12 {
+      BDM::on_initialize(n);
+    }
+    let accumulator = BDM::price_accumulators(pair_key).unwrap();
+    assert!(accumulator.cumulative < accumulator.older.0);
+    assert_eq!(BDM::get_twap(CLV, CUSDT), Some(Price::saturating_from_integer(2)));
+  });
+}
+
+#[test]
+fn runtime_upgrade_migrates_once() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_ok!(BDM::add_liquidity(Origin::signed(AccountId::from(ALICE)), CLV, CETH, 500000000000, 100000000000000000));
+    assert_ok!(BDM::add_liquidity(Origin::signed(AccountId::from(BOB)), CETH, DOT, 80000000000, 4000000000000));
+    let graph = BDM::currency_graph();
+
+    // new networks start with the latest storage version
+    assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+    CurrencyGraph::kill();
+    BDM::on_runtime_upgrade();
+    assert!(BDM::currency_graph().is_empty());
+
+    // networks upgraded from the first release rebuild the graph once
+    StorageVersion::put(Releases::V1_0_0);
+    BDM::on_runtime_upgrade();
+    assert_eq!(BDM::currency_graph(), graph);
+    assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+
+    CurrencyGraph::kill();
+    BDM::on_runtime_upgrade();
+    assert!(BDM::currency_graph().is_empty());
+  });
+}
+
+#[test]
+fn shares_are_migrated_over_blocks() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = AccountId::from(ALICE);
+    let bob = AccountId::from(BOB);
+    assert_ok!(BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CETH, 500000000000, 100000000000000000));
+    let pair_id = BDM::get_pair_key(&CLV, &CETH);
+    let lp_token = BDM::lp_token(pair_id);
+    let alice_lp = <Currencies as MultiCurrency<_>>::free_balance(lp_token, &alice);
+
+    // shares of a network upgraded from the first release
+    StorageVersion::put(Releases::V1_0_0);
+    Shares::<TestRuntime>::insert(pair_id, &alice, 100);
+    Shares::<TestRuntime>::insert(pair_id, &bob, 200);
+    TotalShares::<TestRuntime>::insert(pair_id, 300);
+
+    BDM::on_runtime_upgrade();
+    assert!(BDM::shares_migrating());
+    assert_noop!(
+      BDM::add_liquidity(Origin::signed(alice.clone()), CLV, CETH, 1000, 1000),
+      Error::<TestRuntime>::SharesMigrating
+    );
+    assert_noop!(
+      BDM::withdraw_liquidity(Origin::signed(alice.clone()), CLV, CETH, 100),
+      Error::<TestRuntime>::SharesMigrating
+    );
+
+    // a holder each block
+    BDM::migrate_shares_to_lp_tokens(1);
+    BDM::migrate_shares_to_lp_tokens(1);
+    assert!(BDM::shares_migrating());
+    assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(lp_token, &alice), alice_lp + 100);
+    assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(lp_token, &bob), 200);
+
+    BDM::migrate_shares_to_lp_tokens(1);
+    assert!(!BDM::shares_migrating());
+    assert!(!TotalShares::<TestRuntime>::contains_key(pair_id));
+
+    // nothing is migrated anymore
+    BDM::on_initialize(2);
+    assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(lp_token, &bob), 200);
+    assert_ok!(BDM::withdraw_liquidity(Origin::signed(bob), CLV, CETH, 100));
+  });
+}
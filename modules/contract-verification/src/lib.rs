@@ -0,0 +1,251 @@
+//! Clover Contract Verification Module
+//!
+//! ##Overview
+//! Registry of the compiler metadata and sources of the evm contracts.
+//! The deployer of a contract registers the compiler version, the compiler settings, the abi and the hash
+//! of the sources, a deposit of `DepositPerByte` is reserved for the stored metadata and refunded when
+//! it's unregistered. The deployer proves its evm address either by signing with the account it maps
+//! to, or with the `CallOrigin` of the evm module. Contracts without a deployer, e.g. the genesis
+//! contracts, are registered by `ForceOrigin` without a deposit.
+//! The keccak hash of the contract code is recorded at the registration, explorers verify the metadata
+//! by compiling the sources off chain and comparing the code with it.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+  decl_module, decl_error, decl_event, decl_storage, ensure,
+  dispatch::DispatchResult,
+  traits::{EnsureOrigin, Get, ReservableCurrency},
+  weights::Weight,
+};
+use frame_system::{self as system, ensure_signed};
+use clover_evm::{AddressMapping, EnsureAddressOrigin};
+use sp_core::{H160, H256};
+use sp_runtime::{traits::Saturating, RuntimeDebug};
+use sp_std::prelude::*;
+
+mod default_weight;
+mod mock;
+mod tests;
+
+pub type BalanceOf<T> = clover_evm::BalanceOf<T>;
+
+pub trait WeightInfo {
+  fn register(b: u32, ) -> Weight;
+  fn register_from_evm(b: u32, ) -> Weight;
+  fn force_register(b: u32, ) -> Weight;
+  fn unregister() -> Weight;
+  fn unregister_from_evm() -> Weight;
+}
+
+/// Verification metadata of a contract.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ContractMetadata<AccountId, Balance, BlockNumber> {
+  /// the deployer which registered the metadata
+  pub registrant: AccountId,
+  /// version of the compiler, e.g. `v0.6.12+commit.27d51765`
+  pub compiler_version: Vec<u8>,
+  /// json compiler settings
+  pub settings: Vec<u8>,
+  /// json abi
+  pub abi: Vec<u8>,
+  /// hash of the sources
+  pub source_hash: H256,
+  /// keccak hash of the contract code at the registration
+  pub code_hash: H256,
+  /// reserved from the registrant, zero for the metadata registered by `ForceOrigin`
+  pub deposit: Balance,
+  pub registered_at: BlockNumber,
+}
+
+pub trait Trait: clover_evm::Trait {
+  type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+  /// Deposit reserved for each byte of the metadata.
+  type DepositPerByte: Get<BalanceOf<Self>>;
+
+  /// Max bytes of the compiler version, settings and abi of a contract.
+  type MaxMetadataLength: Get<u32>;
+
+  /// Origin which registers the metadata of any contract without a deposit.
+  type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+  trait Store for Module<T: Trait> as ContractVerification {
+    /// Verification metadata of the contracts
+    pub Contracts get(fn contracts): map hasher(blake2_128_concat) H160 => Option<ContractMetadata<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+  }
+}
+
+decl_event!(
+  pub enum Event<T> where
+    <T as system::Trait>::AccountId,
+  {
+    /// Contract metadata registered. [who, contract, code_hash]
+    ContractRegistered(AccountId, H160, H256),
+    /// Contract metadata removed. [who, contract]
+    ContractUnregistered(AccountId, H160),
+  }
+);
+
+decl_error! {
+  /// Error for contract verification module.
+  pub enum Error for Module<T: Trait> {
+    /// the address has no code
+    NotContract,
+    /// the account is not the deployer of the contract
+    NotDeployer,
+    /// the metadata exceeds the max length
+    MetadataTooLong,
+    /// the contract has no metadata
+    NotRegistered,
+    /// not enough free balance to reserve the deposit
+    InsufficientDeposit,
+  }
+}
+
+decl_module! {
+  pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+    type Error = Error<T>;
+
+    fn deposit_event() = default;
+
+    const DepositPerByte: BalanceOf<T> = T::DepositPerByte::get();
+    const MaxMetadataLength: u32 = T::MaxMetadataLength::get();
+
+    /// Register the metadata of a contract deployed by the caller, replacing its previous metadata.
+    #[weight = <T as Trait>::WeightInfo::register((compiler_version.len() + settings.len() + abi.len()) as u32)]
+    pub fn register(origin, contract: H160, compiler_version: Vec<u8>, settings: Vec<u8>, abi: Vec<u8>, source_hash: H256) {
+      let who = ensure_signed(origin)?;
+      let deployer = clover_evm::Module::<T>::account_connection(&contract);
+      ensure!(<T as clover_evm::Trait>::AddressMapping::into_account_id(&deployer) == who, Error::<T>::NotDeployer);
+
+      Self::do_register(who, contract, compiler_version, settings, abi, source_hash, true)?;
+    }
+
+    /// Register the metadata of a contract, the origin is checked by the `CallOrigin` of the evm
+    /// module for the deployer, the deposit is reserved from the account of the deployer.
+    #[weight = <T as Trait>::WeightInfo::register_from_evm((compiler_version.len() + settings.len() + abi.len()) as u32)]
+    pub fn register_from_evm(origin, contract: H160, compiler_version: Vec<u8>, settings: Vec<u8>, abi: Vec<u8>, source_hash: H256) {
+      let deployer = clover_evm::Module::<T>::account_connection(&contract);
+      <T as clover_evm::Trait>::CallOrigin::ensure_address_origin(&deployer, origin)?;
+      let who = <T as clover_evm::Trait>::AddressMapping::into_account_id(&deployer);
+
+      Self::do_register(who, contract, compiler_version, settings, abi, source_hash, true)?;
+    }
+
+    /// Register the metadata of any contract for `registrant` without a deposit.
+    #[weight = <T as Trait>::WeightInfo::force_register((compiler_version.len() + settings.len() + abi.len()) as u32)]
+    pub fn force_register(
+      origin,
+      contract: H160,
+      registrant: T::AccountId,
+      compiler_version: Vec<u8>,
+      settings: Vec<u8>,
+      abi: Vec<u8>,
+      source_hash: H256,
+    ) {
+      T::ForceOrigin::ensure_origin(origin)?;
+
+      Self::do_register(registrant, contract, compiler_version, settings, abi, source_hash, false)?;
+    }
+
+    /// Remove the metadata of a contract registered by the caller and refund the deposit.
+    #[weight = <T as Trait>::WeightInfo::unregister()]
+    pub fn unregister(origin, contract: H160) {
+      let who = ensure_signed(origin)?;
+      Self::do_unregister(who, contract)?;
+    }
+
+    /// Remove the metadata of a contract registered by `register_from_evm` and refund the deposit.
+    #[weight = <T as Trait>::WeightInfo::unregister_from_evm()]
+    pub fn unregister_from_evm(origin, contract: H160) {
+      let deployer = clover_evm::Module::<T>::account_connection(&contract);
+      <T as clover_evm::Trait>::CallOrigin::ensure_address_origin(&deployer, origin)?;
+      let who = <T as clover_evm::Trait>::AddressMapping::into_account_id(&deployer);
+
+      Self::do_unregister(who, contract)?;
+    }
+  }
+}
+
+impl<T: Trait> Module<T> {
+  /// Whether the code of the contract still has the hash recorded at its registration. The metadata
+  /// itself is only verified off chain, by compiling the sources.
+  pub fn code_unchanged(contract: &H160) -> bool {
+    Self::contracts(contract).map_or(false, |metadata| {
+      H256::from(sp_io::hashing::keccak_256(&clover_evm::Module::<T>::account_codes(contract))) == metadata.code_hash
+    })
+  }
+
+  /// Store the metadata of a contract for `who`, replacing its previous metadata. The deposit of the
+  /// previous metadata is refunded to its registrant.
+  fn do_register(
+    who: T::AccountId,
+    contract: H160,
+    compiler_version: Vec<u8>,
+    settings: Vec<u8>,
+    abi: Vec<u8>,
+    source_hash: H256,
+    with_deposit: bool,
+  ) -> DispatchResult {
+    let length = compiler_version.len() + settings.len() + abi.len();
+    ensure!(length <= T::MaxMetadataLength::get() as usize, Error::<T>::MetadataTooLong);
+
+    let code = clover_evm::Module::<T>::account_codes(&contract);
+    ensure!(!code.is_empty(), Error::<T>::NotContract);
+
+    let deposit = if with_deposit {
+      T::DepositPerByte::get().saturating_mul((length as u32).into())
+    } else {
+      Default::default()
+    };
+    match Self::contracts(&contract) {
+      Some(metadata) if metadata.registrant == who => {
+        if deposit > metadata.deposit {
+          <T as clover_evm::Trait>::Currency::reserve(&who, deposit - metadata.deposit)
+            .map_err(|_| Error::<T>::InsufficientDeposit)?;
+        } else {
+          <T as clover_evm::Trait>::Currency::unreserve(&who, metadata.deposit - deposit);
+        }
+      },
+      previous => {
+        <T as clover_evm::Trait>::Currency::reserve(&who, deposit)
+          .map_err(|_| Error::<T>::InsufficientDeposit)?;
+        // another registrant, e.g. the deployer maps to another account since the registration
+        if let Some(metadata) = previous {
+          <T as clover_evm::Trait>::Currency::unreserve(&metadata.registrant, metadata.deposit);
+        }
+      },
+    }
+
+    let code_hash = H256::from(sp_io::hashing::keccak_256(&code));
+    Contracts::<T>::insert(&contract, ContractMetadata {
+      registrant: who.clone(),
+      compiler_version,
+      settings,
+      abi,
+      source_hash,
+      code_hash,
+      deposit,
+      registered_at: <system::Module<T>>::block_number(),
+    });
+    Self::deposit_event(RawEvent::ContractRegistered(who, contract, code_hash));
+    Ok(())
+  }
+
+  /// Remove the metadata of a contract registered by `who` and refund the deposit.
+  fn do_unregister(who: T::AccountId, contract: H160) -> DispatchResult {
+    let metadata = Self::contracts(&contract).ok_or(Error::<T>::NotRegistered)?;
+    ensure!(metadata.registrant == who, Error::<T>::NotDeployer);
+
+    <T as clover_evm::Trait>::Currency::unreserve(&who, metadata.deposit);
+    Contracts::<T>::remove(&contract);
+    Self::deposit_event(RawEvent::ContractUnregistered(who, contract));
+    Ok(())
+  }
+}
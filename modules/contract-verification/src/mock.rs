@@ -0,0 +1,200 @@
+//! Mocks for the contract verification module.
+
+#![cfg(test)]
+
+use super::*;
+use clover_evm::{EnsureAddressTruncated, Runner};
+use frame_support::{impl_outer_origin, parameter_types};
+use frame_system::EnsureRoot;
+use sp_core::{crypto::AccountId32, H160, H256, U256};
+use sp_runtime::{
+  testing::Header,
+  traits::{BlakeTwo256, IdentityLookup},
+  Perbill,
+};
+
+impl_outer_origin! {
+  pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Test;
+
+parameter_types! {
+  pub const BlockHashCount: u64 = 250;
+  pub const MaximumBlockWeight: Weight = 1024;
+  pub const MaximumBlockLength: u32 = 2 * 1024;
+  pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for Test {
+  type BaseCallFilter = ();
+  type Origin = Origin;
+  type Index = u64;
+  type BlockNumber = u64;
+  type Call = ();
+  type Hash = H256;
+  type Hashing = BlakeTwo256;
+  type AccountId = AccountId32;
+  type Lookup = IdentityLookup<Self::AccountId>;
+  type Header = Header;
+  type Event = ();
+  type BlockHashCount = BlockHashCount;
+  type MaximumBlockWeight = MaximumBlockWeight;
+  type DbWeight = ();
+  type BlockExecutionWeight = ();
+  type ExtrinsicBaseWeight = ();
+  type MaximumExtrinsicWeight = MaximumBlockWeight;
+  type MaximumBlockLength = MaximumBlockLength;
+  type AvailableBlockRatio = AvailableBlockRatio;
+  type Version = ();
+  type PalletInfo = ();
+  type AccountData = pallet_balances::AccountData<u64>;
+  type OnNewAccount = ();
+  type OnKilledAccount = ();
+  type SystemWeightInfo = ();
+}
+
+parameter_types! {
+  pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+  type Balance = u64;
+  type DustRemoval = ();
+  type Event = ();
+  type ExistentialDeposit = ExistentialDeposit;
+  type AccountStore = System;
+  type MaxLocks = ();
+  type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const MinimumPeriod: u64 = 1000;
+}
+
+impl pallet_timestamp::Trait for Test {
+  type Moment = u64;
+  type OnTimestampSet = ();
+  type MinimumPeriod = MinimumPeriod;
+  type WeightInfo = ();
+}
+
+/// Maps an address to the account with the address as its first 20 bytes followed by `0xff` bytes, so
+/// that the signer of `EnsureAddressTruncated` is another account.
+pub struct PrefixedAddressMapping;
+
+impl AddressMapping<AccountId32> for PrefixedAddressMapping {
+  fn into_account_id(address: &H160) -> AccountId32 {
+    let mut data = [0xffu8; 32];
+    data[0..20].copy_from_slice(&address[..]);
+    AccountId32::from(data)
+  }
+
+  fn to_evm_address(account: &AccountId32) -> Option<H160> {
+    Some(H160::from_slice(&AsRef::<[u8; 32]>::as_ref(account)[0..20]))
+  }
+}
+
+parameter_types! {
+  pub const ChainId: u64 = 42;
+  pub const DepositPerStorageSlot: u64 = 0;
+  pub const DepositPerCodeByte: u64 = 0;
+}
+
+impl clover_evm::Trait for Test {
+  type FeeCalculator = ();
+  type GasToWeight = ();
+  type CallOrigin = EnsureAddressTruncated;
+  type WithdrawOrigin = EnsureAddressTruncated;
+  type AddressMapping = PrefixedAddressMapping;
+  type MergeAccount = ();
+  type Currency = Balances;
+  type DefaultDepositPerStorageSlot = DepositPerStorageSlot;
+  type DefaultDepositPerCodeByte = DepositPerCodeByte;
+  type DepositOrigin = EnsureRoot<AccountId32>;
+  type PermissionOrigin = EnsureRoot<AccountId32>;
+  type Event = ();
+  type Precompiles = ();
+  type ChainId = ChainId;
+  type Runner = clover_evm::runner::stack::Runner<Self>;
+}
+
+parameter_types! {
+  pub const DepositPerByte: u64 = 2;
+  pub const MaxMetadataLength: u32 = 100;
+}
+
+impl Trait for Test {
+  type Event = ();
+  type DepositPerByte = DepositPerByte;
+  type MaxMetadataLength = MaxMetadataLength;
+  type ForceOrigin = EnsureRoot<AccountId32>;
+  type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type EVM = clover_evm::Module<Test>;
+pub type ContractVerification = Module<Test>;
+
+pub const ALICE: H160 = H160([1u8; 20]);
+pub const BOB: H160 = H160([2u8; 20]);
+/// A contract of the genesis, it has no deployer.
+pub const GENESIS_CONTRACT: H160 = H160([3u8; 20]);
+
+/// Init code of a contract which stores the second word of the input at the slot of the first word.
+pub const STORE_CONTRACT: [u8; 20] = [
+  0x60, 0x08, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x08, 0x60, 0x00, 0xf3,
+  0x60, 0x20, 0x35, 0x60, 0x00, 0x35, 0x55, 0x00,
+];
+
+/// The account mapped to the address, which signs `register`.
+pub fn account_id(address: &H160) -> AccountId32 {
+  PrefixedAddressMapping::into_account_id(address)
+}
+
+/// The account signing for the address with `EnsureAddressTruncated`.
+pub fn truncated_account_id(address: &H160) -> AccountId32 {
+  let mut data = [0u8; 32];
+  data[0..20].copy_from_slice(&address[..]);
+  AccountId32::from(data)
+}
+
+/// Deploy a contract from `deployer`.
+pub fn deploy_code(deployer: H160, init: Vec<u8>) -> H160 {
+  let (info, _) = <Test as clover_evm::Trait>::Runner::create(
+    deployer,
+    init,
+    U256::zero(),
+    1_000_000,
+    None,
+    None,
+    <Test as clover_evm::Trait>::config(),
+  ).expect("create never fails without fee");
+  assert!(info.exit_reason.is_succeed());
+  info.value
+}
+
+/// Deploy the store contract from `deployer`.
+pub fn deploy(deployer: H160) -> H160 {
+  deploy_code(deployer, STORE_CONTRACT.to_vec())
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+  let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+  pallet_balances::GenesisConfig::<Test> {
+    balances: vec![(account_id(&ALICE), 1_000), (account_id(&BOB), 1_000)],
+  }.assimilate_storage(&mut t).unwrap();
+  let mut accounts = std::collections::BTreeMap::new();
+  accounts.insert(GENESIS_CONTRACT, clover_evm::GenesisAccount {
+    nonce: U256::zero(),
+    balance: U256::zero(),
+    storage: Default::default(),
+    code: STORE_CONTRACT[12..].to_vec(),
+  });
+  clover_evm::GenesisConfig { accounts }.assimilate_storage::<Test>(&mut t).unwrap();
+  let mut ext = sp_io::TestExternalities::new(t);
+  ext.execute_with(|| System::set_block_number(1));
+  ext
+}
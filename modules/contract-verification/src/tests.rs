@@ -0,0 +1,178 @@
+//! Unit tests for the contract verification module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, error::BadOrigin};
+use mock::*;
+
+fn metadata(abi_length: usize) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+  (b"v0.6.12".to_vec(), b"{}".to_vec(), vec![b'x'; abi_length])
+}
+
+fn register(who: &H160, contract: H160, abi_length: usize) -> DispatchResult {
+  let (compiler_version, settings, abi) = metadata(abi_length);
+  ContractVerification::register(
+    Origin::signed(account_id(who)), contract, compiler_version, settings, abi, H256::repeat_byte(1),
+  )
+}
+
+fn register_from_evm(who: &H160, contract: H160, abi_length: usize) -> DispatchResult {
+  let (compiler_version, settings, abi) = metadata(abi_length);
+  ContractVerification::register_from_evm(
+    Origin::signed(truncated_account_id(who)), contract, compiler_version, settings, abi, H256::repeat_byte(1),
+  )
+}
+
+#[test]
+fn register_works() {
+  new_test_ext().execute_with(|| {
+    let contract = deploy(ALICE);
+    assert_ok!(register(&ALICE, contract, 11));
+
+    let metadata = ContractVerification::contracts(&contract).unwrap();
+    assert_eq!(metadata.registrant, account_id(&ALICE));
+    assert_eq!(metadata.code_hash, H256::from(sp_io::hashing::keccak_256(&EVM::account_codes(&contract))));
+    // 7 bytes of compiler version, 2 of settings and 11 of abi
+    assert_eq!(metadata.deposit, 40);
+    assert_eq!(Balances::reserved_balance(&account_id(&ALICE)), 40);
+    assert!(ContractVerification::code_unchanged(&contract));
+
+    // the deposit follows the length of the new metadata
+    assert_ok!(register(&ALICE, contract, 1));
+    assert_eq!(Balances::reserved_balance(&account_id(&ALICE)), 20);
+  });
+}
+
+#[test]
+fn register_checks_the_deployer_and_the_metadata() {
+  new_test_ext().execute_with(|| {
+    let contract = deploy(ALICE);
+    assert_noop!(register(&BOB, contract, 1), Error::<Test>::NotDeployer);
+    // the signer of the evm origin doesn't prove the deployer through the address mapping
+    assert_noop!(
+      ContractVerification::register(
+        Origin::signed(truncated_account_id(&ALICE)), contract, vec![], vec![], vec![], H256::zero(),
+      ),
+      Error::<Test>::NotDeployer
+    );
+    assert_noop!(register(&ALICE, contract, 100), Error::<Test>::MetadataTooLong);
+    assert_noop!(register(&ALICE, BOB, 1), Error::<Test>::NotDeployer);
+    assert_noop!(register(&ALICE, contract, 92), Error::<Test>::MetadataTooLong);
+  });
+}
+
+#[test]
+fn contracts_created_by_contracts_are_registered_by_the_source() {
+  new_test_ext().execute_with(|| {
+    // init code deploying the store contract from the constructor
+    let mut init = vec![0x60, STORE_CONTRACT.len() as u8, 0x60, 0x16, 0x60, 0x00, 0x39, 0x60, STORE_CONTRACT.len() as u8, 0x60, 0x00, 0x60, 0x00, 0xf0, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0xf3];
+    init.extend_from_slice(&STORE_CONTRACT);
+    let factory = deploy_code(BOB, init);
+    let created = H160::from(EVM::account_storages(factory, H256::zero()));
+    assert_eq!(EVM::account_codes(&created).len(), 8);
+
+    assert_noop!(register(&ALICE, created, 1), Error::<Test>::NotDeployer);
+    assert_ok!(register(&BOB, created, 1));
+  });
+}
+
+#[test]
+fn register_from_evm_works() {
+  new_test_ext().execute_with(|| {
+    let contract = deploy(ALICE);
+    assert_noop!(register_from_evm(&BOB, contract, 1), BadOrigin);
+
+    // the deposit is reserved from the account of the deployer
+    assert_ok!(register_from_evm(&ALICE, contract, 1));
+    assert_eq!(ContractVerification::contracts(&contract).unwrap().registrant, account_id(&ALICE));
+    assert_eq!(Balances::reserved_balance(&account_id(&ALICE)), 20);
+
+    assert_noop!(
+      ContractVerification::unregister_from_evm(Origin::signed(truncated_account_id(&BOB)), contract),
+      BadOrigin
+    );
+    assert_ok!(ContractVerification::unregister_from_evm(Origin::signed(truncated_account_id(&ALICE)), contract));
+    assert_eq!(ContractVerification::contracts(&contract), None);
+    assert_eq!(Balances::reserved_balance(&account_id(&ALICE)), 0);
+  });
+}
+
+#[test]
+fn force_register_works() {
+  new_test_ext().execute_with(|| {
+    // the genesis contracts have no deployer
+    let (compiler_version, settings, abi) = metadata(1);
+    assert_noop!(register(&ALICE, GENESIS_CONTRACT, 1), Error::<Test>::NotDeployer);
+    assert_noop!(
+      ContractVerification::force_register(
+        Origin::signed(account_id(&ALICE)), GENESIS_CONTRACT, account_id(&ALICE),
+        compiler_version.clone(), settings.clone(), abi.clone(), H256::zero(),
+      ),
+      BadOrigin
+    );
+    assert_noop!(
+      ContractVerification::force_register(
+        Origin::root(), BOB, account_id(&ALICE),
+        compiler_version.clone(), settings.clone(), abi.clone(), H256::zero(),
+      ),
+      Error::<Test>::NotContract
+    );
+
+    assert_ok!(ContractVerification::force_register(
+      Origin::root(), GENESIS_CONTRACT, account_id(&ALICE), compiler_version, settings, abi, H256::zero(),
+    ));
+    assert_eq!(ContractVerification::contracts(&GENESIS_CONTRACT).unwrap().deposit, 0);
+    assert_eq!(Balances::reserved_balance(&account_id(&ALICE)), 0);
+
+    assert_ok!(ContractVerification::unregister(Origin::signed(account_id(&ALICE)), GENESIS_CONTRACT));
+    assert_eq!(ContractVerification::contracts(&GENESIS_CONTRACT), None);
+  });
+}
+
+#[test]
+fn force_register_refunds_the_previous_registrant() {
+  new_test_ext().execute_with(|| {
+    let contract = deploy(ALICE);
+    assert_ok!(register(&ALICE, contract, 1));
+    let (compiler_version, settings, abi) = metadata(1);
+    assert_ok!(ContractVerification::force_register(
+      Origin::root(), contract, account_id(&BOB), compiler_version, settings, abi, H256::zero(),
+    ));
+    assert_eq!(Balances::reserved_balance(&account_id(&ALICE)), 0);
+    assert_eq!(ContractVerification::contracts(&contract).unwrap().registrant, account_id(&BOB));
+  });
+}
+
+#[test]
+fn unregister_works() {
+  new_test_ext().execute_with(|| {
+    let contract = deploy(ALICE);
+    assert_noop!(
+      ContractVerification::unregister(Origin::signed(account_id(&ALICE)), contract),
+      Error::<Test>::NotRegistered
+    );
+    assert_ok!(register(&ALICE, contract, 1));
+    assert_noop!(
+      ContractVerification::unregister(Origin::signed(account_id(&BOB)), contract),
+      Error::<Test>::NotDeployer
+    );
+
+    assert_ok!(ContractVerification::unregister(Origin::signed(account_id(&ALICE)), contract));
+    assert_eq!(ContractVerification::contracts(&contract), None);
+    assert_eq!(Balances::reserved_balance(&account_id(&ALICE)), 0);
+  });
+}
+
+#[test]
+fn code_unchanged_follows_the_code() {
+  new_test_ext().execute_with(|| {
+    let contract = deploy(ALICE);
+    assert!(!ContractVerification::code_unchanged(&contract));
+    assert_ok!(register(&ALICE, contract, 1));
+    assert!(ContractVerification::code_unchanged(&contract));
+
+    EVM::remove_account(&contract);
+    assert!(!ContractVerification::code_unchanged(&contract));
+  });
+}
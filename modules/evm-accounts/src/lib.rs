@@ -32,6 +32,8 @@ pub trait WeightInfo {
 }
 
 pub type EcdsaSignature = ecdsa::Signature;
+/// Prefix of all messages signed by ethereum keys for clover.
+pub const ETHEREUM_SIGN_PREFIX: &[u8] = b"clover evm:";
 /// Evm Address.
 pub type EvmAddress = sp_core::H160;
 
@@ -147,7 +149,7 @@ impl<T: Trait> Module<T> {
 	// Constructs the message that Ethereum RPC's `personal_sign` and `eth_sign`
 	// would sign.
 	pub fn ethereum_signable_message(what: &[u8], extra: &[u8]) -> Vec<u8> {
-		let prefix = b"clover evm:";
+		let prefix = ETHEREUM_SIGN_PREFIX;
 		let mut l = prefix.len() + what.len() + extra.len();
 		let mut rev = Vec::new();
 		while l > 0 {
@@ -172,6 +174,20 @@ impl<T: Trait> Module<T> {
 		Some(res)
 	}
 
+	/// The message an Ethereum wallet should `personal_sign` to authorize a
+	/// substrate extrinsic whose (possibly hashed) signing payload is `payload`.
+	pub fn extrinsic_signing_message(payload: &[u8]) -> Vec<u8> {
+		let mut v = ETHEREUM_SIGN_PREFIX.to_vec();
+		v.extend(to_ascii_hex(payload));
+		v
+	}
+
+	/// Recover the evm address that signed an extrinsic signing payload with
+	/// `personal_sign`, see `extrinsic_signing_message`.
+	pub fn recover_extrinsic_signer(s: &EcdsaSignature, payload: &[u8]) -> Option<EvmAddress> {
+		Self::eth_recover(s, &to_ascii_hex(payload), &[][..])
+	}
+
 	pub fn eth_public(secret: &secp256k1::SecretKey) -> secp256k1::PublicKey {
 		secp256k1::PublicKey::from_secret_key(secret)
 	}
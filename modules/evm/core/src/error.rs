@@ -135,6 +135,13 @@ pub enum ExitError {
 
 	/// Other normal errors.
 	Other(Cow<'static, str>),
+
+	/// The caller is not allowed to deploy contracts.
+	DeployerNotAllowed,
+	/// The called contract is paused.
+	ContractPaused,
+	/// The caller is not allowed to call the contract.
+	CallerNotAllowed,
 }
 
 impl From<ExitError> for ExitReason {
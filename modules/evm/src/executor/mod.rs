@@ -4,4 +4,4 @@
 //! also handles the call stacks in EVM.
 mod stack;
 
-pub use self::stack::{StackAccount, StackExecutor};
+pub use self::stack::{PolicyCheck, StackAccount, StackExecutor};
@@ -37,11 +37,23 @@ pub struct StackSubstate<'config> {
 	depth: Option<usize>,
 }
 
+/// Operation checked by the policy of an executor before it runs, for the transactions and the
+/// internal calls and creates.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PolicyCheck {
+	/// `caller` creates a contract.
+	Create { caller: H160 },
+	/// `caller` sends a message to the code at `address`, the caller of a delegate call is the
+	/// original sender.
+	Call { caller: H160, address: H160 },
+}
+
 /// Stack-based executor.
 pub struct StackExecutor<'backend, 'config, B> {
 	backend: &'backend B,
 	config: &'config Config,
 	precompile: fn(H160, &[u8], Option<usize>) -> Option<Result<(ExitSucceed, Vec<u8>, usize), ExitError>>,
+	policy: fn(PolicyCheck) -> Result<(), ExitError>,
 	substates: Vec<StackSubstate<'config>>,
 	/// internal calls by current transaction.
 	pub call_graph: Vec<InternalTransaction>,
@@ -55,6 +67,10 @@ fn no_precompile(
 	None
 }
 
+fn no_policy(_check: PolicyCheck) -> Result<(), ExitError> {
+	Ok(())
+}
+
 impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 	/// Create a new stack-based executor.
 	pub fn new(
@@ -76,6 +92,7 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 			backend,
 			config,
 			precompile,
+			policy: no_policy,
 			substates: vec![
 				StackSubstate {
 					gasometer: Gasometer::new(gas_limit, config),
@@ -90,6 +107,12 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 		}
 	}
 
+	/// Check the operations of the executor with the given policy.
+	pub fn with_policy(mut self, policy: fn(PolicyCheck) -> Result<(), ExitError>) -> Self {
+		self.policy = policy;
+		self
+	}
+
 	/// Create a substate executor from the current executor.
 	pub fn enter_substate(
 		&mut self,
@@ -159,21 +182,21 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 			.gasometer.gas()
 	}
 
-	/// Execute a `CREATE` transaction.
+	/// Execute a `CREATE` transaction, the output is the revert data of a reverted init code.
 	pub fn transact_create(
 		&mut self,
 		caller: H160,
 		value: U256,
 		init_code: Vec<u8>,
 		gas_limit: usize,
-	) -> ExitReason {
+	) -> (ExitReason, Vec<u8>) {
 		let current = self.substates.last_mut()
 			.expect("substate vec always have length greater than one; qed");
 
 		let transaction_cost = gasometer::create_transaction_cost(&init_code);
 		match current.gasometer.record_transaction(transaction_cost) {
 			Ok(()) => (),
-			Err(e) => return e.into(),
+			Err(e) => return (e.into(), Vec::new()),
 		}
 
 		match self.create_inner(
@@ -184,12 +207,12 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 			Some(gas_limit),
 			false,
 		) {
-			Capture::Exit((s, _, _)) => s,
+			Capture::Exit((s, _, v)) => (s, v),
 			Capture::Trap(_) => unreachable!(),
 		}
 	}
 
-	/// Execute a `CREATE2` transaction.
+	/// Execute a `CREATE2` transaction, the output is the revert data of a reverted init code.
 	pub fn transact_create2(
 		&mut self,
 		caller: H160,
@@ -197,14 +220,14 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 		init_code: Vec<u8>,
 		salt: H256,
 		gas_limit: usize,
-	) -> ExitReason {
+	) -> (ExitReason, Vec<u8>) {
 		let current = self.substates.last_mut()
 			.expect("substate vec always have length greater than one; qed");
 
 		let transaction_cost = gasometer::create_transaction_cost(&init_code);
 		match current.gasometer.record_transaction(transaction_cost) {
 			Ok(()) => (),
-			Err(e) => return e.into(),
+			Err(e) => return (e.into(), Vec::new()),
 		}
 		let code_hash = H256::from_slice(Keccak256::digest(&init_code).as_slice());
 
@@ -216,7 +239,7 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 			Some(gas_limit),
 			false,
 		) {
-			Capture::Exit((s, _, _)) => s,
+			Capture::Exit((s, _, v)) => (s, v),
 			Capture::Trap(_) => unreachable!(),
 		}
 	}
@@ -450,6 +473,12 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 			return Capture::Exit((ExitError::OutOfFund.into(), None, Vec::new()))
 		}
 
+		if let Err(e) = (self.policy)(PolicyCheck::Create { caller }) {
+			// the nonce is used, so that a denied create transaction can't be replayed
+			self.account_mut(caller).basic.nonce += U256::one();
+			return Capture::Exit((e.into(), None, Vec::new()))
+		}
+
 		let mut after_gas = self.substates.last()
 			.expect("substate vec always have length greater than one; qed")
 			.gasometer.gas();
@@ -606,6 +635,8 @@ impl<'backend, 'config, B: Backend> StackExecutor<'backend, 'config, B> {
 			gas - gas / 64
 		}
 
+		try_or_fail!((self.policy)(PolicyCheck::Call { caller: context.caller, address: code_address }));
+
 		let mut after_gas = self.substates.last()
 			.expect("substate vec always have length greater than one; qed")
 			.gasometer
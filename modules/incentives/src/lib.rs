@@ -6,55 +6,155 @@
 
 use codec::{Decode, Encode};
 use frame_support::{
-  decl_module, decl_error, decl_storage, debug,
+  decl_module, decl_error, decl_event, decl_storage, debug, ensure,
+  traits::{EnsureOrigin, Get},
+  weights::Weight,
+  IterableStorageDoubleMap, IterableStorageMap,
 };
+use frame_system::{self as system};
 use sp_runtime::{
   DispatchError,
   RuntimeDebug,
   traits::{
     SaturatedConversion,
+    Saturating,
     Zero,
   }
 };
 use sp_std::prelude::*;
 use sp_std::vec;
-use primitives::{Balance, CurrencyId, Share, };
+use orml_traits::Happened;
+use primitives::{Amount, Balance, CurrencyId, Share, };
 use clover_traits::{RewardPoolOps, IncentiveOps, IncentivePoolAccountInfo, };
 use reward_pool::traits::RewardHandler;
 
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
-pub struct PairKey {
-  left: CurrencyId,
-  right: CurrencyId,
+pub use primitives::{PairKey, PoolId};
+
+mod default_weight;
+mod mock;
+mod tests;
+
+/// An incentive program of a reward pool in a reward currency.
+/// Pays `reward_per_block` for every block in (start, end],
+/// the reward is halved every `halving_period` blocks if it's set.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct IncentiveProgram<BlockNumber> {
+  /// reward per block of the first halving period
+  pub reward_per_block: Balance,
+  /// block the program starts at
+  pub start: BlockNumber,
+  /// block the program ends at, None means the program never ends
+  pub end: Option<BlockNumber>,
+  /// number of blocks between reward halvings, None means no decay
+  pub halving_period: Option<BlockNumber>,
 }
 
-impl PairKey {
-  fn try_from(first: CurrencyId, second: CurrencyId) -> Option<Self> {
-    if first == second {
-      None
-    } else if first < second {
-      Some(PairKey { left: first, right: second, })
-    } else {
-      Some(PairKey { left: second, right: first, })
+impl<BlockNumber: SaturatedConversion + Copy> IncentiveProgram<BlockNumber> {
+  // halving more than this many times always gives zero reward
+  const MAX_HALVINGS: u64 = 128;
+
+  /// Total reward of blocks in (from, to].
+  pub fn reward_between(&self, from: BlockNumber, to: BlockNumber) -> Balance {
+    self.reward_in(from.saturated_into(), to.saturated_into())
+  }
+
+  /// Reward of the block `now`.
+  pub fn reward_at(&self, now: BlockNumber) -> Balance {
+    let now: u64 = now.saturated_into();
+    self.reward_in(now.saturating_sub(1), now)
+  }
+
+  /// Whether the program hasn't started yet or still pays rewards after `now`.
+  pub fn is_active(&self, now: BlockNumber) -> bool {
+    let now: u64 = now.saturated_into();
+    if self.end.map_or(false, |end| now >= end.saturated_into()) {
+      return false;
     }
+    now < self.start.saturated_into() || !self.reward_in(now, now.saturating_add(1)).is_zero()
+  }
+
+  fn reward_in(&self, from: u64, to: u64) -> Balance {
+    let start: u64 = self.start.saturated_into();
+    let to = match self.end {
+      Some(end) => sp_std::cmp::min(to, end.saturated_into()),
+      None => to,
+    };
+    let halving_period = self.halving_period.map(|p| p.saturated_into::<u64>()).filter(|p| *p > 0);
+
+    let mut from = sp_std::cmp::max(from, start);
+    let mut reward = Balance::zero();
+    // sum up the reward period by period, as the reward per block changes at period boundaries
+    while from < to {
+      let (halvings, period_end) = match halving_period {
+        Some(period) => {
+          let halvings = (from - start) / period;
+          (halvings, start.saturating_add(period.saturating_mul(halvings + 1)))
+        },
+        None => (0, to),
+      };
+      if halvings >= Self::MAX_HALVINGS {
+        break;
+      }
+      let segment_end = sp_std::cmp::min(to, period_end);
+      let reward_per_block = self.reward_per_block >> halvings;
+      reward = reward.saturating_add(reward_per_block.saturating_mul((segment_end - from).into()));
+      from = segment_end;
+    }
+    reward
   }
 }
 
-/// PoolId for various rewards pools
-#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
-pub enum PoolId {
-  /// Rewards for dex module
-  Dex(PairKey),
+// storage layout releases of the module
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+  V1_0_0,
+  V2_0_0,
+}
+
+impl Default for Releases {
+  fn default() -> Self {
+    Releases::V1_0_0
+  }
+}
+
+pub trait WeightInfo {
+  fn create_program() -> Weight;
+  fn update_program() -> Weight;
+  fn end_program() -> Weight;
 }
 
 pub trait Trait: frame_system::Trait {
-  type RewardPool:  RewardPoolOps<Self::AccountId, PoolId, Share, Balance>;
+  type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+  type RewardPool:  RewardPoolOps<Self::AccountId, PoolId, CurrencyId, Share, Balance>;
+
+  /// Reward currency of the genesis programs and the migrated fixed rewards.
+  type GetNativeCurrencyId: Get<CurrencyId>;
+
+  /// Origin which may create, update and end incentive programs.
+  type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+  /// Number of blocks the gas used by ethereum transactions counts as shares of the evm usage pool.
+  type EvmUsageWindow: Get<Self::BlockNumber>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
   trait Store for Module<T: Trait> as Incentives {
     // mapping from pool id to its incentive reward per block
+    // deprecated, migrated to `IncentivePrograms`
     pub DexIncentiveRewards get(fn dex_incentive_rewards): map hasher(twox_64_concat) PoolId => Balance;
+
+    // mapping from pool id and reward currency to its incentive program
+    pub IncentivePrograms get(fn incentive_programs): double_map hasher(twox_64_concat) PoolId, hasher(twox_64_concat) CurrencyId => Option<IncentiveProgram<T::BlockNumber>>;
+
+    // mapping from block number to the gas used by the senders of the ethereum transactions of the block,
+    // the shares are removed from the evm usage pool `EvmUsageWindow` blocks later
+    pub EvmUsages get(fn evm_usages): map hasher(twox_64_concat) T::BlockNumber => Vec<(T::AccountId, Share)>;
+
+    StorageVersion build(|_: &GenesisConfig| Releases::V2_0_0): Releases;
   }
 
   add_extra_genesis {
@@ -65,158 +165,370 @@ decl_storage! {
       for (left, right, reward_per_block) in &config.dex_rewards {
         let pair_key = PairKey::try_from(*left, *right).unwrap();
         assert!(!reward_per_block.is_zero());
-        DexIncentiveRewards::insert(PoolId::Dex(pair_key), reward_per_block);
+        IncentivePrograms::<T>::insert(PoolId::Dex(pair_key), T::GetNativeCurrencyId::get(), IncentiveProgram {
+          reward_per_block: *reward_per_block,
+          start: Zero::zero(),
+          end: None,
+          halving_period: None,
+        });
       }
     })
   }
 }
 
+decl_event!(
+  pub enum Event<T> where
+    <T as system::Trait>::BlockNumber,
+    Balance = Balance,
+    CurrencyId = CurrencyId,
+  {
+    /// Incentive program created. [pool_id, reward_currency_id, reward_per_block, start, end, halving_period]
+    ProgramCreated(PoolId, CurrencyId, Balance, BlockNumber, Option<BlockNumber>, Option<BlockNumber>),
+    /// Incentive program updated. [pool_id, reward_currency_id, reward_per_block, end, halving_period]
+    ProgramUpdated(PoolId, CurrencyId, Balance, Option<BlockNumber>, Option<BlockNumber>),
+    /// Incentive program ended. [pool_id, reward_currency_id, block_number]
+    ProgramEnded(PoolId, CurrencyId, BlockNumber),
+  }
+);
+
 decl_error! {
   /// Error for incentive module.
   pub enum Error for Module<T: Trait> {
     /// invalid currency pair
     InvalidCurrencyPair,
+    /// the pool already has an incentive program in the reward currency
+    ProgramExists,
+    /// the pool doesn't have an incentive program in the reward currency
+    ProgramNotFound,
+    /// reward is zero, end block is not after start block or halving period is zero
+    InvalidProgram,
   }
 }
 
 decl_module! {
   pub struct Module<T: Trait> for enum Call where origin: T::Origin {
     type Error = Error<T>;
+    fn deposit_event() = default;
+
+    fn on_runtime_upgrade() -> Weight {
+      Self::migrate_fixed_rewards()
+    }
+
+    fn on_initialize(now: T::BlockNumber) -> Weight {
+      // the gas used before the window doesn't count anymore
+      let expired = EvmUsages::<T>::take(now.saturating_sub(T::EvmUsageWindow::get()));
+      for (who, share) in &expired {
+        Self::update_share(who, PoolId::EvmUsage, *share, false);
+      }
+      let count = expired.len() as Weight;
+      T::DbWeight::get().reads_writes(1 + count.saturating_mul(4), 1 + count.saturating_mul(3))
+    }
+
+    /// Create an incentive program for the reward pool.
+    /// The pool is rewarded `reward_per_block` of `reward_currency_id` every block after `start`,
+    /// until `end` if it's set, halving every `halving_period` blocks if it's set.
+    /// The rewards are paid from the reward budget of the pool in the reward pool module.
+    #[weight = T::WeightInfo::create_program()]
+    pub fn create_program(
+      origin,
+      pool_id: PoolId,
+      reward_currency_id: CurrencyId,
+      #[compact] reward_per_block: Balance,
+      start: T::BlockNumber,
+      end: Option<T::BlockNumber>,
+      halving_period: Option<T::BlockNumber>,
+    ) {
+      T::UpdateOrigin::ensure_origin(origin)?;
+      Self::ensure_valid_pool_id(&pool_id)?;
+      ensure!(!IncentivePrograms::<T>::contains_key(pool_id, reward_currency_id), Error::<T>::ProgramExists);
+
+      let program = IncentiveProgram { reward_per_block, start, end, halving_period };
+      Self::ensure_valid_program(&program)?;
+
+      // rewards before the program are settled without it
+      T::RewardPool::update_rewards(&pool_id)?;
+      IncentivePrograms::<T>::insert(pool_id, reward_currency_id, program);
+      Self::deposit_event(RawEvent::ProgramCreated(pool_id, reward_currency_id, reward_per_block, start, end, halving_period));
+    }
+
+    /// Change the reward, end block and halving period of an incentive program,
+    /// the new schedule starts at the current block, unless the program hasn't started yet.
+    #[weight = T::WeightInfo::update_program()]
+    pub fn update_program(
+      origin,
+      pool_id: PoolId,
+      reward_currency_id: CurrencyId,
+      #[compact] reward_per_block: Balance,
+      end: Option<T::BlockNumber>,
+      halving_period: Option<T::BlockNumber>,
+    ) {
+      T::UpdateOrigin::ensure_origin(origin)?;
+      Self::ensure_valid_pool_id(&pool_id)?;
+      let program = Self::incentive_programs(pool_id, reward_currency_id).ok_or(Error::<T>::ProgramNotFound)?;
+
+      // the reward and the halvings of the new schedule count from now
+      let start = sp_std::cmp::max(program.start, <frame_system::Module<T>>::block_number());
+      let program = IncentiveProgram { reward_per_block, start, end, halving_period };
+      Self::ensure_valid_program(&program)?;
+
+      // rewards until now are settled with the old schedule
+      T::RewardPool::update_rewards(&pool_id)?;
+      IncentivePrograms::<T>::insert(pool_id, reward_currency_id, program);
+      Self::deposit_event(RawEvent::ProgramUpdated(pool_id, reward_currency_id, reward_per_block, end, halving_period));
+    }
+
+    /// End the incentive program of the pool in the reward currency at the current block.
+    #[weight = T::WeightInfo::end_program()]
+    pub fn end_program(origin, pool_id: PoolId, reward_currency_id: CurrencyId) {
+      T::UpdateOrigin::ensure_origin(origin)?;
+      Self::ensure_valid_pool_id(&pool_id)?;
+      ensure!(IncentivePrograms::<T>::contains_key(pool_id, reward_currency_id), Error::<T>::ProgramNotFound);
+
+      T::RewardPool::update_rewards(&pool_id)?;
+      IncentivePrograms::<T>::remove(pool_id, reward_currency_id);
+      Self::deposit_event(RawEvent::ProgramEnded(pool_id, reward_currency_id, <frame_system::Module<T>>::block_number()));
+    }
+  }
+}
+
+/// Stakes the added liquidity shares of a currency pair to its dex pool.
+/// Cloverdex stakes shares explicitly, this is for runtimes staking the lp shares automatically.
+pub struct OnAddLiquidity<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, CurrencyId, CurrencyId, Share)> for OnAddLiquidity<T> {
+  fn happened(info: &(T::AccountId, CurrencyId, CurrencyId, Share)) {
+    let (who, currency_first, currency_second, increase_share) = info;
+    match PairKey::try_from(*currency_first, *currency_second) {
+      Some(pair_key) => Module::<T>::update_share(who, PoolId::Dex(pair_key), *increase_share, true),
+      None => debug::error!("invalid currency pair for add liquidity event, currency {:?}", currency_first),
+    }
+  }
+}
+
+/// Unstakes the removed liquidity shares of a currency pair from its dex pool.
+pub struct OnRemoveLiquidity<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, CurrencyId, CurrencyId, Share)> for OnRemoveLiquidity<T> {
+  fn happened(info: &(T::AccountId, CurrencyId, CurrencyId, Share)) {
+    let (who, currency_first, currency_second, decrease_share) = info;
+    match PairKey::try_from(*currency_first, *currency_second) {
+      Some(pair_key) => Module::<T>::update_share(who, PoolId::Dex(pair_key), *decrease_share, false),
+      None => debug::error!("invalid currency pair for remove liquidity event, currency {:?}", currency_first),
+    }
+  }
+}
+
+/// Keeps the shares of the loans pool of a currency equal to the debit of the borrower.
+/// The info is (who, currency_id, debit_adjustment, debit before the adjustment).
+pub struct OnUpdateLoan<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, CurrencyId, Amount, Balance)> for OnUpdateLoan<T> {
+  fn happened(info: &(T::AccountId, CurrencyId, Amount, Balance)) {
+    let (who, currency_id, debit_adjustment, _) = info;
+    let amount = debit_adjustment.saturating_abs().saturated_into::<Share>();
+    Module::<T>::update_share(who, PoolId::Loans(*currency_id), amount, debit_adjustment.is_positive());
+  }
+}
+
+/// Keeps the shares of the loans deposit pool of a currency equal to the collateral of the depositor.
+/// The info is (who, currency_id, collateral_adjustment, collateral before the adjustment).
+pub struct OnUpdateCollateral<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, CurrencyId, Amount, Balance)> for OnUpdateCollateral<T> {
+  fn happened(info: &(T::AccountId, CurrencyId, Amount, Balance)) {
+    let (who, currency_id, collateral_adjustment, _) = info;
+    let amount = collateral_adjustment.saturating_abs().saturated_into::<Share>();
+    Module::<T>::update_share(who, PoolId::LoansDeposit(*currency_id), amount, collateral_adjustment.is_positive());
+  }
+}
+
+/// Adds shares of the single asset pool of a currency, for staking and other non-dex activities.
+pub struct OnAddSingleShare<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, CurrencyId, Share)> for OnAddSingleShare<T> {
+  fn happened(info: &(T::AccountId, CurrencyId, Share)) {
+    let (who, currency_id, increase_share) = info;
+    Module::<T>::update_share(who, PoolId::Single(*currency_id), *increase_share, true);
   }
 }
 
-//
-// we don't support auto staking for lp tokens
-// pub struct OnAddLiquidity<T>(sp_std::marker::PhantomData<T>);
-// impl<T: Trait> Happened<(T::AccountId, CurrencyId, CurrencyId, Share)> for OnAddLiquidity<T> {
-// 	fn happened(info: &(T::AccountId, CurrencyId, CurrencyId, Share)) {
-// 		let (who, currency_first, currency_second, increase_share) = info;
-//     if currency_first == currency_second {
-//       debug::error!("invalid currency pair for add liquidity event, currency {:?}", currency_first);
-//       return;
-//     }
-//     let pair_key = PairKey::try_from(*currency_first, *currency_second).unwrap();
-//
-// 		match T::RewardPool::add_share(who, PoolId::Dex(pair_key), *increase_share) {
-//       Ok(_) => (),
-//       Err(e) => {
-//         debug::error!("failed remove share from pool!, {:?}", e);
-//       }
-//     }
-// 	}
-// }
-//
-//
-// pub struct OnRemoveLiquidity<T>(sp_std::marker::PhantomData<T>);
-// impl<T: Trait> Happened<(T::AccountId, CurrencyId, CurrencyId, Share)> for OnRemoveLiquidity<T> {
-// 	fn happened(info: &(T::AccountId, CurrencyId, CurrencyId, Share)) {
-// 		let (who, currency_first, currency_second, decrease_share) = info;
-//     if currency_first == currency_second {
-//       debug::error!("invalid currency pair for remove liquidity event, currency {:?}", currency_first);
-//       return;
-//     }
-//     let pair_key = PairKey::try_from(*currency_first, *currency_second).unwrap();
-// 		match T::RewardPool::remove_share(who, PoolId::Dex(pair_key), *decrease_share) {
-//       Ok(_) => (),
-//       Err(e) => {
-//         debug::error!("failed remove share from pool!, {:?}", e);
-//       }
-//     }
-// 	}
-// }
+/// Removes shares of the single asset pool of a currency.
+pub struct OnRemoveSingleShare<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, CurrencyId, Share)> for OnRemoveSingleShare<T> {
+  fn happened(info: &(T::AccountId, CurrencyId, Share)) {
+    let (who, currency_id, decrease_share) = info;
+    Module::<T>::update_share(who, PoolId::Single(*currency_id), *decrease_share, false);
+  }
+}
 
+/// Adds the bonded amount to the single asset pool of the native currency,
+/// for the modules bonding the native currency like oracle staking.
+pub struct OnBondNative<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, Balance)> for OnBondNative<T> {
+  fn happened(info: &(T::AccountId, Balance)) {
+    let (who, amount) = info;
+    OnAddSingleShare::<T>::happened(&(who.clone(), T::GetNativeCurrencyId::get(), *amount));
+  }
+}
+
+/// Removes the unbonded or slashed amount from the single asset pool of the native currency.
+pub struct OnUnbondNative<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, Balance)> for OnUnbondNative<T> {
+  fn happened(info: &(T::AccountId, Balance)) {
+    let (who, amount) = info;
+    OnRemoveSingleShare::<T>::happened(&(who.clone(), T::GetNativeCurrencyId::get(), *amount));
+  }
+}
+
+/// Adds the gas used by an ethereum transaction to the shares of its sender in the evm usage pool,
+/// the shares are removed after `EvmUsageWindow` blocks.
+pub struct OnEvmUsage<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> Happened<(T::AccountId, u64)> for OnEvmUsage<T> {
+  fn happened(info: &(T::AccountId, u64)) {
+    let (who, used_gas) = info;
+    let share = Share::from(*used_gas);
+    if share.is_zero() || T::EvmUsageWindow::get().is_zero() {
+      return;
+    }
+    Module::<T>::update_share(who, PoolId::EvmUsage, share, true);
+    EvmUsages::<T>::append(<frame_system::Module<T>>::block_number(), (who.clone(), share));
+  }
+}
 
 impl <T: Trait> Module<T> {
-  fn get_dex_id(first: &CurrencyId, second: &CurrencyId) -> Result<PoolId, DispatchError> {
-    let pair_key = PairKey::try_from(*first, *second)
-      .ok_or(Error::<T>::InvalidCurrencyPair)?;
-    Ok(PoolId::Dex(pair_key))
+  fn ensure_valid_pool_id(pool_id: &PoolId) -> Result<(), DispatchError> {
+    ensure!(pool_id.is_valid(), Error::<T>::InvalidCurrencyPair);
+    Ok(())
+  }
+
+  // share hooks can't fail the operation which triggers them, errors are only logged
+  fn update_share(who: &T::AccountId, pool_id: PoolId, amount: Share, increase: bool) {
+    let result = if increase {
+      if amount.is_zero() {
+        return;
+      }
+      T::RewardPool::add_share(who, pool_id, amount)
+    } else {
+      // positions opened before the hook was set have fewer shares than their amount
+      let amount = amount.min(T::RewardPool::get_account_shares(who, &pool_id));
+      if amount.is_zero() {
+        return;
+      }
+      T::RewardPool::remove_share(who, pool_id, amount)
+    };
+    if let Err(e) = result {
+      debug::error!("failed to update share of pool {:?}, {:?}", pool_id, e);
+    }
+  }
+
+  fn ensure_valid_program(program: &IncentiveProgram<T::BlockNumber>) -> Result<(), DispatchError> {
+    ensure!(!program.reward_per_block.is_zero(), Error::<T>::InvalidProgram);
+    ensure!(program.end.map_or(true, |end| end > program.start), Error::<T>::InvalidProgram);
+    ensure!(program.halving_period.map_or(true, |period| !period.is_zero()), Error::<T>::InvalidProgram);
+    Ok(())
+  }
+
+  /// Rewards of each currency the programs of the pool pay for blocks in (from, to],
+  /// the reward budgets of the pool are not taken into account.
+  pub fn get_program_rewards(pool_id: &PoolId, from: T::BlockNumber, to: T::BlockNumber) -> vec::Vec<(CurrencyId, Balance)> {
+    IncentivePrograms::<T>::iter_prefix(pool_id)
+      .map(|(currency_id, program)| (currency_id, program.reward_between(from, to)))
+      .filter(|(_, reward)| !reward.is_zero())
+      .collect()
+  }
+
+  /// Programs which still pay rewards, returns
+  /// (pool_id, reward_currency, current_reward_per_block, start, end, halving_period)
+  pub fn get_active_programs() -> vec::Vec<(PoolId, CurrencyId, Balance, T::BlockNumber, Option<T::BlockNumber>, Option<T::BlockNumber>)> {
+    let now = <frame_system::Module<T>>::block_number();
+    IncentivePrograms::<T>::iter()
+      .filter(|(_, _, program)| program.is_active(now))
+      .map(|(pool_id, reward_currency_id, program)| {
+        (pool_id, reward_currency_id, program.reward_at(now.saturating_add(1u32.into())),
+         program.start, program.end, program.halving_period)
+      })
+      .collect()
+  }
+
+  /// Migrate the fixed native rewards to programs which never end,
+  /// the runtime runs it before the reward pool migration which seeds the budgets from the programs.
+  /// Only runs once, later calls just read the storage version.
+  pub fn migrate_fixed_rewards() -> Weight {
+    if StorageVersion::get() == Releases::V2_0_0 {
+      return T::DbWeight::get().reads(1);
+    }
+
+    let native_currency_id = T::GetNativeCurrencyId::get();
+    let mut count: Weight = 0;
+    for (pool_id, reward_per_block) in DexIncentiveRewards::drain() {
+      if !IncentivePrograms::<T>::contains_key(pool_id, native_currency_id) {
+        IncentivePrograms::<T>::insert(pool_id, native_currency_id, IncentiveProgram {
+          reward_per_block,
+          start: Zero::zero(),
+          end: None,
+          halving_period: None,
+        });
+      }
+      count += 1;
+    }
+    StorageVersion::put(Releases::V2_0_0);
+    T::DbWeight::get().reads_writes(count.saturating_add(1), count.saturating_mul(2).saturating_add(1))
   }
 }
 
-impl <T: Trait> RewardHandler<T::AccountId, T::BlockNumber, Balance, Share, PoolId> for Module<T>
+impl <T: Trait> RewardHandler<T::AccountId, T::BlockNumber, CurrencyId, Balance, Share, PoolId> for Module<T>
 where T::BlockNumber: SaturatedConversion, {
   fn caculate_reward(pool_id: &PoolId,
                      total_share: &Share,
                      last_update_block: T::BlockNumber,
-                     now: T::BlockNumber) -> Balance {
+                     now: T::BlockNumber) -> vec::Vec<(CurrencyId, Balance)> {
     // no shares in the pool, should not pay the reward
     if total_share.is_zero() {
-      return Balance::zero();
+      return vec![];
     }
 
-    if !DexIncentiveRewards::contains_key(pool_id) || last_update_block >= now {
-      return Balance::zero();
+    if last_update_block >= now {
+      return vec![];
     }
-    let reward_ratio = Self::dex_incentive_rewards(pool_id);
-    if reward_ratio.is_zero() {
-      return Balance::zero();
-    }
-    let blocks = now - last_update_block;
-    reward_ratio.saturating_mul(blocks.saturated_into())
+    IncentivePrograms::<T>::iter_prefix(pool_id)
+      .map(|(currency_id, program)| (currency_id, program.reward_between(last_update_block, now)))
+      .filter(|(_, reward)| !reward.is_zero())
+      .collect()
+  }
+
+  fn pays_rewards(pool_id: &PoolId, currency_id: &CurrencyId) -> bool {
+    let now = <frame_system::Module<T>>::block_number();
+    Self::incentive_programs(pool_id, currency_id).map_or(false, |program| program.is_active(now))
   }
 }
 
-impl<T: Trait> IncentiveOps<T::AccountId, CurrencyId, Share, Balance> for Module<T> {
+impl<T: Trait> IncentiveOps<T::AccountId, PoolId, CurrencyId, Share, Balance> for Module<T> {
 
-  fn add_share(who: &T::AccountId,
-               currency_first: &CurrencyId,
-               currency_second: &CurrencyId,
-               amount: &Share) -> Result<Share, DispatchError>{
-    let pair_key = PairKey::try_from(*currency_first, *currency_second)
-      .ok_or(Error::<T>::InvalidCurrencyPair)?;
-    T::RewardPool::add_share(who, PoolId::Dex(pair_key), *amount)
+  fn add_share(who: &T::AccountId, pool_id: &PoolId, amount: &Share) -> Result<Share, DispatchError>{
+    Self::ensure_valid_pool_id(pool_id)?;
+    T::RewardPool::add_share(who, *pool_id, *amount)
   }
 
-  fn remove_share(who: &T::AccountId,
-                  currency_first: &CurrencyId,
-                  currency_second: &CurrencyId,
-                  amount: &Share) -> Result<Share, DispatchError> {
-    let pair_key = PairKey::try_from(*currency_first, *currency_second)
-      .ok_or(Error::<T>::InvalidCurrencyPair)?;
-    T::RewardPool::remove_share(who, PoolId::Dex(pair_key), *amount)
+  fn remove_share(who: &T::AccountId, pool_id: &PoolId, amount: &Share) -> Result<Share, DispatchError> {
+    Self::ensure_valid_pool_id(pool_id)?;
+    T::RewardPool::remove_share(who, *pool_id, *amount)
   }
 
-  fn get_account_shares(who: &T::AccountId, left: &CurrencyId, right: &CurrencyId) -> Share {
-    if let Ok(id) = Self::get_dex_id(left, right) {
-      T::RewardPool::get_account_shares(who, &id)
-    } else {
-      Zero::zero()
-    }
+  fn get_account_shares(who: &T::AccountId, pool_id: &PoolId) -> Share {
+    T::RewardPool::get_account_shares(who, pool_id)
   }
 
-  fn get_accumlated_rewards(who: &T::AccountId, left: &CurrencyId, right: &CurrencyId) -> Share {
-    if let Ok(id) = Self::get_dex_id(left, right) {
-      T::RewardPool::get_accumlated_rewards(who, &id)
-    } else {
-      Zero::zero()
-    }
+  fn get_accumlated_rewards(who: &T::AccountId, pool_id: &PoolId) -> vec::Vec<(CurrencyId, Balance)> {
+    T::RewardPool::get_accumlated_rewards(who, pool_id)
   }
 
-  fn get_account_info(who: &T::AccountId, left: &CurrencyId, right: &CurrencyId) -> IncentivePoolAccountInfo<Share, Balance> {
-    if let Ok(pool_id) = Self::get_dex_id(left, right) {
-      let shares = T::RewardPool::get_account_shares(who, &pool_id);
-      let accumlated_rewards = T::RewardPool::get_accumlated_rewards(who, &pool_id);
-      IncentivePoolAccountInfo { shares, accumlated_rewards, }
-    } else {
-      IncentivePoolAccountInfo { shares: Zero::zero(), accumlated_rewards: Zero::zero(), }
-    }
+  fn get_account_info(who: &T::AccountId, pool_id: &PoolId) -> IncentivePoolAccountInfo<CurrencyId, Share, Balance> {
+    let shares = T::RewardPool::get_account_shares(who, pool_id);
+    let accumlated_rewards = T::RewardPool::get_accumlated_rewards(who, pool_id);
+    IncentivePoolAccountInfo { shares, accumlated_rewards, }
   }
 
-  fn claim_rewards(who: &T::AccountId, left: &CurrencyId, right: &CurrencyId) -> Result<Balance, DispatchError> {
-    Self::get_dex_id(left, right)
-      .and_then(|pool_id| T::RewardPool::claim_rewards(who, &pool_id))
+  fn claim_rewards(who: &T::AccountId, pool_id: &PoolId) -> Result<vec::Vec<(CurrencyId, Balance)>, DispatchError> {
+    Self::ensure_valid_pool_id(pool_id)?;
+    T::RewardPool::claim_rewards(who, pool_id)
   }
 
-  fn get_all_incentive_pools() -> vec::Vec<(CurrencyId, CurrencyId, Share, Balance)>{
+  fn get_all_incentive_pools() -> vec::Vec<(PoolId, Share, vec::Vec<(CurrencyId, Balance)>)>{
     T::RewardPool::get_all_pools()
-      .iter()
-      .filter(|(pool_id, _, _)| match pool_id {
-        PoolId::Dex(_) => true,
-      })
-      .map(|(pool_id, shares, balance)| match pool_id {
-        PoolId::Dex(k) => (k.left, k.right, shares.clone(), balance.clone()),
-      })
-      .collect()
   }
 }
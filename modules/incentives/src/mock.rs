@@ -0,0 +1,177 @@
+#![cfg(test)]
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchResult, Perbill};
+use sp_std::cell::RefCell;
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+
+mod incentives {
+  pub use super::super::*;
+}
+
+impl_outer_event! {
+  pub enum TestEvent for TestRuntime {
+    frame_system<T>,
+    incentives<T>,
+  }
+}
+impl_outer_origin! {
+  pub enum Origin for TestRuntime {}
+}
+
+parameter_types! {
+  pub const BlockHashCount: u64 = 250;
+  pub const MaximumBlockWeight: u32 = 1024;
+  pub const MaximumBlockLength: u32 = 2 * 1024;
+  pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for TestRuntime {
+  type Origin = Origin;
+  type Index = u64;
+  type BlockNumber = BlockNumber;
+  type Call = ();
+  type Hash = H256;
+  type Hashing = ::sp_runtime::traits::BlakeTwo256;
+  type AccountId = AccountId;
+  type Lookup = IdentityLookup<Self::AccountId>;
+  type Header = Header;
+  type Event = TestEvent;
+  type BlockHashCount = BlockHashCount;
+  type MaximumBlockWeight = MaximumBlockWeight;
+  type MaximumBlockLength = MaximumBlockLength;
+  type AvailableBlockRatio = AvailableBlockRatio;
+  type Version = ();
+  type PalletInfo = ();
+  type AccountData = ();
+  type OnNewAccount = ();
+  type OnKilledAccount = ();
+  type DbWeight = ();
+  type BlockExecutionWeight = ();
+  type ExtrinsicBaseWeight = ();
+  type MaximumExtrinsicWeight = ();
+  type BaseCallFilter = ();
+  type SystemWeightInfo = ();
+}
+
+pub type System = frame_system::Module<TestRuntime>;
+
+thread_local! {
+  /// pools whose rewards were accumulated by `update_rewards`
+  pub static UPDATED_POOLS: RefCell<Vec<PoolId>> = RefCell::new(Vec::new());
+  pub static SHARES: RefCell<Vec<(AccountId, PoolId, Share)>> = RefCell::new(Vec::new());
+}
+
+/// Records the share changes and reward updates instead of paying rewards.
+pub struct MockRewardPool;
+
+impl MockRewardPool {
+  pub fn updated_pools() -> Vec<PoolId> {
+    UPDATED_POOLS.with(|v| v.borrow().clone())
+  }
+
+  fn update_share(who: &AccountId, pool: PoolId, f: impl FnOnce(Share) -> Share) -> Share {
+    let new = f(Self::get_account_shares(who, &pool));
+    SHARES.with(|v| {
+      let mut shares = v.borrow_mut();
+      shares.retain(|(account, p, _)| !(account == who && *p == pool));
+      shares.push((*who, pool, new));
+      new
+    })
+  }
+}
+
+impl RewardPoolOps<AccountId, PoolId, CurrencyId, Share, Balance> for MockRewardPool {
+  fn add_share(who: &AccountId, pool: PoolId, amount: Share) -> Result<Share, DispatchError> {
+    Ok(Self::update_share(who, pool, |current| current + amount))
+  }
+
+  fn remove_share(who: &AccountId, pool: PoolId, amount: Share) -> Result<Share, DispatchError> {
+    Ok(Self::update_share(who, pool, |current| current.saturating_sub(amount)))
+  }
+
+  fn get_account_shares(who: &AccountId, pool: &PoolId) -> Share {
+    SHARES.with(|v| {
+      v.borrow().iter()
+        .find(|(account, p, _)| account == who && p == pool)
+        .map_or(0, |(_, _, share)| *share)
+    })
+  }
+
+  fn get_accumlated_rewards(_who: &AccountId, _pool: &PoolId) -> Vec<(CurrencyId, Balance)> {
+    vec![]
+  }
+
+  fn claim_rewards(_who: &AccountId, _pool: &PoolId) -> Result<Vec<(CurrencyId, Balance)>, DispatchError> {
+    Ok(vec![])
+  }
+
+  fn get_all_pools() -> Vec<(PoolId, Share, Vec<(CurrencyId, Balance)>)> {
+    vec![]
+  }
+
+  fn update_rewards(pool: &PoolId) -> DispatchResult {
+    UPDATED_POOLS.with(|v| v.borrow_mut().push(*pool));
+    Ok(())
+  }
+}
+
+parameter_types! {
+  pub const GetNativeCurrencyId: CurrencyId = CurrencyId::CLV;
+  pub const EvmUsageWindow: BlockNumber = 10;
+}
+
+impl Trait for TestRuntime {
+  type Event = TestEvent;
+  type RewardPool = MockRewardPool;
+  type GetNativeCurrencyId = GetNativeCurrencyId;
+  type UpdateOrigin = EnsureRoot<AccountId>;
+  type EvmUsageWindow = EvmUsageWindow;
+  type WeightInfo = ();
+}
+
+pub type Incentives = Module<TestRuntime>;
+
+pub const ALICE: AccountId = 1;
+pub const CLV: CurrencyId = CurrencyId::CLV;
+pub const CUSDT: CurrencyId = CurrencyId::CUSDT;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+
+pub fn dex_pool(left: CurrencyId, right: CurrencyId) -> PoolId {
+  PoolId::Dex(PairKey::try_from(left, right).unwrap())
+}
+
+#[derive(Default)]
+pub struct ExtBuilder {
+  dex_rewards: Vec<(CurrencyId, CurrencyId, Balance)>,
+}
+
+impl ExtBuilder {
+  pub fn dex_rewards(mut self, dex_rewards: Vec<(CurrencyId, CurrencyId, Balance)>) -> Self {
+    self.dex_rewards = dex_rewards;
+    self
+  }
+
+  pub fn build(self) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+      .build_storage::<TestRuntime>()
+      .unwrap();
+
+    GenesisConfig {
+      dex_rewards: self.dex_rewards,
+    }
+    .assimilate_storage::<TestRuntime>(&mut t)
+    .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+  }
+}
@@ -0,0 +1,278 @@
+#![cfg(test)]
+
+use super::*;
+use mock::*;
+use frame_support::{assert_noop, assert_ok, traits::{OnInitialize, OnRuntimeUpgrade}};
+use reward_pool::traits::RewardHandler;
+use sp_runtime::traits::BadOrigin;
+
+fn program(reward_per_block: Balance, start: BlockNumber, end: Option<BlockNumber>, halving_period: Option<BlockNumber>) -> IncentiveProgram<BlockNumber> {
+  IncentiveProgram { reward_per_block, start, end, halving_period }
+}
+
+fn last_event() -> TestEvent {
+  System::events().pop().expect("an event is deposited").event
+}
+
+#[test]
+fn reward_without_halving() {
+  let p = program(100, 10, Some(20), None);
+  assert_eq!(p.reward_between(0, 10), 0);
+  assert_eq!(p.reward_between(0, 12), 200);
+  assert_eq!(p.reward_between(15, 30), 500);
+  assert_eq!(p.reward_at(11), 100);
+  assert_eq!(p.reward_at(21), 0);
+  assert!(p.is_active(5));
+  assert!(p.is_active(19));
+  assert!(!p.is_active(20));
+}
+
+#[test]
+fn reward_halves_across_period_boundaries() {
+  // blocks 1..=10 pay 100, 11..=20 pay 50, 21..=30 pay 25
+  let p = program(100, 0, None, Some(10));
+  assert_eq!(p.reward_between(0, 10), 1_000);
+  assert_eq!(p.reward_between(8, 12), 2 * 100 + 2 * 50);
+  assert_eq!(p.reward_between(5, 25), 5 * 100 + 10 * 50 + 5 * 25);
+  assert_eq!(p.reward_at(10), 100);
+  assert_eq!(p.reward_at(11), 50);
+  assert_eq!(p.reward_at(21), 25);
+
+  // the halvings end at the end block
+  let p = program(100, 0, Some(15), Some(10));
+  assert_eq!(p.reward_between(0, 30), 10 * 100 + 5 * 50);
+
+  // the reward is gone after enough halvings
+  let p = program(1, 0, None, Some(1));
+  assert_eq!(p.reward_at(2), 0);
+  assert!(!p.is_active(1));
+  assert_eq!(p.reward_between(0, 1_000), 1);
+}
+
+#[test]
+fn caculate_reward_sums_the_programs_of_the_pool() {
+  ExtBuilder::default().build().execute_with(|| {
+    let pool = dex_pool(CLV, CUSDT);
+    IncentivePrograms::<TestRuntime>::insert(pool, CLV, program(100, 0, None, Some(10)));
+    IncentivePrograms::<TestRuntime>::insert(pool, DOT, program(10, 5, Some(15), None));
+    IncentivePrograms::<TestRuntime>::insert(dex_pool(CLV, DOT), CLV, program(1_000, 0, None, None));
+
+    let mut rewards = Incentives::caculate_reward(&pool, &1, 8, 12);
+    rewards.sort_by_key(|(currency_id, _)| *currency_id);
+    assert_eq!(rewards, vec![(CLV, 2 * 100 + 2 * 50), (DOT, 40)]);
+
+    // no rewards without shares or blocks
+    assert_eq!(Incentives::caculate_reward(&pool, &0, 8, 12), vec![]);
+    assert_eq!(Incentives::caculate_reward(&pool, &1, 12, 12), vec![]);
+    // ended programs pay nothing
+    assert_eq!(Incentives::caculate_reward(&pool, &1, 20, 21), vec![(CLV, 25)]);
+  });
+}
+
+#[test]
+fn create_program_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    let pool = dex_pool(CLV, CUSDT);
+    assert_noop!(
+      Incentives::create_program(Origin::signed(ALICE), pool, CLV, 100, 1, None, None),
+      BadOrigin
+    );
+    assert_noop!(
+      Incentives::create_program(Origin::root(), pool, CLV, 0, 1, None, None),
+      Error::<TestRuntime>::InvalidProgram
+    );
+    assert_noop!(
+      Incentives::create_program(Origin::root(), pool, CLV, 100, 5, Some(5), None),
+      Error::<TestRuntime>::InvalidProgram
+    );
+    assert_noop!(
+      Incentives::create_program(Origin::root(), pool, CLV, 100, 5, None, Some(0)),
+      Error::<TestRuntime>::InvalidProgram
+    );
+    // a pair key with its currencies in the wrong order
+    let invalid_pool = PoolId::Dex(PairKey::decode(&mut &(CUSDT, CLV).encode()[..]).unwrap());
+    assert_noop!(
+      Incentives::create_program(Origin::root(), invalid_pool, CLV, 100, 1, None, None),
+      Error::<TestRuntime>::InvalidCurrencyPair
+    );
+
+    assert_ok!(Incentives::create_program(Origin::root(), pool, CLV, 100, 1, Some(10), Some(5)));
+    assert_eq!(Incentives::incentive_programs(pool, CLV), Some(program(100, 1, Some(10), Some(5))));
+    assert_eq!(MockRewardPool::updated_pools(), vec![pool]);
+    assert_eq!(last_event(), TestEvent::incentives(RawEvent::ProgramCreated(pool, CLV, 100, 1, Some(10), Some(5))));
+
+    assert_noop!(
+      Incentives::create_program(Origin::root(), pool, CLV, 100, 1, None, None),
+      Error::<TestRuntime>::ProgramExists
+    );
+  });
+}
+
+#[test]
+fn update_program_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    let pool = dex_pool(CLV, CUSDT);
+    assert_noop!(
+      Incentives::update_program(Origin::root(), pool, CLV, 100, None, None),
+      Error::<TestRuntime>::ProgramNotFound
+    );
+    assert_ok!(Incentives::create_program(Origin::root(), pool, CLV, 100, 1, None, None));
+
+    assert_noop!(
+      Incentives::update_program(Origin::signed(ALICE), pool, CLV, 50, None, None),
+      BadOrigin
+    );
+    assert_noop!(
+      Incentives::update_program(Origin::root(), pool, CLV, 50, Some(1), None),
+      Error::<TestRuntime>::InvalidProgram
+    );
+
+    assert_ok!(Incentives::update_program(Origin::root(), pool, CLV, 50, Some(20), Some(10)));
+    // updated at the start block
+    assert_eq!(Incentives::incentive_programs(pool, CLV), Some(program(50, 1, Some(20), Some(10))));
+    assert_eq!(MockRewardPool::updated_pools(), vec![pool, pool]);
+    assert_eq!(last_event(), TestEvent::incentives(RawEvent::ProgramUpdated(pool, CLV, 50, Some(20), Some(10))));
+  });
+}
+
+#[test]
+fn update_program_rebases_the_schedule() {
+  ExtBuilder::default().build().execute_with(|| {
+    let pool = dex_pool(CLV, CUSDT);
+    assert_ok!(Incentives::create_program(Origin::root(), pool, CLV, 100, 1, None, Some(10)));
+
+    // in the middle of the second halving period, paying 50 per block
+    System::set_block_number(15);
+    assert_eq!(Incentives::caculate_reward(&pool, &1, 14, 15), vec![(CLV, 50)]);
+    assert_ok!(Incentives::update_program(Origin::root(), pool, CLV, 80, None, Some(10)));
+    assert_eq!(Incentives::incentive_programs(pool, CLV), Some(program(80, 15, None, Some(10))));
+
+    // the new reward is paid for a full halving period from the update
+    assert_eq!(Incentives::caculate_reward(&pool, &1, 15, 25), vec![(CLV, 800)]);
+    assert_eq!(Incentives::caculate_reward(&pool, &1, 25, 26), vec![(CLV, 40)]);
+
+    // a program which hasn't started keeps its start block
+    let other_pool = dex_pool(CLV, DOT);
+    assert_ok!(Incentives::create_program(Origin::root(), other_pool, CLV, 100, 30, None, None));
+    assert_ok!(Incentives::update_program(Origin::root(), other_pool, CLV, 50, Some(40), None));
+    assert_eq!(Incentives::incentive_programs(other_pool, CLV), Some(program(50, 30, Some(40), None)));
+  });
+}
+
+#[test]
+fn end_program_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    let pool = dex_pool(CLV, CUSDT);
+    assert_noop!(
+      Incentives::end_program(Origin::root(), pool, CLV),
+      Error::<TestRuntime>::ProgramNotFound
+    );
+    assert_ok!(Incentives::create_program(Origin::root(), pool, CLV, 100, 1, None, None));
+
+    assert_noop!(Incentives::end_program(Origin::signed(ALICE), pool, CLV), BadOrigin);
+
+    System::set_block_number(5);
+    assert_ok!(Incentives::end_program(Origin::root(), pool, CLV));
+    assert_eq!(Incentives::incentive_programs(pool, CLV), None);
+    assert_eq!(MockRewardPool::updated_pools(), vec![pool, pool]);
+    assert_eq!(last_event(), TestEvent::incentives(RawEvent::ProgramEnded(pool, CLV, 5)));
+    assert!(Incentives::get_active_programs().is_empty());
+  });
+}
+
+#[test]
+fn genesis_rewards_are_programs() {
+  ExtBuilder::default().dex_rewards(vec![(CLV, CUSDT, 100)]).build().execute_with(|| {
+    assert_eq!(Incentives::incentive_programs(dex_pool(CLV, CUSDT), CLV), Some(program(100, 0, None, None)));
+    assert_eq!(
+      Incentives::get_active_programs(),
+      vec![(dex_pool(CLV, CUSDT), CLV, 100, 0, None, None)]
+    );
+  });
+}
+
+#[test]
+fn runtime_upgrade_migrates_fixed_rewards() {
+  ExtBuilder::default().build().execute_with(|| {
+    StorageVersion::put(Releases::V1_0_0);
+    let pool = dex_pool(CLV, CUSDT);
+    let other_pool = dex_pool(CLV, DOT);
+    DexIncentiveRewards::insert(pool, 100);
+    DexIncentiveRewards::insert(other_pool, 200);
+    // an existing program is kept
+    IncentivePrograms::<TestRuntime>::insert(other_pool, CLV, program(10, 1, None, None));
+
+    Incentives::on_runtime_upgrade();
+
+    assert_eq!(Incentives::incentive_programs(pool, CLV), Some(program(100, 0, None, None)));
+    assert_eq!(Incentives::incentive_programs(other_pool, CLV), Some(program(10, 1, None, None)));
+    assert!(!DexIncentiveRewards::contains_key(pool));
+    assert!(!DexIncentiveRewards::contains_key(other_pool));
+    assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+
+    // the migration runs only once
+    DexIncentiveRewards::insert(pool, 300);
+    Incentives::on_runtime_upgrade();
+    assert_eq!(Incentives::incentive_programs(pool, CLV), Some(program(100, 0, None, None)));
+  });
+}
+
+#[test]
+fn loans_hooks_update_borrower_and_depositor_shares() {
+  ExtBuilder::default().build().execute_with(|| {
+    OnUpdateLoan::<TestRuntime>::happened(&(ALICE, DOT, 100, 0));
+    OnUpdateLoan::<TestRuntime>::happened(&(ALICE, DOT, -40, 100));
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::Loans(DOT)), 60);
+
+    OnUpdateCollateral::<TestRuntime>::happened(&(ALICE, DOT, 500, 0));
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::LoansDeposit(DOT)), 500);
+    // collateral deposited before the hook was set has no shares
+    OnUpdateCollateral::<TestRuntime>::happened(&(ALICE, DOT, -600, 1_000));
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::LoansDeposit(DOT)), 0);
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::Loans(DOT)), 60);
+  });
+}
+
+#[test]
+fn bond_hooks_update_native_single_shares() {
+  ExtBuilder::default().build().execute_with(|| {
+    OnBondNative::<TestRuntime>::happened(&(ALICE, 1_000));
+    OnUnbondNative::<TestRuntime>::happened(&(ALICE, 300));
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::Single(CLV)), 700);
+
+    OnAddSingleShare::<TestRuntime>::happened(&(ALICE, DOT, 50));
+    OnRemoveSingleShare::<TestRuntime>::happened(&(ALICE, DOT, 20));
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::Single(DOT)), 30);
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::Single(CLV)), 700);
+  });
+}
+
+#[test]
+fn evm_usage_shares_expire_after_the_window() {
+  ExtBuilder::default().build().execute_with(|| {
+    OnEvmUsage::<TestRuntime>::happened(&(ALICE, 21_000));
+    OnEvmUsage::<TestRuntime>::happened(&(ALICE, 0));
+    System::set_block_number(5);
+    OnEvmUsage::<TestRuntime>::happened(&(ALICE, 10_000));
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::EvmUsage), 31_000);
+    assert_eq!(Incentives::evm_usages(1), vec![(ALICE, 21_000)]);
+
+    Incentives::on_initialize(10);
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::EvmUsage), 31_000);
+    Incentives::on_initialize(11);
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::EvmUsage), 10_000);
+    assert_eq!(Incentives::evm_usages(1), vec![]);
+    Incentives::on_initialize(15);
+    assert_eq!(MockRewardPool::get_account_shares(&ALICE, &PoolId::EvmUsage), 0);
+  });
+}
+
+#[test]
+fn programs_of_non_dex_pools_work() {
+  ExtBuilder::default().build().execute_with(|| {
+    for pool_id in vec![PoolId::Loans(DOT), PoolId::LoansDeposit(DOT), PoolId::Single(CLV), PoolId::EvmUsage] {
+      assert_ok!(Incentives::create_program(Origin::root(), pool_id, CLV, 10, 0, None, None));
+      assert_eq!(Incentives::caculate_reward(&pool_id, &100, 0, 5), vec![(CLV, 50)]);
+    }
+  });
+}
@@ -0,0 +1,14 @@
+//! Weights for clover_loans, the governance calls are benchmarked in the runtime.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+impl crate::WeightInfo for () {
+	fn set_debit_exchange_rate() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+}
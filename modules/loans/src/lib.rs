@@ -4,11 +4,12 @@ use codec::{Decode, Encode};
 use frame_support::{
   decl_error, decl_event, decl_module, decl_storage,
   traits::Get,
+  weights::Weight,
 };
-use frame_system::{self as system};
-use orml_traits::{MultiCurrency, MultiCurrencyExtended};
+use frame_system::{self as system, ensure_root};
+use orml_traits::{Happened, MultiCurrency, MultiCurrencyExtended};
 use orml_utilities::with_transaction_result;
-use primitives::{Amount, Balance, CurrencyId};
+use primitives::{Amount, Balance, CurrencyId, Rate};
 use sp_runtime::{
   traits::{AccountIdConversion, Zero},
   DispatchResult, ModuleId, RuntimeDebug,
@@ -17,6 +18,11 @@ use sp_std::{convert::TryInto, result};
 
 // mod mock;
 // mod tests;
+mod default_weight;
+
+pub trait WeightInfo {
+  fn set_debit_exchange_rate() -> Weight;
+}
 
 pub trait Trait: system::Trait {
   type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
@@ -28,8 +34,22 @@ pub trait Trait: system::Trait {
   /// The loan's module id, keep all collaterals of CDPs.
   type ModuleId: Get<ModuleId>;
 
-  // Event handler which calls when update loan.
-  // type OnUpdateLoan: Happened<(Self::AccountId, CurrencyId, Amount, Balance)>;
+  /// Event handler which calls when update loan,
+  /// with (owner, collateral_type, debit_adjustment, debit before the adjustment).
+  type OnUpdateLoan: Happened<(Self::AccountId, CurrencyId, Amount, Balance)>;
+
+  /// Event handler which calls when update collateral,
+  /// with (owner, collateral_type, collateral_adjustment, collateral before the adjustment).
+  type OnUpdateCollateral: Happened<(Self::AccountId, CurrencyId, Amount, Balance)>;
+
+  /// Currency the debits are denominated in.
+  type GetStableCurrencyId: Get<CurrencyId>;
+
+  /// Stable currency amount of a debit unit, if the rate of the collateral type isn't set.
+  type DefaultDebitExchangeRate: Get<Rate>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
 }
 
 /// A collateralized debit position.
@@ -50,6 +70,10 @@ decl_storage! {
     /// The total collateralized debit positions, map from
     /// CollateralType -> Position
     pub TotalPositions get(fn total_positions): map hasher(twox_64_concat) CurrencyId => Position;
+
+    /// Stable currency amount of a debit unit, map from
+    /// CollateralType -> Rate
+    pub DebitExchangeRate get(fn debit_exchange_rate): map hasher(twox_64_concat) CurrencyId => Option<Rate>;
   }
 }
 
@@ -59,6 +83,7 @@ decl_event!(
     Amount = Amount,
     Balance = Balance,
     CurrencyId = CurrencyId,
+    Rate = Rate,
   {
     /// Position updated. \[owner, collateral_type, collateral_adjustment, debit_adjustment\]
     PositionUpdated(AccountId, CurrencyId, Amount, Amount),
@@ -66,6 +91,8 @@ decl_event!(
     ConfiscateCollateralAndDebit(AccountId, CurrencyId, Balance, Balance),
     /// Transfer loan. \[from, to, currency_id\]
     TransferLoan(AccountId, AccountId, CurrencyId),
+    /// Debit exchange rate updated. \[collateral_type, debit_exchange_rate\]
+    DebitExchangeRateUpdated(CurrencyId, Option<Rate>),
   }
 );
 
@@ -87,6 +114,20 @@ decl_module! {
 
     /// The loan's module id, keep all collaterals of CDPs.
     const ModuleId: ModuleId = T::ModuleId::get();
+
+    /// Currency the debits are denominated in.
+    const GetStableCurrencyId: CurrencyId = T::GetStableCurrencyId::get();
+
+    /// Stable currency amount of a debit unit, if the rate of the collateral type isn't set.
+    const DefaultDebitExchangeRate: Rate = T::DefaultDebitExchangeRate::get();
+
+    /// Set the debit exchange rate of the collateral type, `None` uses the default.
+    #[weight = T::WeightInfo::set_debit_exchange_rate()]
+    fn set_debit_exchange_rate(origin, currency_id: CurrencyId, rate: Option<Rate>) {
+      ensure_root(origin)?;
+      DebitExchangeRate::mutate_exists(currency_id, |maybe_rate| *maybe_rate = rate);
+      Self::deposit_event(RawEvent::DebitExchangeRateUpdated(currency_id, rate));
+    }
   }
 }
 
@@ -95,6 +136,11 @@ impl<T: Trait> Module<T> {
     T::ModuleId::get().into_account()
   }
 
+  /// stable currency amount of a debit unit of the collateral type
+  pub fn get_debit_exchange_rate(currency_id: CurrencyId) -> Rate {
+    Self::debit_exchange_rate(currency_id).unwrap_or_else(T::DefaultDebitExchangeRate::get)
+  }
+
   /// adjust the position
   pub fn adjust_position(
     who: &T::AccountId,
@@ -128,26 +174,30 @@ impl<T: Trait> Module<T> {
 
   /// transfer whole loan of `from` to `to`
   pub fn transfer_loan(from: &T::AccountId, to: &T::AccountId, currency_id: CurrencyId) -> DispatchResult {
-    // get `from` position data
-    let Position { collateral, debit } = Self::positions(currency_id, from);
-
-    // balance -> amount
-    let collateral_adjustment = Self::amount_try_from_balance(collateral)?;
-    let debit_adjustment = Self::amount_try_from_balance(debit)?;
-
-    Self::update_loan(
-      from,
-      currency_id,
-      collateral_adjustment.saturating_neg(),
-      debit_adjustment.saturating_neg(),
-    )?;
-    Self::update_loan(to, currency_id, collateral_adjustment, debit_adjustment)?;
-
-    Self::deposit_event(RawEvent::TransferLoan(from.clone(), to.clone(), currency_id));
-    Ok(())
+    with_transaction_result(|| -> DispatchResult {
+      // use `with_transaction_result` to ensure operation is atomic
+      // get `from` position data
+      let Position { collateral, debit } = Self::positions(currency_id, from);
+
+      // balance -> amount
+      let collateral_adjustment = Self::amount_try_from_balance(collateral)?;
+      let debit_adjustment = Self::amount_try_from_balance(debit)?;
+
+      Self::update_loan(
+        from,
+        currency_id,
+        collateral_adjustment.saturating_neg(),
+        debit_adjustment.saturating_neg(),
+      )?;
+      Self::update_loan(to, currency_id, collateral_adjustment, debit_adjustment)?;
+
+      Self::deposit_event(RawEvent::TransferLoan(from.clone(), to.clone(), currency_id));
+      Ok(())
+    })
   }
 
-  /// mutate records of collaterals and debits
+  /// mutate records of collaterals and debits,
+  /// the hooks are called once the position and the total positions can be adjusted
   fn update_loan(
     who: &T::AccountId,
     currency_id: CurrencyId,
@@ -157,73 +207,39 @@ impl<T: Trait> Module<T> {
     let collateral_balance = Self::balance_try_from_amount_abs(collateral_adjustment)?;
     let debit_balance = Self::balance_try_from_amount_abs(debit_adjustment)?;
 
-    <Positions<T>>::try_mutate_exists(currency_id, who, |may_be_position| -> DispatchResult {
-      let mut p = may_be_position.take().unwrap_or_default();
-      let new_collateral = if collateral_adjustment.is_positive() {
-        p.collateral
-          .checked_add(collateral_balance)
-          .ok_or(Error::<T>::CollateralOverflow)
-      } else {
-        p.collateral
-          .checked_sub(collateral_balance)
-          .ok_or(Error::<T>::CollateralTooLow)
-      }?;
-      let new_debit = if debit_adjustment.is_positive() {
-        p.debit.checked_add(debit_balance).ok_or(Error::<T>::DebitOverflow)
-      } else {
-        p.debit.checked_sub(debit_balance).ok_or(Error::<T>::DebitTooLow)
-      }?;
-
-      // increase account ref if new position
-      if p.collateral.is_zero() && p.debit.is_zero() {
-        system::Module::<T>::inc_ref(who);
-      }
-
-      p.collateral = new_collateral;
-
-      // T::OnUpdateLoan::happened(&(who.clone(), currency_id, debit_adjustment, p.debit));
-      p.debit = new_debit;
-
-      if p.collateral.is_zero() && p.debit.is_zero() {
+    let position = Self::positions(currency_id, who);
+    let new_position = Position {
+      collateral: Self::adjust_collateral(position.collateral, collateral_adjustment, collateral_balance)?,
+      debit: Self::adjust_debit(position.debit, debit_adjustment, debit_balance)?,
+    };
+    let total_positions = Self::total_positions(currency_id);
+    let new_total_positions = Position {
+      collateral: Self::adjust_collateral(total_positions.collateral, collateral_adjustment, collateral_balance)?,
+      debit: Self::adjust_debit(total_positions.debit, debit_adjustment, debit_balance)?,
+    };
+
+    T::OnUpdateCollateral::happened(&(who.clone(), currency_id, collateral_adjustment, position.collateral));
+    T::OnUpdateLoan::happened(&(who.clone(), currency_id, debit_adjustment, position.debit));
+
+    let was_empty = position.collateral.is_zero() && position.debit.is_zero();
+    let is_empty = new_position.collateral.is_zero() && new_position.debit.is_zero();
+    if is_empty {
+      // remove position storage if zero position
+      <Positions<T>>::remove(currency_id, who);
+      if !was_empty {
         // decrease account ref if zero position
         system::Module::<T>::dec_ref(who);
-
-        // remove position storage if zero position
-        *may_be_position = None;
-      } else {
-        *may_be_position = Some(p);
       }
+    } else {
+      <Positions<T>>::insert(currency_id, who, new_position);
+      if was_empty {
+        // increase account ref if new position
+        system::Module::<T>::inc_ref(who);
+      }
+    }
+    TotalPositions::insert(currency_id, new_total_positions);
 
-      Ok(())
-    })?;
-
-    TotalPositions::try_mutate(currency_id, |total_positions| -> DispatchResult {
-      total_positions.collateral = if collateral_adjustment.is_positive() {
-        total_positions
-          .collateral
-          .checked_add(collateral_balance)
-          .ok_or(Error::<T>::CollateralOverflow)
-      } else {
-        total_positions
-          .collateral
-          .checked_sub(collateral_balance)
-          .ok_or(Error::<T>::CollateralTooLow)
-      }?;
-
-      total_positions.debit = if debit_adjustment.is_positive() {
-        total_positions
-          .debit
-          .checked_add(debit_balance)
-          .ok_or(Error::<T>::DebitOverflow)
-      } else {
-        total_positions
-          .debit
-          .checked_sub(debit_balance)
-          .ok_or(Error::<T>::DebitTooLow)
-      }?;
-
-      Ok(())
-    })
+    Ok(())
   }
 }
 
@@ -237,4 +253,22 @@ impl<T: Trait> Module<T> {
   fn balance_try_from_amount_abs(a: Amount) -> result::Result<Balance, Error<T>> {
     TryInto::<Balance>::try_into(a.saturating_abs()).map_err(|_| Error::<T>::AmountConvertFailed)
   }
+
+  /// Collateral after the `adjustment`, whose absolute value is `balance`.
+  fn adjust_collateral(collateral: Balance, adjustment: Amount, balance: Balance) -> result::Result<Balance, Error<T>> {
+    if adjustment.is_positive() {
+      collateral.checked_add(balance).ok_or(Error::<T>::CollateralOverflow)
+    } else {
+      collateral.checked_sub(balance).ok_or(Error::<T>::CollateralTooLow)
+    }
+  }
+
+  /// Debit after the `adjustment`, whose absolute value is `balance`.
+  fn adjust_debit(debit: Balance, adjustment: Amount, balance: Balance) -> result::Result<Balance, Error<T>> {
+    if adjustment.is_positive() {
+      debit.checked_add(balance).ok_or(Error::<T>::DebitOverflow)
+    } else {
+      debit.checked_sub(balance).ok_or(Error::<T>::DebitTooLow)
+    }
+  }
 }
@@ -0,0 +1,375 @@
+//! Clover Oracle Staking Module
+//!
+//! ##Overview
+//! Bonded oracle operators of `CloverOracle`.
+//! Operators bond CLV, the `MaxOperators` operators with the largest bonds of at least `MinOperatorBond`
+//! are the oracle members. At the start of each block the feeds of the previous block are checked against
+//! the combined price of the oracle: a feed within `MaxFeedDeviation` of it earns `FeedReward` from the
+//! reward pot, a feed outside of it is a strike, and `StrikeThreshold` strikes in a row slash `SlashRatio`
+//! of the bond of the operator.
+//! The reward pot is the account of `ModuleId`, funded at genesis and by transfers to it,
+//! runtimes can also route the slashed funds to it with `RewardPot`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+  decl_module, decl_error, decl_event, decl_storage, debug, ensure,
+  traits::{ChangeMembers, Currency, ExistenceRequirement, Get, OnUnbalanced, ReservableCurrency},
+  weights::Weight,
+  IterableStorageMap,
+};
+use frame_system::{self as system, ensure_signed};
+use orml_oracle::TimestampedValue;
+use orml_traits::{DataProviderExtended, Happened, OnNewData};
+use orml_utilities::with_transaction_result;
+use primitives::{CurrencyId, Moment, Price, Ratio};
+use sp_runtime::{
+  traits::{AccountIdConversion, CheckedDiv, Saturating, Zero},
+  ModuleId, Perbill, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+mod default_weight;
+mod mock;
+mod tests;
+
+pub type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+pub type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+pub trait WeightInfo {
+  fn bond(o: u32, ) -> Weight;
+  fn unbond(o: u32, ) -> Weight;
+  fn withdraw_unbonded() -> Weight;
+  fn check_feeds(f: u32, ) -> Weight;
+}
+
+/// Bond of an oracle operator.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct OperatorInfo<Balance, BlockNumber> {
+  /// active bond, counted for the membership
+  pub bond: Balance,
+  /// unbonded amount, still reserved until `unlock_at`
+  pub unbonding: Balance,
+  pub unlock_at: BlockNumber,
+  /// feeds deviating from the combined price in a row
+  pub strikes: u32,
+}
+
+pub trait Trait: system::Trait {
+  type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+  /// The currency bonded by the operators and paid as rewards.
+  type Currency: ReservableCurrency<Self::AccountId>;
+
+  /// The oracle whose feeds are checked.
+  type Oracle: DataProviderExtended<CurrencyId, TimestampedValue<Price, Moment>>;
+
+  /// Receives the member changes of the oracle.
+  type OnMembersChanged: ChangeMembers<Self::AccountId>;
+
+  /// The module id, the rewards are paid from its account.
+  type ModuleId: Get<ModuleId>;
+
+  /// Min bond of an oracle member.
+  type MinOperatorBond: Get<BalanceOf<Self>>;
+
+  /// Max number of oracle members.
+  type MaxOperators: Get<u32>;
+
+  /// Reward of a feed within the max deviation.
+  type FeedReward: Get<BalanceOf<Self>>;
+
+  /// Max deviation of a feed from the combined price.
+  type MaxFeedDeviation: Get<Ratio>;
+
+  /// Number of deviating feeds in a row which gets the operator slashed.
+  type StrikeThreshold: Get<u32>;
+
+  /// Part of the bond slashed.
+  type SlashRatio: Get<Perbill>;
+
+  /// Handler of the slashed funds.
+  type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+  /// Number of blocks the unbonded funds stay reserved.
+  type UnbondingPeriod: Get<Self::BlockNumber>;
+
+  /// Handler of the bonded funds, with (who, amount).
+  type OnBond: Happened<(Self::AccountId, BalanceOf<Self>)>;
+
+  /// Handler of the unbonded and slashed funds, with (who, amount).
+  type OnUnbond: Happened<(Self::AccountId, BalanceOf<Self>)>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+  trait Store for Module<T: Trait> as OracleStaking {
+    /// Bonds of the oracle operators
+    pub Operators get(fn operators): map hasher(twox_64_concat) T::AccountId => Option<OperatorInfo<BalanceOf<T>, T::BlockNumber>>;
+
+    /// Oracle members, the sorted operators with the largest bonds
+    pub Members get(fn members): Vec<T::AccountId>;
+
+    /// Feeds of the last block, checked at the start of the next block
+    BlockFeeds: Vec<(T::AccountId, CurrencyId, Price)>;
+  }
+}
+
+decl_event!(
+  pub enum Event<T> where
+    <T as system::Trait>::AccountId,
+    <T as system::Trait>::BlockNumber,
+    Balance = BalanceOf<T>,
+  {
+    /// Funds bonded. [who, amount]
+    Bonded(AccountId, Balance),
+    /// Funds unbonded, they can be withdrawn at the block. [who, amount, unlock_at]
+    Unbonded(AccountId, Balance, BlockNumber),
+    /// Unbonded funds withdrawn. [who, amount]
+    Withdrawn(AccountId, Balance),
+    /// Feed rewarded. [who, currency_id, reward]
+    FeedRewarded(AccountId, CurrencyId, Balance),
+    /// Feed deviated from the combined price. [who, currency_id, fed_price, combined_price, strikes]
+    FeedDeviated(AccountId, CurrencyId, Price, Price, u32),
+    /// Operator slashed for deviating feeds. [who, amount]
+    Slashed(AccountId, Balance),
+    /// Feed reward not paid, the reward pot doesn't have enough funds. [who, currency_id, reward]
+    RewardFailed(AccountId, CurrencyId, Balance),
+    /// Oracle members changed. [members]
+    MembersChanged(Vec<AccountId>),
+  }
+);
+
+decl_error! {
+  /// Error for oracle staking module.
+  pub enum Error for Module<T: Trait> {
+    /// amount is zero
+    InvalidAmount,
+    /// the first bond is less than the min operator bond
+    BondTooSmall,
+    /// the account is not an operator
+    NotOperator,
+    /// not enough bonded funds
+    BondNotEnough,
+    /// no unbonded funds
+    NothingToWithdraw,
+    /// the unbonding period hasn't ended
+    UnbondingNotFinished,
+  }
+}
+
+decl_module! {
+  pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+    type Error = Error<T>;
+    fn deposit_event() = default;
+
+    /// The module id, the rewards are paid from its account.
+    const ModuleId: ModuleId = T::ModuleId::get();
+
+    /// Min bond of an oracle member.
+    const MinOperatorBond: BalanceOf<T> = T::MinOperatorBond::get();
+
+    /// Max number of oracle members.
+    const MaxOperators: u32 = T::MaxOperators::get();
+
+    /// Reward of a feed within the max deviation.
+    const FeedReward: BalanceOf<T> = T::FeedReward::get();
+
+    /// Number of blocks the unbonded funds stay reserved.
+    const UnbondingPeriod: T::BlockNumber = T::UnbondingPeriod::get();
+
+    fn on_initialize() -> Weight {
+      let feeds = BlockFeeds::<T>::take();
+      let weight = T::WeightInfo::check_feeds(feeds.len() as u32);
+      Self::check_feeds(feeds);
+      weight
+    }
+
+    /// Bond funds, the operators with the largest bonds become oracle members.
+    #[weight = T::WeightInfo::bond(T::MaxOperators::get())]
+    pub fn bond(origin, #[compact] amount: BalanceOf<T>) {
+      let who = ensure_signed(origin)?;
+      ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+      with_transaction_result(|| {
+        let mut info = match Self::operators(&who) {
+          Some(info) => info,
+          None => {
+            ensure!(amount >= T::MinOperatorBond::get(), Error::<T>::BondTooSmall);
+            OperatorInfo {
+              bond: Zero::zero(),
+              unbonding: Zero::zero(),
+              unlock_at: Zero::zero(),
+              strikes: 0,
+            }
+          },
+        };
+        T::Currency::reserve(&who, amount)?;
+        info.bond = info.bond.saturating_add(amount);
+        Operators::<T>::insert(&who, info);
+        T::OnBond::happened(&(who.clone(), amount));
+        Self::deposit_event(RawEvent::Bonded(who, amount));
+        Self::update_members();
+        Ok(())
+      })?;
+    }
+
+    /// Unbond funds, they can be withdrawn after the unbonding period.
+    /// Unbonding again before the period ends restarts it.
+    #[weight = T::WeightInfo::unbond(T::MaxOperators::get())]
+    pub fn unbond(origin, #[compact] amount: BalanceOf<T>) {
+      let who = ensure_signed(origin)?;
+      ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+      let mut info = Self::operators(&who).ok_or(Error::<T>::NotOperator)?;
+      ensure!(info.bond >= amount, Error::<T>::BondNotEnough);
+
+      let unlock_at = <system::Module<T>>::block_number().saturating_add(T::UnbondingPeriod::get());
+      info.bond = info.bond.saturating_sub(amount);
+      info.unbonding = info.unbonding.saturating_add(amount);
+      info.unlock_at = unlock_at;
+      Operators::<T>::insert(&who, info);
+      T::OnUnbond::happened(&(who.clone(), amount));
+      Self::deposit_event(RawEvent::Unbonded(who, amount, unlock_at));
+      Self::update_members();
+    }
+
+    /// Withdraw the unbonded funds after the unbonding period.
+    #[weight = T::WeightInfo::withdraw_unbonded()]
+    pub fn withdraw_unbonded(origin) {
+      let who = ensure_signed(origin)?;
+      let mut info = Self::operators(&who).ok_or(Error::<T>::NotOperator)?;
+      ensure!(!info.unbonding.is_zero(), Error::<T>::NothingToWithdraw);
+      ensure!(info.unlock_at <= <system::Module<T>>::block_number(), Error::<T>::UnbondingNotFinished);
+
+      let amount = info.unbonding;
+      T::Currency::unreserve(&who, amount);
+      info.unbonding = Zero::zero();
+      if info.bond.is_zero() {
+        Operators::<T>::remove(&who);
+      } else {
+        Operators::<T>::insert(&who, info);
+      }
+      Self::deposit_event(RawEvent::Withdrawn(who, amount));
+    }
+  }
+}
+
+impl<T: Trait> Module<T> {
+  /// the account paying the rewards
+  pub fn account_id() -> T::AccountId {
+    T::ModuleId::get().into_account()
+  }
+
+  /// reward the feeds close to the combined price and strike the others
+  fn check_feeds(feeds: Vec<(T::AccountId, CurrencyId, Price)>) {
+    let mut slashed = false;
+    for (who, currency_id, price) in feeds {
+      let combined = match T::Oracle::get_no_op(&currency_id) {
+        Some(combined) => combined.value,
+        None => continue,
+      };
+      let mut info = match Self::operators(&who) {
+        Some(info) => info,
+        None => continue,
+      };
+
+      let deviation = if price > combined {
+        price.saturating_sub(combined)
+      } else {
+        combined.saturating_sub(price)
+      }.checked_div(&combined).unwrap_or_else(Zero::zero);
+
+      if deviation <= T::MaxFeedDeviation::get() {
+        info.strikes = 0;
+        Self::reward(&who, currency_id);
+      } else {
+        info.strikes = info.strikes.saturating_add(1);
+        Self::deposit_event(RawEvent::FeedDeviated(who.clone(), currency_id, price, combined, info.strikes));
+        if info.strikes >= T::StrikeThreshold::get() {
+          info.strikes = 0;
+          let amount = Self::slash(&who, T::SlashRatio::get() * info.bond);
+          info.bond = info.bond.saturating_sub(amount);
+          slashed = true;
+        }
+      }
+      Operators::<T>::insert(&who, info);
+    }
+
+    if slashed {
+      Self::update_members();
+    }
+  }
+
+  fn reward(who: &T::AccountId, currency_id: CurrencyId) {
+    let reward = T::FeedReward::get();
+    if reward.is_zero() {
+      return;
+    }
+    match T::Currency::transfer(&Self::account_id(), who, reward, ExistenceRequirement::KeepAlive) {
+      Ok(_) => Self::deposit_event(RawEvent::FeedRewarded(who.clone(), currency_id, reward)),
+      Err(e) => {
+        debug::warn!("failed to reward oracle feed of {:?}: {:?}", who, e);
+        Self::deposit_event(RawEvent::RewardFailed(who.clone(), currency_id, reward));
+      },
+    }
+  }
+
+  /// slash the bond of the operator, returns the slashed amount
+  fn slash(who: &T::AccountId, amount: BalanceOf<T>) -> BalanceOf<T> {
+    let (imbalance, remaining) = T::Currency::slash_reserved(who, amount);
+    T::Slash::on_unbalanced(imbalance);
+    let slashed = amount.saturating_sub(remaining);
+    T::OnUnbond::happened(&(who.clone(), slashed));
+    Self::deposit_event(RawEvent::Slashed(who.clone(), slashed));
+    slashed
+  }
+
+  /// make the operators with the largest bonds the oracle members
+  fn update_members() {
+    let min_bond = T::MinOperatorBond::get();
+    let mut candidates: Vec<(T::AccountId, BalanceOf<T>)> = Operators::<T>::iter()
+      .filter(|(_, info)| info.bond >= min_bond)
+      .map(|(who, info)| (who, info.bond))
+      .collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+    candidates.truncate(T::MaxOperators::get() as usize);
+
+    let mut members: Vec<T::AccountId> = candidates.into_iter().map(|(who, _)| who).collect();
+    members.sort();
+    let old_members = Self::members();
+    if members == old_members {
+      return;
+    }
+
+    let incoming: Vec<T::AccountId> = members.iter()
+      .filter(|who| old_members.binary_search(who).is_err())
+      .cloned()
+      .collect();
+    let outgoing: Vec<T::AccountId> = old_members.iter()
+      .filter(|who| members.binary_search(who).is_err())
+      .cloned()
+      .collect();
+    T::OnMembersChanged::change_members_sorted(&incoming, &outgoing, &members);
+    Members::<T>::put(&members);
+    Self::deposit_event(RawEvent::MembersChanged(members));
+  }
+}
+
+/// Adds the funds to the reward pot, e.g. to pay the feed rewards with the slashed bonds.
+pub struct RewardPot<T>(sp_std::marker::PhantomData<T>);
+impl<T: Trait> OnUnbalanced<NegativeImbalanceOf<T>> for RewardPot<T> {
+  fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T>) {
+    T::Currency::resolve_creating(&Module::<T>::account_id(), amount);
+  }
+}
+
+/// Records the feeds of the oracle, to be checked in the next block.
+impl<T: Trait> OnNewData<T::AccountId, CurrencyId, Price> for Module<T> {
+  fn on_new_data(who: &T::AccountId, key: &CurrencyId, value: &Price) {
+    if Operators::<T>::contains_key(who) {
+      BlockFeeds::<T>::append((who.clone(), *key, *value));
+    }
+  }
+}
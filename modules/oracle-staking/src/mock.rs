@@ -0,0 +1,192 @@
+#![cfg(test)]
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, FixedPointNumber, Perbill};
+use sp_std::cell::RefCell;
+use std::collections::HashMap;
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+pub type Balance = u128;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CHARLIE: AccountId = 3;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+
+mod clover_oracle_staking {
+  pub use super::super::*;
+}
+
+impl_outer_event! {
+  pub enum TestEvent for TestRuntime {
+    frame_system<T>,
+    pallet_balances<T>,
+    clover_oracle_staking<T>,
+  }
+}
+
+impl_outer_origin! {
+  pub enum Origin for TestRuntime {}
+}
+
+parameter_types! {
+  pub const BlockHashCount: u64 = 250;
+  pub const MaximumBlockWeight: u32 = 1024;
+  pub const MaximumBlockLength: u32 = 2 * 1024;
+  pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for TestRuntime {
+  type Origin = Origin;
+  type Index = u64;
+  type BlockNumber = BlockNumber;
+  type Call = ();
+  type Hash = H256;
+  type Hashing = ::sp_runtime::traits::BlakeTwo256;
+  type AccountId = AccountId;
+  type Lookup = IdentityLookup<Self::AccountId>;
+  type Header = Header;
+  type Event = TestEvent;
+  type BlockHashCount = BlockHashCount;
+  type MaximumBlockWeight = MaximumBlockWeight;
+  type MaximumBlockLength = MaximumBlockLength;
+  type AvailableBlockRatio = AvailableBlockRatio;
+  type Version = ();
+  type PalletInfo = ();
+  type AccountData = pallet_balances::AccountData<Balance>;
+  type OnNewAccount = ();
+  type OnKilledAccount = ();
+  type DbWeight = ();
+  type BlockExecutionWeight = ();
+  type ExtrinsicBaseWeight = ();
+  type MaximumExtrinsicWeight = ();
+  type BaseCallFilter = ();
+  type SystemWeightInfo = ();
+}
+
+pub type System = frame_system::Module<TestRuntime>;
+
+parameter_types! {
+  pub const ExistentialDeposit: Balance = 1;
+  pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Trait for TestRuntime {
+  type Balance = Balance;
+  type Event = TestEvent;
+  type DustRemoval = ();
+  type ExistentialDeposit = ExistentialDeposit;
+  type AccountStore = System;
+  type MaxLocks = MaxLocks;
+  type WeightInfo = ();
+}
+
+pub type Balances = pallet_balances::Module<TestRuntime>;
+
+thread_local! {
+  static ORACLE_PRICES: RefCell<HashMap<CurrencyId, Price>> = RefCell::new(HashMap::new());
+  /// members of the oracle
+  pub static ORACLE_MEMBERS: RefCell<Vec<AccountId>> = RefCell::new(vec![]);
+}
+
+pub fn set_oracle_price(currency_id: CurrencyId, price: Price) {
+  ORACLE_PRICES.with(|v| v.borrow_mut().insert(currency_id, price));
+}
+
+pub fn oracle_members() -> Vec<AccountId> {
+  ORACLE_MEMBERS.with(|v| v.borrow().clone())
+}
+
+pub struct MockOracle;
+impl DataProviderExtended<CurrencyId, TimestampedValue<Price, Moment>> for MockOracle {
+  fn get_no_op(key: &CurrencyId) -> Option<TimestampedValue<Price, Moment>> {
+    ORACLE_PRICES.with(|v| v.borrow().get(key).map(|value| TimestampedValue { value: *value, timestamp: 0 }))
+  }
+
+  fn get_all_values() -> Vec<(CurrencyId, Option<TimestampedValue<Price, Moment>>)> {
+    vec![]
+  }
+}
+
+pub struct MockMembers;
+impl ChangeMembers<AccountId> for MockMembers {
+  fn change_members_sorted(_incoming: &[AccountId], _outgoing: &[AccountId], new: &[AccountId]) {
+    ORACLE_MEMBERS.with(|v| *v.borrow_mut() = new.to_vec());
+  }
+}
+
+parameter_types! {
+  pub const OracleStakingModuleId: ModuleId = ModuleId(*b"clv/orcl");
+  pub const MinOperatorBond: Balance = 100;
+  pub const MaxOperators: u32 = 2;
+  pub const FeedReward: Balance = 10;
+  pub MaxFeedDeviation: Ratio = Ratio::saturating_from_rational(5, 100);
+  pub const StrikeThreshold: u32 = 2;
+  pub const SlashRatio: Perbill = Perbill::from_percent(10);
+  pub const UnbondingPeriod: BlockNumber = 10;
+}
+
+impl Trait for TestRuntime {
+  type Event = TestEvent;
+  type Currency = Balances;
+  type Oracle = MockOracle;
+  type OnMembersChanged = MockMembers;
+  type ModuleId = OracleStakingModuleId;
+  type MinOperatorBond = MinOperatorBond;
+  type MaxOperators = MaxOperators;
+  type FeedReward = FeedReward;
+  type MaxFeedDeviation = MaxFeedDeviation;
+  type StrikeThreshold = StrikeThreshold;
+  type SlashRatio = SlashRatio;
+  type Slash = RewardPot<TestRuntime>;
+  type UnbondingPeriod = UnbondingPeriod;
+  type OnBond = ();
+  type OnUnbond = ();
+  type WeightInfo = ();
+}
+
+pub type OracleStaking = Module<TestRuntime>;
+
+pub struct ExtBuilder {
+  reward_pot: Balance,
+}
+
+impl Default for ExtBuilder {
+  fn default() -> Self {
+    Self { reward_pot: 1_000 }
+  }
+}
+
+impl ExtBuilder {
+  pub fn reward_pot(mut self, reward_pot: Balance) -> Self {
+    self.reward_pot = reward_pot;
+    self
+  }
+
+  pub fn build(self) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+      .build_storage::<TestRuntime>()
+      .unwrap();
+
+    let mut balances = vec![(ALICE, 1_000), (BOB, 1_000), (CHARLIE, 1_000)];
+    if !self.reward_pot.is_zero() {
+      balances.push((OracleStaking::account_id(), self.reward_pot));
+    }
+    pallet_balances::GenesisConfig::<TestRuntime> { balances }
+      .assimilate_storage(&mut t)
+      .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+      System::set_block_number(1);
+      ORACLE_PRICES.with(|v| v.borrow_mut().clear());
+      ORACLE_MEMBERS.with(|v| v.borrow_mut().clear());
+    });
+    ext
+  }
+}
@@ -0,0 +1,160 @@
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use mock::{
+  oracle_members, set_oracle_price, Balances, ExtBuilder, OracleStaking, Origin, System, TestEvent, TestRuntime, ALICE,
+  BOB, CHARLIE, DOT,
+};
+use sp_runtime::FixedPointNumber;
+
+fn price(n: u128) -> Price {
+  Price::saturating_from_integer(n)
+}
+
+fn has_event(event: RawEvent<u64, u64, u128>) -> bool {
+  System::events().iter().any(|record| record.event == TestEvent::clover_oracle_staking(event.clone()))
+}
+
+/// feed the price and check the feeds at the start of the next block
+fn feed(who: u64, fed_price: Price) {
+  OracleStaking::on_new_data(&who, &DOT, &fed_price);
+  let next = System::block_number() + 1;
+  System::set_block_number(next);
+  OracleStaking::on_initialize(next);
+}
+
+#[test]
+fn bond_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_noop!(OracleStaking::bond(Origin::signed(ALICE), 0), Error::<TestRuntime>::InvalidAmount);
+    assert_noop!(OracleStaking::bond(Origin::signed(ALICE), 99), Error::<TestRuntime>::BondTooSmall);
+    assert_noop!(
+      OracleStaking::bond(Origin::signed(ALICE), 1_001),
+      pallet_balances::Error::<TestRuntime>::InsufficientBalance
+    );
+
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 100));
+    assert_eq!(Balances::reserved_balance(ALICE), 100);
+    assert_eq!(OracleStaking::operators(ALICE).map(|info| info.bond), Some(100));
+    assert!(has_event(RawEvent::Bonded(ALICE, 100)));
+    assert_eq!(OracleStaking::members(), vec![ALICE]);
+    assert_eq!(oracle_members(), vec![ALICE]);
+    assert!(has_event(RawEvent::MembersChanged(vec![ALICE])));
+
+    // operators can add less than the min bond
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 1));
+    assert_eq!(Balances::reserved_balance(ALICE), 101);
+  });
+}
+
+#[test]
+fn members_are_the_operators_with_the_largest_bonds() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 100));
+    assert_ok!(OracleStaking::bond(Origin::signed(BOB), 200));
+    assert_ok!(OracleStaking::bond(Origin::signed(CHARLIE), 300));
+    assert_eq!(OracleStaking::members(), vec![BOB, CHARLIE]);
+    assert_eq!(oracle_members(), vec![BOB, CHARLIE]);
+
+    // the bond of charlie is less than the min bond after unbonding
+    assert_ok!(OracleStaking::unbond(Origin::signed(CHARLIE), 250));
+    assert_eq!(OracleStaking::members(), vec![ALICE, BOB]);
+    assert_eq!(oracle_members(), vec![ALICE, BOB]);
+  });
+}
+
+#[test]
+fn unbond_and_withdraw_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_noop!(OracleStaking::unbond(Origin::signed(ALICE), 10), Error::<TestRuntime>::NotOperator);
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 100));
+    assert_noop!(OracleStaking::unbond(Origin::signed(ALICE), 101), Error::<TestRuntime>::BondNotEnough);
+    assert_noop!(OracleStaking::withdraw_unbonded(Origin::signed(ALICE)), Error::<TestRuntime>::NothingToWithdraw);
+
+    assert_ok!(OracleStaking::unbond(Origin::signed(ALICE), 100));
+    assert!(has_event(RawEvent::Unbonded(ALICE, 100, 11)));
+    assert_eq!(OracleStaking::members(), Vec::<u64>::new());
+    // the unbonded funds stay reserved until the unbonding period ends
+    assert_eq!(Balances::reserved_balance(ALICE), 100);
+    assert_noop!(OracleStaking::withdraw_unbonded(Origin::signed(ALICE)), Error::<TestRuntime>::UnbondingNotFinished);
+
+    System::set_block_number(11);
+    assert_ok!(OracleStaking::withdraw_unbonded(Origin::signed(ALICE)));
+    assert_eq!(Balances::reserved_balance(ALICE), 0);
+    assert_eq!(Balances::free_balance(ALICE), 1_000);
+    assert_eq!(OracleStaking::operators(ALICE), None);
+    assert!(has_event(RawEvent::Withdrawn(ALICE, 100)));
+  });
+}
+
+#[test]
+fn feeds_close_to_the_combined_price_are_rewarded() {
+  ExtBuilder::default().build().execute_with(|| {
+    set_oracle_price(DOT, price(10));
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 100));
+
+    feed(ALICE, Price::saturating_from_rational(104, 10));
+    assert_eq!(Balances::free_balance(ALICE), 910);
+    assert_eq!(Balances::free_balance(OracleStaking::account_id()), 990);
+    assert!(has_event(RawEvent::FeedRewarded(ALICE, DOT, 10)));
+
+    // feeds of accounts which aren't operators are ignored
+    feed(BOB, price(10));
+    assert_eq!(Balances::free_balance(BOB), 1_000);
+  });
+}
+
+#[test]
+fn reward_failure_is_reported() {
+  ExtBuilder::default().reward_pot(0).build().execute_with(|| {
+    set_oracle_price(DOT, price(10));
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 100));
+
+    feed(ALICE, price(10));
+    assert_eq!(Balances::free_balance(ALICE), 900);
+    assert!(has_event(RawEvent::RewardFailed(ALICE, DOT, 10)));
+  });
+}
+
+#[test]
+fn deviating_feeds_are_struck_and_slashed() {
+  ExtBuilder::default().build().execute_with(|| {
+    set_oracle_price(DOT, price(10));
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 200));
+
+    feed(ALICE, price(12));
+    assert_eq!(OracleStaking::operators(ALICE).map(|info| info.strikes), Some(1));
+    assert!(has_event(RawEvent::FeedDeviated(ALICE, DOT, price(12), price(10), 1)));
+
+    // a good feed resets the strikes
+    feed(ALICE, price(10));
+    assert_eq!(OracleStaking::operators(ALICE).map(|info| info.strikes), Some(0));
+
+    feed(ALICE, price(12));
+    feed(ALICE, price(8));
+    // 10% of the bond is slashed to the reward pot
+    let info = OracleStaking::operators(ALICE).unwrap();
+    assert_eq!((info.bond, info.strikes), (180, 0));
+    assert_eq!(Balances::reserved_balance(ALICE), 180);
+    assert_eq!(Balances::free_balance(OracleStaking::account_id()), 1_000 - 10 + 20);
+    assert!(has_event(RawEvent::Slashed(ALICE, 20)));
+    assert_eq!(OracleStaking::members(), vec![ALICE]);
+  });
+}
+
+#[test]
+fn slashed_operators_below_the_min_bond_lose_the_membership() {
+  ExtBuilder::default().build().execute_with(|| {
+    set_oracle_price(DOT, price(10));
+    assert_ok!(OracleStaking::bond(Origin::signed(ALICE), 100));
+    assert_ok!(OracleStaking::bond(Origin::signed(BOB), 100));
+    assert_eq!(OracleStaking::members(), vec![ALICE, BOB]);
+
+    feed(BOB, price(20));
+    feed(BOB, price(20));
+    assert_eq!(OracleStaking::operators(BOB).map(|info| info.bond), Some(90));
+    assert_eq!(OracleStaking::members(), vec![ALICE]);
+    assert_eq!(oracle_members(), vec![ALICE]);
+  });
+}
@@ -0,0 +1,235 @@
+//! Clover Price Feeder Module
+//!
+//! ##Overview
+//! Offchain worker feeding `CloverOracle` with prices fetched from http endpoints, on behalf of
+//! the oracle member whose key is in the local keystore under the `orac` key type.
+//!
+//! The endpoints are read from the persistent offchain storage under `ENDPOINTS_KEY`, as a SCALE
+//! encoded `Vec<(CurrencyId, Vec<Vec<u8>>)>` of each currency and the urls of its endpoints,
+//! which can be set with the `offchain_localStorageSet` rpc. An endpoint responds with the USD price
+//! of the currency as a plain decimal number, e.g. `12.34`.
+//!
+//! Prices deviating more than `MaxSourceDeviation` from the median of the endpoints are rejected,
+//! the mean of the rest is fed if at least `MinSources` endpoints agree.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{debug, decl_module, traits::Get};
+use frame_system::offchain::{AppCrypto, CreateSignedTransaction, ForAny, SendSignedTransaction, Signer};
+use orml_oracle::Instance1;
+use orml_utilities::OffchainErr;
+use primitives::{CurrencyId, Price, Ratio};
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+  offchain::{http, storage::StorageValueRef, Duration},
+  traits::{CheckedDiv, Saturating, Zero},
+  FixedPointNumber,
+};
+use sp_std::prelude::*;
+
+mod mock;
+mod tests;
+
+/// Key type of the oracle member keys.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"orac");
+
+/// Offchain storage key of the price endpoints.
+pub const ENDPOINTS_KEY: &[u8] = b"clover/price-feeder/endpoints";
+
+/// Offchain storage key of the block of the last feed.
+const LAST_FEED_KEY: &[u8] = b"clover/price-feeder/last-feed";
+
+/// Offchain storage key of the lock held by the worker feeding the prices.
+const FEED_LOCK_KEY: &[u8] = b"clover/price-feeder/feed-lock";
+
+/// Timeout of a price request, in milliseconds.
+const HTTP_TIMEOUT: u64 = 5_000;
+
+pub mod crypto {
+  use super::KEY_TYPE;
+  use sp_runtime::{
+    app_crypto::{app_crypto, sr25519},
+    MultiSignature, MultiSigner,
+  };
+  app_crypto!(sr25519, KEY_TYPE);
+
+  /// Signs the feeds with the sr25519 oracle key.
+  pub struct OracleAuthId;
+
+  impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleAuthId {
+    type RuntimeAppPublic = Public;
+    type GenericSignature = sp_core::sr25519::Signature;
+    type GenericPublic = sp_core::sr25519::Public;
+  }
+}
+
+pub trait Trait:
+  CreateSignedTransaction<orml_oracle::Call<Self, Instance1>>
+  + orml_oracle::Config<Instance1, OracleKey = CurrencyId, OracleValue = Price>
+{
+  /// Key of the oracle member signing the feeds.
+  type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+  /// Blocks between two feeds.
+  type FeedInterval: Get<Self::BlockNumber>;
+
+  /// Max deviation of the price of an endpoint from the median price of all the endpoints.
+  type MaxSourceDeviation: Get<Ratio>;
+
+  /// Min count of endpoints within the max deviation for the price to be fed.
+  type MinSources: Get<u32>;
+}
+
+decl_module! {
+  pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+    const FeedInterval: T::BlockNumber = T::FeedInterval::get();
+    const MaxSourceDeviation: Ratio = T::MaxSourceDeviation::get();
+    const MinSources: u32 = T::MinSources::get();
+
+    fn offchain_worker(now: T::BlockNumber) {
+      if let Err(e) = Self::feed_prices(now) {
+        debug::info!("price feeder at block {:?}: {:?}", now, e);
+      }
+    }
+  }
+}
+
+impl<T: Trait> Module<T> {
+  fn feed_prices(now: T::BlockNumber) -> Result<(), OffchainErr> {
+    let signer = Signer::<T, T::AuthorityId>::any_account();
+    if !signer.can_sign() {
+      return Err(OffchainErr::NotValidator);
+    }
+
+    let endpoints = match StorageValueRef::persistent(ENDPOINTS_KEY).get::<Vec<(CurrencyId, Vec<Vec<u8>>)>>() {
+      Some(Some(endpoints)) => endpoints,
+      Some(None) => return Err(OffchainErr::OffchainStore),
+      None => return Ok(()),
+    };
+
+    // feed once per interval
+    let last_feed = StorageValueRef::persistent(LAST_FEED_KEY);
+    if let Some(Some(last)) = last_feed.get::<T::BlockNumber>() {
+      if now < last.saturating_add(T::FeedInterval::get()) {
+        return Err(OffchainErr::OffchainLock);
+      }
+    }
+
+    // keeps overlapping workers from feeding twice,
+    // expires after an interval in case a worker didn't release it
+    let mut lock = StorageValueRef::persistent(FEED_LOCK_KEY);
+    match lock.mutate(|locked: Option<Option<T::BlockNumber>>| match locked {
+      Some(Some(locked_at)) if now < locked_at.saturating_add(T::FeedInterval::get()) => Err(()),
+      _ => Ok(now),
+    }) {
+      Ok(Ok(_)) => {},
+      Ok(Err(_)) => return Err(OffchainErr::OffchainLock),
+      Err(_) => return Ok(()),
+    }
+
+    let result = Self::fetch_and_feed(&signer, endpoints);
+    // the feed interval only starts once the prices are fed, a failed feed is retried in the next block
+    if let Ok(true) = result {
+      last_feed.set(&now);
+    }
+    lock.clear();
+    result.map(|_| ())
+  }
+
+  /// Fetch the prices of the endpoints and feed them, returns whether any price was fed.
+  fn fetch_and_feed(signer: &Signer<T, T::AuthorityId, ForAny>, endpoints: Vec<(CurrencyId, Vec<Vec<u8>>)>) -> Result<bool, OffchainErr> {
+    let values: Vec<(CurrencyId, Price)> = endpoints
+      .into_iter()
+      .filter_map(|(currency_id, urls)| {
+        let prices = urls
+          .iter()
+          .filter_map(|url| {
+            fetch_price(url)
+              .map_err(|e| debug::warn!("failed to fetch {:?} price: {:?}", currency_id, e))
+              .ok()
+          })
+          .collect();
+        aggregate_prices(prices, T::MaxSourceDeviation::get(), T::MinSources::get()).map(|price| (currency_id, price))
+      })
+      .collect();
+    if values.is_empty() {
+      return Ok(false);
+    }
+
+    debug::info!("feeding prices: {:?}", values);
+    match signer.send_signed_transaction(|_| orml_oracle::Call::<T, Instance1>::feed_values(values.clone())) {
+      Some((_, Ok(()))) => Ok(true),
+      _ => Err(OffchainErr::SubmitTransaction),
+    }
+  }
+}
+
+/// Fetch the price from the endpoint.
+pub fn fetch_price(url: &[u8]) -> Result<Price, http::Error> {
+  let url = sp_std::str::from_utf8(url).map_err(|_| http::Error::Unknown)?;
+  let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(HTTP_TIMEOUT));
+  let pending = http::Request::get(url)
+    .deadline(deadline)
+    .send()
+    .map_err(|_| http::Error::IoError)?;
+  let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+  if response.code != 200 {
+    return Err(http::Error::Unknown);
+  }
+  parse_price(&response.body().collect::<Vec<u8>>()).ok_or(http::Error::Unknown)
+}
+
+/// Parse a positive decimal price, digits beyond the precision of `Price` are truncated.
+pub fn parse_price(body: &[u8]) -> Option<Price> {
+  let body = sp_std::str::from_utf8(body).ok()?.trim();
+  let mut parts = body.splitn(2, '.');
+  let integer = parts.next()?;
+  let fraction = parts.next().unwrap_or("");
+  if integer.is_empty() || !integer.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+    return None;
+  }
+
+  let mut inner = integer.parse::<u128>().ok()?.checked_mul(Price::accuracy())?;
+  let mut unit = Price::accuracy();
+  for digit in fraction.bytes() {
+    unit /= 10;
+    if unit.is_zero() {
+      break;
+    }
+    inner = inner.checked_add(u128::from(digit - b'0') * unit)?;
+  }
+
+  let price = Price::from_inner(inner);
+  if price.is_zero() {
+    None
+  } else {
+    Some(price)
+  }
+}
+
+/// Mean of the prices within `max_deviation` of their median,
+/// `None` if less than `min_sources` of them are.
+pub fn aggregate_prices(mut prices: Vec<Price>, max_deviation: Ratio, min_sources: u32) -> Option<Price> {
+  if prices.is_empty() {
+    return None;
+  }
+  prices.sort();
+  let median = prices[prices.len() / 2];
+
+  let accepted: Vec<Price> = prices
+    .into_iter()
+    .filter(|price| {
+      let deviation = if *price > median {
+        price.saturating_sub(median)
+      } else {
+        median.saturating_sub(*price)
+      };
+      deviation.checked_div(&median).map_or(false, |deviation| deviation <= max_deviation)
+    })
+    .collect();
+  if (accepted.len() as u32) < min_sources.max(1) {
+    return None;
+  }
+
+  let sum = accepted.iter().fold(Price::zero(), |sum, price| sum.saturating_add(*price));
+  sum.checked_div(&Price::saturating_from_integer(accepted.len() as u128))
+}
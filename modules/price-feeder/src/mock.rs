@@ -0,0 +1,142 @@
+#![cfg(test)]
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types, traits::Time};
+use frame_system::offchain::{SendTransactionTypes, SigningTypes};
+use sp_core::H256;
+use sp_runtime::{
+  testing::{Header, TestXt},
+  traits::{Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+  MultiSignature, MultiSigner, Perbill,
+};
+use sp_std::cell::RefCell;
+
+pub type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+pub type BlockNumber = u64;
+pub type OracleCall = orml_oracle::Call<TestRuntime, Instance1>;
+pub type Extrinsic = TestXt<OracleCall, ()>;
+
+pub const DOT: CurrencyId = CurrencyId::DOT;
+pub const CETH: CurrencyId = CurrencyId::CETH;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+
+impl_outer_event! {
+  pub enum TestEvent for TestRuntime {
+    frame_system<T>,
+    orml_oracle Instance1<T>,
+  }
+}
+
+impl_outer_origin! {
+  pub enum Origin for TestRuntime {}
+}
+
+parameter_types! {
+  pub const BlockHashCount: u64 = 250;
+  pub const MaximumBlockWeight: u32 = 1024;
+  pub const MaximumBlockLength: u32 = 2 * 1024;
+  pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for TestRuntime {
+  type Origin = Origin;
+  type Index = u64;
+  type BlockNumber = BlockNumber;
+  type Call = ();
+  type Hash = H256;
+  type Hashing = ::sp_runtime::traits::BlakeTwo256;
+  type AccountId = AccountId;
+  type Lookup = IdentityLookup<Self::AccountId>;
+  type Header = Header;
+  type Event = TestEvent;
+  type BlockHashCount = BlockHashCount;
+  type MaximumBlockWeight = MaximumBlockWeight;
+  type MaximumBlockLength = MaximumBlockLength;
+  type AvailableBlockRatio = AvailableBlockRatio;
+  type Version = ();
+  type PalletInfo = ();
+  type AccountData = ();
+  type OnNewAccount = ();
+  type OnKilledAccount = ();
+  type DbWeight = ();
+  type BlockExecutionWeight = ();
+  type ExtrinsicBaseWeight = ();
+  type MaximumExtrinsicWeight = ();
+  type BaseCallFilter = ();
+  type SystemWeightInfo = ();
+}
+
+thread_local! {
+  /// whether signing the feeds fails
+  static REJECT_TRANSACTIONS: RefCell<bool> = RefCell::new(false);
+}
+
+pub fn set_reject_transactions(reject: bool) {
+  REJECT_TRANSACTIONS.with(|v| *v.borrow_mut() = reject);
+}
+
+impl SigningTypes for TestRuntime {
+  type Public = MultiSigner;
+  type Signature = MultiSignature;
+}
+
+impl SendTransactionTypes<OracleCall> for TestRuntime {
+  type OverarchingCall = OracleCall;
+  type Extrinsic = Extrinsic;
+}
+
+impl CreateSignedTransaction<OracleCall> for TestRuntime {
+  fn create_transaction<C: AppCrypto<Self::Public, Self::Signature>>(
+    call: OracleCall,
+    _public: MultiSigner,
+    _account: AccountId,
+    nonce: u64,
+  ) -> Option<(OracleCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+    if REJECT_TRANSACTIONS.with(|v| *v.borrow()) {
+      return None;
+    }
+    Some((call, (nonce, ())))
+  }
+}
+
+pub struct MockTime;
+impl Time for MockTime {
+  type Moment = primitives::Moment;
+
+  fn now() -> Self::Moment {
+    0
+  }
+}
+
+parameter_types! {
+  pub const MinimumCount: u32 = 1;
+  pub const ExpiresIn: primitives::Moment = 600;
+  pub const RootOperatorAccountId: AccountId = AccountId::new([0u8; 32]);
+}
+
+impl orml_oracle::Config<Instance1> for TestRuntime {
+  type Event = TestEvent;
+  type OnNewData = ();
+  type CombineData = orml_oracle::DefaultCombineData<TestRuntime, MinimumCount, ExpiresIn, Instance1>;
+  type Time = MockTime;
+  type OracleKey = CurrencyId;
+  type OracleValue = Price;
+  type RootOperatorAccountId = RootOperatorAccountId;
+  type WeightInfo = ();
+}
+
+parameter_types! {
+  pub const FeedInterval: BlockNumber = 5;
+  pub MaxSourceDeviation: Ratio = Ratio::saturating_from_rational(1, 10);
+  pub const MinSources: u32 = 2;
+}
+
+impl Trait for TestRuntime {
+  type AuthorityId = crypto::OracleAuthId;
+  type FeedInterval = FeedInterval;
+  type MaxSourceDeviation = MaxSourceDeviation;
+  type MinSources = MinSources;
+}
+
+pub type PriceFeeder = Module<TestRuntime>;
@@ -0,0 +1,169 @@
+#![cfg(test)]
+
+use super::*;
+use mock::{set_reject_transactions, Extrinsic, OracleCall, PriceFeeder, DOT};
+use sp_core::{
+  offchain::{testing, OffchainExt, TransactionPoolExt},
+  testing::KeyStore,
+  traits::KeystoreExt,
+};
+use sp_runtime::codec::Decode;
+
+fn price(n: u128, d: u128) -> Price {
+  Price::saturating_from_rational(n, d)
+}
+
+#[test]
+fn parse_price_works() {
+  assert_eq!(parse_price(b"12"), Some(price(12, 1)));
+  assert_eq!(parse_price(b" 12.345\n"), Some(price(12345, 1000)));
+  assert_eq!(parse_price(b"0.000000000000000001"), Some(Price::from_inner(1)));
+  // digits beyond the precision are truncated
+  assert_eq!(parse_price(b"1.0000000000000000019"), Some(Price::from_inner(1_000_000_000_000_000_001)));
+
+  assert_eq!(parse_price(b"0"), None);
+  assert_eq!(parse_price(b"0.0000000000000000001"), None);
+  assert_eq!(parse_price(b".5"), None);
+  assert_eq!(parse_price(b"-1"), None);
+  assert_eq!(parse_price(b"1.2.3"), None);
+  assert_eq!(parse_price(b"{\"price\":1}"), None);
+}
+
+#[test]
+fn aggregate_prices_rejects_outliers() {
+  let max_deviation = price(2, 10);
+  assert_eq!(aggregate_prices(vec![], max_deviation, 1), None);
+  assert_eq!(aggregate_prices(vec![price(10, 1)], max_deviation, 1), Some(price(10, 1)));
+
+  // 20 is too far from the median 12
+  assert_eq!(
+    aggregate_prices(vec![price(20, 1), price(10, 1), price(11, 1), price(12, 1)], max_deviation, 3),
+    Some(price(11, 1))
+  );
+  assert_eq!(
+    aggregate_prices(vec![price(20, 1), price(10, 1), price(11, 1), price(12, 1)], max_deviation, 4),
+    None
+  );
+}
+
+#[test]
+fn fetch_price_works() {
+  let (offchain, state) = testing::TestOffchainExt::new();
+  let mut t = sp_io::TestExternalities::default();
+  t.register_extension(OffchainExt::new(offchain));
+
+  state.write().expect_request(testing::PendingRequest {
+    method: "GET".into(),
+    uri: "http://localhost:8000/dot".into(),
+    response: Some(b"4.56".to_vec()),
+    sent: true,
+    ..Default::default()
+  });
+  state.write().expect_request(testing::PendingRequest {
+    method: "GET".into(),
+    uri: "http://localhost:8000/ceth".into(),
+    response: Some(b"unavailable".to_vec()),
+    sent: true,
+    ..Default::default()
+  });
+
+  t.execute_with(|| {
+    assert_eq!(fetch_price(b"http://localhost:8000/dot"), Ok(price(456, 100)));
+    assert_eq!(fetch_price(b"http://localhost:8000/ceth"), Err(http::Error::Unknown));
+  });
+}
+
+/// externalities with an oracle key expecting the requests of (dot endpoint, response),
+/// returns the externalities and a function taking the submitted transactions
+fn new_offchain_ext(requests: &[(usize, &[u8])]) -> (sp_io::TestExternalities, impl Fn() -> Vec<Extrinsic>) {
+  let (offchain, offchain_state) = testing::TestOffchainExt::new();
+  let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+  let keystore = KeyStore::new();
+  keystore.write().sr25519_generate_new(KEY_TYPE, Some("//Oracle")).unwrap();
+
+  for (endpoint, response) in requests {
+    offchain_state.write().expect_request(testing::PendingRequest {
+      method: "GET".into(),
+      uri: dot_url(*endpoint),
+      response: Some(response.to_vec()),
+      sent: true,
+      ..Default::default()
+    });
+  }
+
+  let mut t = sp_io::TestExternalities::default();
+  t.register_extension(OffchainExt::new(offchain));
+  t.register_extension(TransactionPoolExt::new(pool));
+  t.register_extension(KeystoreExt(keystore));
+  let take_transactions = move || {
+    sp_std::mem::take(&mut pool_state.write().transactions)
+      .into_iter()
+      .map(|tx| Extrinsic::decode(&mut &*tx).unwrap())
+      .collect()
+  };
+  (t, take_transactions)
+}
+
+fn dot_url(i: usize) -> String {
+  format!("http://localhost:800{}/dot", i)
+}
+
+/// set the first `count` endpoints of dot
+fn set_endpoints(count: usize) {
+  let endpoints: Vec<(CurrencyId, Vec<Vec<u8>>)> = vec![(DOT, (0..count).map(|i| dot_url(i).into_bytes()).collect())];
+  StorageValueRef::persistent(ENDPOINTS_KEY).set(&endpoints);
+}
+
+fn last_feed() -> Option<u64> {
+  StorageValueRef::persistent(LAST_FEED_KEY).get::<u64>().flatten()
+}
+
+#[test]
+fn feed_prices_submits_the_aggregated_prices() {
+  let (mut t, take_transactions) = new_offchain_ext(&[(0, b"10"), (1, b"11"), (2, b"30")]);
+  t.execute_with(|| {
+    set_endpoints(3);
+    assert_eq!(PriceFeeder::feed_prices(1), Ok(()));
+    let txs = take_transactions();
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].signature, Some((0, ())));
+    // 30 is too far from the median 11
+    assert_eq!(txs[0].call, OracleCall::feed_values(vec![(DOT, price(105, 10))]));
+    assert_eq!(last_feed(), Some(1));
+
+    // nothing is fetched until the interval passed
+    assert_eq!(PriceFeeder::feed_prices(5), Err(OffchainErr::OffchainLock));
+    assert!(take_transactions().is_empty());
+  });
+}
+
+#[test]
+fn failed_feed_is_retried_in_the_next_block() {
+  let (mut t, take_transactions) = new_offchain_ext(&[(0, b"10"), (1, b"11"), (0, b"12"), (1, b"12")]);
+  t.execute_with(|| {
+    set_endpoints(2);
+    set_reject_transactions(true);
+    assert_eq!(PriceFeeder::feed_prices(1), Err(OffchainErr::SubmitTransaction));
+    assert!(take_transactions().is_empty());
+    assert_eq!(last_feed(), None);
+
+    set_reject_transactions(false);
+    assert_eq!(PriceFeeder::feed_prices(2), Ok(()));
+    let txs = take_transactions();
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].call, OracleCall::feed_values(vec![(DOT, price(12, 1))]));
+    assert_eq!(last_feed(), Some(2));
+  });
+}
+
+#[test]
+fn feed_prices_requires_an_oracle_key() {
+  let (offchain, _) = testing::TestOffchainExt::new();
+  let mut t = sp_io::TestExternalities::default();
+  t.register_extension(OffchainExt::new(offchain));
+  t.register_extension(KeystoreExt(KeyStore::new()));
+
+  t.execute_with(|| {
+    assert_eq!(PriceFeeder::feed_prices(1), Err(OffchainErr::NotValidator));
+  });
+}
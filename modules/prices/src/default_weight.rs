@@ -0,0 +1,30 @@
+//! Weights for clover_prices, estimated from the previous hand-written weights.
+//! Regenerate with the `benchmark` subcommand of the node built with `--features runtime-benchmarks`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+impl crate::WeightInfo for () {
+	fn lock_price() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn unlock_price() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn set_feed_params() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn update_prices(c: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((45_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads((9 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(c as Weight)))
+	}
+}
@@ -1,49 +1,173 @@
+//! Clover Prices Module
+//!
+//! ##Overview
+//! Prices in USD validated from the oracles and the dex.
+//! Every block the price of each feed currency is taken from the first fresh source of
+//! `CloverOracle`, `BandOracle` and the DEX TWAP, a price which moves more than the max deviation
+//! since the last valid price trips the circuit breaker, which locks the last valid price.
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
 use frame_support::{
-  decl_event, decl_module, decl_storage,
-  traits::{EnsureOrigin, Get},
-  weights::DispatchClass,
+  decl_error, decl_event, decl_module, decl_storage, ensure,
+  traits::{EnsureOrigin, Get, Time},
+  weights::{DispatchClass, Weight},
 };
 use frame_system::{self as system};
-use orml_traits::{DataFeeder, DataProvider};
+use orml_oracle::TimestampedValue;
+use orml_traits::DataProviderExtended;
 use orml_utilities::with_transaction_result;
-use primitives::{CurrencyId, Price};
-use sp_runtime::traits::CheckedDiv;
-use clover_traits::PriceProvider;
+use primitives::{CurrencyId, Moment, Price, Ratio};
+use sp_runtime::{
+  traits::{CheckedDiv, Saturating, UniqueSaturatedInto, Zero},
+  FixedPointNumber, RuntimeDebug,
+};
+use sp_std::prelude::*;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use clover_traits::{PriceProvider, TwapProvider};
+
+mod default_weight;
+mod mock;
+mod tests;
+
+pub type TimestampedPrice = TimestampedValue<Price, Moment>;
+
+pub trait WeightInfo {
+  fn lock_price() -> Weight;
+  fn unlock_price() -> Weight;
+  fn set_feed_params() -> Weight;
+  fn update_prices(c: u32, ) -> Weight;
+}
+
+/// Sources of the prices, in the order they are used.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PriceSource {
+  CloverOracle,
+  BandOracle,
+  DexTwap,
+}
 
-// mod mock;
-// mod tests;
+impl PriceSource {
+  pub const ALL: [PriceSource; 3] = [PriceSource::CloverOracle, PriceSource::BandOracle, PriceSource::DexTwap];
+}
+
+/// Guards of the price feed of a currency.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PriceFeedParams {
+  /// max age of an oracle price, in milliseconds
+  pub max_age: Moment,
+  /// max change of the price per block since the last valid price
+  pub max_deviation: Ratio,
+}
+
+/// A price which passed the guards.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ValidPrice<BlockNumber> {
+  pub price: Price,
+  pub source: PriceSource,
+  /// block the price was validated at
+  pub updated_at: BlockNumber,
+}
 
 pub trait Trait: system::Trait {
   type Event: From<Event> + Into<<Self as system::Trait>::Event>;
-  type Source: DataProvider<CurrencyId, Price> + DataFeeder<CurrencyId, Price, Self::AccountId>;
+
+  /// Prices fed by the clover oracle operators, the first source.
+  type CloverSource: DataProviderExtended<CurrencyId, TimestampedPrice>;
+
+  /// Prices fed by band protocol, used when the clover oracle price is stale.
+  type BandSource: DataProviderExtended<CurrencyId, TimestampedPrice>;
+
+  /// Time weighted average prices of the dex pools, used when the oracle prices are stale.
+  type DexSource: TwapProvider<CurrencyId, Price>;
+
+  /// Time the oracle prices are timestamped with.
+  type Time: Time<Moment = Moment>;
+
+  /// Currencies which are priced every block.
+  type FeedCurrencies: Get<Vec<CurrencyId>>;
+
+  /// Max age of oracle prices of currencies without feed params.
+  type DefaultMaxPriceAge: Get<Moment>;
+
+  /// Max price change per block of currencies without feed params.
+  type DefaultMaxPriceDeviation: Get<Ratio>;
+
   type GetStableCurrencyId: Get<CurrencyId>;
   type StableCurrencyFixedPrice: Get<Price>;
+
+  /// Origin which may lock and unlock prices and set the feed params.
   type LockOrigin: EnsureOrigin<Self::Origin>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
 }
 
 decl_event!(
   pub enum Event {
     LockPrice(CurrencyId, Price),
     UnlockPrice(CurrencyId),
+    /// The price of a source is older than the max age. [currency_id, source, timestamp]
+    PriceFeedStale(CurrencyId, PriceSource, Moment),
+    /// The price of a stale source is fresh again. [currency_id, source]
+    PriceFeedRecovered(CurrencyId, PriceSource),
+    /// No source has a fresh price, the currency has no price until one has. [currency_id]
+    NoValidPrice(CurrencyId),
+    /// The new price moved too far from the last valid price, which is locked. [currency_id, locked_price, rejected_price]
+    CircuitBreakerTripped(CurrencyId, Price, Price),
+    /// Feed params of the currency set, None means the defaults. [currency_id, params]
+    FeedParamsUpdated(CurrencyId, Option<PriceFeedParams>),
   }
 );
 
+decl_error! {
+  /// Error for prices module.
+  pub enum Error for Module<T: Trait> {
+    /// max deviation is zero
+    InvalidFeedParams,
+  }
+}
+
 decl_storage! {
   trait Store for Module<T: Trait> as Prices {
     LockedPrice get(fn locked_price): map hasher(twox_64_concat) CurrencyId => Option<Price>;
+
+    /// Last price of the currency which passed the guards
+    ValidPrices get(fn valid_prices): map hasher(twox_64_concat) CurrencyId => Option<ValidPrice<T::BlockNumber>>;
+
+    /// Sources whose price of the currency is stale, to only report them once
+    StaleFeeds get(fn stale_feeds): double_map hasher(twox_64_concat) CurrencyId, hasher(twox_64_concat) PriceSource => bool;
+
+    /// Guards of the price feed of the currency, the defaults are used if not set
+    FeedParams get(fn feed_params): map hasher(twox_64_concat) CurrencyId => Option<PriceFeedParams>;
   }
 }
 
 decl_module! {
   pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+    type Error = Error<T>;
     fn deposit_event() = default;
 
     const GetStableCurrencyId: CurrencyId = T::GetStableCurrencyId::get();
     const StableCurrencyFixedPrice: Price = T::StableCurrencyFixedPrice::get();
+    const DefaultMaxPriceAge: Moment = T::DefaultMaxPriceAge::get();
+    const DefaultMaxPriceDeviation: Ratio = T::DefaultMaxPriceDeviation::get();
+
+    fn on_initialize() -> Weight {
+      T::WeightInfo::update_prices(T::FeedCurrencies::get().len() as u32)
+    }
 
-    #[weight = (10_000, DispatchClass::Operational)]
+    // the prices are validated after the timestamp of the block is set
+    fn on_finalize(now: T::BlockNumber) {
+      for currency_id in T::FeedCurrencies::get() {
+        Self::update_price(currency_id, now);
+      }
+    }
+
+    #[weight = (T::WeightInfo::lock_price(), DispatchClass::Operational)]
     fn lock_price(origin, currency_id: CurrencyId) {
       with_transaction_result(|| {
         T::LockOrigin::ensure_origin(origin)?;
@@ -52,7 +176,7 @@ decl_module! {
       })?;
     }
 
-    #[weight = (10_000, DispatchClass::Operational)]
+    #[weight = (T::WeightInfo::unlock_price(), DispatchClass::Operational)]
     fn unlock_price(origin, currency_id: CurrencyId) {
       with_transaction_result(|| {
         T::LockOrigin::ensure_origin(origin)?;
@@ -60,10 +184,117 @@ decl_module! {
         Ok(())
       })?;
     }
+
+    /// Set the max age and the max deviation per block of the price feed of the currency,
+    /// `None` uses the defaults.
+    #[weight = (T::WeightInfo::set_feed_params(), DispatchClass::Operational)]
+    fn set_feed_params(origin, currency_id: CurrencyId, params: Option<PriceFeedParams>) {
+      T::LockOrigin::ensure_origin(origin)?;
+      if let Some(params) = params {
+        ensure!(!params.max_deviation.is_zero(), Error::<T>::InvalidFeedParams);
+        FeedParams::insert(currency_id, params);
+      } else {
+        FeedParams::remove(currency_id);
+      }
+      Self::deposit_event(Event::FeedParamsUpdated(currency_id, params));
+    }
   }
 }
 
-impl<T: Trait> Module<T> {}
+impl<T: Trait> Module<T> {
+  /// Guards of the price feed of the currency
+  pub fn get_feed_params(currency_id: CurrencyId) -> PriceFeedParams {
+    Self::feed_params(currency_id).unwrap_or_else(|| PriceFeedParams {
+      max_age: T::DefaultMaxPriceAge::get(),
+      max_deviation: T::DefaultMaxPriceDeviation::get(),
+    })
+  }
+
+  fn oracle_price(source: PriceSource, currency_id: CurrencyId) -> Option<TimestampedPrice> {
+    match source {
+      PriceSource::CloverOracle => T::CloverSource::get_no_op(&currency_id),
+      PriceSource::BandOracle => T::BandSource::get_no_op(&currency_id),
+      PriceSource::DexTwap => None,
+    }
+  }
+
+  /// Price of the first source with a fresh price, reports the sources which went stale or recovered.
+  fn fresh_price(currency_id: CurrencyId, max_age: Moment) -> Option<(Price, PriceSource)> {
+    let now = T::Time::now();
+    let mut fresh = None;
+    for source in PriceSource::ALL.iter().copied() {
+      let price = if source == PriceSource::DexTwap {
+        T::DexSource::get_twap(currency_id, T::GetStableCurrencyId::get())
+      } else {
+        match Self::oracle_price(source, currency_id) {
+          Some(timestamped) if now.saturating_sub(timestamped.timestamp) > max_age => {
+            if !Self::stale_feeds(currency_id, source) {
+              StaleFeeds::insert(currency_id, source, true);
+              Self::deposit_event(Event::PriceFeedStale(currency_id, source, timestamped.timestamp));
+            }
+            None
+          },
+          timestamped => timestamped.map(|timestamped| timestamped.value),
+        }
+      };
+
+      if price.is_some() && Self::stale_feeds(currency_id, source) {
+        StaleFeeds::remove(currency_id, source);
+        Self::deposit_event(Event::PriceFeedRecovered(currency_id, source));
+      }
+      if fresh.is_none() {
+        fresh = price.map(|price| (price, source));
+      }
+    }
+    fresh
+  }
+
+  /// Validate the price of the currency from the sources, trips the circuit breaker
+  /// if it moved more than the max deviation since the last valid price.
+  fn update_price(currency_id: CurrencyId, now: T::BlockNumber) {
+    if currency_id == T::GetStableCurrencyId::get() || Self::locked_price(currency_id).is_some() {
+      return;
+    }
+
+    let params = Self::get_feed_params(currency_id);
+    let (price, source) = match Self::fresh_price(currency_id, params.max_age) {
+      Some(fresh) => fresh,
+      None => {
+        if Self::valid_prices(currency_id).map_or(false, |last| last.updated_at.saturating_add(1u32.into()) == now) {
+          Self::deposit_event(Event::NoValidPrice(currency_id));
+        }
+        return;
+      },
+    };
+
+    if let Some(last) = Self::valid_prices(currency_id) {
+      let blocks: u128 = now.saturating_sub(last.updated_at).unique_saturated_into();
+      let max_deviation = params.max_deviation.saturating_mul(Ratio::saturating_from_integer(blocks));
+      let deviation = if price > last.price {
+        price.saturating_sub(last.price)
+      } else {
+        last.price.saturating_sub(price)
+      }.checked_div(&last.price).unwrap_or_else(Zero::zero);
+
+      if deviation > max_deviation {
+        LockedPrice::insert(currency_id, last.price);
+        Self::deposit_event(Event::CircuitBreakerTripped(currency_id, last.price, price));
+        Self::deposit_event(Event::LockPrice(currency_id, last.price));
+        return;
+      }
+    }
+
+    ValidPrices::<T>::insert(currency_id, ValidPrice { price, source, updated_at: now });
+  }
+
+  /// Last valid price of the currency, if it was validated at the end of the last block or later
+  fn current_price(currency_id: CurrencyId) -> Option<Price> {
+    let now = <system::Module<T>>::block_number();
+    Self::valid_prices(currency_id)
+      .filter(|valid| valid.updated_at.saturating_add(1u32.into()) >= now)
+      .map(|valid| valid.price)
+  }
+}
 
 impl<T: Trait> PriceProvider<CurrencyId, Price> for Module<T> {
   fn get_relative_price(base_currency_id: CurrencyId, quote_currency_id: CurrencyId) -> Option<Price> {
@@ -82,14 +313,14 @@ impl<T: Trait> PriceProvider<CurrencyId, Price> for Module<T> {
       // if is stable currency, return fixed price
       Some(T::StableCurrencyFixedPrice::get())
     } else {
-      // if locked price exists, return it, otherwise return latest price from oracle.
-      Self::locked_price(currency_id).or_else(|| T::Source::get(&currency_id))
+      // if locked price exists, return it, otherwise return the latest valid price.
+      Self::locked_price(currency_id).or_else(|| Self::current_price(currency_id))
     }
   }
 
   fn lock_price(currency_id: CurrencyId) {
-    // lock price when get valid price from source
-    if let Some(val) = T::Source::get(&currency_id) {
+    // lock price when there is a valid price
+    if let Some(val) = Self::current_price(currency_id) {
       LockedPrice::insert(currency_id, val);
       <Module<T>>::deposit_event(Event::LockPrice(currency_id, val));
     }
@@ -97,6 +328,8 @@ impl<T: Trait> PriceProvider<CurrencyId, Price> for Module<T> {
 
   fn unlock_price(currency_id: CurrencyId) {
     LockedPrice::remove(currency_id);
+    // the price after unlocking isn't compared with the price before locking
+    ValidPrices::<T>::remove(currency_id);
     <Module<T>>::deposit_event(Event::UnlockPrice(currency_id));
   }
 }
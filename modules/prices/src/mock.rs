@@ -0,0 +1,190 @@
+#![cfg(test)]
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_std::cell::RefCell;
+use std::collections::HashMap;
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+
+pub const DOT: CurrencyId = CurrencyId::DOT;
+pub const CETH: CurrencyId = CurrencyId::CETH;
+pub const CUSDT: CurrencyId = CurrencyId::CUSDT;
+
+mod clover_prices {
+  pub use super::super::*;
+}
+
+impl_outer_event! {
+  pub enum TestEvent for TestRuntime {
+    frame_system<T>,
+    clover_prices,
+  }
+}
+
+impl_outer_origin! {
+  pub enum Origin for TestRuntime {}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+
+parameter_types! {
+  pub const BlockHashCount: u64 = 250;
+  pub const MaximumBlockWeight: u32 = 1024;
+  pub const MaximumBlockLength: u32 = 2 * 1024;
+  pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for TestRuntime {
+  type Origin = Origin;
+  type Index = u64;
+  type BlockNumber = BlockNumber;
+  type Call = ();
+  type Hash = H256;
+  type Hashing = ::sp_runtime::traits::BlakeTwo256;
+  type AccountId = AccountId;
+  type Lookup = IdentityLookup<Self::AccountId>;
+  type Header = Header;
+  type Event = TestEvent;
+  type BlockHashCount = BlockHashCount;
+  type MaximumBlockWeight = MaximumBlockWeight;
+  type MaximumBlockLength = MaximumBlockLength;
+  type AvailableBlockRatio = AvailableBlockRatio;
+  type Version = ();
+  type PalletInfo = ();
+  type AccountData = ();
+  type OnNewAccount = ();
+  type OnKilledAccount = ();
+  type DbWeight = ();
+  type BlockExecutionWeight = ();
+  type ExtrinsicBaseWeight = ();
+  type MaximumExtrinsicWeight = ();
+  type BaseCallFilter = ();
+  type SystemWeightInfo = ();
+}
+
+pub type System = frame_system::Module<TestRuntime>;
+
+thread_local! {
+  static CLOVER_PRICES: RefCell<HashMap<CurrencyId, TimestampedPrice>> = RefCell::new(HashMap::new());
+  static BAND_PRICES: RefCell<HashMap<CurrencyId, TimestampedPrice>> = RefCell::new(HashMap::new());
+  static DEX_PRICES: RefCell<HashMap<CurrencyId, Price>> = RefCell::new(HashMap::new());
+  static NOW: RefCell<Moment> = RefCell::new(0);
+}
+
+/// set the price of the source at the timestamp, `None` removes it
+pub fn set_source_price(source: PriceSource, currency_id: CurrencyId, price: Option<(Price, Moment)>) {
+  let prices = match source {
+    PriceSource::CloverOracle => &CLOVER_PRICES,
+    PriceSource::BandOracle => &BAND_PRICES,
+    PriceSource::DexTwap => {
+      DEX_PRICES.with(|v| match price {
+        Some((price, _)) => v.borrow_mut().insert(currency_id, price),
+        None => v.borrow_mut().remove(&currency_id),
+      });
+      return;
+    },
+  };
+  prices.with(|v| match price {
+    Some((value, timestamp)) => v.borrow_mut().insert(currency_id, TimestampedPrice { value, timestamp }),
+    None => v.borrow_mut().remove(&currency_id),
+  });
+}
+
+pub fn set_now(now: Moment) {
+  NOW.with(|v| *v.borrow_mut() = now);
+}
+
+pub struct MockCloverSource;
+impl DataProviderExtended<CurrencyId, TimestampedPrice> for MockCloverSource {
+  fn get_no_op(key: &CurrencyId) -> Option<TimestampedPrice> {
+    CLOVER_PRICES.with(|v| v.borrow().get(key).cloned())
+  }
+
+  fn get_all_values() -> Vec<(CurrencyId, Option<TimestampedPrice>)> {
+    CLOVER_PRICES.with(|v| v.borrow().iter().map(|(k, v)| (*k, Some(v.clone()))).collect())
+  }
+}
+
+pub struct MockBandSource;
+impl DataProviderExtended<CurrencyId, TimestampedPrice> for MockBandSource {
+  fn get_no_op(key: &CurrencyId) -> Option<TimestampedPrice> {
+    BAND_PRICES.with(|v| v.borrow().get(key).cloned())
+  }
+
+  fn get_all_values() -> Vec<(CurrencyId, Option<TimestampedPrice>)> {
+    BAND_PRICES.with(|v| v.borrow().iter().map(|(k, v)| (*k, Some(v.clone()))).collect())
+  }
+}
+
+pub struct MockDexSource;
+impl TwapProvider<CurrencyId, Price> for MockDexSource {
+  fn get_twap(base: CurrencyId, quote: CurrencyId) -> Option<Price> {
+    assert_eq!(quote, CUSDT);
+    DEX_PRICES.with(|v| v.borrow().get(&base).cloned())
+  }
+}
+
+pub struct MockTime;
+impl Time for MockTime {
+  type Moment = Moment;
+
+  fn now() -> Moment {
+    NOW.with(|v| *v.borrow())
+  }
+}
+
+parameter_types! {
+  pub FeedCurrencies: Vec<CurrencyId> = vec![DOT, CETH];
+  pub const DefaultMaxPriceAge: Moment = 1000;
+  pub DefaultMaxPriceDeviation: Ratio = Ratio::saturating_from_rational(5, 100);
+  pub const GetStableCurrencyId: CurrencyId = CUSDT;
+  pub StableCurrencyFixedPrice: Price = Price::saturating_from_rational(1, 1);
+}
+
+impl Trait for TestRuntime {
+  type Event = TestEvent;
+  type CloverSource = MockCloverSource;
+  type BandSource = MockBandSource;
+  type DexSource = MockDexSource;
+  type Time = MockTime;
+  type FeedCurrencies = FeedCurrencies;
+  type DefaultMaxPriceAge = DefaultMaxPriceAge;
+  type DefaultMaxPriceDeviation = DefaultMaxPriceDeviation;
+  type GetStableCurrencyId = GetStableCurrencyId;
+  type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
+  type LockOrigin = EnsureRoot<AccountId>;
+  type WeightInfo = ();
+}
+
+pub type PricesModule = Module<TestRuntime>;
+
+pub struct ExtBuilder;
+
+impl Default for ExtBuilder {
+  fn default() -> Self {
+    ExtBuilder
+  }
+}
+
+impl ExtBuilder {
+  pub fn build(self) -> sp_io::TestExternalities {
+    let t = frame_system::GenesisConfig::default()
+      .build_storage::<TestRuntime>()
+      .unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+      System::set_block_number(1);
+      set_now(0);
+      CLOVER_PRICES.with(|v| v.borrow_mut().clear());
+      BAND_PRICES.with(|v| v.borrow_mut().clear());
+      DEX_PRICES.with(|v| v.borrow_mut().clear());
+    });
+    ext
+  }
+}
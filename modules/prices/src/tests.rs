@@ -0,0 +1,192 @@
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize};
+use mock::{set_now, set_source_price, ExtBuilder, Origin, PricesModule, System, TestEvent, CETH, CUSDT, DOT};
+use sp_runtime::DispatchError;
+
+fn price(n: u128, d: u128) -> Price {
+  Price::saturating_from_rational(n, d)
+}
+
+/// validate the prices at the end of the block `n`
+fn finalize(n: u64) {
+  System::set_block_number(n);
+  PricesModule::on_finalize(n);
+}
+
+fn has_event(event: Event) -> bool {
+  System::events().iter().any(|record| record.event == TestEvent::clover_prices(event.clone()))
+}
+
+fn count_events(event: Event) -> usize {
+  System::events().iter().filter(|record| record.event == TestEvent::clover_prices(event.clone())).count()
+}
+
+#[test]
+fn oracle_price_is_validated_at_the_end_of_the_block() {
+  ExtBuilder::default().build().execute_with(|| {
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(10, 1), 0)));
+    assert_eq!(PricesModule::get_price(DOT), None);
+
+    finalize(1);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(10, 1)));
+    assert_eq!(PricesModule::valid_prices(DOT).map(|valid| valid.source), Some(PriceSource::CloverOracle));
+    // the currency without sources has no price
+    assert_eq!(PricesModule::get_price(CETH), None);
+    assert_eq!(PricesModule::get_price(CUSDT), Some(price(1, 1)));
+    assert_eq!(PricesModule::get_relative_price(DOT, CUSDT), Some(price(10, 1)));
+  });
+}
+
+#[test]
+fn stale_sources_fall_back_to_the_next_source() {
+  ExtBuilder::default().build().execute_with(|| {
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(10, 1), 0)));
+    set_source_price(PriceSource::BandOracle, DOT, Some((price(11, 1), 0)));
+    set_source_price(PriceSource::DexTwap, DOT, Some((price(12, 1), 0)));
+    finalize(1);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(10, 1)));
+
+    // the clover oracle price is older than the max age
+    set_now(1001);
+    set_source_price(PriceSource::BandOracle, DOT, Some((price(102, 10), 1001)));
+    finalize(2);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(102, 10)));
+    assert_eq!(PricesModule::valid_prices(DOT).map(|valid| valid.source), Some(PriceSource::BandOracle));
+    assert!(PricesModule::stale_feeds(DOT, PriceSource::CloverOracle));
+    assert!(has_event(Event::PriceFeedStale(DOT, PriceSource::CloverOracle, 0)));
+
+    // a stale source is only reported once
+    finalize(3);
+    assert_eq!(count_events(Event::PriceFeedStale(DOT, PriceSource::CloverOracle, 0)), 1);
+
+    // both oracles are stale, the dex price is used
+    set_now(2002);
+    set_source_price(PriceSource::DexTwap, DOT, Some((price(105, 10), 0)));
+    finalize(4);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(105, 10)));
+    assert_eq!(PricesModule::valid_prices(DOT).map(|valid| valid.source), Some(PriceSource::DexTwap));
+    assert!(has_event(Event::PriceFeedStale(DOT, PriceSource::BandOracle, 1001)));
+
+    // the clover oracle recovered
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(104, 10), 2002)));
+    finalize(5);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(104, 10)));
+    assert!(!PricesModule::stale_feeds(DOT, PriceSource::CloverOracle));
+    assert!(PricesModule::stale_feeds(DOT, PriceSource::BandOracle));
+    assert!(has_event(Event::PriceFeedRecovered(DOT, PriceSource::CloverOracle)));
+  });
+}
+
+#[test]
+fn price_expires_one_block_after_all_sources_are_stale() {
+  ExtBuilder::default().build().execute_with(|| {
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(10, 1), 0)));
+    finalize(1);
+
+    set_now(1001);
+    finalize(2);
+    assert!(has_event(Event::NoValidPrice(DOT)));
+    // the price validated at the end of the last block is still current
+    assert_eq!(PricesModule::get_price(DOT), Some(price(10, 1)));
+
+    System::set_block_number(3);
+    assert_eq!(PricesModule::get_price(DOT), None);
+    finalize(3);
+    assert_eq!(PricesModule::get_price(DOT), None);
+    assert_eq!(count_events(Event::NoValidPrice(DOT)), 1);
+
+    // a fresh price is valid again, guarded by the last valid price
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(105, 10), 1001)));
+    finalize(4);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(105, 10)));
+  });
+}
+
+#[test]
+fn circuit_breaker_locks_the_last_valid_price() {
+  ExtBuilder::default().build().execute_with(|| {
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(10, 1), 0)));
+    finalize(1);
+
+    // 10% in a block is more than the max deviation of 5% per block
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(11, 1), 0)));
+    finalize(2);
+    assert_eq!(PricesModule::locked_price(DOT), Some(price(10, 1)));
+    assert!(has_event(Event::CircuitBreakerTripped(DOT, price(10, 1), price(11, 1))));
+    assert!(has_event(Event::LockPrice(DOT, price(10, 1))));
+
+    // the locked price is used until it's unlocked
+    finalize(3);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(10, 1)));
+    assert_ok!(PricesModule::unlock_price(Origin::root(), DOT));
+    assert_eq!(PricesModule::get_price(DOT), None);
+
+    // the price after unlocking isn't compared with the locked price
+    finalize(4);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(11, 1)));
+
+    // the max deviation grows with the blocks since the last valid price
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(12, 1), 0)));
+    finalize(6);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(12, 1)));
+    assert_eq!(PricesModule::locked_price(DOT), None);
+  });
+}
+
+#[test]
+fn lock_price_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_noop!(PricesModule::lock_price(Origin::signed(1), DOT), DispatchError::BadOrigin);
+    // no price to lock
+    assert_ok!(PricesModule::lock_price(Origin::root(), DOT));
+    assert_eq!(PricesModule::locked_price(DOT), None);
+
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(10, 1), 0)));
+    finalize(1);
+    assert_ok!(PricesModule::lock_price(Origin::root(), DOT));
+    assert_eq!(PricesModule::locked_price(DOT), Some(price(10, 1)));
+    assert!(has_event(Event::LockPrice(DOT, price(10, 1))));
+
+    // the locked price doesn't expire
+    System::set_block_number(10);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(10, 1)));
+
+    assert_noop!(PricesModule::unlock_price(Origin::signed(1), DOT), DispatchError::BadOrigin);
+    assert_ok!(PricesModule::unlock_price(Origin::root(), DOT));
+    assert_eq!(PricesModule::get_price(DOT), None);
+    assert!(has_event(Event::UnlockPrice(DOT)));
+  });
+}
+
+#[test]
+fn set_feed_params_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    let params = PriceFeedParams { max_age: 5000, max_deviation: price(1, 2) };
+    assert_noop!(PricesModule::set_feed_params(Origin::signed(1), DOT, Some(params)), DispatchError::BadOrigin);
+    assert_noop!(
+      PricesModule::set_feed_params(Origin::root(), DOT, Some(PriceFeedParams { max_age: 5000, max_deviation: Zero::zero() })),
+      Error::<mock::TestRuntime>::InvalidFeedParams
+    );
+
+    assert_ok!(PricesModule::set_feed_params(Origin::root(), DOT, Some(params)));
+    assert_eq!(PricesModule::get_feed_params(DOT), params);
+    assert!(has_event(Event::FeedParamsUpdated(DOT, Some(params))));
+
+    // the price is fresh for the max age of the params
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(10, 1), 0)));
+    set_now(2000);
+    finalize(1);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(10, 1)));
+
+    // and moves up to the max deviation of the params
+    set_source_price(PriceSource::CloverOracle, DOT, Some((price(14, 1), 0)));
+    finalize(2);
+    assert_eq!(PricesModule::get_price(DOT), Some(price(14, 1)));
+
+    assert_ok!(PricesModule::set_feed_params(Origin::root(), DOT, None));
+    assert_eq!(PricesModule::feed_params(DOT), None);
+    assert_eq!(PricesModule::get_feed_params(DOT).max_age, 1000);
+  });
+}
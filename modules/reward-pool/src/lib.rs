@@ -2,22 +2,25 @@
 //!
 //! ##Overview
 //! Reward pooling based on shares,
-//! Add shares to the pool, receive native currency reward
-//! Allow add shares, withdraw shares and coressponding native currency
+//! Add shares to the pool, receive rewards in one or more currencies
+//! Allow add shares, withdraw shares and coressponding rewards
+//! Rewards are paid from budgets deposited to the pool, nothing is minted
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode, FullCodec, HasCompact};
 use frame_support::{
-  decl_error, decl_event, decl_module, decl_storage, Parameter,
+  decl_error, decl_event, decl_module, decl_storage, ensure, Parameter,
   debug,
-  traits::{Get},
+  traits::{EnsureOrigin, Get},
+  weights::Weight,
+  IterableStorageDoubleMap, IterableStorageMap,
 };
+use frame_system::ensure_signed;
 use sp_runtime::{
   traits::{
     AccountIdConversion,
     Member,
-    UniqueSaturatedInto,
-    Zero,
+    One, Saturating, Zero,
   },
   DispatchResult, DispatchError,
   FixedPointNumber,
@@ -26,6 +29,7 @@ use sp_runtime::{
 
 use sp_std::{
   cmp::{Eq, PartialEq},
+  collections::btree_map::BTreeMap,
 };
 use sp_std::vec;
 
@@ -38,15 +42,18 @@ pub mod traits;
 use traits::RewardHandler;
 use clover_traits::RewardPoolOps;
 
+mod default_weight;
 mod mock;
 mod tests;
 
-/// The Reward Pool Info.
+pub trait WeightInfo {
+  fn deposit_rewards() -> Weight;
+  fn refund_rewards() -> Weight;
+}
+
+/// Rewards of a currency in the reward pool.
 #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, Default)]
-pub struct PoolInfo<Share: HasCompact, Balance: HasCompact + From<Share>, Block: HasCompact> {
-  /// Total shares amount
-  #[codec(compact)]
-  pub total_shares: Share,
+pub struct RewardInfo<Balance: HasCompact> {
   /// Total rewards amount
   /// including some "virtual" amount added while adding shares
   #[codec(compact)]
@@ -55,19 +62,94 @@ pub struct PoolInfo<Share: HasCompact, Balance: HasCompact + From<Share>, Block:
   /// this is equals to total_rewards - virtual_rewards_amount
   #[codec(compact)]
   pub total_rewards_useable: Balance,
+}
+
+/// The Reward Pool Info.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, Default)]
+pub struct PoolInfo<Share: HasCompact, Balance: HasCompact + From<Share>, Block: HasCompact> {
+  /// Total shares amount
+  #[codec(compact)]
+  pub total_shares: Share,
+  /// Rewards of each reward currency
+  pub rewards: BTreeMap<CurrencyId, RewardInfo<Balance>>,
 
   /// last reward grant block number
   #[codec[compact]]
   pub last_update_block: Block,
 }
 
+impl<Share, Balance, Block> PoolInfo<Share, Balance, Block>
+where
+  Share: HasCompact,
+  Balance: HasCompact + From<Share> + Clone + Default,
+  Block: HasCompact,
+{
+  /// Rewards of the currency, zero if the pool has never been rewarded in it
+  pub fn reward_info(&self, currency_id: &CurrencyId) -> RewardInfo<Balance> {
+    self.rewards.get(currency_id).cloned().unwrap_or_default()
+  }
+}
+
 /// The Reward Pool balance info for an account
 #[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, Default)]
 pub struct PoolAccountInfo <Share: HasCompact, Balance: HasCompact> {
   #[codec(compact)]
   pub shares: Share,
+  pub borrowed_amounts: BTreeMap<CurrencyId, Balance>, // borrow balances of each reward currency
+}
+
+impl<Share, Balance> PoolAccountInfo<Share, Balance>
+where
+  Share: HasCompact,
+  Balance: HasCompact + Copy + Zero,
+{
+  /// Amount of the reward currency the account borrowed
+  pub fn borrowed_amount(&self, currency_id: &CurrencyId) -> Balance {
+    self.borrowed_amounts.get(currency_id).copied().unwrap_or_else(Zero::zero)
+  }
+
+  fn set_borrowed_amount(&mut self, currency_id: CurrencyId, amount: Balance) {
+    if amount.is_zero() {
+      self.borrowed_amounts.remove(&currency_id);
+    } else {
+      self.borrowed_amounts.insert(currency_id, amount);
+    }
+  }
+}
+
+/// Pool info before rewards were tracked per currency, all rewards were native currency.
+#[derive(Decode)]
+struct PoolInfoV1<Block: HasCompact> {
+  #[codec(compact)]
+  total_shares: Share,
+  #[codec(compact)]
+  total_rewards: Balance,
   #[codec(compact)]
-  pub borrowed_amount: Balance, // borrow balances
+  total_rewards_useable: Balance,
+  #[codec(compact)]
+  last_update_block: Block,
+}
+
+/// Account info before rewards were tracked per currency.
+#[derive(Decode)]
+struct PoolAccountInfoV1 {
+  #[codec(compact)]
+  shares: Share,
+  #[codec(compact)]
+  borrowed_amount: Balance,
+}
+
+// storage layout releases of the module
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+  V1_0_0,
+  V2_0_0,
+}
+
+impl Default for Releases {
+  fn default() -> Self {
+    Releases::V1_0_0
+  }
 }
 
 pub trait Trait: frame_system::Trait {
@@ -79,15 +161,35 @@ pub trait Trait: frame_system::Trait {
   /// The reward  module id, keep all assets in DEX sub account.
   type ModuleId: Get<ModuleId>;
 
-  type Handler: RewardHandler<Self::AccountId, Self::BlockNumber, Balance, Share, Self::PoolId>;
+  type Handler: RewardHandler<Self::AccountId, Self::BlockNumber, CurrencyId, Balance, Share, Self::PoolId>;
 
   /// Currency for transfer currencies
   type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
 
+  /// Currency of the rewards before multi currency rewards, used by the storage migration
   type GetNativeCurrencyId: Get<CurrencyId>;
 
   /// minimum amount that reward could be sent to account
   type ExistentialReward: Get<Balance>;
+
+  /// Blocks of rewards the storage migration deposits to the budgets of the existing pools,
+  /// rewards were minted before the budgets were introduced
+  type MigratedRewardPeriod: Get<Self::BlockNumber>;
+
+  /// Account the storage migration funds the seeded budgets from
+  type MigratedRewardSource: Get<Self::AccountId>;
+
+  /// Origin which may refund the budgets left when a pool isn't rewarded anymore
+  type RefundOrigin: EnsureOrigin<Self::Origin>;
+
+  /// Blocks of the periods the paid rewards of the pools are recorded in, must not be zero
+  type PaidRewardsPeriod: Get<Self::BlockNumber>;
+
+  /// Number of periods of paid rewards kept for each pool, including the current period
+  type PaidRewardsHistory: Get<u32>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
 }
 
 decl_event!(
@@ -96,9 +198,14 @@ decl_event!(
     <T as Trait>::PoolId,
     Share = Share,
     Balance = Balance,
+    CurrencyId = CurrencyId,
   {
     RewardUpdated(PoolId, Balance),
     ShareRemoved(PoolId, AccountId, Share),
+    /// Rewards deposited to the budget of the pool. [pool_id, who, currency_id, amount]
+    RewardsDeposited(PoolId, AccountId, CurrencyId, Balance),
+    /// Budget left of a pool which isn't rewarded anymore refunded. [pool_id, currency_id, dest, amount]
+    RewardsRefunded(PoolId, CurrencyId, AccountId, Balance),
   }
 );
 
@@ -110,6 +217,12 @@ decl_error! {
     InsufficientShares,
     InvalidAmount,
     InvalidRewards,
+    /// the reward budget of the pool is used up
+    InsufficientBudget,
+    /// the pool isn't rewarded in the currency
+    NotRewarded,
+    /// the pool is still rewarded in the currency
+    StillRewarded,
   }
 }
 
@@ -120,6 +233,32 @@ decl_storage! {
 
     /// Record share amount and virtual amount in the account
     pub PoolAccountData get(fn pool_account_data): double_map hasher(twox_64_concat) T::PoolId, hasher(twox_64_concat) T::AccountId => PoolAccountInfo<Share, Balance>;
+
+    /// Rewards left to pay of the pool in each currency,
+    /// the budgets are kept in the module account
+    pub RewardBudgets get(fn reward_budgets): double_map hasher(twox_64_concat) T::PoolId, hasher(twox_64_concat) CurrencyId => Balance;
+
+    /// Rewards paid to the pool in each currency during a period, map from
+    /// PoolId -> first block of the period -> rewards,
+    /// the last `PaidRewardsHistory` periods are kept
+    pub PaidRewards get(fn paid_rewards): double_map hasher(twox_64_concat) T::PoolId, hasher(twox_64_concat) T::BlockNumber => vec::Vec<(CurrencyId, Balance)>;
+
+    StorageVersion build(|_: &GenesisConfig<T>| Releases::V2_0_0): Releases;
+  }
+
+  add_extra_genesis {
+    config(reward_budgets): vec::Vec<(T::PoolId, CurrencyId, Balance)>;
+
+    build(|config: &GenesisConfig<T>| {
+      let account_id = <Module<T>>::account_id();
+      for (pool_id, currency_id, amount) in &config.reward_budgets {
+        T::Currency::deposit(*currency_id, &account_id, *amount)
+          .expect("reward budget should be deposited");
+        RewardBudgets::<T>::mutate(pool_id, currency_id, |budget| {
+          *budget = budget.saturating_add(*amount);
+        });
+      }
+    })
   }
 }
 
@@ -130,10 +269,54 @@ decl_module! {
 
     const GetNativeCurrencyId: CurrencyId = T::GetNativeCurrencyId::get();
     const ExistentialReward: Balance = T::ExistentialReward::get();
+
+    fn on_runtime_upgrade() -> Weight {
+      if StorageVersion::get() == Releases::V2_0_0 {
+        return 0;
+      }
+      Self::migrate_to_multi_currency_rewards()
+    }
+
+    /// Deposit `amount` of `currency_id` to the reward budget of the pool,
+    /// rewards in the currency are paid from the budget until it's used up.
+    #[weight = T::WeightInfo::deposit_rewards()]
+    pub fn deposit_rewards(origin, pool_id: T::PoolId, currency_id: CurrencyId, #[compact] amount: Balance) {
+      let who = ensure_signed(origin)?;
+      ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+      ensure!(T::Handler::pays_rewards(&pool_id, &currency_id), Error::<T>::NotRewarded);
+
+      let budget = Self::reward_budgets(pool_id, currency_id).checked_add(amount)
+        .ok_or(Error::<T>::RewardCaculationError)?;
+      T::Currency::transfer(currency_id, &who, &Self::account_id(), amount)?;
+      RewardBudgets::<T>::insert(pool_id, currency_id, budget);
+
+      Self::deposit_event(RawEvent::RewardsDeposited(pool_id, who, currency_id, amount));
+    }
+
+    /// Refund the budget of `currency_id` left in the pool to `dest`,
+    /// after the pool stopped being rewarded in the currency.
+    #[weight = T::WeightInfo::refund_rewards()]
+    pub fn refund_rewards(origin, pool_id: T::PoolId, currency_id: CurrencyId, dest: T::AccountId) {
+      T::RefundOrigin::ensure_origin(origin)?;
+      ensure!(!T::Handler::pays_rewards(&pool_id, &currency_id), Error::<T>::StillRewarded);
+
+      // the rewards until the end of the program are paid from the budget first
+      Self::update_pool_reward(&pool_id)?;
+      let amount = RewardBudgets::<T>::take(pool_id, currency_id);
+      ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+      T::Currency::transfer(currency_id, &Self::account_id(), &dest, amount)?;
+
+      Self::deposit_event(RawEvent::RewardsRefunded(pool_id, currency_id, dest, amount));
+    }
   }
 }
 
 impl<T: Trait> Module<T> {
+  /// account which keeps the reward budgets of all pools
+  pub fn account_id() -> T::AccountId {
+    T::ModuleId::get().into_account()
+  }
+
   pub fn sub_account_id(pool_id: T::PoolId) -> T::AccountId {
     T::ModuleId::get().into_sub_account(pool_id)
   }
@@ -152,7 +335,7 @@ impl<T: Trait> Module<T> {
     amount: Share) -> Result<
       (PoolInfo<Share, Balance, T::BlockNumber>,
        PoolAccountInfo<Share, Balance>,
-       Balance),
+       vec::Vec<(CurrencyId, Balance)>),
     DispatchError> {
 
     // amount > 0 and user has sufficient shares to remove
@@ -160,61 +343,69 @@ impl<T: Trait> Module<T> {
       return Err(Error::<T>::InsufficientShares.into());
     }
 
-    // total rewards should send to account per shares including some amount 'borrowed'
-    let reward_with_virtual = Self::calc_reward_by_shares(&pool_info, &amount)?;
+    let mut new_pool_info = pool_info.clone();
+    let mut new_account_info = account_info.clone();
+    let mut rewards = vec::Vec::new();
 
-    let PoolInfo { total_shares, total_rewards, total_rewards_useable, ..} = pool_info;
-    let PoolAccountInfo { shares, borrowed_amount } = account_info;
+    for (currency_id, reward_info) in pool_info.rewards.iter() {
+      // total rewards should send to account per shares including some amount 'borrowed'
+      let reward_with_virtual = Self::calc_reward_by_shares(&pool_info.total_shares, reward_info, &amount)?;
 
-    // remove the balance from reward pool account
-    let account_balance_to_remove = Ratio::checked_from_rational(amount, shares)
-      .and_then(|n| n.checked_mul_int(borrowed_amount))
-      .ok_or(Error::<T>::RewardCaculationError)?;
+      let RewardInfo { total_rewards, total_rewards_useable } = reward_info.clone();
+      let borrowed_amount = account_info.borrowed_amount(currency_id);
 
-    let new_balance = borrowed_amount.checked_sub(account_balance_to_remove)
-      .ok_or(Error::<T>::RewardCaculationError)?;
-
-    let reward = reward_with_virtual.checked_sub(account_balance_to_remove)
-      .ok_or(Error::<T>::RewardCaculationError)?;
-
-    // should not happen, but it's nice to have a check
-    if reward > total_rewards_useable || new_balance > borrowed_amount {
-      debug::error!("got wrong reward for account: {:?}, pool info: {:?}, shares: {:?}", account_info, pool_info, amount);
-      return Err(Error::<T>::RewardCaculationError.into());
-    }
-    let total_shares = total_shares.checked_sub(amount)
-      .ok_or(Error::<T>::InsufficientShares)?;
-    let (reward, total_rewards, total_rewards_useable) = if reward <= T::ExistentialReward::get() {
-      debug::warn!("reward {:?} is less than existential reward, don't send the reward", reward);
-      (0, total_rewards, total_rewards_useable)
-    } else {
-      let rewards = total_rewards.checked_sub(reward_with_virtual)
+      // remove the balance from reward pool account
+      let account_balance_to_remove = Ratio::checked_from_rational(amount, account_info.shares)
+        .and_then(|n| n.checked_mul_int(borrowed_amount))
         .ok_or(Error::<T>::RewardCaculationError)?;
-      let rewards_useable = total_rewards_useable.checked_sub(reward)
+
+      let new_balance = borrowed_amount.checked_sub(account_balance_to_remove)
         .ok_or(Error::<T>::RewardCaculationError)?;
-      (reward, rewards, rewards_useable)
-    };
 
-    let pool_info = PoolInfo::<Share, Balance,  T::BlockNumber> {
-      total_rewards, total_rewards_useable, total_shares,
-      ..pool_info
-    };
-    let shares = shares.checked_sub(amount)
-      .ok_or(Error::<T>::RewardCaculationError)?;
+      let reward = reward_with_virtual.checked_sub(account_balance_to_remove)
+        .ok_or(Error::<T>::RewardCaculationError)?;
 
-    let account_info = PoolAccountInfo::<Share, Balance> {
-      shares, borrowed_amount: new_balance,
-      ..account_info
-    };
+      // should not happen, but it's nice to have a check
+      if reward > total_rewards_useable || new_balance > borrowed_amount {
+        debug::error!("got wrong reward for account: {:?}, pool info: {:?}, shares: {:?}", account_info, pool_info, amount);
+        return Err(Error::<T>::RewardCaculationError.into());
+      }
+      // a zero reward is settled as usual, so the 'borrowed' amount leaves the pool with the shares
+      let (reward, total_rewards, total_rewards_useable) = if !reward.is_zero() && reward <= T::ExistentialReward::get() {
+        debug::warn!("reward {:?} of {:?} is less than existential reward, don't send the reward", reward, currency_id);
+        (0, total_rewards, total_rewards_useable)
+      } else {
+        let rewards = total_rewards.checked_sub(reward_with_virtual)
+          .ok_or(Error::<T>::RewardCaculationError)?;
+        let rewards_useable = total_rewards_useable.checked_sub(reward)
+          .ok_or(Error::<T>::RewardCaculationError)?;
+        (reward, rewards, rewards_useable)
+      };
+
+      if total_rewards.is_zero() && total_rewards_useable.is_zero() {
+        new_pool_info.rewards.remove(currency_id);
+      } else {
+        new_pool_info.rewards.insert(*currency_id, RewardInfo { total_rewards, total_rewards_useable });
+      }
+      new_account_info.set_borrowed_amount(*currency_id, new_balance);
+      if !reward.is_zero() {
+        rewards.push((*currency_id, reward));
+      }
+    }
 
+    new_pool_info.total_shares = pool_info.total_shares.checked_sub(amount)
+      .ok_or(Error::<T>::InsufficientShares)?;
+    new_account_info.shares = account_info.shares.checked_sub(amount)
+      .ok_or(Error::<T>::RewardCaculationError)?;
 
-    Ok((pool_info, account_info, reward))
+    Ok((new_pool_info, new_account_info, rewards))
   }
 
-  // returns (actual_reward, total_rewards_in_pool, total_rewards_useable_in_pool)
-  fn calc_reward_by_shares(pool_info: &PoolInfo<Share, Balance, T::BlockNumber>,
+  // returns the reward of `amount` shares including the 'borrowed' amount
+  fn calc_reward_by_shares(total_shares: &Share,
+                           reward_info: &RewardInfo<Balance>,
                            amount: &Share) -> Result<Balance, DispatchError> {
-    let PoolInfo { total_shares, total_rewards, ..} = pool_info;
+    let RewardInfo { total_rewards, ..} = reward_info;
 
     if total_shares.is_zero() || amount.is_zero() {
       return Ok(Zero::zero());
@@ -233,7 +424,7 @@ impl<T: Trait> Module<T> {
 
     // should not happen, but it's nice to have a check
     if &reward_with_virtual > total_rewards {
-      debug::error!("got wrong reward for pool info: {:?}, shares: {:?}", pool_info, amount);
+      debug::error!("got wrong reward for reward info: {:?}, total shares: {:?}, shares: {:?}", reward_info, total_shares, amount);
       return Err(Error::<T>::RewardCaculationError.into());
     }
 
@@ -241,16 +432,23 @@ impl<T: Trait> Module<T> {
   }
 
   /// update the pool reward and releated storage
+  /// the rewards are moved from the budgets to the pool account
   fn update_pool_reward(pool: &T::PoolId,)
                         -> Result<PoolInfo<Share, Balance, T::BlockNumber>, DispatchError> {
-    let (pool_info, balance_change) = Self::calc_pool_reward(pool)?;
+    let (pool_info, rewards) = Self::calc_pool_reward(pool)?;
 
-    if !balance_change.is_zero() {
+    if !rewards.is_empty() {
       let sub_account = Self::sub_account_id(pool.clone());
-      debug::info!("updating reward pool {:?}, account {:?} balance by: {:?}", pool, sub_account, balance_change);
-
-      let amount = balance_change.unique_saturated_into();
-      T::Currency::update_balance(T::GetNativeCurrencyId::get(), &sub_account, amount)?;
+      for &(currency_id, amount) in &rewards {
+        debug::info!("updating reward pool {:?}, account {:?} balance of {:?} by: {:?}", pool, sub_account, currency_id, amount);
+
+        RewardBudgets::<T>::try_mutate(pool, currency_id, |budget| -> DispatchResult {
+          *budget = budget.checked_sub(amount).ok_or(Error::<T>::InsufficientBudget)?;
+          Ok(())
+        })?;
+        T::Currency::transfer(currency_id, &Self::account_id(), &sub_account, amount)?;
+      }
+      Self::record_paid_rewards(pool, &rewards);
     }
     <Pools<T>>::mutate(pool, |info| {
       *info = pool_info.clone();
@@ -259,10 +457,36 @@ impl<T: Trait> Module<T> {
     Ok(pool_info)
  }
 
+  /// add the rewards paid to the pool to the current period,
+  /// the periods older than `PaidRewardsHistory` are removed
+  fn record_paid_rewards(pool: &T::PoolId, rewards: &[(CurrencyId, Balance)]) {
+    let now = <frame_system::Module<T>>::block_number();
+    let period = T::PaidRewardsPeriod::get();
+    let period_start = now - now % period;
+    PaidRewards::<T>::mutate(pool, period_start, |paid_rewards| {
+      for &(currency_id, amount) in rewards {
+        match paid_rewards.iter_mut().find(|(id, _)| *id == currency_id) {
+          Some((_, paid)) => *paid = paid.saturating_add(amount),
+          None => paid_rewards.push((currency_id, amount)),
+        }
+      }
+    });
+
+    let kept_periods = T::PaidRewardsHistory::get().saturating_sub(1);
+    let oldest_kept = period_start.saturating_sub(period.saturating_mul(kept_periods.into()));
+    let expired: vec::Vec<T::BlockNumber> = PaidRewards::<T>::iter_prefix(pool)
+      .map(|(start, _)| start)
+      .filter(|start| *start < oldest_kept)
+      .collect();
+    for start in expired {
+      PaidRewards::<T>::remove(pool, start);
+    }
+  }
+
   /// update the pool reward at the specified block height
   fn calc_pool_reward(
     pool: &T::PoolId,
-  ) -> Result<(PoolInfo<Share, Balance, T::BlockNumber>, Balance), DispatchError> {
+  ) -> Result<(PoolInfo<Share, Balance, T::BlockNumber>, vec::Vec<(CurrencyId, Balance)>), DispatchError> {
     let pool_info = Self::get_pool(pool);
     let cur_block = <frame_system::Module<T>>::block_number();
     Self::calc_pool_reward_at_block(pool, &pool_info, &cur_block)
@@ -272,41 +496,162 @@ impl<T: Trait> Module<T> {
     pool: &T::PoolId,
     pool_info: &PoolInfo<Share, Balance, T::BlockNumber>,
     cur_block: &T::BlockNumber
-  ) -> Result<(PoolInfo<Share, Balance, T::BlockNumber>, Balance), DispatchError> {
+  ) -> Result<(PoolInfo<Share, Balance, T::BlockNumber>, vec::Vec<(CurrencyId, Balance)>), DispatchError> {
     let last_update_block  = pool_info.last_update_block;
     if cur_block <= &last_update_block {
       debug::info!("ignore update pool reward: {:?} at block: {:?}, already updated at: {:?}", pool, cur_block, last_update_block);
 
-      return Ok((pool_info.clone(), 0));
+      return Ok((pool_info.clone(), vec![]));
     }
 
-    let reward = T::Handler::caculate_reward(pool, &pool_info.total_shares, last_update_block, cur_block.clone());
+    let rewards = T::Handler::caculate_reward(pool, &pool_info.total_shares, last_update_block, cur_block.clone());
 
     let mut new_info = pool_info.clone();
     new_info.last_update_block = cur_block.clone();
 
-    // reward is zero, this is a valid case
-    // it's not necessary to update the storage in this case
-    if reward == 0 {
-      debug::warn!("0 reward: {:?}, pool: {:?}, between {:?} - {:?}", reward, pool, last_update_block, cur_block);
-      return Ok((new_info, 0));
+    let mut paid_rewards = vec::Vec::new();
+    for (currency_id, reward) in rewards {
+      // rewards are paid from the budget, the part exceeds the budget is not paid
+      let reward = sp_std::cmp::min(reward, Self::reward_budgets(pool, currency_id));
+
+      // reward is zero, this is a valid case
+      // it's not necessary to update the storage in this case
+      if reward == 0 {
+        debug::warn!("0 reward of {:?}, pool: {:?}, between {:?} - {:?}", currency_id, pool, last_update_block, cur_block);
+        continue;
+      }
+
+      let reward_info = new_info.rewards.entry(currency_id).or_default();
+      reward_info.total_rewards = reward_info.total_rewards.checked_add(reward).ok_or(Error::<T>::RewardCaculationError)?;
+      reward_info.total_rewards_useable = reward_info.total_rewards_useable.checked_add(reward).ok_or(Error::<T>::RewardCaculationError)?;
+      paid_rewards.push((currency_id, reward));
+    }
+
+    Ok((new_info, paid_rewards))
+  }
+
+  /// rewards of each currency the account can claim with `claim_rewards`, skips the rewards less than the existential reward,
+  /// returns (currency_id, reward, reward including the 'borrowed' amount)
+  fn calc_claimable_rewards(
+    pool_info: &PoolInfo<Share, Balance, T::BlockNumber>,
+    account_info: &PoolAccountInfo<Share, Balance>,
+  ) -> Result<vec::Vec<(CurrencyId, Balance, Balance)>, DispatchError> {
+    let mut rewards = vec::Vec::new();
+    if account_info.shares.is_zero() {
+      return Ok(rewards);
     }
 
-    new_info.total_rewards = new_info.total_rewards.checked_add(reward).ok_or(Error::<T>::RewardCaculationError)?;
-    new_info.total_rewards_useable = new_info.total_rewards_useable.checked_add(reward).ok_or(Error::<T>::RewardCaculationError)?;
+    for (currency_id, reward_info) in pool_info.rewards.iter() {
+      let reward_with_virtual = Self::calc_reward_by_shares(&pool_info.total_shares, reward_info, &account_info.shares)?;
+      let borrowed_amount = account_info.borrowed_amount(currency_id);
+
+      // another check, total rewards should be greater than borrowed amount
+      if borrowed_amount > reward_with_virtual {
+        return Err(Error::<T>::RewardCaculationError.into());
+      }
+      let actual_reward = reward_with_virtual.checked_sub(borrowed_amount)
+        .ok_or(Error::<T>::RewardCaculationError)?;
+      // don't have enough rewards to claim
+      if actual_reward < T::ExistentialReward::get() {
+        continue;
+      }
+      rewards.push((*currency_id, actual_reward, reward_with_virtual));
+    }
+    Ok(rewards)
+  }
+
+  /// rewards of each currency the account could claim from the pool at block `at`,
+  /// the pool rewards until `at` are simulated, this is a readonly api and doesn't write the storage
+  pub fn get_pending_rewards_at(who: &T::AccountId, pool: &T::PoolId, at: T::BlockNumber) -> vec::Vec<(CurrencyId, Balance)> {
+    let account_info = Self::get_pool_account_info(pool, who);
+    if account_info.shares.is_zero() {
+      return vec![];
+    }
+
+    let calc_reward = || -> Result<vec::Vec<(CurrencyId, Balance)>, DispatchError> {
+      let (pool_info, _) = Self::calc_pool_reward_at_block(pool, &Self::get_pool(pool), &at)?;
+      let rewards = Self::calc_claimable_rewards(&pool_info, &account_info)?;
+      Ok(rewards.into_iter().map(|(currency_id, reward, _)| (currency_id, reward)).collect())
+    };
+    match calc_reward() {
+      Ok(rewards) => rewards,
+      Err(e) => {
+        debug::error!("failed to calculate pending reward for account: {:?}, pool: {:?}, error: {:?}", who, pool, e);
+        vec![]
+      }
+    }
+  }
+
+  /// pools the account has shares in, returns (pool_id, shares, rewards of each currency claimable at block `at`)
+  pub fn get_account_pending_rewards(who: &T::AccountId, at: T::BlockNumber) -> vec::Vec<(T::PoolId, Share, vec::Vec<(CurrencyId, Balance)>)> {
+    <Pools<T>>::iter()
+      .map(|(pool_id, _)| (pool_id, Self::get_account_shares(who, &pool_id)))
+      .filter(|(_, shares)| !shares.is_zero())
+      .map(|(pool_id, shares)| (pool_id, shares, Self::get_pending_rewards_at(who, &pool_id, at)))
+      .collect()
+  }
+
+  /// track the rewards of existing pools and accounts as native currency rewards
+  fn migrate_to_multi_currency_rewards() -> Weight {
+    let native_currency_id = T::GetNativeCurrencyId::get();
+
+    <Pools<T>>::translate::<PoolInfoV1<T::BlockNumber>, _>(|_, old| {
+      let mut rewards = BTreeMap::new();
+      if !old.total_rewards.is_zero() || !old.total_rewards_useable.is_zero() {
+        rewards.insert(native_currency_id, RewardInfo {
+          total_rewards: old.total_rewards,
+          total_rewards_useable: old.total_rewards_useable,
+        });
+      }
+      Some(PoolInfo {
+        total_shares: old.total_shares,
+        rewards,
+        last_update_block: old.last_update_block,
+      })
+    });
+
+    <PoolAccountData<T>>::translate::<PoolAccountInfoV1, _>(|_, _, old| {
+      let mut info = PoolAccountInfo { shares: old.shares, borrowed_amounts: BTreeMap::new() };
+      info.set_borrowed_amount(native_currency_id, old.borrowed_amount);
+      Some(info)
+    });
+
+    Self::seed_reward_budgets();
 
-    Ok((new_info, reward))
+    StorageVersion::put(Releases::V2_0_0);
+    T::MaximumBlockWeight::get()
+  }
+
+  /// fund the rewards of the next `MigratedRewardPeriod` blocks to the budget of every pool from `MigratedRewardSource`,
+  /// so the pools keep being rewarded as they were when the rewards were minted
+  fn seed_reward_budgets() {
+    let source = T::MigratedRewardSource::get();
+    let now = <frame_system::Module<T>>::block_number();
+    let end = now.saturating_add(T::MigratedRewardPeriod::get());
+    let pool_ids: vec::Vec<T::PoolId> = <Pools<T>>::iter().map(|(pool_id, _)| pool_id).collect();
+    for pool_id in pool_ids {
+      // the budget doesn't depend on the shares, any non zero share gets the rewards of the pool
+      for (currency_id, amount) in T::Handler::caculate_reward(&pool_id, &One::one(), now, end) {
+        if let Err(e) = T::Currency::transfer(currency_id, &source, &Self::account_id(), amount) {
+          debug::error!("failed to seed reward budget of pool {:?}, {:?}: {:?}", pool_id, currency_id, e);
+          continue;
+        }
+        RewardBudgets::<T>::mutate(pool_id, currency_id, |budget| {
+          *budget = budget.saturating_add(amount);
+        });
+      }
+    }
   }
 }
 
-impl<T: Trait> RewardPoolOps<T::AccountId, T::PoolId, Share, Balance> for Module<T> {
+impl<T: Trait> RewardPoolOps<T::AccountId, T::PoolId, CurrencyId, Share, Balance> for Module<T> {
   /// add shares to the reward pool
   /// note: should call this function insdie a storage transaction
   /// steps:
   /// 1. update the rewards
-  /// 2. caculate the share price in the pool
-  /// 3. calculate native currency amount needs to add to the pool to balance the share price
-  /// 4. the native currency amount is user "borrowed" which should repay back when user
+  /// 2. caculate the share price of each reward currency in the pool
+  /// 3. calculate the reward amount needs to add to the pool to balance the share price
+  /// 4. the reward amount is user "borrowed" which should repay back when user
   ///    removes shares from the reward pool
   /// the rewards are allocated at (block_add, block_remove]
   fn add_share(who: &T::AccountId, pool: T::PoolId, amount: Share) -> Result<Share, DispatchError> {
@@ -316,23 +661,28 @@ impl<T: Trait> RewardPoolOps<T::AccountId, T::PoolId, Share, Balance> for Module
 
     let mut pool_info = Self::update_pool_reward(&pool)?;
 
-    let price = if pool_info.total_shares.is_zero() {
-      Ok(Price::zero())
-    } else {
-      Price::checked_from_rational(pool_info.total_rewards, pool_info.total_shares)
-        .ok_or(Error::<T>::RewardCaculationError)
-    }?;
+    let mut virtual_reward_amounts = vec::Vec::new();
+    for (currency_id, reward_info) in pool_info.rewards.iter_mut() {
+      let price = if pool_info.total_shares.is_zero() {
+        Ok(Price::zero())
+      } else {
+        Price::checked_from_rational(reward_info.total_rewards, pool_info.total_shares)
+          .ok_or(Error::<T>::RewardCaculationError)
+      }?;
+
+      let virtual_reward_amount = price
+        .checked_mul_int(amount)
+        .ok_or(Error::<T>::RewardCaculationError)?;
 
-    let virtual_reward_amount = price
-      .checked_mul_int(amount)
-      .ok_or(Error::<T>::RewardCaculationError)?;
+      reward_info.total_rewards = reward_info.total_rewards.checked_add(virtual_reward_amount.into())
+        .ok_or(Error::<T>::RewardCaculationError)?;
+      virtual_reward_amounts.push((*currency_id, virtual_reward_amount));
+    }
 
     pool_info.total_shares = pool_info.total_shares.checked_add(amount)
       .ok_or(Error::<T>::RewardCaculationError)?;
-    pool_info.total_rewards = pool_info.total_rewards.checked_add(virtual_reward_amount.into())
-      .ok_or(Error::<T>::RewardCaculationError)?;
     //
-    // the account need to "borrow" the amount of native currencies to balance the reward pool
+    // the account need to "borrow" the amount of reward currencies to balance the reward pool
     <Pools<T>>::mutate(pool, |info| {
       *info = pool_info;
     });
@@ -341,7 +691,11 @@ impl<T: Trait> RewardPoolOps<T::AccountId, T::PoolId, Share, Balance> for Module
     <PoolAccountData<T>>::try_mutate(pool, who, |data| -> DispatchResult {
       data.shares = data.shares.checked_add(amount).ok_or(Error::<T>::RewardCaculationError)?;
       // record the virtual rewards that the account 'borrowed'
-      data.borrowed_amount = data.borrowed_amount.checked_add(virtual_reward_amount.into()).ok_or(Error::<T>::RewardCaculationError)?;
+      for (currency_id, virtual_reward_amount) in virtual_reward_amounts {
+        let borrowed_amount = data.borrowed_amount(&currency_id).checked_add(virtual_reward_amount.into())
+          .ok_or(Error::<T>::RewardCaculationError)?;
+        data.set_borrowed_amount(currency_id, borrowed_amount);
+      }
       total_shares = data.shares;
       Ok(())
     })?;
@@ -359,7 +713,7 @@ impl<T: Trait> RewardPoolOps<T::AccountId, T::PoolId, Share, Balance> for Module
       return Err(Error::<T>::InsufficientShares.into());
     }
 
-    let (pool_info, account_info, reward) = Self::get_rewards_by_account_shares(pool_info, account_info, amount)?;
+    let (pool_info, account_info, rewards) = Self::get_rewards_by_account_shares(pool_info, account_info, amount)?;
 
     <Pools<T>>::mutate(pool, |info| {
       *info = pool_info;
@@ -370,7 +724,9 @@ impl<T: Trait> RewardPoolOps<T::AccountId, T::PoolId, Share, Balance> for Module
     });
 
     let sub_account = Self::sub_account_id(pool);
-    T::Currency::transfer(T::GetNativeCurrencyId::get(), &sub_account, &who, reward)?;
+    for (currency_id, reward) in rewards {
+      T::Currency::transfer(currency_id, &sub_account, &who, reward)?;
+    }
 
     Ok(account_info.shares)
   }
@@ -381,88 +737,81 @@ impl<T: Trait> RewardPoolOps<T::AccountId, T::PoolId, Share, Balance> for Module
     shares
   }
 
-  /// calculate accumlated rewards which haven't been claimed
+  /// calculate accumlated rewards of each currency which haven't been claimed
   /// this is a readonly api and should not write the storage
-  fn get_accumlated_rewards(who: &T::AccountId, pool: &T::PoolId) -> Balance {
+  fn get_accumlated_rewards(who: &T::AccountId, pool: &T::PoolId) -> vec::Vec<(CurrencyId, Balance)> {
     let account_info  = Self::get_pool_account_info(&pool, who);
     if account_info.shares.is_zero() {
-      return 0;
+      return vec![];
     }
 
-    let calc_reward = || -> Result<Balance, DispatchError> {
+    let calc_reward = || -> Result<vec::Vec<(CurrencyId, Balance)>, DispatchError> {
       // update the pool info to now
       let (pool_info, _) = Self::calc_pool_reward(pool)?;
       let shares = account_info.shares.clone();
-      let (_, _, reward) = Self::get_rewards_by_account_shares(pool_info, account_info, shares)?;
-      Ok(reward)
+      let (_, _, rewards) = Self::get_rewards_by_account_shares(pool_info, account_info, shares)?;
+      Ok(rewards)
     };
     match calc_reward() {
-      Ok(reward) => reward,
+      Ok(rewards) => rewards,
       Err(e) => {
         debug::error!("failed to calculate reward for account: {:?}, pool: {:?}, error: {:?}", who, pool, e);
-        Zero::zero()
+        vec![]
       }
     }
   }
 
-  fn claim_rewards(who: &T::AccountId, pool: &T::PoolId) -> Result<Balance, DispatchError> {
+  fn claim_rewards(who: &T::AccountId, pool: &T::PoolId) -> Result<vec::Vec<(CurrencyId, Balance)>, DispatchError> {
     // update accumlated rewards for the pool
     let pool_info = Self::update_pool_reward(&pool)?;
     let account_info  = Self::get_pool_account_info(&pool, who);
 
-    if account_info.shares.is_zero() {
-      return Ok(Zero::zero());
-    }
-
-    let reward_with_virtual = Self::calc_reward_by_shares(&pool_info, &account_info.shares)?;
-
-    let PoolInfo { total_rewards_useable, ..} = pool_info;
-    let PoolAccountInfo { borrowed_amount, ..} = account_info;
-
-    let actual_reward = reward_with_virtual.checked_sub(borrowed_amount)
-      .ok_or(Error::<T>::RewardCaculationError)?;
-    // don't have enough rewards to claim
-    if actual_reward < T::ExistentialReward::get() {
-      return Ok(Zero::zero());
-    }
-    let total_rewards_useable = total_rewards_useable.checked_sub(actual_reward)
-      .ok_or(Error::<T>::RewardCaculationError)?;
+    let sub_account = Self::sub_account_id(pool.clone());
+    let mut rewards = vec::Vec::new();
 
-    // another check, total rewards should be greater than borrowed amount
-    if borrowed_amount > reward_with_virtual {
-      return Err(Error::<T>::RewardCaculationError.into());
-    }
-    // since we've claimed all available rewards, we should borrow the reward from the pool, the claimable rewards is zero
-    let borrowed_amount = reward_with_virtual;
+    for (currency_id, actual_reward, reward_with_virtual) in Self::calc_claimable_rewards(&pool_info, &account_info)? {
+      let total_rewards_useable = pool_info.reward_info(&currency_id).total_rewards_useable.checked_sub(actual_reward)
+        .ok_or(Error::<T>::RewardCaculationError)?;
 
-    let sub_account = Self::sub_account_id(pool.clone());
+      T::Currency::transfer(currency_id, &sub_account, &who, actual_reward)?;
 
-    T::Currency::transfer(T::GetNativeCurrencyId::get(), &sub_account, &who, actual_reward.unique_saturated_into())?;
+      <Pools<T>>::mutate(pool, |info| {
+        info.rewards.entry(currency_id).or_default().total_rewards_useable = total_rewards_useable;
+      });
 
-    <Pools<T>>::mutate(pool, |info| {
-      info.total_rewards_useable = total_rewards_useable;
-    });
+      // since we've claimed all available rewards, we should borrow the reward from the pool, the claimable rewards is zero
+      <PoolAccountData<T>>::mutate(pool, who, |data| {
+        data.set_borrowed_amount(currency_id, reward_with_virtual);
+      });
 
-    <PoolAccountData<T>>::mutate(pool, who, |data| {
-      data.borrowed_amount = borrowed_amount;
-    });
+      rewards.push((currency_id, actual_reward));
+    }
 
-    Ok(actual_reward)
+    Ok(rewards)
   }
 
-  fn get_all_pools() -> vec::Vec<(T::PoolId, Share, Balance)> {
+  fn get_all_pools() -> vec::Vec<(T::PoolId, Share, vec::Vec<(CurrencyId, Balance)>)> {
     let cur_block = <frame_system::Module<T>>::block_number();
     <Pools<T>>::iter()
       .map(|(pool_id, info)| {
         let result = Self::calc_pool_reward_at_block(&pool_id, &info, &cur_block);
         match result {
-          Ok((new_info, _)) => (pool_id, new_info.total_shares, new_info.total_rewards_useable),
+          Ok((new_info, _)) => {
+            let rewards = new_info.rewards.iter()
+              .map(|(currency_id, reward_info)| (*currency_id, reward_info.total_rewards_useable))
+              .collect();
+            (pool_id, new_info.total_shares, rewards)
+          },
           Err(e) => {
             debug::error!("failed to get pool info for {:?}, error: {:?}", pool_id, e);
-            (pool_id, info.total_shares, Zero::zero())
+            (pool_id, info.total_shares, vec![])
           },
         }
       }).collect()
   }
-}
 
+  fn update_rewards(pool: &T::PoolId) -> DispatchResult {
+    Self::update_pool_reward(pool)?;
+    Ok(())
+  }
+}
@@ -18,6 +18,8 @@ pub use primitives::{
 };
 
 use orml_currencies::{BasicCurrencyAdapter};
+use std::cell::RefCell;
+use serde::{Deserialize, Serialize};
 
 pub type BlockNumber = u64;
 
@@ -125,26 +127,53 @@ pub type Currencies = orml_currencies::Module<TestRuntime>;
 
 parameter_types! {
   pub const RewardPoolModuleId: ModuleId = ModuleId(*b"clv/repm");
+  pub const MigratedRewardPeriod: BlockNumber = 10;
+  pub MigratedRewardSource: AccountId = AccountId::from(BOB);
+  pub const PaidRewardsPeriod: BlockNumber = 10;
+  pub const PaidRewardsHistory: u32 = 3;
 }
 
-#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, Ord, PartialOrd)]
+thread_local! {
+  static ENDED_POOLS: RefCell<Vec<PoolId>> = RefCell::new(vec![]);
+}
+
+/// stop rewarding the pool
+pub fn end_rewards(pool_id: PoolId) {
+  ENDED_POOLS.with(|pools| pools.borrow_mut().push(pool_id));
+}
+
+fn is_ended(pool_id: &PoolId) -> bool {
+  ENDED_POOLS.with(|pools| pools.borrow().contains(pool_id))
+}
+
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, Ord, PartialOrd, Serialize, Deserialize)]
 pub enum PoolId {
   Swap(u64),
 }
 
 pub struct Handler;
-impl RewardHandler<AccountId, BlockNumber, Balance, Share, PoolId> for Handler {
-  // simple reward calculation, 1 block 1 reward
+impl RewardHandler<AccountId, BlockNumber, CurrencyId, Balance, Share, PoolId> for Handler {
+  // simple reward calculation, 1 block 1 CLV and 2 DOT reward
   fn caculate_reward(pool_id: &PoolId, total_share: &Share, last_update_block: BlockNumber,
-                     now: BlockNumber) -> Balance {
+                     now: BlockNumber) -> Vec<(CurrencyId, Balance)> {
     println!("calculate reward for pool: {:?}", pool_id);
     if total_share.is_zero() {
       println!("no reward because no share in pool, pool: {:?}", pool_id);
-      0
+      vec![]
+    } else if is_ended(pool_id) {
+      vec![]
     } else {
-      DOLLARS.checked_mul((now - last_update_block).into()).unwrap()
+      let blocks: Balance = (now - last_update_block).into();
+      vec![
+        (CLV, DOLLARS.checked_mul(blocks).unwrap()),
+        (DOT, (2 * DOLLARS).checked_mul(blocks).unwrap()),
+      ]
     }
   }
+
+  fn pays_rewards(pool_id: &PoolId, currency_id: &CurrencyId) -> bool {
+    !is_ended(pool_id) && (*currency_id == CLV || *currency_id == DOT)
+  }
 }
 
 impl Trait for TestRuntime {
@@ -155,6 +184,12 @@ impl Trait for TestRuntime {
   type PoolId = PoolId;
   type Handler = Handler;
   type ExistentialReward = ExistentialDeposit;
+  type MigratedRewardPeriod = MigratedRewardPeriod;
+  type MigratedRewardSource = MigratedRewardSource;
+  type RefundOrigin = frame_system::EnsureRoot<AccountId>;
+  type PaidRewardsPeriod = PaidRewardsPeriod;
+  type PaidRewardsHistory = PaidRewardsHistory;
+  type WeightInfo = ();
 }
 
 pub type RewardPoolModule = Module<TestRuntime>;
@@ -218,6 +253,12 @@ impl ExtBuilder {
     }
     .assimilate_storage(&mut t).unwrap();
 
+    // only the CLV rewards of the swap pool 1 are funded by default
+    reward_pool::GenesisConfig::<TestRuntime> {
+      reward_budgets: vec![(PoolId::Swap(1), CLV, 1_000_000 * DOLLARS)],
+    }
+    .assimilate_storage(&mut t).unwrap();
+
     t.into()
   }
 }
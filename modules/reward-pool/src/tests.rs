@@ -1,9 +1,10 @@
 #![cfg(test)]
 
 use super::*;
+use frame_support::{assert_noop, assert_ok};
 use mock::{
-  ALICE, BOB, DAVE, Currencies, PoolId, RewardPoolModule,
-  run_to_block,
+  ALICE, BOB, DAVE, CLV, CUSDT, DOT, Currencies, Origin, PoolId, RewardPoolModule,
+  end_rewards, run_to_block,
   ExtBuilder,
 };
 
@@ -17,12 +18,12 @@ fn check_pool_data (pool_id: &PoolId, account: &AccountId,
                     alice_shares: Share, alice_borrow: Balance,) {
   let pool_info = RPM::get_pool_info(&pool_id);
   assert_eq!(pool_info.total_shares, total_shares);
-  assert_eq!(pool_info.total_rewards, total_rewards);
-  assert_eq!(pool_info.total_rewards_useable, total_rewards_useable);
+  assert_eq!(pool_info.reward_info(&CLV).total_rewards, total_rewards);
+  assert_eq!(pool_info.reward_info(&CLV).total_rewards_useable, total_rewards_useable);
 
   let alice_info = RPM::get_pool_account_info(&pool_id, &account);
   assert_eq!(alice_info.shares, alice_shares);
-  assert_eq!(alice_info.borrowed_amount, alice_borrow);
+  assert_eq!(alice_info.borrowed_amount(&CLV), alice_borrow);
 }
 
 
@@ -35,8 +36,8 @@ fn test_reward_calc_on_no_shares() {
     assert!(r.is_ok());
     let pool_info = RPM::get_pool_info(&pool_id);
     assert_eq!(pool_info.total_shares, 0, "should be no shares");
-    assert_eq!(pool_info.total_rewards, 0, "should be no rewards");
-    assert_eq!(pool_info.total_rewards_useable, 0, "should be no rewards usable");
+    assert_eq!(pool_info.reward_info(&CLV).total_rewards, 0, "should be no rewards");
+    assert_eq!(pool_info.reward_info(&CLV).total_rewards_useable, 0, "should be no rewards usable");
 
     // sometime passed...
     run_to_block(20);
@@ -44,8 +45,8 @@ fn test_reward_calc_on_no_shares() {
     assert_eq!(r.is_ok(), true);
     let pool_info = RPM::get_pool_info(&pool_id);
     assert_eq!(pool_info.total_shares, 0, "should be no shares");
-    assert_eq!(pool_info.total_rewards, 0, "should be no rewards");
-    assert_eq!(pool_info.total_rewards_useable, 0, "should be no rewards usable");
+    assert_eq!(pool_info.reward_info(&CLV).total_rewards, 0, "should be no rewards");
+    assert_eq!(pool_info.reward_info(&CLV).total_rewards_useable, 0, "should be no rewards usable");
   });
 }
 
@@ -236,3 +237,182 @@ fn test_claim_rewards() {
     assert_eq!(Currencies::total_balance(CurrencyId::CLV, &pool_account), 625 * DOLLARS / 10);
   });
 }
+
+#[test]
+fn test_multi_currency_rewards_from_budget() {
+  let pool_id = PoolId::Swap(1);
+  let alice = AccountId::from(ALICE);
+  let bob = AccountId::from(BOB);
+
+  ExtBuilder::default().build().execute_with(|| {
+    let initial_clv = Currencies::total_balance(CLV, &alice);
+    let initial_dot = Currencies::total_balance(DOT, &alice);
+
+    assert_noop!(RPM::deposit_rewards(Origin::signed(bob.clone()), pool_id, DOT, 0), Error::<mock::TestRuntime>::InvalidAmount);
+    // the pool isn't rewarded in CUSDT
+    assert_noop!(
+      RPM::deposit_rewards(Origin::signed(bob.clone()), pool_id, CUSDT, 15 * DOLLARS),
+      Error::<mock::TestRuntime>::NotRewarded
+    );
+    // only enough for 7.5 blocks of DOT rewards
+    assert_ok!(RPM::deposit_rewards(Origin::signed(bob.clone()), pool_id, DOT, 15 * DOLLARS));
+    assert_eq!(RPM::reward_budgets(pool_id, DOT), 15 * DOLLARS);
+    assert_eq!(Currencies::total_balance(DOT, &RPM::account_id()), 15 * DOLLARS);
+
+    run_to_block(10);
+    assert!(RPM::add_share(&alice, pool_id, 100).is_ok(), "should add shares to the pool");
+    run_to_block(20);
+    // DOT rewards are limited by the budget
+    assert_eq!(RPM::get_accumlated_rewards(&alice, &pool_id), vec![(CLV, 10 * DOLLARS), (DOT, 15 * DOLLARS)]);
+    assert_eq!(RPM::claim_rewards(&alice, &pool_id), Ok(vec![(CLV, 10 * DOLLARS), (DOT, 15 * DOLLARS)]));
+    assert_eq!(RPM::reward_budgets(pool_id, DOT), 0);
+    assert_eq!(RPM::reward_budgets(pool_id, CLV), 1_000_000 * DOLLARS - 10 * DOLLARS);
+    assert_eq!(Currencies::total_balance(CLV, &alice), initial_clv + 10 * DOLLARS);
+    assert_eq!(Currencies::total_balance(DOT, &alice), initial_dot + 15 * DOLLARS);
+
+    // the DOT budget is used up, only CLV is rewarded
+    run_to_block(30);
+    assert_eq!(RPM::get_accumlated_rewards(&alice, &pool_id), vec![(CLV, 10 * DOLLARS)]);
+    assert!(RPM::remove_share(&alice, pool_id, 100).is_ok(), "should remove shares from the pool");
+    assert_eq!(Currencies::total_balance(CLV, &alice), initial_clv + 20 * DOLLARS);
+    assert_eq!(Currencies::total_balance(DOT, &alice), initial_dot + 15 * DOLLARS);
+    assert_eq!(RPM::get_pool_info(&pool_id).rewards, Default::default());
+  });
+}
+
+#[test]
+fn test_pending_rewards() {
+  let pool_id = PoolId::Swap(1);
+  let alice = AccountId::from(ALICE);
+  let bob = AccountId::from(BOB);
+
+  ExtBuilder::default().build().execute_with(|| {
+    assert_eq!(RPM::get_account_pending_rewards(&alice, 10), vec![]);
+
+    run_to_block(10);
+    assert!(RPM::add_share(&alice, pool_id, 100).is_ok(), "should add shares to the pool");
+    assert!(RPM::add_share(&bob, pool_id, 300).is_ok(), "should add shares to the pool");
+
+    run_to_block(20);
+    // the rewards of the next block are included
+    assert_eq!(RPM::get_pending_rewards_at(&alice, &pool_id, 21), vec![(CLV, 11 * DOLLARS / 4)]);
+    assert_eq!(RPM::get_account_pending_rewards(&bob, 21), vec![(pool_id, 300, vec![(CLV, 33 * DOLLARS / 4)])]);
+    // the storage is not updated
+    assert_eq!(RPM::get_pool_info(&pool_id).last_update_block, 10);
+
+    assert_eq!(RPM::claim_rewards(&alice, &pool_id), Ok(vec![(CLV, 10 * DOLLARS / 4)]));
+    assert_eq!(RPM::get_pending_rewards_at(&alice, &pool_id, 20), vec![]);
+    assert_eq!(RPM::get_pending_rewards_at(&alice, &pool_id, 24), vec![(CLV, DOLLARS)]);
+  });
+}
+
+#[test]
+fn paid_rewards_are_recorded_by_period() {
+  let pool_id = PoolId::Swap(1);
+  let alice = AccountId::from(ALICE);
+
+  ExtBuilder::default().build().execute_with(|| {
+    run_to_block(10);
+    assert!(RPM::add_share(&alice, pool_id, 100).is_ok(), "should add shares to the pool");
+    run_to_block(15);
+    assert_ok!(RPM::update_rewards(&pool_id));
+    run_to_block(25);
+    assert_ok!(RPM::update_rewards(&pool_id));
+    // the rewards are recorded in the period they are paid, there is no DOT budget
+    assert_eq!(RPM::paid_rewards(pool_id, 10), vec![(CLV, 5 * DOLLARS)]);
+    assert_eq!(RPM::paid_rewards(pool_id, 20), vec![(CLV, 10 * DOLLARS)]);
+    assert_ok!(RPM::update_rewards(&pool_id));
+    assert_eq!(RPM::paid_rewards(pool_id, 20), vec![(CLV, 10 * DOLLARS)]);
+
+    // only the last 3 periods are kept
+    run_to_block(43);
+    assert_ok!(RPM::update_rewards(&pool_id));
+    assert_eq!(RPM::paid_rewards(pool_id, 40), vec![(CLV, 18 * DOLLARS)]);
+    assert_eq!(RPM::paid_rewards(pool_id, 20), vec![(CLV, 10 * DOLLARS)]);
+    assert_eq!(RPM::paid_rewards(pool_id, 10), vec![]);
+  });
+}
+
+#[test]
+fn migration_seeds_reward_budgets() {
+  use codec::Compact;
+  use frame_support::{storage::unhashed, traits::OnRuntimeUpgrade, StorageMap, StorageValue};
+  use mock::TestRuntime;
+
+  let pool_id = PoolId::Swap(2);
+  let alice = AccountId::from(ALICE);
+
+  ExtBuilder::default().build().execute_with(|| {
+    run_to_block(5);
+    StorageVersion::put(Releases::V1_0_0);
+    let old_pool = (Compact(100 as Share), Compact(30 * DOLLARS), Compact(20 * DOLLARS), Compact(4u64));
+    unhashed::put_raw(&Pools::<TestRuntime>::hashed_key_for(pool_id), &old_pool.encode());
+    let module_clv = Currencies::total_balance(CLV, &RPM::account_id());
+    // the budgets are funded by the `MigratedRewardSource`
+    let bob = AccountId::from(BOB);
+    let (bob_clv, bob_dot) = (Currencies::total_balance(CLV, &bob), Currencies::total_balance(DOT, &bob));
+
+    RPM::on_runtime_upgrade();
+
+    let pool_info = RPM::get_pool_info(&pool_id);
+    assert_eq!(pool_info.total_shares, 100);
+    assert_eq!(pool_info.reward_info(&CLV).total_rewards, 30 * DOLLARS);
+    assert_eq!(pool_info.reward_info(&CLV).total_rewards_useable, 20 * DOLLARS);
+    assert_eq!(pool_info.last_update_block, 4);
+
+    // the rewards of `MigratedRewardPeriod` blocks are deposited to the budgets
+    assert_eq!(RPM::reward_budgets(pool_id, CLV), 10 * DOLLARS);
+    assert_eq!(RPM::reward_budgets(pool_id, DOT), 20 * DOLLARS);
+    assert_eq!(Currencies::total_balance(CLV, &RPM::account_id()), module_clv + 10 * DOLLARS);
+    assert_eq!(Currencies::total_balance(DOT, &RPM::account_id()), 20 * DOLLARS);
+    assert_eq!(Currencies::total_balance(CLV, &bob), bob_clv - 10 * DOLLARS);
+    assert_eq!(Currencies::total_balance(DOT, &bob), bob_dot - 20 * DOLLARS);
+    assert_eq!(StorageVersion::get(), Releases::V2_0_0);
+
+    // the migration runs only once
+    RPM::on_runtime_upgrade();
+    assert_eq!(RPM::reward_budgets(pool_id, CLV), 10 * DOLLARS);
+
+    // the seeded budgets pay the rewards of the pool
+    assert_ok!(RPM::add_share(&alice, pool_id, 100));
+    assert_eq!(RPM::reward_budgets(pool_id, CLV), 9 * DOLLARS);
+  });
+}
+
+#[test]
+fn refund_rewards_works() {
+  use sp_runtime::traits::BadOrigin;
+
+  let pool_id = PoolId::Swap(1);
+  let alice = AccountId::from(ALICE);
+  let bob = AccountId::from(BOB);
+  let dave = AccountId::from(DAVE);
+
+  ExtBuilder::default().build().execute_with(|| {
+    assert_ok!(RPM::deposit_rewards(Origin::signed(bob.clone()), pool_id, DOT, 30 * DOLLARS));
+    run_to_block(10);
+    assert!(RPM::add_share(&alice, pool_id, 100).is_ok(), "should add shares to the pool");
+    run_to_block(15);
+
+    assert_noop!(RPM::refund_rewards(Origin::signed(bob.clone()), pool_id, DOT, dave.clone()), BadOrigin);
+    assert_noop!(
+      RPM::refund_rewards(Origin::root(), pool_id, DOT, dave.clone()),
+      Error::<mock::TestRuntime>::StillRewarded
+    );
+
+    // the rewards of the 5 blocks before the end are paid, the rest is refunded
+    assert_ok!(RPM::update_rewards(&pool_id));
+    end_rewards(pool_id);
+    run_to_block(20);
+    assert_ok!(RPM::refund_rewards(Origin::root(), pool_id, DOT, dave.clone()));
+    assert_eq!(RPM::reward_budgets(pool_id, DOT), 0);
+    assert_eq!(Currencies::total_balance(DOT, &dave), 20 * DOLLARS);
+    assert_eq!(RPM::get_accumlated_rewards(&alice, &pool_id), vec![(CLV, 5 * DOLLARS), (DOT, 10 * DOLLARS)]);
+
+    // nothing left to refund
+    assert_noop!(
+      RPM::refund_rewards(Origin::root(), pool_id, DOT, dave),
+      Error::<mock::TestRuntime>::InvalidAmount
+    );
+  });
+}
@@ -1,13 +1,18 @@
 //! traits for reward pool
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use sp_std::vec::Vec;
+
 /// Hooks to manage reward pool
-pub trait RewardHandler<AccountId, BlockNumber, Balance, Share, PoolId> {
-  /// Accumulate rewards
+pub trait RewardHandler<AccountId, BlockNumber, CurrencyId, Balance, Share, PoolId> {
+  /// Accumulate rewards of each reward currency
   fn caculate_reward(
     pool_id: &PoolId,
     shares: &Share,
     last_update_block: BlockNumber,
     now: BlockNumber,
-  ) -> Balance;
+  ) -> Vec<(CurrencyId, Balance)>;
+
+  /// Whether the pool is or will be rewarded in the currency, budgets are only deposited to such pools
+  fn pays_rewards(pool_id: &PoolId, currency_id: &CurrencyId) -> bool;
 }
@@ -4,19 +4,20 @@ use sp_runtime::{
 
 use sp_std::vec;
 
-pub struct IncentivePoolAccountInfo<Share, Balance> {
+pub struct IncentivePoolAccountInfo<CurrencyId, Share, Balance> {
   pub shares: Share,
-  pub accumlated_rewards: Balance,
+  pub accumlated_rewards: vec::Vec<(CurrencyId, Balance)>,
 }
 
-pub trait IncentiveOps<AccountId, CurrencyId, Share, Balance> {
-  fn add_share(who: &AccountId, left: &CurrencyId, right: &CurrencyId, amount: &Share) -> Result<Share, DispatchError>;
-  fn remove_share(who: &AccountId, left: &CurrencyId, right: &CurrencyId, amount: &Share) -> Result<Share, DispatchError>;
+pub trait IncentiveOps<AccountId, PoolId, CurrencyId, Share, Balance> {
+  fn add_share(who: &AccountId, pool: &PoolId, amount: &Share) -> Result<Share, DispatchError>;
+  fn remove_share(who: &AccountId, pool: &PoolId, amount: &Share) -> Result<Share, DispatchError>;
 
-  fn get_account_shares(who: &AccountId, left: &CurrencyId, right: &CurrencyId) -> Share;
-  fn get_accumlated_rewards(who: &AccountId, left: &CurrencyId, right: &CurrencyId) -> Balance;
-  fn get_account_info(who: &AccountId, left: &CurrencyId, right: &CurrencyId) -> IncentivePoolAccountInfo<Share, Balance>;
-  fn claim_rewards(who: &AccountId, left: &CurrencyId, right: &CurrencyId) -> Result<Balance, DispatchError>;
+  fn get_account_shares(who: &AccountId, pool: &PoolId) -> Share;
+  fn get_accumlated_rewards(who: &AccountId, pool: &PoolId) -> vec::Vec<(CurrencyId, Balance)>;
+  fn get_account_info(who: &AccountId, pool: &PoolId) -> IncentivePoolAccountInfo<CurrencyId, Share, Balance>;
+  fn claim_rewards(who: &AccountId, pool: &PoolId) -> Result<vec::Vec<(CurrencyId, Balance)>, DispatchError>;
 
-  fn get_all_incentive_pools() -> vec::Vec<(CurrencyId, CurrencyId, Share, Balance)>;
+  /// returns (pool_id, total_shares, useable rewards of each currency)
+  fn get_all_incentive_pools() -> vec::Vec<(PoolId, Share, vec::Vec<(CurrencyId, Balance)>)>;
 }
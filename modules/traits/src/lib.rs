@@ -2,7 +2,7 @@
 
 pub use reward_pool_ops::RewardPoolOps;
 pub use incentive_ops::IncentiveOps;
-pub use price_ops::PriceProvider;
+pub use price_ops::{PriceProvider, TwapProvider};
 pub use incentive_ops::IncentivePoolAccountInfo;
 pub mod reward_pool_ops;
 pub mod incentive_ops;
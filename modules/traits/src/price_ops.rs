@@ -4,3 +4,8 @@ pub trait PriceProvider<CurrencyId, Price> {
   fn lock_price(currency_id: CurrencyId);
   fn unlock_price(currency_id: CurrencyId);
 }
+
+pub trait TwapProvider<CurrencyId, Price> {
+  /// time weighted average price of `base` in `quote`, None if there isn't enough history
+  fn get_twap(base: CurrencyId, quote: CurrencyId) -> Option<Price>;
+}
@@ -1,13 +1,19 @@
 use sp_runtime::{
-  DispatchError,
+  DispatchError, DispatchResult,
 };
 use sp_std::vec;
 
-pub trait RewardPoolOps<AccountId, PoolId, Share, Balance> {
+pub trait RewardPoolOps<AccountId, PoolId, CurrencyId, Share, Balance> {
   fn add_share(who: &AccountId, pool: PoolId, amount: Share) -> Result<Share, DispatchError>;
   fn remove_share(who: &AccountId, pool: PoolId, amount: Share) -> Result<Share, DispatchError>;
   fn get_account_shares(who: &AccountId, pool: &PoolId) -> Share;
-  fn get_accumlated_rewards(who: &AccountId, pool: &PoolId) -> Balance;
-  fn claim_rewards(who: &AccountId, pool: &PoolId) -> Result<Balance, DispatchError>;
-  fn get_all_pools() -> vec::Vec<(PoolId, Share, Balance)>;
+  /// rewards of each currency the account can claim
+  fn get_accumlated_rewards(who: &AccountId, pool: &PoolId) -> vec::Vec<(CurrencyId, Balance)>;
+  /// claim the rewards of each currency, returns the claimed amounts
+  fn claim_rewards(who: &AccountId, pool: &PoolId) -> Result<vec::Vec<(CurrencyId, Balance)>, DispatchError>;
+  /// returns (pool_id, total_shares, useable rewards of each currency)
+  fn get_all_pools() -> vec::Vec<(PoolId, Share, vec::Vec<(CurrencyId, Balance)>)>;
+  /// accumulate the pool rewards up to the current block,
+  /// should be called before the reward rate of the pool changes
+  fn update_rewards(pool: &PoolId) -> DispatchResult;
 }
@@ -0,0 +1,31 @@
+//! Weights for clover_vaults, estimated from similar calls of cloverdex.
+//! Regenerate with the `benchmark` subcommand of the node built with `--features runtime-benchmarks`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+impl crate::WeightInfo for () {
+	fn create_vault() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn deposit() -> Weight {
+		(260_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(12 as Weight))
+			.saturating_add(DbWeight::get().writes(9 as Weight))
+	}
+	fn withdraw() -> Weight {
+		(260_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(12 as Weight))
+			.saturating_add(DbWeight::get().writes(9 as Weight))
+	}
+	fn compound() -> Weight {
+		(900_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(40 as Weight))
+			.saturating_add(DbWeight::get().writes(30 as Weight))
+	}
+}
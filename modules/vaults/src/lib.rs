@@ -0,0 +1,339 @@
+//! Clover Vaults Module
+//!
+//! ##Overview
+//! Auto-compounding vaults of cloverdex liquidity pools.
+//! A vault stakes the lp tokens deposited to it in the reward pool, and issues vault shares to the depositors.
+//! Every `CompoundPeriod` blocks the vault claims its rewards, swaps them to the two currencies of the pool,
+//! adds the liquidity back and stakes the new lp tokens, so the lp tokens of each vault share grow over time.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+  decl_module, decl_error, decl_event, decl_storage, debug, ensure,
+  traits::{EnsureOrigin, Get},
+  weights::Weight,
+  IterableStorageMap,
+};
+use frame_system::{self as system, ensure_signed, RawOrigin};
+use orml_traits::MultiCurrency;
+use orml_utilities::with_transaction_result;
+use primitives::{Balance, CurrencyId, Ratio};
+use sp_runtime::{
+  traits::{AccountIdConversion, CheckedAdd, CheckedSub, Saturating, UniqueSaturatedInto, Zero},
+  DispatchError, DispatchResult, FixedPointNumber, ModuleId, RuntimeDebug,
+};
+use clover_traits::IncentiveOps;
+use cloverdex::PairKey;
+
+mod default_weight;
+mod mock;
+mod tests;
+
+pub trait WeightInfo {
+  fn create_vault() -> Weight;
+  fn deposit() -> Weight;
+  fn withdraw() -> Weight;
+  fn compound() -> Weight;
+}
+
+/// Auto-compounding vault of a liquidity pool.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct VaultInfo<Share, BlockNumber> {
+  pub currency_left: CurrencyId,
+  pub currency_right: CurrencyId,
+  /// vault shares issued to the depositors
+  pub total_shares: Share,
+  /// block the vault was compounded at
+  pub last_compounded: BlockNumber,
+  /// rewards swapped to the pool currencies which weren't added to the pool by the previous compounds,
+  /// balances sent to the vault account by others are never compounded
+  pub leftover_left: Balance,
+  pub leftover_right: Balance,
+}
+
+pub trait Trait: cloverdex::Trait {
+  type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+  /// The vaults' module id, each vault keeps its assets in a sub account.
+  type VaultModuleId: Get<ModuleId>;
+
+  /// Min number of blocks between two compounds of a vault.
+  type CompoundPeriod: Get<Self::BlockNumber>;
+
+  /// Max weight of compounding vaults in a block.
+  type MaxCompoundWeight: Get<Weight>;
+
+  /// Max slippage of swapping the rewards to the pool currencies.
+  type MaxSwapSlippage: Get<Ratio>;
+
+  /// Origin which may create vaults.
+  type UpdateOrigin: EnsureOrigin<Self::Origin>;
+
+  /// Weight information for the extrinsics in this module.
+  type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+  trait Store for Module<T: Trait> as Vaults {
+    /// Vaults of the liquidity pools
+    /// PairKey -> VaultInfo
+    pub Vaults get(fn vaults): map hasher(twox_64_concat) PairKey => Option<VaultInfo<T::Share, T::BlockNumber>>;
+
+    /// Vault shares owned by the depositors
+    /// PairKey -> Owner -> VaultShares
+    pub VaultShares get(fn vault_shares): double_map hasher(twox_64_concat) PairKey, hasher(twox_64_concat) T::AccountId => T::Share;
+  }
+}
+
+decl_event!(
+  pub enum Event<T> where
+    <T as system::Trait>::AccountId,
+    Share = <T as cloverdex::Trait>::Share,
+    CurrencyId = CurrencyId,
+  {
+    /// Vault created. [currency_left, currency_right]
+    VaultCreated(CurrencyId, CurrencyId),
+    /// Lp tokens deposited to a vault. [who, currency_left, currency_right, lp_amount, vault_shares]
+    Deposited(AccountId, CurrencyId, CurrencyId, Share, Share),
+    /// Lp tokens withdrawn from a vault. [who, currency_left, currency_right, lp_amount, vault_shares]
+    Withdrawn(AccountId, CurrencyId, CurrencyId, Share, Share),
+    /// Rewards of a vault were compounded. [currency_left, currency_right, staked_lp_amount]
+    Compounded(CurrencyId, CurrencyId, Share),
+  }
+);
+
+decl_error! {
+  /// Error for vaults module.
+  pub enum Error for Module<T: Trait> {
+    /// the currency pair has no liquidity pool
+    InvalidCurrencyPair,
+    /// the vault of the pool already exists
+    VaultExists,
+    /// the vault of the pool doesn't exist
+    VaultNotFound,
+    /// amount is zero or too small for a vault share
+    InvalidAmount,
+    /// not enough vault shares
+    SharesNotEnough,
+    /// vault shares overflow
+    SharesOverflow,
+  }
+}
+
+decl_module! {
+  pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+    type Error = Error<T>;
+    fn deposit_event() = default;
+
+    /// The vaults' module id, each vault keeps its assets in a sub account.
+    const VaultModuleId: ModuleId = T::VaultModuleId::get();
+
+    /// Min number of blocks between two compounds of a vault.
+    const CompoundPeriod: T::BlockNumber = T::CompoundPeriod::get();
+
+    fn on_initialize(now: T::BlockNumber) -> Weight {
+      let compound_weight = <T as Trait>::WeightInfo::compound();
+      let max_weight = T::MaxCompoundWeight::get();
+      let mut weight: Weight = 0;
+      for (pair_id, vault) in Vaults::<T>::iter() {
+        weight = weight.saturating_add(T::DbWeight::get().reads(1));
+        if vault.last_compounded.saturating_add(T::CompoundPeriod::get()) > now {
+          continue;
+        }
+        // the vaults not compounded in this block are compounded in the following blocks
+        if weight.saturating_add(compound_weight) > max_weight {
+          break;
+        }
+        let mut vault = vault;
+        Self::compound(pair_id, &mut vault, now);
+        Vaults::<T>::insert(pair_id, vault);
+        weight = weight.saturating_add(compound_weight);
+      }
+      weight
+    }
+
+    /// Create the vault of the liquidity pool of the currency pair.
+    #[weight = <T as Trait>::WeightInfo::create_vault()]
+    pub fn create_vault(origin, currency_first: CurrencyId, currency_second: CurrencyId) {
+      T::UpdateOrigin::ensure_origin(origin)?;
+      ensure!(cloverdex::Module::<T>::get_pool_info(currency_first, currency_second).is_ok(), Error::<T>::InvalidCurrencyPair);
+      let pair_id = cloverdex::Module::<T>::get_pair_key(&currency_first, &currency_second);
+      ensure!(!Vaults::<T>::contains_key(pair_id), Error::<T>::VaultExists);
+
+      let (currency_left, currency_right) = if currency_first < currency_second {
+        (currency_first, currency_second)
+      } else {
+        (currency_second, currency_first)
+      };
+      Vaults::<T>::insert(pair_id, VaultInfo {
+        currency_left,
+        currency_right,
+        total_shares: Zero::zero(),
+        last_compounded: <frame_system::Module<T>>::block_number(),
+        leftover_left: Zero::zero(),
+        leftover_right: Zero::zero(),
+      });
+      Self::deposit_event(RawEvent::VaultCreated(currency_left, currency_right));
+    }
+
+    /// Deposit lp tokens of the pool to its vault, the vault stakes them and issues vault shares
+    /// in proportion to the lp tokens staked by the vault.
+    /// The pending rewards are compounded first, so they only go to the previous depositors.
+    #[weight = <T as Trait>::WeightInfo::deposit().saturating_add(<T as Trait>::WeightInfo::compound())]
+    pub fn deposit(origin, currency_first: CurrencyId, currency_second: CurrencyId, #[compact] amount: T::Share) {
+      let who = ensure_signed(origin)?;
+      ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+      let pair_id = cloverdex::Module::<T>::get_pair_key(&currency_first, &currency_second);
+      let mut vault = Self::vaults(pair_id).ok_or(Error::<T>::VaultNotFound)?;
+
+      with_transaction_result(|| {
+        Self::compound(pair_id, &mut vault, <frame_system::Module<T>>::block_number());
+        let account = Self::vault_account_id(pair_id);
+        let staked = cloverdex::Module::<T>::get_staked_shares(&account, vault.currency_left, vault.currency_right);
+        let shares = if vault.total_shares.is_zero() || staked.is_zero() {
+          amount
+        } else {
+          Ratio::checked_from_rational(amount, staked)
+            .and_then(|n| n.checked_mul_int(vault.total_shares))
+            .ok_or(Error::<T>::SharesOverflow)?
+        };
+        ensure!(!shares.is_zero(), Error::<T>::InvalidAmount);
+
+        <T as cloverdex::Trait>::Currency::transfer(cloverdex::Module::<T>::lp_token(pair_id), &who, &account, amount.unique_saturated_into())?;
+        cloverdex::Module::<T>::add_stake_to_reward_pool(&account, vault.currency_left, vault.currency_right, amount)?;
+
+        vault.total_shares = vault.total_shares.checked_add(&shares).ok_or(Error::<T>::SharesOverflow)?;
+        VaultShares::<T>::mutate(pair_id, &who, |s| *s = s.saturating_add(shares));
+        Self::deposit_event(RawEvent::Deposited(who, vault.currency_left, vault.currency_right, amount, shares));
+        Vaults::<T>::insert(pair_id, vault);
+        Ok(())
+      })?;
+    }
+
+    /// Burn vault shares, and withdraw their part of the lp tokens staked by the vault.
+    /// The pending rewards are compounded first, so the withdrawn lp tokens include their part.
+    #[weight = <T as Trait>::WeightInfo::withdraw().saturating_add(<T as Trait>::WeightInfo::compound())]
+    pub fn withdraw(origin, currency_first: CurrencyId, currency_second: CurrencyId, #[compact] shares: T::Share) {
+      let who = ensure_signed(origin)?;
+      ensure!(!shares.is_zero(), Error::<T>::InvalidAmount);
+      let pair_id = cloverdex::Module::<T>::get_pair_key(&currency_first, &currency_second);
+      let mut vault = Self::vaults(pair_id).ok_or(Error::<T>::VaultNotFound)?;
+
+      with_transaction_result(|| {
+        let remaining = Self::vault_shares(pair_id, &who).checked_sub(&shares).ok_or(Error::<T>::SharesNotEnough)?;
+        if remaining.is_zero() {
+          VaultShares::<T>::remove(pair_id, &who);
+        } else {
+          VaultShares::<T>::insert(pair_id, &who, remaining);
+        }
+
+        Self::compound(pair_id, &mut vault, <frame_system::Module<T>>::block_number());
+        let account = Self::vault_account_id(pair_id);
+        let staked = cloverdex::Module::<T>::get_staked_shares(&account, vault.currency_left, vault.currency_right);
+        let amount = Ratio::checked_from_rational(shares, vault.total_shares)
+          .unwrap_or_default()
+          .saturating_mul_int(staked);
+        vault.total_shares = vault.total_shares.saturating_sub(shares);
+        if !amount.is_zero() {
+          cloverdex::Module::<T>::remove_stake_from_reward_pool(&account, vault.currency_left, vault.currency_right, amount)?;
+          <T as cloverdex::Trait>::Currency::transfer(cloverdex::Module::<T>::lp_token(pair_id), &account, &who, amount.unique_saturated_into())?;
+        }
+
+        Self::deposit_event(RawEvent::Withdrawn(who, vault.currency_left, vault.currency_right, amount, shares));
+        Vaults::<T>::insert(pair_id, vault);
+        Ok(())
+      })?;
+    }
+  }
+}
+
+impl<T: Trait> Module<T> {
+  /// the account keeping the assets of the vault
+  pub fn vault_account_id(pair_id: PairKey) -> T::AccountId {
+    T::VaultModuleId::get().into_sub_account(pair_id)
+  }
+
+  /// lp tokens of the vault shares of the account
+  pub fn get_deposited_amount(who: &T::AccountId, currency_first: CurrencyId, currency_second: CurrencyId) -> T::Share {
+    let pair_id = cloverdex::Module::<T>::get_pair_key(&currency_first, &currency_second);
+    match Self::vaults(pair_id) {
+      Some(vault) => {
+        let staked = cloverdex::Module::<T>::get_staked_shares(&Self::vault_account_id(pair_id), vault.currency_left, vault.currency_right);
+        Ratio::checked_from_rational(Self::vault_shares(pair_id, who), vault.total_shares)
+          .unwrap_or_default()
+          .saturating_mul_int(staked)
+      },
+      None => Zero::zero(),
+    }
+  }
+
+  /// compound the vault, a failed compound is rolled back and retried after `CompoundPeriod`,
+  /// the caller stores the vault
+  fn compound(pair_id: PairKey, vault: &mut VaultInfo<T::Share, T::BlockNumber>, now: T::BlockNumber) {
+    let account = Self::vault_account_id(pair_id);
+    let mut compounded = vault.clone();
+    let result = with_transaction_result(|| -> Result<T::Share, DispatchError> {
+      Self::do_compound(pair_id, &account, &mut compounded)
+    });
+    match result {
+      Ok(staked) => {
+        *vault = compounded;
+        if !staked.is_zero() {
+          Self::deposit_event(RawEvent::Compounded(vault.currency_left, vault.currency_right, staked));
+        }
+      },
+      Err(e) => debug::warn!("failed to compound vault {:?}: {:?}", pair_id, e),
+    }
+    vault.last_compounded = now;
+  }
+
+  /// claim the rewards, swap them to the pool currencies, add liquidity and stake the new lp tokens,
+  /// returns the staked lp tokens.
+  /// only the rewards are compounded, so the lp tokens of a vault share can't be inflated
+  /// by sending lp tokens or pool currencies to the vault account.
+  fn do_compound(pair_id: PairKey,
+                 account: &T::AccountId,
+                 vault: &mut VaultInfo<T::Share, T::BlockNumber>) -> Result<T::Share, DispatchError> {
+    let (currency_left, currency_right) = (vault.currency_left, vault.currency_right);
+    let balance_left = <T as cloverdex::Trait>::Currency::free_balance(currency_left, account);
+    let balance_right = <T as cloverdex::Trait>::Currency::free_balance(currency_right, account);
+
+    let pool_id = cloverdex::Module::<T>::incentive_pool_id(currency_left, currency_right)?;
+    let rewards = <T as cloverdex::Trait>::IncentiveOps::claim_rewards(account, &pool_id)?;
+    for (currency_id, amount) in rewards {
+      // half of the reward for each currency of the pool
+      let half = amount / 2;
+      Self::swap_reward(account, currency_id, currency_left, half)?;
+      Self::swap_reward(account, currency_id, currency_right, amount.saturating_sub(half))?;
+    }
+
+    // leftovers of previous compounds are added too
+    let swapped_left = <T as cloverdex::Trait>::Currency::free_balance(currency_left, account);
+    let swapped_right = <T as cloverdex::Trait>::Currency::free_balance(currency_right, account);
+    let amount_left = vault.leftover_left.saturating_add(swapped_left.saturating_sub(balance_left));
+    let amount_right = vault.leftover_right.saturating_add(swapped_right.saturating_sub(balance_right));
+
+    let lp_tokens = cloverdex::Module::<T>::shares(pair_id, account);
+    if !amount_left.is_zero() && !amount_right.is_zero() {
+      cloverdex::Module::<T>::add_liquidity(RawOrigin::Signed(account.clone()).into(), currency_left, currency_right, amount_left, amount_right)?;
+    }
+    let added_left = swapped_left.saturating_sub(<T as cloverdex::Trait>::Currency::free_balance(currency_left, account));
+    let added_right = swapped_right.saturating_sub(<T as cloverdex::Trait>::Currency::free_balance(currency_right, account));
+    vault.leftover_left = amount_left.saturating_sub(added_left);
+    vault.leftover_right = amount_right.saturating_sub(added_right);
+
+    let minted = cloverdex::Module::<T>::shares(pair_id, account).saturating_sub(lp_tokens);
+    if !minted.is_zero() {
+      cloverdex::Module::<T>::add_stake_to_reward_pool(account, currency_left, currency_right, minted)?;
+    }
+    Ok(minted)
+  }
+
+  fn swap_reward(account: &T::AccountId, from: CurrencyId, to: CurrencyId, amount: Balance) -> DispatchResult {
+    if from == to || amount.is_zero() {
+      return Ok(());
+    }
+    cloverdex::Module::<T>::swap_exact_supply(account, from, to, amount, T::MaxSwapSlippage::get())?;
+    Ok(())
+  }
+}
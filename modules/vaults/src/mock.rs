@@ -0,0 +1,295 @@
+#![cfg(test)]
+use super::*;
+use frame_support::{impl_outer_event, impl_outer_origin, parameter_types};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup, Perbill};
+use sp_std::cell::RefCell;
+use std::collections::HashMap;
+use clover_traits::IncentivePoolAccountInfo;
+use orml_currencies::BasicCurrencyAdapter;
+
+pub use primitives::{AccountId, Amount, Balance, CurrencyId, PoolId, Rate, Share};
+
+pub type BlockNumber = u64;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+
+mod clover_vaults {
+  pub use super::super::*;
+}
+
+impl_outer_event! {
+  pub enum TestEvent for TestRuntime {
+    frame_system<T>,
+    cloverdex<T>,
+    clover_vaults<T>,
+    orml_tokens<T>,
+    orml_currencies<T>,
+    pallet_balances<T>,
+  }
+}
+
+impl_outer_origin! {
+  pub enum Origin for TestRuntime {}
+}
+
+parameter_types! {
+  pub const BlockHashCount: u64 = 250;
+  pub const MaximumBlockWeight: u32 = 1024;
+  pub const MaximumBlockLength: u32 = 2 * 1024;
+  pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Trait for TestRuntime {
+  type Origin = Origin;
+  type Index = u64;
+  type BlockNumber = BlockNumber;
+  type Call = ();
+  type Hash = H256;
+  type Hashing = ::sp_runtime::traits::BlakeTwo256;
+  type AccountId = AccountId;
+  type Lookup = IdentityLookup<Self::AccountId>;
+  type Header = Header;
+  type Event = TestEvent;
+  type BlockHashCount = BlockHashCount;
+  type MaximumBlockWeight = MaximumBlockWeight;
+  type MaximumBlockLength = MaximumBlockLength;
+  type AvailableBlockRatio = AvailableBlockRatio;
+  type Version = ();
+  type PalletInfo = ();
+  type AccountData = pallet_balances::AccountData<Balance>;
+  type OnNewAccount = ();
+  type OnKilledAccount = ();
+  type DbWeight = ();
+  type BlockExecutionWeight = ();
+  type ExtrinsicBaseWeight = ();
+  type MaximumExtrinsicWeight = ();
+  type BaseCallFilter = ();
+  type SystemWeightInfo = ();
+}
+
+pub type System = frame_system::Module<TestRuntime>;
+
+parameter_types! {
+  pub const ExistentialDeposit: u128 = 500;
+  pub const MaxLocks: u32 = 50;
+}
+
+impl pallet_balances::Trait for TestRuntime {
+  type Balance = Balance;
+  type Event = TestEvent;
+  type DustRemoval = ();
+  type ExistentialDeposit = ExistentialDeposit;
+  type AccountStore = System;
+  type MaxLocks = MaxLocks;
+  type WeightInfo = ();
+}
+
+pub type Balances = pallet_balances::Module<TestRuntime>;
+
+impl orml_tokens::Trait for TestRuntime {
+  type Event = TestEvent;
+  type Balance = Balance;
+  type Amount = Amount;
+  type CurrencyId = CurrencyId;
+  type OnReceived = ();
+  type WeightInfo = ();
+}
+
+pub type Tokens = orml_tokens::Module<TestRuntime>;
+
+parameter_types! {
+  pub const GetNativeCurrencyId: CurrencyId = CurrencyId::CLV;
+}
+
+impl orml_currencies::Trait for TestRuntime {
+  type Event = TestEvent;
+  type MultiCurrency = Tokens;
+  type NativeCurrency = BasicCurrencyAdapter<TestRuntime, Balances, Amount, BlockNumber>;
+  type GetNativeCurrencyId = GetNativeCurrencyId;
+  type WeightInfo = ();
+}
+
+pub type Currencies = orml_currencies::Module<TestRuntime>;
+
+parameter_types! {
+  pub GetExchangeFee: Rate = Rate::saturating_from_rational(1, 100);
+  pub const CloverdexModuleId: ModuleId = ModuleId(*b"clv/dexm");
+  pub const MaxStakeLockDuration: BlockNumber = 1000;
+  pub MaxStakeBoost: Rate = Rate::saturating_from_integer(3);
+  pub EarlyUnlockPenalty: Ratio = Ratio::saturating_from_rational(1, 2);
+  pub const MaxStakeLocks: u32 = 3;
+  pub TreasuryAccount: AccountId = AccountId::from(TREASURY);
+  pub const TwapWindow: u64 = 10;
+}
+
+impl cloverdex::Trait for TestRuntime {
+  type Event = TestEvent;
+  type Currency = Currencies;
+  type Share = Share;
+  type GetExchangeFee = GetExchangeFee;
+  type ModuleId = CloverdexModuleId;
+  type OnAddLiquidity = ();
+  type OnRemoveLiquidity = ();
+  type MaxStakeLockDuration = MaxStakeLockDuration;
+  type MaxStakeBoost = MaxStakeBoost;
+  type EarlyUnlockPenalty = EarlyUnlockPenalty;
+  type MaxStakeLocks = MaxStakeLocks;
+  type TreasuryAccount = TreasuryAccount;
+  type TwapWindow = TwapWindow;
+  type IncentiveOps = IncentiveOpsHandler;
+  type WeightInfo = ();
+}
+
+pub type CloverdexModule = cloverdex::Module<TestRuntime>;
+
+parameter_types! {
+  pub const VaultsModuleId: ModuleId = ModuleId(*b"clv/vlts");
+  pub const CompoundPeriod: BlockNumber = 10;
+  pub const MaxCompoundWeight: Weight = Weight::max_value();
+  pub MaxSwapSlippage: Ratio = Ratio::saturating_from_rational(1, 10);
+}
+
+impl Trait for TestRuntime {
+  type Event = TestEvent;
+  type VaultModuleId = VaultsModuleId;
+  type CompoundPeriod = CompoundPeriod;
+  type MaxCompoundWeight = MaxCompoundWeight;
+  type MaxSwapSlippage = MaxSwapSlippage;
+  type UpdateOrigin = EnsureRoot<AccountId>;
+  type WeightInfo = ();
+}
+
+pub type Vaults = Module<TestRuntime>;
+
+pub const ALICE: [u8; 32] = [0u8; 32];
+pub const BOB: [u8; 32] = [1u8; 32];
+pub const TREASURY: [u8; 32] = [2u8; 32];
+pub const CLV: CurrencyId = CurrencyId::CLV;
+pub const DOT: CurrencyId = CurrencyId::DOT;
+pub const CETH: CurrencyId = CurrencyId::CETH;
+
+thread_local! {
+  pub static SHARES_STAKED: RefCell<HashMap<(AccountId, PairKey), Share>> = RefCell::new(HashMap::new());
+  /// rewards paid by the next `claim_rewards`
+  pub static REWARDS: RefCell<Vec<(CurrencyId, Balance)>> = RefCell::new(vec![]);
+}
+
+pub fn set_rewards(rewards: Vec<(CurrencyId, Balance)>) {
+  REWARDS.with(|v| *v.borrow_mut() = rewards);
+}
+
+pub struct IncentiveOpsHandler;
+
+fn staked_key(who: &AccountId, pool: &PoolId) -> (AccountId, PairKey) {
+  let (left, right) = pool.currency_pair().expect("cloverdex only stakes to dex pools");
+  (who.clone(), CloverdexModule::get_pair_key(&left, &right))
+}
+
+impl IncentiveOps<AccountId, PoolId, CurrencyId, Share, Balance> for IncentiveOpsHandler {
+  fn add_share(who: &AccountId, pool: &PoolId, amount: &Share) -> Result<Share, DispatchError> {
+    Ok(SHARES_STAKED.with(|v| {
+      let mut shares = v.borrow_mut();
+      let total = shares.entry(staked_key(who, pool)).or_default();
+      *total += amount;
+      *total
+    }))
+  }
+
+  fn remove_share(who: &AccountId, pool: &PoolId, amount: &Share) -> Result<Share, DispatchError> {
+    Ok(SHARES_STAKED.with(|v| {
+      let mut shares = v.borrow_mut();
+      let total = shares.entry(staked_key(who, pool)).or_default();
+      *total -= amount;
+      *total
+    }))
+  }
+
+  fn get_account_shares(who: &AccountId, pool: &PoolId) -> Share {
+    SHARES_STAKED.with(|v| v.borrow().get(&staked_key(who, pool)).cloned().unwrap_or_default())
+  }
+
+  fn get_accumlated_rewards(_who: &AccountId, _pool: &PoolId) -> Vec<(CurrencyId, Balance)> {
+    REWARDS.with(|v| v.borrow().clone())
+  }
+
+  fn get_account_info(who: &AccountId, pool: &PoolId) -> IncentivePoolAccountInfo<CurrencyId, Share, Balance> {
+    IncentivePoolAccountInfo {
+      shares: Self::get_account_shares(who, pool),
+      accumlated_rewards: Self::get_accumlated_rewards(who, pool),
+    }
+  }
+
+  fn claim_rewards(who: &AccountId, _pool: &PoolId) -> Result<Vec<(CurrencyId, Balance)>, DispatchError> {
+    let rewards = REWARDS.with(|v| v.replace(vec![]));
+    for (currency_id, amount) in &rewards {
+      Currencies::deposit(*currency_id, who, *amount)?;
+    }
+    Ok(rewards)
+  }
+
+  fn get_all_incentive_pools() -> Vec<(PoolId, Share, Vec<(CurrencyId, Balance)>)> {
+    vec![]
+  }
+}
+
+pub struct ExtBuilder {
+  endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
+}
+
+impl Default for ExtBuilder {
+  fn default() -> Self {
+    let alice = AccountId::from(ALICE);
+    let bob = AccountId::from(BOB);
+
+    Self {
+      endowed_accounts: vec![
+        (alice.clone(), CLV, 1_000_000_000_000_000_000u128),
+        (bob.clone(), CLV, 1_000_000_000_000_000_000u128),
+        (alice.clone(), DOT, 1_000_000_000_000_000_000u128),
+        (bob.clone(), DOT, 1_000_000_000_000_000_000u128),
+        (alice.clone(), CETH, 1_000_000_000_000_000_000u128),
+        (bob.clone(), CETH, 1_000_000_000_000_000_000u128),
+      ],
+    }
+  }
+}
+
+impl ExtBuilder {
+  pub fn build(self) -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default()
+      .build_storage::<TestRuntime>()
+      .unwrap();
+
+    pallet_balances::GenesisConfig::<TestRuntime> {
+      balances: self
+        .endowed_accounts
+        .clone()
+        .into_iter()
+        .filter(|(_, currency_id, _)| *currency_id == CLV)
+        .map(|(account_id, _, initial_balance)| (account_id, initial_balance))
+        .collect::<Vec<_>>(),
+    }
+    .assimilate_storage(&mut t)
+      .unwrap();
+
+    orml_tokens::GenesisConfig::<TestRuntime> {
+      endowed_accounts: self
+        .endowed_accounts
+        .into_iter()
+        .filter(|(_, currency_id, _)| *currency_id != CLV)
+        .collect::<Vec<_>>(),
+    }
+    .assimilate_storage(&mut t).unwrap();
+
+    let mut ext = sp_io::TestExternalities::new(t);
+    ext.execute_with(|| {
+      System::set_block_number(1);
+      SHARES_STAKED.with(|v| v.borrow_mut().clear());
+      set_rewards(vec![]);
+    });
+    ext
+  }
+}
@@ -0,0 +1,164 @@
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use mock::{
+  set_rewards, AccountId, CloverdexModule, Currencies, ExtBuilder, Origin, Share, System, TestEvent, TestRuntime, Vaults,
+  ALICE, BOB, CETH, DOT,
+};
+
+fn pair_id() -> PairKey {
+  CloverdexModule::get_pair_key(&DOT, &CETH)
+}
+
+/// add liquidity to the DOT-CETH pool and create its vault, returns the lp tokens of alice
+fn setup_vault() -> Share {
+  let alice = AccountId::from(ALICE);
+  assert_ok!(CloverdexModule::add_liquidity(Origin::signed(alice.clone()), DOT, CETH, 1_000_000_000_000, 1_000_000_000_000));
+  assert_ok!(Vaults::create_vault(Origin::root(), DOT, CETH));
+  CloverdexModule::shares(pair_id(), &alice)
+}
+
+fn staked(pair_id: PairKey) -> Share {
+  let vault = Vaults::vaults(pair_id).unwrap();
+  CloverdexModule::get_staked_shares(&Vaults::vault_account_id(pair_id), vault.currency_left, vault.currency_right)
+}
+
+#[test]
+fn create_vault_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    assert_noop!(Vaults::create_vault(Origin::root(), DOT, CETH), Error::<TestRuntime>::InvalidCurrencyPair);
+    setup_vault();
+    assert_noop!(Vaults::create_vault(Origin::root(), CETH, DOT), Error::<TestRuntime>::VaultExists);
+    assert_noop!(
+      Vaults::create_vault(Origin::signed(AccountId::from(ALICE)), DOT, CETH),
+      DispatchError::BadOrigin
+    );
+
+    let vault = Vaults::vaults(pair_id()).unwrap();
+    assert_eq!((vault.currency_left, vault.currency_right), (DOT, CETH));
+    assert_eq!(vault.total_shares, 0);
+  });
+}
+
+#[test]
+fn deposit_and_withdraw_works() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = AccountId::from(ALICE);
+    let lp_tokens = setup_vault();
+
+    assert_noop!(Vaults::deposit(Origin::signed(alice.clone()), DOT, CETH, 0), Error::<TestRuntime>::InvalidAmount);
+    assert_ok!(Vaults::deposit(Origin::signed(alice.clone()), DOT, CETH, 1_000));
+    assert_eq!(Vaults::vault_shares(pair_id(), &alice), 1_000);
+    assert_eq!(staked(pair_id()), 1_000);
+    assert_eq!(CloverdexModule::shares(pair_id(), &alice), lp_tokens - 1_000);
+    let event = TestEvent::clover_vaults(RawEvent::Deposited(alice.clone(), DOT, CETH, 1_000, 1_000));
+    assert!(System::events().iter().any(|record| record.event == event));
+
+    assert_noop!(Vaults::withdraw(Origin::signed(alice.clone()), DOT, CETH, 1_001), Error::<TestRuntime>::SharesNotEnough);
+    assert_ok!(Vaults::withdraw(Origin::signed(alice.clone()), DOT, CETH, 400));
+    assert_eq!(Vaults::vault_shares(pair_id(), &alice), 600);
+    assert_eq!(Vaults::get_deposited_amount(&alice, DOT, CETH), 600);
+    assert_eq!(CloverdexModule::shares(pair_id(), &alice), lp_tokens - 600);
+
+    assert_ok!(Vaults::withdraw(Origin::signed(alice.clone()), DOT, CETH, 600));
+    assert_eq!(Vaults::vaults(pair_id()).unwrap().total_shares, 0);
+    assert_eq!(CloverdexModule::shares(pair_id(), &alice), lp_tokens);
+  });
+}
+
+#[test]
+fn compound_stakes_the_rewards() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = AccountId::from(ALICE);
+    let bob = AccountId::from(BOB);
+    setup_vault();
+    assert_ok!(Vaults::deposit(Origin::signed(alice.clone()), DOT, CETH, 1_000_000));
+
+    set_rewards(vec![(DOT, 2_000_000)]);
+    // not compounded before `CompoundPeriod` passed
+    Vaults::on_initialize(10);
+    assert_eq!(staked(pair_id()), 1_000_000);
+
+    Vaults::on_initialize(11);
+    let vault = Vaults::vaults(pair_id()).unwrap();
+    let compounded = staked(pair_id()) - 1_000_000;
+    assert!(compounded > 0);
+    assert_eq!(vault.last_compounded, 11);
+    // the part of the rewards which doesn't fit the pool ratio is kept for the next compound
+    let account = Vaults::vault_account_id(pair_id());
+    assert_eq!(Currencies::free_balance(DOT, &account), vault.leftover_left);
+    assert_eq!(Currencies::free_balance(CETH, &account), vault.leftover_right);
+    let event = TestEvent::clover_vaults(RawEvent::Compounded(DOT, CETH, compounded));
+    assert!(System::events().iter().any(|record| record.event == event));
+
+    // the lp tokens of the vault shares grew
+    assert_eq!(Vaults::get_deposited_amount(&alice, DOT, CETH), 1_000_000 + compounded);
+    assert_ok!(CloverdexModule::add_liquidity(Origin::signed(bob.clone()), DOT, CETH, 10_000_000, 10_000_000));
+    assert_ok!(Vaults::deposit(Origin::signed(bob.clone()), DOT, CETH, 1_000_000 + compounded));
+    assert_eq!(Vaults::vault_shares(pair_id(), &bob), 1_000_000);
+  });
+}
+
+#[test]
+fn donations_are_not_compounded() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = AccountId::from(ALICE);
+    let bob = AccountId::from(BOB);
+    let lp_tokens = setup_vault();
+    let lp_token = CloverdexModule::lp_token(pair_id());
+    let account = Vaults::vault_account_id(pair_id());
+
+    // the first depositor sends lp tokens and pool currencies to the vault account
+    // to inflate the lp tokens of its single vault share
+    assert_ok!(Vaults::deposit(Origin::signed(alice.clone()), DOT, CETH, 1));
+    assert_ok!(Currencies::transfer(Origin::signed(alice.clone()), account.clone(), lp_token, lp_tokens / 2));
+    assert_ok!(Currencies::transfer(Origin::signed(alice.clone()), account.clone(), DOT, 1_000_000));
+    assert_ok!(Currencies::transfer(Origin::signed(alice.clone()), account.clone(), CETH, 1_000_000));
+    Vaults::on_initialize(11);
+    assert_eq!(staked(pair_id()), 1);
+
+    // later depositors get vault shares for all their lp tokens
+    assert_ok!(CloverdexModule::add_liquidity(Origin::signed(bob.clone()), DOT, CETH, 10_000_000, 10_000_000));
+    assert_ok!(Vaults::deposit(Origin::signed(bob.clone()), DOT, CETH, 1_000));
+    assert_eq!(Vaults::vault_shares(pair_id(), &bob), 1_000);
+    assert_eq!(Vaults::get_deposited_amount(&bob, DOT, CETH), 1_000);
+    assert_eq!(Vaults::get_deposited_amount(&alice, DOT, CETH), 1);
+  });
+}
+
+#[test]
+fn deposit_compounds_the_pending_rewards_first() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = AccountId::from(ALICE);
+    let bob = AccountId::from(BOB);
+    setup_vault();
+    assert_ok!(Vaults::deposit(Origin::signed(alice.clone()), DOT, CETH, 1_000_000));
+    assert_ok!(CloverdexModule::add_liquidity(Origin::signed(bob.clone()), DOT, CETH, 10_000_000, 10_000_000));
+
+    // the rewards earned before bob's deposit go to alice only
+    set_rewards(vec![(DOT, 2_000_000)]);
+    assert_ok!(Vaults::deposit(Origin::signed(bob.clone()), DOT, CETH, 1_000_000));
+    let compounded = staked(pair_id()) - 2_000_000;
+    assert!(compounded > 0);
+    assert_eq!(Vaults::vaults(pair_id()).unwrap().last_compounded, 1);
+    assert_eq!(Vaults::get_deposited_amount(&alice, DOT, CETH), 1_000_000 + compounded);
+    assert!(Vaults::get_deposited_amount(&bob, DOT, CETH) <= 1_000_000);
+    assert!(Vaults::vault_shares(pair_id(), &bob) < 1_000_000);
+  });
+}
+
+#[test]
+fn withdraw_compounds_the_pending_rewards_first() {
+  ExtBuilder::default().build().execute_with(|| {
+    let alice = AccountId::from(ALICE);
+    let lp_tokens = setup_vault();
+    assert_ok!(Vaults::deposit(Origin::signed(alice.clone()), DOT, CETH, 1_000_000));
+
+    // the withdrawn lp tokens include the rewards not compounded yet
+    set_rewards(vec![(DOT, 2_000_000)]);
+    assert_ok!(Vaults::withdraw(Origin::signed(alice.clone()), DOT, CETH, 1_000_000));
+    assert!(CloverdexModule::shares(pair_id(), &alice) > lp_tokens);
+    assert_eq!(staked(pair_id()), 0);
+  });
+}
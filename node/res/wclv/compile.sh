@@ -0,0 +1,15 @@
+#!/usr/bin/env bash
+# Compile WCLV into the genesis code of node/src/wclv_contract, with solc 0.6.12.
+# With `--check`, only verify that node/src/wclv_contract is the compiled code.
+set -e
+cd "$(dirname "$0")"
+solc --overwrite --optimize --optimize-runs 200 --evm-version istanbul --bin-runtime -o ./build WCLV.sol
+code="\"0x$(cat ./build/WCLV.bin-runtime)\""
+if [ "$1" = "--check" ]; then
+  if [ "$code" != "$(cat ../../src/wclv_contract)" ]; then
+    echo "node/src/wclv_contract is not the compiled WCLV.sol, run node/res/wclv/compile.sh" >&2
+    exit 1
+  fi
+else
+  echo "$code" > ../../src/wclv_contract
+fi
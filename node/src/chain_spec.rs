@@ -4,11 +4,11 @@ use clover_runtime::{
   AccountId, BabeConfig, Balance, BalancesConfig, ContractsConfig, CurrencyId, IndicesConfig, GenesisConfig, ImOnlineId,
   GrandpaConfig, SessionConfig, SessionKeys, StakingConfig, SudoConfig, SystemConfig, WASM_BINARY,
   Signature, StakerStatus, TokensConfig, IncentivesConfig, CloverDexConfig, BandOracleConfig,
-  CloverOracleConfig, EVMConfig, EthereumConfig, DOLLARS
+  CloverOracleConfig, EVMConfig, EthereumConfig, RewardPoolConfig, IncentivePoolId, PairKey, OracleStakingModuleId, DOLLARS
 };
 use sp_consensus_babe::AuthorityId as BabeId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::{traits::{IdentifyAccount, Verify}, Perbill};
+use sp_runtime::{traits::{AccountIdConversion, IdentifyAccount, Verify}, Perbill};
 use sc_service::ChainType;
 use hex_literal::hex;
 use sc_telemetry::TelemetryEndpoints;
@@ -80,8 +80,23 @@ fn dev_endowed_evm_accounts() -> BTreeMap<H160, GenesisAccount>{
   get_endowed_evm_accounts(endowed_account)
 }
 
+/// WCLV, the WETH9 style erc20 wrapper of CLV: `deposit` and calls without data wrap the sent CLV,
+/// `withdraw` unwraps it, with the erc20 calls and events, 12 decimals like CLV, and the balances and
+/// allowances in the slots of the solidity mappings 0 and 1.
+/// The code is the output of `res/wclv/compile.sh` for `res/wclv/WCLV.sol`, `compile.sh --check` verifies it.
+fn wclv_account() -> GenesisAccount {
+  let code: String = serde_json::from_str(include_str!("wclv_contract")).expect("valid wclv contract");
+  GenesisAccount {
+    nonce: U256::from(0),
+    balance: U256::from(0),
+    storage: Default::default(),
+    code: sp_core::bytes::from_hex(&code).expect("valid wclv contract code"),
+  }
+}
+
 fn get_endowed_evm_accounts(endowed_account: Vec<H160>) -> BTreeMap<H160, GenesisAccount>{
   let mut evm_accounts = BTreeMap::new();
+  evm_accounts.insert(primitives::WCLV_ADDRESS, wclv_account());
   for account in endowed_account {
     evm_accounts.insert(
       account,
@@ -275,6 +290,8 @@ fn testnet_genesis(
 
   const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
   const STASH: Balance = 100 * DOLLARS;
+  // pays the oracle feed rewards
+  const ORACLE_REWARD_POT: Balance = 100_000 * DOLLARS;
 
   GenesisConfig {
     frame_system: Some(SystemConfig {
@@ -287,6 +304,7 @@ fn testnet_genesis(
       balances: endowed_accounts.iter().cloned()
             .map(|k| (k, ENDOWMENT))
             .chain(initial_authorities.iter().map(|x| (x.0.clone(), STASH)))
+            .chain(std::iter::once((OracleStakingModuleId::get().into_account(), ORACLE_REWARD_POT)))
             .collect(),
     }),
     pallet_contracts: Some(ContractsConfig {
@@ -333,7 +351,7 @@ fn testnet_genesis(
       key: root_key,
     }),
     orml_oracle_Instance1: Some(CloverOracleConfig {
-      members: Default::default(), // initialized by OperatorMembership
+      members: Default::default(), // derived from the bonded operators of OracleStaking
       phantom: Default::default(),
     }),
     orml_oracle_Instance2: Some(BandOracleConfig {
@@ -352,6 +370,17 @@ fn testnet_genesis(
         })
         .collect(),
     }),
+    reward_pool: Some(RewardPoolConfig {
+      // CLV budgets of the genesis incentive programs
+      reward_budgets: vec![
+        (CurrencyId::CLV, CurrencyId::DOT),
+        (CurrencyId::CLV, CurrencyId::CUSDT),
+        (CurrencyId::CETH, CurrencyId::CUSDT),
+      ].into_iter().map(|(left, right)| {
+        let pair_key = PairKey::try_from(left, right).expect("valid currency pair");
+        (IncentivePoolId::Dex(pair_key), CurrencyId::CLV, 1_000_000 * DOLLARS)
+      }).collect(),
+    }),
     clover_incentives: Some(IncentivesConfig{
       dex_rewards: vec![
         (CurrencyId::CLV, CurrencyId::DOT, 1 * DOLLARS),
@@ -374,3 +403,204 @@ fn testnet_genesis(
     pallet_membership_Instance1: Some(Default::default()),
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use evm::{
+    backend::{ApplyBackend, Log, MemoryAccount, MemoryBackend, MemoryVicinity},
+    executor::StackExecutor,
+    Config, ExitReason,
+  };
+  use primitive_types::H256;
+  use sp_core::hashing::keccak_256;
+
+  const GAS_LIMIT: usize = 1_000_000;
+  const ALICE: H160 = H160([0xaa; 20]);
+  const BOB: H160 = H160([0xbb; 20]);
+  const CHARLIE: H160 = H160([0xcc; 20]);
+
+  /// Evm state with WCLV, alice and bob.
+  struct Evm {
+    state: BTreeMap<H160, MemoryAccount>,
+    logs: Vec<Log>,
+  }
+
+  impl Evm {
+    fn new() -> Self {
+      let wclv = wclv_account();
+      let mut state = BTreeMap::new();
+      state.insert(primitives::WCLV_ADDRESS, MemoryAccount {
+        nonce: wclv.nonce,
+        balance: wclv.balance,
+        storage: wclv.storage,
+        code: wclv.code,
+      });
+      for address in &[ALICE, BOB] {
+        state.insert(*address, MemoryAccount { balance: U256::from(1_000), ..Default::default() });
+      }
+      Evm { state, logs: vec![] }
+    }
+
+    /// Call WCLV from `caller`, the state is only applied if the call succeeds.
+    fn call(&mut self, caller: H160, value: u64, input: Vec<u8>) -> (ExitReason, Vec<u8>) {
+      let vicinity = MemoryVicinity {
+        gas_price: U256::zero(),
+        origin: caller,
+        chain_id: U256::from(1337),
+        block_hashes: vec![],
+        block_number: U256::zero(),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::zero(),
+        block_difficulty: U256::zero(),
+        block_gas_limit: U256::from(GAS_LIMIT),
+      };
+      let config = Config::istanbul();
+      let mut backend = MemoryBackend::new(&vicinity, self.state.clone());
+      let mut executor = StackExecutor::new(&backend, GAS_LIMIT, &config);
+      let (reason, output) = executor.transact_call(caller, primitives::WCLV_ADDRESS, U256::from(value), input, GAS_LIMIT);
+      let (values, logs, _) = executor.deconstruct();
+      if reason.is_succeed() {
+        self.logs = logs.into_iter().collect();
+        backend.apply(values, vec![], false);
+        self.state = backend.state().clone();
+      }
+      (reason, output)
+    }
+
+    fn word(&mut self, caller: H160, input: Vec<u8>) -> U256 {
+      let (reason, output) = self.call(caller, 0, input);
+      assert!(reason.is_succeed(), "{:?}", reason);
+      U256::from_big_endian(&output)
+    }
+
+    fn succeeds(&mut self, caller: H160, value: u64, input: Vec<u8>) -> bool {
+      self.call(caller, value, input).0.is_succeed()
+    }
+
+    fn balance(&self, address: H160) -> U256 {
+      self.state.get(&address).map_or_else(U256::zero, |account| account.balance)
+    }
+
+    fn wclv_balance(&mut self, address: H160) -> U256 {
+      self.word(CHARLIE, encode("balanceOf(address)", &[word(address)]))
+    }
+
+    fn allowance(&mut self, owner: H160, spender: H160) -> U256 {
+      self.word(CHARLIE, encode("allowance(address,address)", &[word(owner), word(spender)]))
+    }
+
+    /// The only log of the last successful call, as `(topics, data)`.
+    fn log(&self) -> (Vec<H256>, U256) {
+      assert_eq!(self.logs.len(), 1);
+      let log = &self.logs[0];
+      assert_eq!(log.address, primitives::WCLV_ADDRESS);
+      (log.topics.clone(), U256::from_big_endian(&log.data))
+    }
+  }
+
+  fn word(address: H160) -> U256 {
+    U256::from_big_endian(H256::from(address).as_bytes())
+  }
+
+  fn topic(signature: &str) -> H256 {
+    H256::from(keccak_256(signature.as_bytes()))
+  }
+
+  fn encode(signature: &str, args: &[U256]) -> Vec<u8> {
+    let mut input = keccak_256(signature.as_bytes())[..4].to_vec();
+    for arg in args {
+      let mut bytes = [0u8; 32];
+      arg.to_big_endian(&mut bytes);
+      input.extend_from_slice(&bytes);
+    }
+    input
+  }
+
+  #[test]
+  fn wclv_deposit_and_withdraw_work() {
+    let mut evm = Evm::new();
+    // calls without data deposit
+    assert!(evm.succeeds(ALICE, 100, vec![]));
+    assert_eq!(evm.log(), (vec![topic("Deposit(address,uint256)"), H256::from(ALICE)], U256::from(100)));
+    assert!(evm.succeeds(BOB, 50, encode("deposit()", &[])));
+    assert_eq!(evm.wclv_balance(ALICE), U256::from(100));
+    assert_eq!(evm.wclv_balance(BOB), U256::from(50));
+    assert_eq!(evm.word(CHARLIE, encode("totalSupply()", &[])), U256::from(150));
+    assert_eq!(evm.balance(primitives::WCLV_ADDRESS), U256::from(150));
+
+    // the balances are the solidity mapping of the slot 0
+    let mut key = H256::from(ALICE).as_bytes().to_vec();
+    key.extend_from_slice(H256::zero().as_bytes());
+    let slot = H256::from(keccak_256(&key));
+    assert_eq!(evm.state[&primitives::WCLV_ADDRESS].storage.get(&slot), Some(&H256::from_low_u64_be(100)));
+
+    assert!(!evm.succeeds(ALICE, 0, encode("withdraw(uint256)", &[U256::from(101)])));
+    assert!(evm.succeeds(ALICE, 0, encode("withdraw(uint256)", &[U256::from(60)])));
+    assert_eq!(evm.log(), (vec![topic("Withdrawal(address,uint256)"), H256::from(ALICE)], U256::from(60)));
+    assert_eq!(evm.balance(ALICE), U256::from(960));
+    assert_eq!(evm.wclv_balance(ALICE), U256::from(40));
+    assert_eq!(evm.word(CHARLIE, encode("totalSupply()", &[])), U256::from(90));
+  }
+
+  #[test]
+  fn wclv_transfer_works() {
+    let mut evm = Evm::new();
+    assert!(evm.succeeds(ALICE, 100, encode("deposit()", &[])));
+
+    assert!(!evm.succeeds(ALICE, 0, encode("transfer(address,uint256)", &[word(BOB), U256::from(101)])));
+    assert_eq!(evm.word(ALICE, encode("transfer(address,uint256)", &[word(BOB), U256::from(30)])), U256::one());
+    assert_eq!(
+      evm.log(),
+      (vec![topic("Transfer(address,address,uint256)"), H256::from(ALICE), H256::from(BOB)], U256::from(30))
+    );
+    assert_eq!(evm.wclv_balance(ALICE), U256::from(70));
+    assert_eq!(evm.wclv_balance(BOB), U256::from(30));
+    // transfers don't move the wrapped CLV
+    assert_eq!(evm.balance(primitives::WCLV_ADDRESS), U256::from(100));
+  }
+
+  #[test]
+  fn wclv_transfer_from_works() {
+    let mut evm = Evm::new();
+    assert!(evm.succeeds(ALICE, 100, encode("deposit()", &[])));
+    let transfer_from = |amount: u64| {
+      encode("transferFrom(address,address,uint256)", &[word(ALICE), word(CHARLIE), U256::from(amount)])
+    };
+
+    assert!(!evm.succeeds(BOB, 0, transfer_from(1)));
+    assert_eq!(evm.word(ALICE, encode("approve(address,uint256)", &[word(BOB), U256::from(20)])), U256::one());
+    assert_eq!(
+      evm.log(),
+      (vec![topic("Approval(address,address,uint256)"), H256::from(ALICE), H256::from(BOB)], U256::from(20))
+    );
+    assert_eq!(evm.allowance(ALICE, BOB), U256::from(20));
+
+    assert!(!evm.succeeds(BOB, 0, transfer_from(21)));
+    assert_eq!(evm.word(BOB, transfer_from(15)), U256::one());
+    assert_eq!(evm.allowance(ALICE, BOB), U256::from(5));
+    assert_eq!(evm.wclv_balance(CHARLIE), U256::from(15));
+
+    // unlimited allowances are not decreased
+    assert!(evm.succeeds(ALICE, 0, encode("approve(address,uint256)", &[word(BOB), U256::max_value()])));
+    assert!(evm.succeeds(BOB, 0, transfer_from(50)));
+    assert_eq!(evm.allowance(ALICE, BOB), U256::max_value());
+
+    // the owner doesn't need an allowance
+    assert!(evm.succeeds(ALICE, 0, transfer_from(35)));
+    assert_eq!(evm.wclv_balance(ALICE), U256::zero());
+    assert_eq!(evm.wclv_balance(CHARLIE), U256::from(100));
+  }
+
+  #[test]
+  fn wclv_metadata_works() {
+    let mut evm = Evm::new();
+    let (_, output) = evm.call(CHARLIE, 0, encode("name()", &[]));
+    assert_eq!(U256::from_big_endian(&output[32..64]), U256::from(11));
+    assert_eq!(&output[64..75], b"Wrapped CLV");
+    let (_, output) = evm.call(CHARLIE, 0, encode("symbol()", &[]));
+    assert_eq!(&output[64..68], b"WCLV");
+    assert_eq!(evm.word(CHARLIE, encode("decimals()", &[])), U256::from(12));
+    assert!(!evm.succeeds(CHARLIE, 0, vec![0xde, 0xad, 0xbe, 0xef]));
+  }
+}
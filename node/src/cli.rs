@@ -1,6 +1,32 @@
+use std::str::FromStr;
 use sc_cli::{KeySubcommand, RunCmd, SignCmd, VanityCmd, VerifyCmd};
 use structopt::StructOpt;
 
+/// Block authoring of a development node, replacing Babe and Grandpa.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+  /// Seal a block for each transaction imported in the pool.
+  Instant,
+  /// Seal blocks on `engine_createBlock` and `evm_mine` calls only.
+  Manual,
+  /// Seal a block every given milliseconds, and on `engine_createBlock` and `evm_mine` calls.
+  Interval(u64),
+}
+
+impl FromStr for Sealing {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(match s {
+      "instant" => Sealing::Instant,
+      "manual" => Sealing::Manual,
+      millis => Sealing::Interval(millis.parse().map_err(|_| {
+        format!("invalid sealing `{}`, expected `instant`, `manual` or a number of milliseconds", millis)
+      })?),
+    })
+  }
+}
+
 /// Possible subcommands of the main binary.
 #[derive(Debug, StructOpt)]
 pub enum Subcommand {
@@ -37,6 +63,10 @@ pub enum Subcommand {
 
   /// Revert the chain to a previous state.
   Revert(sc_cli::RevertCmd),
+
+  /// The custom benchmark subcommmand benchmarking runtime modules.
+  #[structopt(name = "benchmark", about = "Benchmark runtime modules.")]
+  Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 }
 
 #[derive(Debug, StructOpt)]
@@ -46,4 +76,13 @@ pub struct Cli {
 
   #[structopt(flatten)]
   pub run: RunCmd,
+
+  /// Sign ethereum transactions with the well known development account, only allowed with `--dev`.
+  #[structopt(long = "enable-dev-signer")]
+  pub enable_dev_signer: bool,
+
+  /// Seal blocks on demand instead of every BABE slot, without Grandpa: `instant`, `manual` or every given
+  /// milliseconds. Only allowed with `--dev`.
+  #[structopt(long = "sealing")]
+  pub sealing: Option<Sealing>,
 }
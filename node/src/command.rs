@@ -21,6 +21,7 @@ use crate::service;
 use sc_cli::{SubstrateCli, RuntimeVersion, Role, ChainSpec};
 use sc_service::PartialComponents;
 use crate::service::new_partial;
+use clover_runtime::Block;
 
 impl SubstrateCli for Cli {
   fn impl_name() -> String {
@@ -125,11 +126,31 @@ pub fn run() -> sc_cli::Result<()> {
         Ok((cmd.run(client, backend), task_manager))
       })
     }
+
+    Some(Subcommand::Benchmark(cmd)) => {
+      if cfg!(feature = "runtime-benchmarks") {
+        let runner = cli.create_runner(cmd)?;
+
+        runner.sync_run(|config| cmd.run::<Block, service::Executor>(config))
+      } else {
+        Err("Benchmarking wasn't enabled when building the node. \
+          You can enable it with `--features runtime-benchmarks`.".into())
+      }
+    }
     None => {
+      if cli.enable_dev_signer && !cli.run.shared_params.dev {
+        return Err("`--enable-dev-signer` is only allowed with `--dev`".into());
+      }
+      if cli.sealing.is_some() && !cli.run.shared_params.dev {
+        return Err("`--sealing` is only allowed with `--dev`".into());
+      }
+      let enable_dev_signer = cli.enable_dev_signer;
+      let sealing = cli.sealing;
       let runner = cli.create_runner(&cli.run)?;
-      runner.run_node_until_exit(|config| match config.role {
-        Role::Light => service::new_light(config),
-        _ => service::new_full(config),
+      runner.run_node_until_exit(|config| match (config.role.clone(), sealing) {
+        (Role::Light, _) => service::new_light(config),
+        (_, Some(sealing)) => service::new_manual_seal(config, sealing, enable_dev_signer),
+        _ => service::new_full(config, enable_dev_signer),
       })
     }
   }
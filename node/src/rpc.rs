@@ -6,7 +6,7 @@
 #![warn(missing_docs)]
 use std::sync::Arc;
 
-use primitives::{Block, BlockNumber, AccountId, CurrencyId, Index, Balance, Hash, Rate, Share};
+use primitives::{Block, BlockNumber, AccountId, CurrencyId, Index, Balance, Hash, PoolId, Rate, Share};
 use sc_consensus_babe::{Config, Epoch};
 use sc_consensus_babe_rpc::BabeRpcHandler;
 use sc_consensus_epochs::SharedEpochChanges;
@@ -20,9 +20,12 @@ use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sp_consensus::SelectChain;
 use sp_consensus_babe::BabeApi;
+use sp_core::H160;
 use sp_transaction_pool::TransactionPool;
 use sc_network::NetworkService;
 use jsonrpc_pubsub::manager::SubscriptionManager;
+use fc_rpc::{DevState, FilterPool, SyncProgress};
+use sc_consensus_manual_seal::{EngineCommand, rpc::{ManualSeal, ManualSealApi}};
 
 
 /// Light client extra dependencies.
@@ -61,6 +64,16 @@ pub struct GrandpaDeps<B> {
   pub finality_provider: Arc<FinalityProofProvider<B, Block>>,
 }
 
+/// Extra dependencies for manual seal
+pub struct ManualSealDeps<B> {
+  /// Sends the sealing commands to the block authorship.
+  pub command_sink: futures::channel::mpsc::Sender<EngineCommand<Hash>>,
+  /// The backend, to revert blocks.
+  pub backend: Arc<B>,
+  /// Time offset and snapshots of the chain.
+  pub dev_state: DevState,
+}
+
 /// Full client dependencies.
 pub struct FullDeps<C, P, SC, B> {
   /// The client instance to use.
@@ -71,16 +84,29 @@ pub struct FullDeps<C, P, SC, B> {
   pub select_chain: SC,
   /// Whether to deny unsafe calls
   pub deny_unsafe: DenyUnsafe,
-  /// BABE specific dependencies.
-  pub babe: BabeDeps,
-  /// GRANDPA specific dependencies.
-  pub grandpa: GrandpaDeps<B>,
+  /// BABE specific dependencies, `None` when sealing manually.
+  pub babe: Option<BabeDeps>,
+  /// GRANDPA specific dependencies, `None` when sealing manually.
+  pub grandpa: Option<GrandpaDeps<B>>,
+  /// Manual seal specific dependencies, `None` when running BABE and GRANDPA.
+  pub manual_seal: Option<ManualSealDeps<B>>,
+  /// The keystore of the ethereum accounts.
+  pub keystore: KeyStorePtr,
   /// The Node authority flag
   pub is_authority: bool,
   /// Network service
   pub network: Arc<NetworkService<Block, Hash>>,
+  /// Ethereum filters installed by the clients
+  pub filter_pool: FilterPool,
+  /// Whether to sign with the development account
+  pub enable_dev_signer: bool,
+  /// Progress of the major sync
+  pub sync_progress: SyncProgress,
 }
 
+/// Max number of ethereum filters installed at once.
+pub const MAX_STORED_FILTERS: usize = 500;
+
 /// A IO handler that uses all Full RPC extensions.
 pub type IoHandler = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 
@@ -100,6 +126,11 @@ pub fn create_full<C, P, SC, B>(
   C::Api: clover_rpc::pair::CurrencyPairRuntimeApi<Block>,
   C::Api: clover_rpc::incentive_pool::IncentivePoolRuntimeApi<Block, AccountId, CurrencyId, Share, Balance>,
   C::Api: clover_rpc::exchange::CurrencyExchangeRuntimeApi<Block, AccountId, CurrencyId, Balance, Rate, Share>,
+  C::Api: clover_rpc::incentive_program::IncentiveProgramRuntimeApi<Block, CurrencyId, Balance, BlockNumber>,
+  C::Api: clover_rpc::incentive_reward::IncentiveRewardRuntimeApi<Block, AccountId, PoolId, CurrencyId, Balance, Share, Rate>,
+  C::Api: clover_rpc::stake_lock::StakeLockRuntimeApi<Block, AccountId, CurrencyId, Share, Rate, BlockNumber>,
+  C::Api: clover_rpc::fee::CurrencyFeeRuntimeApi<Block, CurrencyId, Balance>,
+  C::Api: clover_rpc::contract_verification::ContractVerificationRuntimeApi<Block, H160, AccountId, Hash, BlockNumber>,
   C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
   C::Api: BabeApi<Block>,
   C::Api: BlockBuilder<Block>,
@@ -109,8 +140,9 @@ pub fn create_full<C, P, SC, B>(
   B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
   use fc_rpc::{
-    EthApi, EthApiServer, NetApi, NetApiServer, EthPubSubApi, EthPubSubApiServer,
-    Web3Api, Web3ApiServer, EthDevSigner, EthSigner, HexEncodedIdProvider,
+    EthApi, EthApiServer, EthFilterApi, EthFilterApiServer, NetApi, NetApiServer, EthPubSubApi, EthPubSubApiServer,
+    Web3Api, Web3ApiServer, TxPoolApi, TxPoolApiServer, PersonalApiServer, EthDevSigner, EthKeystoreSigner,
+    EthSigner, EvmDevApi, EvmDevApiServer, HexEncodedIdProvider,
   };
   use substrate_frame_rpc_system::{FullSystem, SystemApi};
   use pallet_contracts_rpc::{Contracts, ContractsApi};
@@ -124,23 +156,15 @@ pub fn create_full<C, P, SC, B>(
     deny_unsafe,
     babe,
     grandpa,
+    manual_seal,
+    keystore,
     is_authority,
     network,
+    filter_pool,
+    enable_dev_signer,
+    sync_progress,
   } = deps;
 
-  let BabeDeps {
-    keystore,
-    babe_config,
-    shared_epoch_changes,
-  } = babe;
-  let GrandpaDeps {
-    shared_voter_state,
-    shared_authority_set,
-    justification_stream,
-    subscription_executor,
-    finality_provider,
-  } = grandpa;
-
   io.extend_with(
     SystemApi::to_delegate(FullSystem::new(client.clone(), pool.clone(), deny_unsafe))
   );
@@ -148,29 +172,49 @@ pub fn create_full<C, P, SC, B>(
     TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone()))
   );
   io.extend_with(ContractsApi::to_delegate(Contracts::new(client.clone())));
-  io.extend_with(
-    sc_consensus_babe_rpc::BabeApi::to_delegate(
-      BabeRpcHandler::new(
-        client.clone(),
-        shared_epoch_changes,
-        keystore,
-        babe_config,
-        select_chain,
-        deny_unsafe,
-      ),
-    )
-  );
-  io.extend_with(
-    sc_finality_grandpa_rpc::GrandpaApi::to_delegate(
-      GrandpaRpcHandler::new(
-        shared_authority_set,
-        shared_voter_state,
-        justification_stream,
-        subscription_executor,
-        finality_provider,
+  if let Some(BabeDeps { keystore, babe_config, shared_epoch_changes }) = babe {
+    io.extend_with(
+      sc_consensus_babe_rpc::BabeApi::to_delegate(
+        BabeRpcHandler::new(
+          client.clone(),
+          shared_epoch_changes,
+          keystore,
+          babe_config,
+          select_chain,
+          deny_unsafe,
+        ),
       )
-    )
-  );
+    );
+  }
+  if let Some(GrandpaDeps {
+    shared_voter_state,
+    shared_authority_set,
+    justification_stream,
+    subscription_executor,
+    finality_provider,
+  }) = grandpa {
+    io.extend_with(
+      sc_finality_grandpa_rpc::GrandpaApi::to_delegate(
+        GrandpaRpcHandler::new(
+          shared_authority_set,
+          shared_voter_state,
+          justification_stream,
+          subscription_executor,
+          finality_provider,
+        )
+      )
+    );
+  }
+  if let Some(ManualSealDeps { command_sink, backend, dev_state }) = manual_seal {
+    io.extend_with(ManualSealApi::to_delegate(ManualSeal::new(command_sink.clone())));
+    io.extend_with(EvmDevApiServer::to_delegate(EvmDevApi::new(
+      client.clone(),
+      backend,
+      pool.clone(),
+      command_sink,
+      dev_state,
+    )));
+  }
 
   io.extend_with(clover_rpc::balance::CurrencyBalanceRpc::to_delegate(
     clover_rpc::balance::CurrencyBalance::new(client.clone()),
@@ -192,17 +236,68 @@ pub fn create_full<C, P, SC, B>(
     clover_rpc::incentive_pool::IncentivePool::new(client.clone()),
   ));
 
-  let mut signers = Vec::new();
-  signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
+  io.extend_with(clover_rpc::incentive_program::IncentiveProgramRpc::to_delegate(
+    clover_rpc::incentive_program::IncentiveProgram::new(client.clone()),
+  ));
+
+  io.extend_with(clover_rpc::incentive_reward::IncentiveRewardRpc::to_delegate(
+    clover_rpc::incentive_reward::IncentiveReward::new(client.clone()),
+  ));
+
+  io.extend_with(clover_rpc::stake_lock::StakeLockRpc::to_delegate(
+    clover_rpc::stake_lock::StakeLock::new(client.clone()),
+  ));
+
+  io.extend_with(clover_rpc::fee::CurrencyFeeRpc::to_delegate(
+    clover_rpc::fee::CurrencyFee::new(client.clone()),
+  ));
+
+  io.extend_with(clover_rpc::contract_verification::ContractVerificationRpc::to_delegate(
+    clover_rpc::contract_verification::ContractVerification::new(client.clone()),
+  ));
+
+  let eth_keystore = EthKeystoreSigner::new(keystore.clone());
+  let signers = || {
+    let mut signers = vec![Box::new(eth_keystore.clone()) as Box<dyn EthSigner>];
+    if enable_dev_signer {
+      signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
+    }
+    signers
+  };
   io.extend_with(EthApiServer::to_delegate(EthApi::new(
     client.clone(),
     pool.clone(),
     clover_runtime::TransactionConverter,
     network.clone(),
-    signers,
+    sync_progress.clone(),
+    signers(),
     is_authority,
+    deny_unsafe,
   )));
 
+  // the personal rpcs manage the keystore, they aren't served on the public rpc servers
+  if deny_unsafe.check_if_safe().is_ok() {
+    io.extend_with(PersonalApiServer::to_delegate(EthApi::new(
+      client.clone(),
+      pool.clone(),
+      clover_runtime::TransactionConverter,
+      network.clone(),
+      sync_progress.clone(),
+      signers(),
+      is_authority,
+      deny_unsafe,
+    )));
+  }
+
+  io.extend_with(
+    EthFilterApiServer::to_delegate(EthFilterApi::new(
+      client.clone(),
+      pool.clone(),
+      filter_pool,
+      MAX_STORED_FILTERS,
+    ))
+  );
+
   io.extend_with(
     NetApiServer::to_delegate(NetApi::new(
       client.clone(),
@@ -216,11 +311,19 @@ pub fn create_full<C, P, SC, B>(
     ))
   );
 
+  io.extend_with(
+    TxPoolApiServer::to_delegate(TxPoolApi::new(
+      client.clone(),
+      pool.clone(),
+    ))
+  );
+
   io.extend_with(
     EthPubSubApiServer::to_delegate(EthPubSubApi::new(
       pool.clone(),
       client.clone(),
       network.clone(),
+      sync_progress,
       SubscriptionManager::<HexEncodedIdProvider>::with_id_provider(
         HexEncodedIdProvider::default(),
         Arc::new(subscription_task_executor)
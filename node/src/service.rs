@@ -1,22 +1,28 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, atomic::{AtomicU64, Ordering}};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use futures::{FutureExt, StreamExt};
 use sc_client_api::{ExecutorProvider, RemoteBackend};
+use sp_blockchain::HeaderBackend;
 use clover_runtime::{self, opaque::Block, RuntimeApi};
 use sc_service::{error::Error as ServiceError, Configuration, RpcHandlers, TaskManager};
-use sp_inherents::InherentDataProviders;
-use sp_runtime::traits::Block as BlockT;
+use sp_inherents::{InherentData, InherentDataProviders, InherentIdentifier, ProvideInherentData};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sc_finality_grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider, SharedVoterState};
 use fc_consensus::FrontierBlockImport;
+use fc_rpc::{pending_transaction_filters_task, DevState, FilterPool, SyncProgress};
+use sc_consensus_manual_seal::EngineCommand;
+use crate::cli::Sealing;
 
 // Our native executor instance.
 native_executor_instance!(
   pub Executor,
   clover_runtime::api::dispatch,
   clover_runtime::native_version,
+  frame_benchmarking::benchmarking::HostFunctions,
 );
 
 type FullClient = sc_service::TFullClient<Block, RuntimeApi, Executor>;
@@ -116,6 +122,7 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 
 /// Builds a new service for a full client.
 pub fn new_full_base(config: Configuration,
+  enable_dev_signer: bool,
   with_startup_data: impl FnOnce(
     &sc_consensus_babe::BabeBlockImport<Block, FullClient,
       FrontierBlockImport<Block, FullGrandpaBlockImport, FullClient>,
@@ -177,26 +184,38 @@ pub fn new_full_base(config: Configuration,
   let copy_role = config.role.clone();
   let copy_keystore = keystore.clone();
   let copy_select_chain = select_chain.clone();
+  let filter_pool: FilterPool = Arc::new(Mutex::new(Default::default()));
+  task_manager.spawn_handle().spawn(
+    "eth-pending-transaction-filters",
+    pending_transaction_filters_task(client.clone(), transaction_pool.clone(), filter_pool.clone()),
+  );
+  let sync_progress = SyncProgress::default();
+  spawn_sync_progress(&task_manager, &network_status_sinks, network.clone(), client.clone(), sync_progress.clone());
   let rpc_extensions_builder = move |_deny_unsafe, _subscription_executor| {
     let deps = crate::rpc::FullDeps {
       client: copy_client.clone(),
       pool: pool.clone(),
       select_chain: copy_select_chain.clone(),
       deny_unsafe: _deny_unsafe,
-      babe: crate::rpc::BabeDeps {
+      babe: Some(crate::rpc::BabeDeps {
         babe_config: babe_config.clone(),
         shared_epoch_changes: shared_epoch_changes.clone(),
         keystore: copy_keystore.clone(),
-      },
-      grandpa: crate::rpc::GrandpaDeps {
+      }),
+      grandpa: Some(crate::rpc::GrandpaDeps {
         shared_voter_state: _rpc_setup.0.clone(),
         shared_authority_set: shared_authority_set.clone(),
         justification_stream: justification_stream.clone(),
         subscription_executor: _subscription_executor,
         finality_provider: finality_proof_provider.clone(),
-      },
+      }),
+      manual_seal: None,
+      keystore: copy_keystore.clone(),
       network: copy_network.clone(),
-      is_authority: copy_role.is_authority()
+      is_authority: copy_role.is_authority(),
+      filter_pool: filter_pool.clone(),
+      enable_dev_signer,
+      sync_progress: sync_progress.clone(),
     };
 
     crate::rpc::create_full(
@@ -303,13 +322,291 @@ pub fn new_full_base(config: Configuration,
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration)
+pub fn new_full(config: Configuration, enable_dev_signer: bool)
 -> Result<TaskManager, ServiceError> {
-  new_full_base(config, |_, _| ()).map(|(task_manager, _, _, _, _)| {
+  new_full_base(config, enable_dev_signer, |_, _| ()).map(|(task_manager, _, _, _, _)| {
     task_manager
   })
 }
 
+/// Updates the sync progress reported by the ethereum rpc from the network status.
+fn spawn_sync_progress(
+  task_manager: &TaskManager,
+  network_status_sinks: &sc_service::NetworkStatusSinks<Block>,
+  network: Arc<sc_network::NetworkService<Block, <Block as BlockT>::Hash>>,
+  client: Arc<FullClient>,
+  sync_progress: SyncProgress,
+) {
+  let statuses = network_status_sinks.network_status(Duration::from_secs(1));
+  task_manager.spawn_handle().spawn("eth-sync-progress", statuses.for_each(move |(status, _)| {
+    sync_progress.update(
+      network.is_major_syncing(),
+      client.info().best_number.into(),
+      status.best_seen_block.map(Into::into),
+    );
+    futures::future::ready(())
+  }));
+}
+
+/// Timestamps of the manually sealed blocks: the system time shifted by `evm_increaseTime`,
+/// at least a slot after the previous block so blocks can be sealed faster than the slots.
+struct DevTimestampProvider {
+  last: AtomicU64,
+  time_offset: Arc<AtomicU64>,
+}
+
+impl ProvideInherentData for DevTimestampProvider {
+  fn inherent_identifier(&self) -> &'static InherentIdentifier {
+    &sp_timestamp::INHERENT_IDENTIFIER
+  }
+
+  fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)
+      .map_err(|e| format!("Current time is before unix epoch: {:?}", e))?
+      .as_millis() as u64;
+    let shifted = now.saturating_add(self.time_offset.load(Ordering::SeqCst).saturating_mul(1000));
+    let timestamp = shifted.max(self.last.load(Ordering::SeqCst).saturating_add(clover_runtime::SLOT_DURATION));
+    self.last.store(timestamp, Ordering::SeqCst);
+    inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp)
+  }
+
+  fn error_to_string(&self, error: &[u8]) -> Option<String> {
+    sp_timestamp::InherentError::try_from(&sp_timestamp::INHERENT_IDENTIFIER, error).map(|e| format!("{:?}", e))
+  }
+}
+
+/// Slots of the manually sealed blocks, each one after the slot of the previous block.
+/// BABE can't skip epochs, so the slots don't follow the timestamps shifted by `evm_increaseTime`.
+struct DevSlotProvider {
+  last: AtomicU64,
+}
+
+impl ProvideInherentData for DevSlotProvider {
+  fn inherent_identifier(&self) -> &'static InherentIdentifier {
+    &sp_consensus_babe::inherents::INHERENT_IDENTIFIER
+  }
+
+  fn provide_inherent_data(&self, inherent_data: &mut InherentData) -> Result<(), sp_inherents::Error> {
+    let slot: u64 = self.last.fetch_add(1, Ordering::SeqCst).saturating_add(1);
+    inherent_data.put_data(sp_consensus_babe::inherents::INHERENT_IDENTIFIER, &slot)
+  }
+
+  fn error_to_string(&self, _error: &[u8]) -> Option<String> {
+    None
+  }
+}
+
+/// Slot of the best block, the current slot if the chain only has the genesis block.
+fn best_slot(client: &FullClient) -> u64 {
+  let slot = client.header(BlockId::Hash(client.info().best_hash))
+    .ok()
+    .flatten()
+    .and_then(|header| sc_consensus_babe::find_pre_digest::<Block>(&header).ok())
+    .map(|pre_digest| pre_digest.slot_number())
+    .unwrap_or_default();
+  if slot > 0 {
+    return slot;
+  }
+  SystemTime::now().duration_since(UNIX_EPOCH)
+    .map(|now| now.as_millis() as u64 / clover_runtime::SLOT_DURATION)
+    .unwrap_or_default()
+}
+
+/// Builds a new service for a full client sealing the blocks with `sealing` instead of the BABE slots and GRANDPA.
+/// The blocks carry BABE pre-digests of the development authorities, so `--sealing` requires `--dev`.
+pub fn new_manual_seal(config: Configuration, sealing: Sealing, enable_dev_signer: bool)
+-> Result<TaskManager, ServiceError> {
+  let inherent_data_providers = InherentDataProviders::new();
+
+  let (client, backend, keystore, mut task_manager) =
+    sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
+  let client = Arc::new(client);
+
+  let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+  let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+    config.transaction_pool.clone(),
+    config.prometheus_registry(),
+    task_manager.spawn_handle(),
+    client.clone(),
+  );
+
+  let dev_state = DevState::default();
+  inherent_data_providers
+    .register_provider(DevTimestampProvider {
+      last: AtomicU64::new(0),
+      time_offset: dev_state.time_offset.clone(),
+    })
+    .map_err(Into::into)
+    .map_err(sp_consensus::Error::InherentData)?;
+  inherent_data_providers
+    .register_provider(DevSlotProvider {
+      last: AtomicU64::new(best_slot(&client)),
+    })
+    .map_err(Into::into)
+    .map_err(sp_consensus::Error::InherentData)?;
+
+  let frontier_block_import = FrontierBlockImport::new(client.clone(), client.clone(), true);
+  let babe_config = sc_consensus_babe::Config::get_or_compute(&*client)?;
+  let (block_import, babe_link) = sc_consensus_babe::block_import(
+    babe_config.clone(),
+    frontier_block_import.clone(),
+    client.clone(),
+  )?;
+  // the slots and timestamps are provided by `DevSlotProvider` and `DevTimestampProvider`,
+  // the providers registered by BABE go to a set which isn't used
+  let consensus_data_provider = sc_consensus_manual_seal::consensus::babe::BabeConsensusDataProvider::new(
+    client.clone(),
+    keystore.clone(),
+    &InherentDataProviders::new(),
+    babe_link.epoch_changes().clone(),
+    babe_config.genesis_authorities.clone(),
+  ).map_err(|err| ServiceError::Other(format!("create babe consensus data provider failed: {:?}", err)))?;
+
+  let import_queue = sc_consensus_manual_seal::import_queue(
+    Box::new(frontier_block_import),
+    &task_manager.spawn_handle(),
+    config.prometheus_registry(),
+  );
+
+  let (network, network_status_sinks, system_rpc_tx, network_starter) =
+    sc_service::build_network(sc_service::BuildNetworkParams {
+      config: &config,
+      client: client.clone(),
+      transaction_pool: transaction_pool.clone(),
+      spawn_handle: task_manager.spawn_handle(),
+      import_queue,
+      on_demand: None,
+      block_announce_validator_builder: None,
+      finality_proof_request_builder: None,
+      finality_proof_provider: None,
+    })?;
+
+  if config.offchain_worker.enabled {
+    sc_service::build_offchain_workers(
+      &config, backend.clone(), task_manager.spawn_handle(), client.clone(), network.clone(),
+    );
+  }
+
+  let prometheus_registry = config.prometheus_registry().cloned();
+  let (command_sink, commands_stream) = futures::channel::mpsc::channel(1000);
+
+  let pool = transaction_pool.clone();
+  let subscription_task_executor = sc_rpc::SubscriptionTaskExecutor::new(task_manager.spawn_handle());
+  let copy_network = network.clone();
+  let copy_client = client.clone();
+  let copy_backend = backend.clone();
+  let copy_role = config.role.clone();
+  let copy_keystore = keystore.clone();
+  let copy_select_chain = select_chain.clone();
+  let filter_pool: FilterPool = Arc::new(Mutex::new(Default::default()));
+  task_manager.spawn_handle().spawn(
+    "eth-pending-transaction-filters",
+    pending_transaction_filters_task(client.clone(), transaction_pool.clone(), filter_pool.clone()),
+  );
+  let sync_progress = SyncProgress::default();
+  spawn_sync_progress(&task_manager, &network_status_sinks, network.clone(), client.clone(), sync_progress.clone());
+  let rpc_extensions_builder = move |_deny_unsafe, _subscription_executor| {
+    let deps = crate::rpc::FullDeps {
+      client: copy_client.clone(),
+      pool: pool.clone(),
+      select_chain: copy_select_chain.clone(),
+      deny_unsafe: _deny_unsafe,
+      babe: None,
+      grandpa: None,
+      manual_seal: Some(crate::rpc::ManualSealDeps {
+        command_sink: command_sink.clone(),
+        backend: copy_backend.clone(),
+        dev_state: dev_state.clone(),
+      }),
+      keystore: copy_keystore.clone(),
+      network: copy_network.clone(),
+      is_authority: copy_role.is_authority(),
+      filter_pool: filter_pool.clone(),
+      enable_dev_signer,
+      sync_progress: sync_progress.clone(),
+    };
+
+    crate::rpc::create_full(
+      deps,
+      subscription_task_executor.clone()
+    )
+  };
+
+  sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+    network: network.clone(),
+    client: client.clone(),
+    keystore,
+    task_manager: &mut task_manager,
+    transaction_pool: transaction_pool.clone(),
+    telemetry_connection_sinks: sc_service::TelemetryConnectionSinks::default(),
+    rpc_extensions_builder: Box::new(rpc_extensions_builder),
+    on_demand: None,
+    remote_blockchain: None,
+    backend, network_status_sinks, system_rpc_tx, config,
+  })?;
+
+  let proposer = sc_basic_authorship::ProposerFactory::new(
+    client.clone(),
+    transaction_pool.clone(),
+    prometheus_registry.as_ref(),
+  );
+
+  let authorship = match sealing {
+    Sealing::Instant => sc_consensus_manual_seal::run_instant_seal(
+      sc_consensus_manual_seal::InstantSealParams {
+        block_import,
+        env: proposer,
+        client,
+        pool: transaction_pool.pool().clone(),
+        select_chain,
+        consensus_data_provider: Some(Box::new(consensus_data_provider)),
+        inherent_data_providers,
+      }
+    ).boxed(),
+    Sealing::Manual => sc_consensus_manual_seal::run_manual_seal(
+      sc_consensus_manual_seal::ManualSealParams {
+        block_import,
+        env: proposer,
+        client,
+        pool: transaction_pool.pool().clone(),
+        commands_stream,
+        select_chain,
+        consensus_data_provider: Some(Box::new(consensus_data_provider)),
+        inherent_data_providers,
+      }
+    ).boxed(),
+    Sealing::Interval(millis) => {
+      let interval = futures::stream::unfold((), move |_| async move {
+        futures_timer::Delay::new(Duration::from_millis(millis)).await;
+        Some((EngineCommand::SealNewBlock {
+          create_empty: true,
+          finalize: false,
+          parent_hash: None,
+          sender: None,
+        }, ()))
+      });
+      sc_consensus_manual_seal::run_manual_seal(
+        sc_consensus_manual_seal::ManualSealParams {
+          block_import,
+          env: proposer,
+          client,
+          pool: transaction_pool.pool().clone(),
+          commands_stream: futures::stream::select(commands_stream, interval),
+          select_chain,
+          consensus_data_provider: Some(Box::new(consensus_data_provider)),
+          inherent_data_providers,
+        }
+      ).boxed()
+    },
+  };
+
+  task_manager.spawn_essential_handle().spawn_blocking("manual-seal", authorship);
+
+  network_starter.start_network();
+  Ok(task_manager)
+}
+
 pub fn new_light_base(config: Configuration) -> Result<(
   TaskManager, RpcHandlers, Arc<LightClient>,
   Arc<sc_network::NetworkService<Block, <Block as BlockT>::Hash>>,
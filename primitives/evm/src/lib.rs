@@ -37,7 +37,8 @@ pub type CreateInfo = ExecutionInfo<H160>;
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 pub enum CallOrCreateInfo {
 	Call(CallInfo),
-	Create(CreateInfo),
+	/// The info of a create, with the revert data of a reverted init code.
+	Create(CreateInfo, Vec<u8>),
 }
 
 /// A mapping between `AccountId` and `H160`.
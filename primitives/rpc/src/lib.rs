@@ -1,11 +1,12 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_core::{H160, H256, U256};
-use ethereum::{Log, Block as EthereumBlock};
-use evm::backend::InternalTransaction;
+use ethereum::{Log, Block as EthereumBlock, Transaction as EthereumTransaction};
+use evm::{ExitReason, backend::InternalTransaction};
 use ethereum_types::Bloom;
 use codec::{Encode, Decode};
 use sp_std::vec::Vec;
+use sp_runtime::traits::Block as BlockT;
 
 #[derive(Eq, PartialEq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
 pub struct TransactionStatus {
@@ -36,6 +37,7 @@ impl Default for TransactionStatus {
 
 sp_api::decl_runtime_apis! {
 	/// API necessary for Ethereum-compatibility layer.
+	#[api_version(3)]
 	pub trait EthereumRuntimeRPCApi {
 		/// Returns runtime defined pallet_evm::ChainId.
 		fn chain_id() -> u64;
@@ -82,6 +84,12 @@ sp_api::decl_runtime_apis! {
 			Option<Vec<ethereum::Receipt>>,
 			Option<Vec<TransactionStatus>>
 		);
+		/// Return the ethereum transactions of the `Ethereum::transact` and `transact_sponsored` extrinsics.
+		/// Added in version 2.
+		fn extrinsic_filter(xts: Vec<<Block as BlockT>::Extrinsic>) -> Vec<EthereumTransaction>;
+		/// Return the exit reasons of the current transactions, with the revert data of the reverted ones.
+		/// Added in version 3.
+		fn current_exit_reasons() -> Option<Vec<(ExitReason, Vec<u8>)>>;
 	}
 }
 
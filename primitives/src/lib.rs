@@ -1,12 +1,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-#[macro_use]
-extern crate num_derive;
 use codec::{Decode, Encode};
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
+use sp_core::H160;
 use sp_runtime::{
   FixedU128,
   generic,
@@ -64,14 +63,133 @@ pub type BlockId = generic::BlockId<Block>;
 /// Opaque, encoded, unchecked extrinsic.
 pub use sp_runtime::OpaqueExtrinsic as UncheckedExtrinsic;
 
-#[repr(u32)]
-#[derive(Encode, Decode, Eq, FromPrimitive, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord, enum_iterator::IntoEnumIterator)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize, strum_macros::EnumIter, strum_macros::Display, int_enum::IntEnum))]
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, strum_macros::Display))]
 pub enum CurrencyId {
-	  CLV = 0,
-	  CUSDT = 1,
-	  DOT = 2,
-	  CETH = 3,
+	  CLV,
+	  CUSDT,
+	  DOT,
+	  CETH,
+	  /// Liquidity pool token of cloverdex, identified by the pair key of the pool
+	  LpToken(u64),
+}
+
+impl CurrencyId {
+  /// All currencies which are not lp tokens
+  pub const TOKENS: [CurrencyId; 4] = [CurrencyId::CLV, CurrencyId::CUSDT, CurrencyId::DOT, CurrencyId::CETH];
+
+  /// Numeric id of a token, `None` for lp tokens
+  pub fn token_id(&self) -> Option<u32> {
+    match self {
+      CurrencyId::CLV => Some(0),
+      CurrencyId::CUSDT => Some(1),
+      CurrencyId::DOT => Some(2),
+      CurrencyId::CETH => Some(3),
+      CurrencyId::LpToken(_) => None,
+    }
+  }
+
+  /// The token of the numeric id
+  pub fn from_token_id(id: u32) -> Option<CurrencyId> {
+    CurrencyId::TOKENS.iter().find(|c| c.token_id() == Some(id)).copied()
+  }
+
+  pub fn is_lp_token(&self) -> bool {
+    matches!(self, CurrencyId::LpToken(_))
+  }
+
+  /// Address of a token in the evm, CLV is wrapped by WCLV,
+  /// the other tokens have no erc20 contract and are named by `TOKEN_ADDRESS_PREFIX` and their token id
+  pub fn evm_address(&self) -> Option<H160> {
+    match self {
+      CurrencyId::CLV => Some(WCLV_ADDRESS),
+      _ => self.token_id().map(|id| {
+        let mut address = [0u8; 20];
+        address[..16].copy_from_slice(&TOKEN_ADDRESS_PREFIX);
+        address[16..].copy_from_slice(&id.to_be_bytes());
+        H160(address)
+      }),
+    }
+  }
+
+  /// The token of an evm address
+  pub fn from_evm_address(address: &H160) -> Option<CurrencyId> {
+    CurrencyId::TOKENS.iter().find(|c| c.evm_address().as_ref() == Some(address)).copied()
+  }
+}
+
+/// Address of WCLV, the erc20 contract wrapping CLV in the evm
+pub const WCLV_ADDRESS: H160 = H160([
+  0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x08, 0x02,
+]);
+
+/// Prefix of the evm addresses of the tokens without an erc20 contract, followed by the token id
+const TOKEN_ADDRESS_PREFIX: [u8; 16] = [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+/// A currency pair in canonical order, `left` is always less than `right`
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PairKey {
+  left: CurrencyId,
+  right: CurrencyId,
+}
+
+impl PairKey {
+  pub fn try_from(first: CurrencyId, second: CurrencyId) -> Option<Self> {
+    if first == second {
+      None
+    } else if first < second {
+      Some(PairKey { left: first, right: second, })
+    } else {
+      Some(PairKey { left: second, right: first, })
+    }
+  }
+
+  pub fn left(&self) -> CurrencyId {
+    self.left
+  }
+
+  pub fn right(&self) -> CurrencyId {
+    self.right
+  }
+
+  /// Whether the pair is in canonical order, keys decoded from raw input may not be
+  pub fn is_valid(&self) -> bool {
+    self.left < self.right
+  }
+}
+
+/// PoolId for various rewards pools
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PoolId {
+  /// Rewards for dex module
+  Dex(PairKey),
+  /// Rewards for borrowers of the currency in loans module
+  Loans(CurrencyId),
+  /// Rewards for single asset staking and other non-dex activities
+  Single(CurrencyId),
+  /// Rewards for depositors of the collateral currency in loans module
+  LoansDeposit(CurrencyId),
+  /// Rewards for the gas used by ethereum transactions in the last blocks
+  EvmUsage,
+}
+
+impl PoolId {
+  pub fn is_valid(&self) -> bool {
+    match self {
+      PoolId::Dex(pair_key) => pair_key.is_valid(),
+      PoolId::Loans(_) | PoolId::Single(_) | PoolId::LoansDeposit(_) | PoolId::EvmUsage => true,
+    }
+  }
+
+  /// The currency pair of a dex pool
+  pub fn currency_pair(&self) -> Option<(CurrencyId, CurrencyId)> {
+    match self {
+      PoolId::Dex(pair_key) => Some((pair_key.left, pair_key.right)),
+      PoolId::Loans(_) | PoolId::Single(_) | PoolId::LoansDeposit(_) | PoolId::EvmUsage => None,
+    }
+  }
 }
 
 /// dex related types
@@ -78,3 +78,35 @@ pub fn write_transaction_metadata<F, R>(
 	let key = transaction_metadata_key(hash);
 	write_aux(&[(&key, &metadata.encode())])
 }
+
+/// Remove the mappings of a reverted block: the substrate block from the blocks of its ethereum
+/// block hash, and the transactions which were included in it.
+pub fn revert_block_mappings<Hash: Encode + Decode + PartialEq, Backend: AuxStore>(
+	client: &Backend,
+	ethereum_hash: H256,
+	block_hash: Hash,
+	transaction_hashes: &[H256],
+) -> ClientResult<()> {
+	let block_key = block_hash_key(ethereum_hash);
+	let mut hashes: Vec<Hash> = load_decode(client, &block_key)?.unwrap_or_default();
+	hashes.retain(|hash| hash != &block_hash);
+	let encoded = hashes.encode();
+
+	let mut transaction_keys = Vec::new();
+	for hash in transaction_hashes {
+		if let Some((included_in, _)) = load_transaction_metadata(client, *hash)? {
+			if included_in == ethereum_hash {
+				transaction_keys.push(transaction_metadata_key(*hash));
+			}
+		}
+	}
+
+	let mut delete: Vec<&[u8]> = transaction_keys.iter().map(|key| &key[..]).collect();
+	let mut insert: Vec<(&[u8], &[u8])> = Vec::new();
+	if hashes.is_empty() {
+		delete.push(&block_key[..]);
+	} else {
+		insert.push((&block_key[..], &encoded[..]));
+	}
+	client.insert_aux(insert.iter(), delete.iter())
+}
@@ -1,6 +1,6 @@
 mod aux_schema;
 
-pub use crate::aux_schema::{load_block_hash, load_transaction_metadata};
+pub use crate::aux_schema::{load_block_hash, load_transaction_metadata, revert_block_mappings};
 
 use std::sync::Arc;
 use std::collections::HashMap;
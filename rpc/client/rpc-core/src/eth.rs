@@ -4,9 +4,10 @@ use jsonrpc_derive::rpc;
 
 use crate::types::{
 	BlockNumber, Bytes, CallRequest, Filter, FilterChanges, Index, Log, Receipt,
-	RichBlock, SyncStatus, Transaction, Work, TransactionRequest,
+	RichBlock, SyncStatus, Transaction, Work, TransactionRequest, TypedData,
 };
 pub use rpc_impl_EthApi::gen_server::EthApi as EthApiServer;
+pub use rpc_impl_EthFilterApi::gen_server::EthFilterApi as EthFilterApiServer;
 
 /// Eth rpc interface.
 #[rpc(server)]
@@ -89,6 +90,14 @@ pub trait EthApi {
 	#[rpc(name = "eth_getCode")]
 	fn code_at(&self, _: H160, _: Option<BlockNumber>) -> Result<Bytes>;
 
+	/// Signs `keccak256("\x19Ethereum Signed Message:\n" + len(message) + message)` with the given account.
+	#[rpc(name = "eth_sign")]
+	fn sign(&self, _: H160, _: Bytes) -> Result<Bytes>;
+
+	/// Signs the EIP-712 hash of the typed structured data with the given account.
+	#[rpc(name = "eth_signTypedData")]
+	fn sign_typed_data(&self, _: H160, _: TypedData) -> Result<Bytes>;
+
 	/// Sends transaction; will block waiting for signer to return the
 	/// transaction hash.
 	#[rpc(name = "eth_sendTransaction")]
@@ -129,6 +138,10 @@ pub trait EthApi {
 		_: Index,
 	) -> Result<Option<Transaction>>;
 
+	/// Returns the ethereum transactions ready in the transaction pool.
+	#[rpc(name = "eth_pendingTransactions")]
+	fn pending_transactions(&self) -> Result<Vec<Transaction>>;
+
 	/// Returns transaction receipt by transaction hash.
 	#[rpc(name = "eth_getTransactionReceipt")]
 	fn transaction_receipt(&self, _: H256) -> Result<Option<Receipt>>;
@@ -0,0 +1,26 @@
+use ethereum_types::{H256, U256};
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_EvmDevApi::gen_server::EvmDevApi as EvmDevApiServer;
+
+/// Development chain rpc interface, only available when the node seals blocks manually.
+#[rpc(server)]
+pub trait EvmDevApi {
+	/// Seals a new block, returns its hash.
+	#[rpc(name = "evm_mine")]
+	fn mine(&self) -> BoxFuture<H256>;
+
+	/// Moves the timestamps of the next blocks forward by the given seconds,
+	/// returns the total shift in seconds.
+	#[rpc(name = "evm_increaseTime")]
+	fn increase_time(&self, _: u64) -> Result<u64>;
+
+	/// Snapshots the chain at the best block, returns the snapshot id.
+	#[rpc(name = "evm_snapshot")]
+	fn snapshot(&self) -> Result<U256>;
+
+	/// Reverts the chain to the given snapshot, the snapshot and the later ones are removed.
+	#[rpc(name = "evm_revert")]
+	fn revert(&self, _: U256) -> Result<bool>;
+}
@@ -2,10 +2,16 @@ pub mod types;
 
 mod eth;
 mod eth_pubsub;
+mod evm_dev;
 mod net;
+mod personal;
+mod txpool;
 mod web3;
 
-pub use eth::{EthApi, EthApiServer, EthFilterApi};
+pub use eth::{EthApi, EthApiServer, EthFilterApi, EthFilterApiServer};
 pub use eth_pubsub::{EthPubSubApi, EthPubSubApiServer};
+pub use evm_dev::{EvmDevApi, EvmDevApiServer};
 pub use net::{NetApi, NetApiServer};
+pub use personal::{PersonalApi, PersonalApiServer};
+pub use txpool::{TxPoolApi, TxPoolApiServer};
 pub use web3::{Web3Api, Web3ApiServer};
@@ -0,0 +1,37 @@
+use ethereum_types::{H160, H256};
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+use crate::types::{Bytes, TransactionRequest};
+
+pub use rpc_impl_PersonalApi::gen_server::PersonalApi as PersonalApiServer;
+
+/// Personal rpc interface.
+///
+/// Keys are protected by the node keystore, the passwords are accepted for compatibility and ignored.
+#[rpc(server)]
+pub trait PersonalApi {
+	/// Returns the accounts of the node signers.
+	#[rpc(name = "personal_listAccounts")]
+	fn list_accounts(&self) -> Result<Vec<H160>>;
+
+	/// Creates a new account in the keystore.
+	#[rpc(name = "personal_newAccount")]
+	fn new_account(&self, _: String) -> Result<H160>;
+
+	/// Imports an unencrypted secret key in the keystore.
+	#[rpc(name = "personal_importRawKey")]
+	fn import_raw_key(&self, _: Bytes, _: String) -> Result<H160>;
+
+	/// Signs `keccak256("\x19Ethereum Signed Message:\n" + len(data) + data)` with the given account.
+	#[rpc(name = "personal_sign")]
+	fn sign(&self, _: Bytes, _: H160, _: String) -> Result<Bytes>;
+
+	/// Returns the account that signed the data with `personal_sign`.
+	#[rpc(name = "personal_ecRecover")]
+	fn ec_recover(&self, _: Bytes, _: Bytes) -> Result<H160>;
+
+	/// Signs and sends the transaction with the account of `from`.
+	#[rpc(name = "personal_sendTransaction")]
+	fn send_transaction(&self, _: TransactionRequest, _: String) -> BoxFuture<H256>;
+}
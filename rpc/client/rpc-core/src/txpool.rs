@@ -0,0 +1,19 @@
+use ethereum_types::U256;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+use crate::types::{Transaction, TransactionMap, TxPoolResult};
+
+pub use rpc_impl_TxPoolApi::gen_server::TxPoolApi as TxPoolApiServer;
+
+/// TxPool rpc interface.
+#[rpc(server)]
+pub trait TxPoolApi {
+	/// Returns the ethereum transactions of the pool by sender and nonce.
+	#[rpc(name = "txpool_content")]
+	fn content(&self) -> Result<TxPoolResult<TransactionMap<Transaction>>>;
+
+	/// Returns the number of ethereum transactions of the pool.
+	#[rpc(name = "txpool_status")]
+	fn status(&self) -> Result<TxPoolResult<U256>>;
+}
@@ -10,6 +10,8 @@ mod receipt;
 mod sync;
 mod transaction;
 mod transaction_request;
+mod txpool;
+mod typed_data;
 mod work;
 mod internal_transaction;
 
@@ -30,5 +32,7 @@ pub use self::sync::{
 };
 pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
 pub use self::transaction_request::TransactionRequest;
+pub use self::txpool::{TransactionMap, TxPoolResult};
+pub use self::typed_data::{MemberType, TypedData};
 pub use self::work::Work;
 pub use self::internal_transaction::InternalTransaction;
@@ -2,7 +2,7 @@ use ethereum_types::H256;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde::de::Error;
 use serde_json::{Value, from_value};
-use crate::types::{RichHeader, Filter, Log};
+use crate::types::{RichHeader, Filter, Log, SyncInfo, Transaction};
 
 /// Subscription result.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -13,6 +13,8 @@ pub enum Result {
 	Log(Box<Log>),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Pending transaction
+	Transaction(Box<Transaction>),
 	/// SyncStatus
 	SyncState(PubSubSyncStatus)
 }
@@ -23,6 +25,9 @@ pub enum Result {
 pub struct PubSubSyncStatus {
 	/// is_major_syncing?
 	pub syncing: bool,
+	/// Progress of the sync, while syncing
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub status: Option<SyncInfo>,
 }
 
 impl Serialize for Result {
@@ -33,6 +38,7 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
+			Result::Transaction(ref transaction) => transaction.serialize(serializer),
 			Result::SyncState(ref sync) => sync.serialize(serializer),
 		}
 	}
@@ -60,6 +66,8 @@ pub enum Params {
 	None,
 	/// Log parameters.
 	Logs(Filter),
+	/// Full transaction objects of new pending transactions.
+	Bool(bool),
 }
 
 impl Default for Params {
@@ -77,6 +85,10 @@ impl<'a> Deserialize<'a> for Params {
 			return Ok(Params::None);
 		}
 
+		if let Value::Bool(full) = v {
+			return Ok(Params::Bool(full));
+		}
+
 		from_value(v.clone()).map(Params::Logs)
 			.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)))
 	}
@@ -37,5 +37,8 @@ pub struct Receipt {
 	// NOTE(niklasad1): Unknown after EIP98 rules, if it's missing then skip serializing it
 	#[serde(skip_serializing_if = "Option::is_none", rename = "status")]
 	pub status_code: Option<U64>,
+	/// Reason of a failed execution
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub revert_reason: Option<String>,
 	pub internal_transactions: Vec<InternalTransaction>,
 }
@@ -4,19 +4,15 @@ use ethereum_types::{U256, H512};
 use serde::{Serialize, Serializer};
 
 /// Sync info
-#[derive(Default, Debug, Serialize, PartialEq)]
+#[derive(Default, Debug, Clone, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct SyncInfo {
-	/// Starting block
+	/// Best block when the sync started
 	pub starting_block: U256,
 	/// Current block
 	pub current_block: U256,
-	/// Highest block seen so far
+	/// Highest block seen from the peers so far
 	pub highest_block: U256,
-	/// Warp sync snapshot chunks total.
-	pub warp_chunks_amount: Option<U256>,
-	/// Warp sync snpashot chunks processed.
-	pub warp_chunks_processed: Option<U256>,
 }
 
 /// Peers info
@@ -0,0 +1,15 @@
+use std::collections::HashMap;
+use ethereum_types::{H160, U256};
+use serde::Serialize;
+
+/// Transactions by sender and nonce.
+pub type TransactionMap<T> = HashMap<H160, HashMap<U256, T>>;
+
+/// Transactions of the pool.
+#[derive(Debug, Serialize)]
+pub struct TxPoolResult<T: Serialize> {
+	/// Transactions ready to be included
+	pub pending: T,
+	/// Transactions waiting for an earlier nonce
+	pub queued: T,
+}
@@ -0,0 +1,28 @@
+use std::collections::BTreeMap;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Member of an EIP-712 struct type.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+pub struct MemberType {
+	/// Name
+	pub name: String,
+	/// Solidity type
+	#[serde(rename = "type")]
+	pub type_: String,
+}
+
+/// EIP-712 typed structured data.
+#[derive(Debug, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+	/// Struct types by name, including `EIP712Domain`
+	pub types: BTreeMap<String, Vec<MemberType>>,
+	/// Type of the message
+	pub primary_type: String,
+	/// Domain separator values
+	pub domain: Value,
+	/// Message values
+	pub message: Value,
+}
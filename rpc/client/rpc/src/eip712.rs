@@ -0,0 +1,271 @@
+//! EIP-712 hashing of typed structured data.
+
+use std::collections::BTreeSet;
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::Error;
+use rustc_hex::FromHex;
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use fc_rpc_core::types::TypedData;
+use crate::internal_err;
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+	let mut res = [0u8; 32];
+	res.copy_from_slice(Keccak256::digest(data).as_slice());
+	res
+}
+
+/// Struct name of a type, without its array suffixes.
+fn base_type(type_: &str) -> &str {
+	type_.split('[').next().unwrap_or(type_)
+}
+
+fn dependencies(data: &TypedData, type_: &str, found: &mut BTreeSet<String>) {
+	let type_ = base_type(type_);
+	if found.contains(type_) {
+		return;
+	}
+	if let Some(members) = data.types.get(type_) {
+		found.insert(type_.to_string());
+		for member in members {
+			dependencies(data, &member.type_, found);
+		}
+	}
+}
+
+/// `Type(member type,member name...)` followed by the referenced struct types sorted by name.
+fn encode_type(data: &TypedData, type_: &str) -> Result<String, Error> {
+	let mut found = BTreeSet::new();
+	dependencies(data, type_, &mut found);
+	found.remove(type_);
+
+	let mut encoded = String::new();
+	for name in std::iter::once(type_.to_string()).chain(found.into_iter()) {
+		let members = data.types.get(&name)
+			.ok_or(internal_err(format!("unknown type {}", name)))?;
+		let members: Vec<String> = members.iter()
+			.map(|member| format!("{} {}", member.type_, member.name))
+			.collect();
+		encoded.push_str(&format!("{}({})", name, members.join(",")));
+	}
+	Ok(encoded)
+}
+
+fn parse_bytes(value: &Value) -> Result<Vec<u8>, Error> {
+	let hex = value.as_str().ok_or(internal_err("bytes value must be a hex string"))?;
+	hex.trim_start_matches("0x").from_hex()
+		.map_err(|_| internal_err(format!("invalid hex value {}", hex)))
+}
+
+fn parse_uint(value: &Value) -> Result<U256, Error> {
+	let invalid = || internal_err(format!("invalid integer value {}", value));
+	match value {
+		Value::Number(number) => number.as_u64().map(U256::from).ok_or_else(invalid),
+		Value::String(string) if string.starts_with("0x") =>
+			U256::from_str_radix(&string[2..], 16).map_err(|_| invalid()),
+		Value::String(string) => U256::from_dec_str(string).map_err(|_| invalid()),
+		_ => Err(invalid()),
+	}
+}
+
+/// Two's complement encoding of signed integers.
+fn parse_int(value: &Value) -> Result<U256, Error> {
+	let negative = match value {
+		Value::Number(number) => number.as_i64().map(|number| number < 0).unwrap_or(false),
+		Value::String(string) => string.starts_with('-'),
+		_ => false,
+	};
+	if !negative {
+		return parse_uint(value);
+	}
+	let abs = match value {
+		Value::Number(number) => Value::Number(
+			number.as_i64().map(|number| (number as i128).abs() as u64).unwrap_or_default().into()
+		),
+		Value::String(string) => Value::String(string[1..].to_string()),
+		_ => value.clone(),
+	};
+	Ok((!parse_uint(&abs)?).overflowing_add(U256::one()).0)
+}
+
+fn encode_value(data: &TypedData, type_: &str, value: &Value) -> Result<[u8; 32], Error> {
+	if type_.ends_with(']') {
+		let item_type = &type_[..type_.rfind('[').unwrap_or(0)];
+		let items = value.as_array().ok_or(internal_err(format!("{} value must be an array", type_)))?;
+		let mut encoded = Vec::new();
+		for item in items {
+			encoded.extend_from_slice(&encode_value(data, item_type, item)?);
+		}
+		return Ok(keccak(&encoded));
+	}
+	if data.types.contains_key(type_) {
+		return hash_struct(data, type_, value).map(|hash| hash.0);
+	}
+
+	let mut res = [0u8; 32];
+	match type_ {
+		"string" => {
+			let string = value.as_str().ok_or(internal_err("string value must be a string"))?;
+			res = keccak(string.as_bytes());
+		},
+		"bytes" => res = keccak(&parse_bytes(value)?),
+		"bool" => res[31] = value.as_bool().ok_or(internal_err("bool value must be a bool"))? as u8,
+		"address" => {
+			let address = parse_bytes(value)?;
+			if address.len() != 20 {
+				return Err(internal_err(format!("invalid address {}", value)));
+			}
+			res[12..].copy_from_slice(&address);
+		},
+		_ if type_.starts_with("bytes") => {
+			let bytes = parse_bytes(value)?;
+			if bytes.len() > 32 {
+				return Err(internal_err(format!("invalid {} value {}", type_, value)));
+			}
+			res[..bytes.len()].copy_from_slice(&bytes);
+		},
+		_ if type_.starts_with("uint") => parse_uint(value)?.to_big_endian(&mut res),
+		_ if type_.starts_with("int") => parse_int(value)?.to_big_endian(&mut res),
+		_ => return Err(internal_err(format!("unknown type {}", type_))),
+	}
+	Ok(res)
+}
+
+/// `keccak256(typeHash ++ encodeData(value))`, missing members are encoded as zero.
+fn hash_struct(data: &TypedData, type_: &str, value: &Value) -> Result<H256, Error> {
+	let members = data.types.get(type_)
+		.ok_or(internal_err(format!("unknown type {}", type_)))?;
+	let mut encoded = keccak(encode_type(data, type_)?.as_bytes()).to_vec();
+	for member in members {
+		match value.get(&member.name) {
+			Some(member_value) if !member_value.is_null() =>
+				encoded.extend_from_slice(&encode_value(data, &member.type_, member_value)?),
+			_ => encoded.extend_from_slice(&[0u8; 32]),
+		}
+	}
+	Ok(H256::from(keccak(&encoded)))
+}
+
+/// The hash signed by `eth_signTypedData`:
+/// `keccak256("\x19\x01" ++ hashStruct(domain) ++ hashStruct(message))`.
+pub fn typed_data_hash(data: &TypedData) -> Result<H256, Error> {
+	let mut encoded = vec![0x19, 0x01];
+	encoded.extend_from_slice(hash_struct(data, "EIP712Domain", &data.domain)?.as_bytes());
+	encoded.extend_from_slice(hash_struct(data, &data.primary_type, &data.message)?.as_bytes());
+	Ok(H256::from(keccak(&encoded)))
+}
+
+/// The hash signed by `eth_sign` and `personal_sign`.
+pub fn message_hash(message: &[u8]) -> H256 {
+	let mut encoded = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+	encoded.extend_from_slice(message);
+	H256::from(keccak(&encoded))
+}
+
+/// The account which signed the message with `personal_sign`, from the `r ++ s ++ v` signature.
+pub fn recover_signer(message: &[u8], signature: &[u8]) -> Result<H160, Error> {
+	if signature.len() != 65 {
+		return Err(internal_err("signature must be 65 bytes"));
+	}
+	let mut sig = [0u8; 65];
+	sig.copy_from_slice(signature);
+	let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, message_hash(message).as_fixed_bytes())
+		.map_err(|_| internal_err("invalid signature"))?;
+	Ok(H160::from(H256::from_slice(&keccak(&pubkey))))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{EthDevSigner, EthSigner};
+
+	/// The example of EIP-712.
+	fn mail() -> TypedData {
+		serde_json::from_value(serde_json::json!({
+			"types": {
+				"EIP712Domain": [
+					{ "name": "name", "type": "string" },
+					{ "name": "version", "type": "string" },
+					{ "name": "chainId", "type": "uint256" },
+					{ "name": "verifyingContract", "type": "address" }
+				],
+				"Person": [
+					{ "name": "name", "type": "string" },
+					{ "name": "wallet", "type": "address" }
+				],
+				"Mail": [
+					{ "name": "from", "type": "Person" },
+					{ "name": "to", "type": "Person" },
+					{ "name": "contents", "type": "string" }
+				]
+			},
+			"primaryType": "Mail",
+			"domain": {
+				"name": "Ether Mail",
+				"version": "1",
+				"chainId": 1,
+				"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+			},
+			"message": {
+				"from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+				"to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+				"contents": "Hello, Bob!"
+			}
+		})).unwrap()
+	}
+
+	fn h256(hex: &str) -> H256 {
+		H256::from_slice(&hex.from_hex::<Vec<u8>>().unwrap())
+	}
+
+	#[test]
+	fn encode_type_appends_the_referenced_types() {
+		assert_eq!(
+			encode_type(&mail(), "Mail").unwrap(),
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)",
+		);
+	}
+
+	#[test]
+	fn typed_data_hash_works() {
+		let data = mail();
+		assert_eq!(
+			hash_struct(&data, "EIP712Domain", &data.domain).unwrap(),
+			h256("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"),
+		);
+		assert_eq!(
+			hash_struct(&data, "Mail", &data.message).unwrap(),
+			h256("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"),
+		);
+		assert_eq!(
+			typed_data_hash(&data).unwrap(),
+			h256("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"),
+		);
+	}
+
+	#[test]
+	fn unknown_types_are_rejected() {
+		let mut data = mail();
+		data.primary_type = "Letter".to_string();
+		assert!(typed_data_hash(&data).is_err());
+	}
+
+	#[test]
+	fn message_hash_works() {
+		assert_eq!(
+			message_hash(b"hello"),
+			h256("50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750"),
+		);
+	}
+
+	#[test]
+	fn recover_signer_returns_the_signing_account() {
+		let signer = EthDevSigner::new();
+		let address = signer.accounts()[0];
+		let signature = signer.sign_hash(message_hash(b"hello"), &address).unwrap();
+		assert_eq!(recover_signer(b"hello", &signature).unwrap(), address);
+		// a different message recovers a different account
+		assert_ne!(recover_signer(b"hello!", &signature).unwrap(), address);
+		assert!(recover_signer(b"hello", &signature[..64]).is_err());
+	}
+}
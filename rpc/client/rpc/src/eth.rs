@@ -1,33 +1,42 @@
-use std::{marker::PhantomData, sync::Arc};
-use std::collections::BTreeMap;
+use std::{marker::PhantomData, sync::{Arc, Mutex, MutexGuard}};
+use std::collections::{BTreeMap, HashSet};
 use ethereum::{
 	Block as EthereumBlock, Transaction as EthereumTransaction,
 	TransactionMessage as EthereumTransactionMessage,
 };
 use frame_support::debug;
+use log::warn;
 use ethereum_types::{H160, H256, H64, U256, U64, H512};
 use jsonrpc_core::{BoxFuture, Result, futures::future::{self, Future}};
-use futures::future::TryFutureExt;
+use futures::{future::TryFutureExt, StreamExt as _};
 use sp_runtime::{
-	traits::{Block as BlockT, UniqueSaturatedInto, Zero, One, Saturating, BlakeTwo256},
+	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto, Zero, One, Saturating, BlakeTwo256},
 	transaction_validity::TransactionSource
 };
-use sp_api::{ProvideRuntimeApi, BlockId, Core};
-use sp_transaction_pool::{TransactionPool, InPoolTransaction};
-use sc_client_api::backend::{StorageProvider, Backend, StateBackend, AuxStore};
+use sp_api::{ProvideRuntimeApi, BlockId, Core, ApiExt};
+use sp_transaction_pool::{TransactionPool, InPoolTransaction, TxHash};
+use sc_client_api::{
+	backend::{StorageProvider, Backend, StateBackend, AuxStore},
+	client::BlockchainEvents,
+};
 use sha3::{Keccak256, Digest};
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
 use sc_network::{NetworkService, ExHashT};
-use fc_rpc_core::{EthApi as EthApiT, NetApi as NetApiT, Web3Api as Web3ApiT};
+use sc_rpc::DenyUnsafe;
+use fc_rpc_core::{
+	EthApi as EthApiT, EthFilterApi as EthFilterApiT, NetApi as NetApiT, PersonalApi as PersonalApiT,
+	Web3Api as Web3ApiT,
+};
 use fc_rpc_core::types::{
 	BlockNumber, Bytes, CallRequest, Filter, FilteredParams, Index, Log, Receipt, RichBlock,
-	SyncStatus, SyncInfo, Transaction, Work, Rich, Block, BlockTransactions, VariadicValue,
-	TransactionRequest, InternalTransaction
+	SyncStatus, Transaction, Work, Rich, Block, BlockTransactions, FilterChanges,
+	TransactionRequest, InternalTransaction, TypedData,
 };
 use fp_rpc::{EthereumRuntimeRPCApi, ConvertTransaction, TransactionStatus};
-use crate::{internal_err, error_on_execution_failure, EthSigner};
+use crate::{internal_err, error_on_execution_failure, exit_reason_message, EthSigner, SyncProgress};
+use crate::eip712::{message_hash, recover_signer, typed_data_hash};
 
-pub use fc_rpc_core::{EthApiServer, NetApiServer, Web3ApiServer};
+pub use fc_rpc_core::{EthApiServer, EthFilterApiServer, NetApiServer, PersonalApiServer, Web3ApiServer};
 use codec::{self, Encode};
 
 pub struct EthApi<B: BlockT, C, P, CT, BE, H: ExHashT> {
@@ -35,8 +44,11 @@ pub struct EthApi<B: BlockT, C, P, CT, BE, H: ExHashT> {
 	client: Arc<C>,
 	convert_transaction: CT,
 	network: Arc<NetworkService<B, H>>,
+	sync_progress: SyncProgress,
 	is_authority: bool,
 	signers: Vec<Box<dyn EthSigner>>,
+	/// whether the signing and keystore methods are denied
+	deny_unsafe: DenyUnsafe,
 	_marker: PhantomData<(B, BE)>,
 }
 
@@ -46,16 +58,20 @@ impl<B: BlockT, C, P, CT, BE, H: ExHashT> EthApi<B, C, P, CT, BE, H> {
 		pool: Arc<P>,
 		convert_transaction: CT,
 		network: Arc<NetworkService<B, H>>,
+		sync_progress: SyncProgress,
 		signers: Vec<Box<dyn EthSigner>>,
 		is_authority: bool,
+		deny_unsafe: DenyUnsafe,
 	) -> Self {
 		Self {
 			client,
 			pool,
 			convert_transaction,
 			network,
+			sync_progress,
 			is_authority,
 			signers,
+			deny_unsafe,
 			_marker: PhantomData,
 		}
 	}
@@ -121,11 +137,7 @@ fn rich_block_build(
 	}
 }
 
-fn transaction_build(
-	transaction: EthereumTransaction,
-	block: EthereumBlock,
-	status: TransactionStatus
-) -> Transaction {
+fn recover_public(transaction: &EthereumTransaction) -> Option<H512> {
 	let mut sig = [0u8; 65];
 	let mut msg = [0u8; 32];
 	sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
@@ -133,10 +145,15 @@ fn transaction_build(
 	sig[64] = transaction.signature.standard_v();
 	msg.copy_from_slice(&EthereumTransactionMessage::from(transaction.clone()).hash()[..]);
 
-	let pubkey = match sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg) {
-		Ok(p) => Some(H512::from(p)),
-		Err(_e) => None,
-	};
+	sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg).ok().map(H512::from)
+}
+
+fn transaction_build(
+	transaction: EthereumTransaction,
+	block: EthereumBlock,
+	status: TransactionStatus
+) -> Transaction {
+	let pubkey = recover_public(&transaction);
 
 	Transaction {
 		hash: H256::from_slice(
@@ -169,6 +186,143 @@ fn transaction_build(
 	}
 }
 
+/// Builds the rpc transaction of a transaction not yet included in a block,
+/// the sender is recovered from the signature.
+pub fn pending_transaction_build(transaction: EthereumTransaction) -> Transaction {
+	let pubkey = recover_public(&transaction);
+	let from = pubkey.map(|pubkey| H160::from(
+		H256::from_slice(Keccak256::digest(pubkey.as_bytes()).as_slice())
+	)).unwrap_or_default();
+	let to = match transaction.action {
+		ethereum::TransactionAction::Call(to) => Some(to),
+		ethereum::TransactionAction::Create => None,
+	};
+
+	Transaction {
+		hash: H256::from_slice(
+			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
+		),
+		nonce: transaction.nonce,
+		block_hash: None,
+		block_number: None,
+		transaction_index: None,
+		from,
+		to,
+		value: transaction.value,
+		gas_price: transaction.gas_price,
+		gas: transaction.gas_limit,
+		input: Bytes(transaction.clone().input),
+		creates: None,
+		raw: Bytes(rlp::encode(&transaction)),
+		public_key: pubkey,
+		chain_id: transaction.signature.chain_id().map(U64::from),
+		standard_v: U256::from(transaction.signature.standard_v()),
+		v: U256::from(transaction.signature.v()),
+		r: U256::from(transaction.signature.r().as_bytes()),
+		s: U256::from(transaction.signature.s().as_bytes()),
+	}
+}
+
+/// Ethereum transactions of the given extrinsics, decoded by the runtime at the best block.
+/// Runtimes older than version 2 of the runtime api can't decode them, no transactions are returned.
+pub fn extrinsics_transactions<B, C>(
+	client: &C,
+	extrinsics: Vec<<B as BlockT>::Extrinsic>,
+) -> Result<Vec<EthereumTransaction>> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+{
+	if extrinsics.is_empty() {
+		return Ok(Vec::new());
+	}
+	let best = BlockId::Hash(client.info().best_hash);
+	let api = client.runtime_api();
+	let has_extrinsic_filter = api.has_api_with::<dyn EthereumRuntimeRPCApi<B>, _>(&best, |version| version >= 2)
+		.map_err(|err| internal_err(format!("fetch runtime api version failed: {:?}", err)))?;
+	if !has_extrinsic_filter {
+		return Ok(Vec::new());
+	}
+	api.extrinsic_filter(&best, extrinsics)
+		.map_err(|err| internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err)))
+}
+
+/// Ethereum transactions ready in the transaction pool.
+pub fn pool_transactions<B, C, P>(client: &C, pool: &P) -> Result<Vec<EthereumTransaction>> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block=B>,
+{
+	let extrinsics = pool.ready()
+		.map(|in_pool_tx| in_pool_tx.data().clone())
+		.collect::<Vec<<B as BlockT>::Extrinsic>>();
+	extrinsics_transactions(client, extrinsics)
+}
+
+/// Ethereum transactions entering the ready queue of the transaction pool, the future transactions
+/// once they become ready. The transactions already ready when called aren't returned.
+pub fn ready_transactions_stream<B, C, P>(
+	client: Arc<C>,
+	pool: Arc<P>,
+) -> impl futures::Stream<Item=EthereumTransaction> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockchainEvents<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block=B>,
+{
+	// future transactions become ready when the transactions they depend on
+	// are imported into the pool or included in a block
+	let pool_imports = pool.import_notification_stream().map(|_| ());
+	let block_imports = client.import_notification_stream().map(|_| ());
+	let mut emitted: HashSet<TxHash<P>> = pool.ready().map(|in_pool_tx| in_pool_tx.hash().clone()).collect();
+	futures::stream::select(pool_imports, block_imports)
+		.flat_map(move |_| {
+			let mut ready = HashSet::new();
+			let mut extrinsics = Vec::new();
+			for in_pool_tx in pool.ready() {
+				if !emitted.contains(in_pool_tx.hash()) {
+					extrinsics.push(in_pool_tx.data().clone());
+				}
+				ready.insert(in_pool_tx.hash().clone());
+			}
+			emitted = ready;
+			let transactions = extrinsics_transactions(client.as_ref(), extrinsics)
+				.unwrap_or_else(|err| {
+					warn!("Failed to decode the pool transactions: {:?}", err);
+					Vec::new()
+				});
+			futures::stream::iter(transactions)
+		})
+}
+
+/// Records the ethereum transactions becoming ready in the transaction pool
+/// in the pending transaction filters, until they are polled.
+pub async fn pending_transaction_filters_task<B, C, P>(
+	client: Arc<C>,
+	pool: Arc<P>,
+	filter_pool: FilterPool,
+) where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockchainEvents<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block=B> + Send + Sync + 'static,
+{
+	ready_transactions_stream(client, pool)
+		.for_each(|transaction| {
+			let hash = H256::from_slice(Keccak256::digest(&rlp::encode(&transaction)).as_slice());
+			if let Ok(mut filters) = filter_pool.lock() {
+				for item in filters.items.values_mut() {
+					if let FilterType::PendingTransaction(ref mut hashes) = item.filter_type {
+						hashes.push(hash);
+					}
+				}
+			}
+			futures::future::ready(())
+		})
+		.await
+}
+
 impl<B, C, P, CT, BE, H: ExHashT> EthApi<B, C, P, CT, BE, H> where
 	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + AuxStore,
 	C: HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError> + 'static,
@@ -180,6 +334,15 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApi<B, C, P, CT, BE, H> where
 	P: TransactionPool<Block=B> + Send + Sync + 'static,
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
 {
+	fn sign_hash(&self, hash: H256, address: H160) -> Result<Bytes> {
+		for signer in &self.signers {
+			if signer.accounts().contains(&address) {
+				return signer.sign_hash(hash, &address).map(|signature| Bytes(signature.to_vec()));
+			}
+		}
+		Err(internal_err("no signer available"))
+	}
+
 	fn native_block_id(&self, number: Option<BlockNumber>) -> Result<Option<BlockId<B>>> {
 		Ok(match number.unwrap_or(BlockNumber::Latest) {
 			BlockNumber::Hash { hash, .. } => {
@@ -202,29 +365,77 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApi<B, C, P, CT, BE, H> where
 		})
 	}
 
-	// Asumes there is only one mapped canonical block in the AuxStore, otherwise something is wrong
 	fn load_hash(&self, hash: H256) -> Result<Option<BlockId<B>>> {
-		let hashes = match fc_consensus::load_block_hash::<B, _>(self.client.as_ref(), hash)
-			.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?
-		{
-			Some(hashes) => hashes,
-			None => return Ok(None),
-		};
-		let out: Vec<H256> = hashes.into_iter()
-			.filter_map(|h| {
-				if let Ok(Some(_)) = self.client.header(BlockId::Hash(h)) {
-					Some(h)
-				} else {
-					None
-				}
-			}).collect();
+		load_hash::<B, C>(self.client.as_ref(), hash)
+	}
+}
+
+// Asumes there is only one mapped canonical block in the AuxStore, otherwise something is wrong
+fn load_hash<B, C>(client: &C, hash: H256) -> Result<Option<BlockId<B>>> where
+	B: BlockT<Hash=H256>,
+	C: HeaderBackend<B> + AuxStore,
+{
+	let hashes = match fc_consensus::load_block_hash::<B, _>(client, hash)
+		.map_err(|err| internal_err(format!("fetch aux store failed: {:?}", err)))?
+	{
+		Some(hashes) => hashes,
+		None => return Ok(None),
+	};
+	let out: Vec<H256> = hashes.into_iter()
+		.filter_map(|h| {
+			if let Ok(Some(_)) = client.header(BlockId::Hash(h)) {
+				Some(h)
+			} else {
+				None
+			}
+		}).collect();
+
+	if out.len() == 1 {
+		return Ok(Some(
+			BlockId::Hash(out[0])
+		));
+	}
+	Ok(None)
+}
 
-		if out.len() == 1 {
-			return Ok(Some(
-				BlockId::Hash(out[0])
-			));
+/// Append the logs of the block matching the filter params to `ret`.
+fn filter_block_logs(
+	ret: &mut Vec<Log>,
+	params: &FilteredParams,
+	block: &EthereumBlock,
+	statuses: &[TransactionStatus],
+) {
+	let mut block_log_index: u32 = 0;
+	let block_hash = H256::from_slice(
+		Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+	);
+	for status in statuses.iter() {
+		let mut transaction_log_index: u32 = 0;
+		for ethereum_log in status.logs.iter() {
+			let mut log = Log {
+				address: ethereum_log.address.clone(),
+				topics: ethereum_log.topics.clone(),
+				data: Bytes(ethereum_log.data.clone()),
+				block_hash: None,
+				block_number: None,
+				transaction_hash: None,
+				transaction_index: None,
+				log_index: None,
+				transaction_log_index: None,
+				removed: false,
+			};
+			if params.filter_address(&log) && params.filter_topics(&log) {
+				log.block_hash = Some(block_hash);
+				log.block_number = Some(block.header.number.clone());
+				log.transaction_hash = Some(status.transaction_hash);
+				log.transaction_index = Some(U256::from(status.transaction_index));
+				log.log_index = Some(U256::from(block_log_index));
+				log.transaction_log_index = Some(U256::from(transaction_log_index));
+				ret.push(log);
+			}
+			transaction_log_index += 1;
+			block_log_index += 1;
 		}
-		Ok(None)
 	}
 }
 
@@ -245,19 +456,8 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApiT for EthApi<B, C, P, CT, BE, H> where
 
 	fn syncing(&self) -> Result<SyncStatus> {
 		if self.network.is_major_syncing() {
-			let block_number = U256::from(
-				self.client.info().best_number.clone().unique_saturated_into()
-			);
-			Ok(SyncStatus::Info(SyncInfo {
-				starting_block: U256::zero(),
-				current_block: block_number,
-				// TODO `highest_block` is not correct, should load `best_seen_block` from NetworkWorker,
-				// but afaik that is not currently possible in Substrate:
-				// https://github.com/paritytech/substrate/issues/7311
-				highest_block: block_number,
-				warp_chunks_amount: None,
-				warp_chunks_processed: None,
-			}))
+			let block_number = self.client.info().best_number.clone().unique_saturated_into();
+			Ok(SyncStatus::Info(self.sync_progress.sync_info(block_number)))
 		} else {
 			Ok(SyncStatus::None)
 		}
@@ -486,6 +686,16 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApiT for EthApi<B, C, P, CT, BE, H> where
 		Ok(Bytes(vec![]))
 	}
 
+	fn sign(&self, address: H160, message: Bytes) -> Result<Bytes> {
+		self.deny_unsafe.check_if_safe()?;
+		self.sign_hash(message_hash(&message.0), address)
+	}
+
+	fn sign_typed_data(&self, address: H160, data: TypedData) -> Result<Bytes> {
+		self.deny_unsafe.check_if_safe()?;
+		self.sign_hash(typed_data_hash(&data)?, address)
+	}
+
 	fn send_transaction(&self, request: TransactionRequest) -> BoxFuture<H256> {
 		let from = match request.from {
 			Some(from) => from,
@@ -833,6 +1043,13 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApiT for EthApi<B, C, P, CT, BE, H> where
 		}
 	}
 
+	fn pending_transactions(&self) -> Result<Vec<Transaction>> {
+		Ok(pool_transactions(self.client.as_ref(), self.pool.as_ref())?
+			.into_iter()
+			.map(pending_transaction_build)
+			.collect())
+	}
+
 	fn transaction_receipt(&self, hash: H256) -> Result<Option<Receipt>> {
 		let (hash, index) = match fc_consensus::load_transaction_metadata(
 			self.client.as_ref(),
@@ -855,6 +1072,16 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApiT for EthApi<B, C, P, CT, BE, H> where
 			.map_err(|err| internal_err(format!("call runtime failed: {:?}", err)))?;
 		let statuses = self.client.runtime_api().current_transaction_statuses(&id)
 			.map_err(|err| internal_err(format!("call runtime failed: {:?}", err)))?;
+		let has_exit_reasons = self.client.runtime_api()
+			.has_api_with::<dyn EthereumRuntimeRPCApi<B>, _>(&id, |version| version >= 3)
+			.map_err(|err| internal_err(format!("fetch runtime api version failed: {:?}", err)))?;
+		let exit_reason = if has_exit_reasons {
+			self.client.runtime_api().current_exit_reasons(&id)
+				.map_err(|err| internal_err(format!("call runtime failed: {:?}", err)))?
+				.and_then(|reasons| reasons.get(index).cloned())
+		} else {
+			None
+		};
 
 		match (block, statuses, receipts) {
 			(Some(block), Some(statuses), Some(receipts)) => {
@@ -908,6 +1135,7 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApiT for EthApi<B, C, P, CT, BE, H> where
 						}).collect()
 					},
 					status_code: Some(U64::from(receipt.state_root.to_low_u64_be())),
+					revert_reason: exit_reason.and_then(|(reason, data)| exit_reason_message(&reason, &data)),
 					logs_bloom: receipt.logs_bloom,
 					state_root: None,
 					internal_transactions: status.internal_transactions.iter().enumerate().map(|(_i, x)| {
@@ -991,62 +1219,7 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApiT for EthApi<B, C, P, CT, BE, H> where
 		}
 
 		for (block, statuses) in blocks_and_statuses {
-			let mut block_log_index: u32 = 0;
-			let block_hash = H256::from_slice(
-				Keccak256::digest(&rlp::encode(&block.header)).as_slice()
-			);
-			for status in statuses.iter() {
-				let logs = status.logs.clone();
-				let mut transaction_log_index: u32 = 0;
-				let transaction_hash = status.transaction_hash;
-				for ethereum_log in logs {
-					let mut log = Log {
-						address: ethereum_log.address.clone(),
-						topics: ethereum_log.topics.clone(),
-						data: Bytes(ethereum_log.data.clone()),
-						block_hash: None,
-						block_number: None,
-						transaction_hash: None,
-						transaction_index: None,
-						log_index: None,
-						transaction_log_index: None,
-						removed: false,
-					};
-					let mut add: bool = false;
-					if let (
-						Some(VariadicValue::Single(_)),
-						Some(VariadicValue::Multiple(_))
-					) = (
-						filter.address.clone(),
-						filter.topics.clone(),
-					) {
-						if !params.filter_address(&log) && params.filter_topics(&log) {
-							add = true;
-						}
-					} else if let Some(VariadicValue::Single(_)) = filter.address {
-						if !params.filter_address(&log) {
-							add = true;
-						}
-					} else if let Some(VariadicValue::Multiple(_)) = &filter.topics {
-						if params.filter_topics(&log) {
-							add = true;
-						}
-					} else {
-						add = true;
-					}
-					if add {
-						log.block_hash = Some(block_hash);
-						log.block_number = Some(block.header.number.clone());
-						log.transaction_hash = Some(transaction_hash);
-						log.transaction_index = Some(U256::from(status.transaction_index));
-						log.log_index = Some(U256::from(block_log_index));
-						log.transaction_log_index = Some(U256::from(transaction_log_index));
-						ret.push(log);
-					}
-					transaction_log_index += 1;
-					block_log_index += 1;
-				}
-			}
+			filter_block_logs(&mut ret, &params, &block, &statuses);
 		}
 
 		Ok(ret)
@@ -1070,6 +1243,284 @@ impl<B, C, P, CT, BE, H: ExHashT> EthApiT for EthApi<B, C, P, CT, BE, H> where
 	}
 }
 
+impl<B, C, P, CT, BE, H: ExHashT> PersonalApiT for EthApi<B, C, P, CT, BE, H> where
+	C: ProvideRuntimeApi<B> + StorageProvider<B, BE> + AuxStore,
+	C: HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError> + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+	P: TransactionPool<Block=B> + Send + Sync + 'static,
+	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + Send + Sync + 'static,
+{
+	fn list_accounts(&self) -> Result<Vec<H160>> {
+		EthApiT::accounts(self)
+	}
+
+	fn new_account(&self, _password: String) -> Result<H160> {
+		self.deny_unsafe.check_if_safe()?;
+		self.signers.iter()
+			.find_map(|signer| signer.new_account())
+			.unwrap_or(Err(internal_err("no signer can store keys")))
+	}
+
+	fn import_raw_key(&self, secret: Bytes, _password: String) -> Result<H160> {
+		self.deny_unsafe.check_if_safe()?;
+		self.signers.iter()
+			.find_map(|signer| signer.import_raw_key(&secret.0))
+			.unwrap_or(Err(internal_err("no signer can store keys")))
+	}
+
+	fn sign(&self, data: Bytes, address: H160, _password: String) -> Result<Bytes> {
+		self.deny_unsafe.check_if_safe()?;
+		self.sign_hash(message_hash(&data.0), address)
+	}
+
+	fn ec_recover(&self, data: Bytes, signature: Bytes) -> Result<H160> {
+		recover_signer(&data.0, &signature.0)
+	}
+
+	fn send_transaction(&self, request: TransactionRequest, _password: String) -> BoxFuture<H256> {
+		EthApiT::send_transaction(self, request)
+	}
+}
+
+/// Number of blocks a filter is kept without being polled.
+pub const FILTER_RETAIN_THRESHOLD: u64 = 100;
+/// Max number of blocks searched for the logs of a filter at once.
+pub const MAX_FILTER_BLOCK_RANGE: u64 = 1024;
+
+/// Kind of an installed filter.
+#[derive(Clone, Debug)]
+pub enum FilterType {
+	Block,
+	/// hashes of the transactions becoming ready since the last poll
+	PendingTransaction(Vec<H256>),
+	Log(Filter),
+}
+
+/// An installed filter.
+#[derive(Clone, Debug)]
+pub struct FilterPoolItem {
+	/// best block at the last poll, the next poll returns the changes after it
+	pub last_poll: u64,
+	pub filter_type: FilterType,
+}
+
+/// Installed filters and the id of the next filter.
+#[derive(Debug, Default)]
+pub struct Filters {
+	/// ids are never reused, so a removed filter can't be polled by mistake
+	next_id: U256,
+	items: BTreeMap<U256, FilterPoolItem>,
+}
+
+/// Filters installed on the node, shared by all the rpc servers.
+pub type FilterPool = Arc<Mutex<Filters>>;
+
+pub struct EthFilterApi<B, C, P> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	filter_pool: FilterPool,
+	max_stored_filters: usize,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C, P> EthFilterApi<B, C, P> {
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		filter_pool: FilterPool,
+		max_stored_filters: usize,
+	) -> Self {
+		Self {
+			client,
+			pool,
+			filter_pool,
+			max_stored_filters,
+			_marker: PhantomData,
+		}
+	}
+}
+
+impl<B, C, P> EthFilterApi<B, C, P> where
+	C: ProvideRuntimeApi<B> + AuxStore,
+	C: HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError> + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+	P: TransactionPool<Block=B> + Send + Sync + 'static,
+{
+	fn best_number(&self) -> u64 {
+		self.client.info().best_number.unique_saturated_into()
+	}
+
+	fn filters(&self) -> Result<MutexGuard<Filters>> {
+		self.filter_pool.lock().map_err(|_| internal_err("filter pool is poisoned"))
+	}
+
+	fn create_filter(&self, filter_type: FilterType) -> Result<U256> {
+		let best_number = self.best_number();
+		let mut filters = self.filters()?;
+		// filters not polled for a while are dropped
+		filters.items.retain(|_, item| item.last_poll.saturating_add(FILTER_RETAIN_THRESHOLD) >= best_number);
+		if filters.items.len() >= self.max_stored_filters {
+			return Err(internal_err(format!("Filter pool is full (limit {}).", self.max_stored_filters)));
+		}
+
+		filters.next_id = filters.next_id.saturating_add(U256::one());
+		let id = filters.next_id;
+		filters.items.insert(id, FilterPoolItem {
+			last_poll: best_number,
+			filter_type,
+		});
+		Ok(id)
+	}
+
+	fn block_and_statuses(&self, number: u64) -> Result<Option<(EthereumBlock, Vec<TransactionStatus>)>> {
+		let id = BlockId::Number(number.unique_saturated_into());
+		let (block, _, statuses) = self.client.runtime_api()
+			.current_all(&id)
+			.map_err(|err| internal_err(format!("fetch runtime block failed: {:?}", err)))?;
+		Ok(match (block, statuses) {
+			(Some(block), Some(statuses)) => Some((block, statuses)),
+			_ => None,
+		})
+	}
+
+	/// Logs matching the filter in the blocks from `from` to `to`, within the block range of the filter.
+	/// At most `MAX_FILTER_BLOCK_RANGE` blocks are searched.
+	fn filter_range_logs(&self, filter: &Filter, from: u64, to: u64) -> Result<Vec<Log>> {
+		let mut ret = Vec::new();
+		let params = FilteredParams::new(Some(filter.clone()));
+
+		if let Some(hash) = filter.block_hash {
+			let id = match load_hash::<B, C>(self.client.as_ref(), hash)? {
+				Some(id) => id,
+				None => return Ok(ret),
+			};
+			let number: u64 = match self.client.header(id)
+				.map_err(|err| internal_err(format!("{:?}", err)))?
+			{
+				Some(header) => (*header.number()).unique_saturated_into(),
+				None => return Ok(ret),
+			};
+			if number >= from && number <= to {
+				if let Some((block, statuses)) = self.block_and_statuses(number)? {
+					filter_block_logs(&mut ret, &params, &block, &statuses);
+				}
+			}
+			return Ok(ret);
+		}
+
+		let from = filter.from_block.and_then(|v| v.to_min_block_num()).map_or(from, |n| n.max(from));
+		let to = filter.to_block.and_then(|v| v.to_min_block_num()).map_or(to, |n| n.min(to));
+		if to >= from && to - from >= MAX_FILTER_BLOCK_RANGE {
+			return Err(internal_err(format!(
+				"Query exceeds the max block range of {} blocks.", MAX_FILTER_BLOCK_RANGE
+			)));
+		}
+		for number in from..=to {
+			if let Some((block, statuses)) = self.block_and_statuses(number)? {
+				filter_block_logs(&mut ret, &params, &block, &statuses);
+			}
+		}
+		Ok(ret)
+	}
+
+	fn filter_changes_of(&self, index: Index) -> Result<FilterChanges> {
+		let key = U256::from(index.value());
+		let best_number = self.best_number();
+		let item = {
+			let mut filters = self.filters()?;
+			let item = filters.items.get_mut(&key)
+				.ok_or_else(|| internal_err(format!("Filter id {:?} does not exist.", key)))?;
+			let polled = item.clone();
+			item.last_poll = best_number;
+			polled
+		};
+
+		let from = item.last_poll.saturating_add(1);
+		match item.filter_type {
+			FilterType::Block => {
+				let mut hashes = Vec::new();
+				for number in from..=best_number {
+					if let Some((block, _)) = self.block_and_statuses(number)? {
+						hashes.push(H256::from_slice(
+							Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+						));
+					}
+				}
+				Ok(FilterChanges::Hashes(hashes))
+			},
+			// recorded by `pending_transaction_filters_task`
+			FilterType::PendingTransaction(_) => {
+				let hashes = match self.filters()?.items.get_mut(&key) {
+					Some(FilterPoolItem { filter_type: FilterType::PendingTransaction(ref mut hashes), .. }) =>
+						std::mem::take(hashes),
+					_ => Vec::new(),
+				};
+				Ok(FilterChanges::Hashes(hashes))
+			},
+			FilterType::Log(ref filter) => {
+				Ok(FilterChanges::Logs(self.filter_range_logs(filter, from, best_number)?))
+			},
+		}
+	}
+
+	fn filter_logs_of(&self, index: Index) -> Result<Vec<Log>> {
+		let key = U256::from(index.value());
+		let item = self.filters()?
+			.items
+			.get(&key)
+			.cloned()
+			.ok_or_else(|| internal_err(format!("Filter id {:?} does not exist.", key)))?;
+		match item.filter_type {
+			FilterType::Log(ref filter) => {
+				let best_number = self.best_number();
+				let from = filter.from_block.and_then(|v| v.to_min_block_num()).unwrap_or(best_number);
+				self.filter_range_logs(filter, from, best_number)
+			},
+			_ => Err(internal_err(format!("Filter id {:?} is not a log filter.", key))),
+		}
+	}
+}
+
+impl<B, C, P> EthFilterApiT for EthFilterApi<B, C, P> where
+	C: ProvideRuntimeApi<B> + AuxStore,
+	C: HeaderBackend<B> + HeaderMetadata<B, Error=BlockChainError> + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: Send + Sync + 'static,
+	P: TransactionPool<Block=B> + Send + Sync + 'static,
+{
+	fn new_filter(&self, filter: Filter) -> Result<U256> {
+		self.create_filter(FilterType::Log(filter))
+	}
+
+	fn new_block_filter(&self) -> Result<U256> {
+		self.create_filter(FilterType::Block)
+	}
+
+	fn new_pending_transaction_filter(&self) -> Result<U256> {
+		self.create_filter(FilterType::PendingTransaction(Vec::new()))
+	}
+
+	fn filter_changes(&self, index: Index) -> BoxFuture<FilterChanges> {
+		Box::new(future::result(self.filter_changes_of(index)))
+	}
+
+	fn filter_logs(&self, index: Index) -> BoxFuture<Vec<Log>> {
+		Box::new(future::result(self.filter_logs_of(index)))
+	}
+
+	fn uninstall_filter(&self, index: Index) -> Result<bool> {
+		let key = U256::from(index.value());
+		Ok(self.filters()?.items.remove(&key).is_some())
+	}
+}
+
 pub struct NetApi<B: BlockT, BE, C, H: ExHashT> {
 	client: Arc<C>,
 	network: Arc<NetworkService<B, H>>,
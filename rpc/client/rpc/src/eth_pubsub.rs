@@ -10,7 +10,7 @@ use sp_runtime::traits::{
 use sp_transaction_pool::TransactionPool;
 use sp_api::{ProvideRuntimeApi, BlockId};
 use sp_blockchain::{Error as BlockChainError, HeaderMetadata, HeaderBackend};
-use sp_storage::{StorageKey, StorageData};
+use sp_storage::StorageKey;
 use sp_io::hashing::twox_128;
 use sc_client_api::{
 	backend::{StorageProvider, Backend, StateBackend, AuxStore},
@@ -36,10 +36,12 @@ pub use fc_rpc_core::EthPubSubApiServer;
 use futures::{StreamExt as _, TryStreamExt as _};
 
 use jsonrpc_core::{Result as JsonRpcResult, futures::{Future, Sink}};
-use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
+use fp_rpc::EthereumRuntimeRPCApi;
 
 use sc_network::{NetworkService, ExHashT};
 
+use crate::{pending_transaction_build, ready_transactions_stream, SyncProgress};
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct HexEncodedIdProvider {
 	len: usize,
@@ -65,21 +67,23 @@ impl IdProvider for HexEncodedIdProvider {
 }
 
 pub struct EthPubSubApi<B: BlockT, P, C, BE, H: ExHashT> {
-	_pool: Arc<P>,
+	pool: Arc<P>,
 	client: Arc<C>,
 	network: Arc<NetworkService<B, H>>,
+	sync_progress: SyncProgress,
 	subscriptions: SubscriptionManager<HexEncodedIdProvider>,
 	_marker: PhantomData<(B, BE)>,
 }
 
 impl<B: BlockT, P, C, BE, H: ExHashT> EthPubSubApi<B, P, C, BE, H> {
 	pub fn new(
-		_pool: Arc<P>,
+		pool: Arc<P>,
 		client: Arc<C>,
 		network: Arc<NetworkService<B, H>>,
+		sync_progress: SyncProgress,
 		subscriptions: SubscriptionManager<HexEncodedIdProvider>,
 	) -> Self {
-		Self { _pool, client, network, subscriptions, _marker: PhantomData }
+		Self { pool, client, network, sync_progress, subscriptions, _marker: PhantomData }
 	}
 }
 
@@ -245,7 +249,7 @@ impl<B: BlockT, P, C, BE, H: ExHashT> EthPubSubApiT for EthPubSubApi<B, P, C, BE
 		kind: Kind,
 		params: Option<Params>,
 	) {
-		let filtered_params = match params {
+		let filtered_params = match params.clone() {
 			Some(Params::Logs(filter)) => FilteredParams::new(Some(filter)),
 			_ => FilteredParams::default()
 		};
@@ -317,55 +321,36 @@ impl<B: BlockT, P, C, BE, H: ExHashT> EthPubSubApiT for EthPubSubApi<B, P, C, BE
 				}
 			},
 			Kind::NewPendingTransactions => {
-				if let Some(stream) = stream_build!(
-					self => b"Ethereum", b"Pending"
-				) {
-					self.subscriptions.add(subscriber, |sink| {
-						let stream = stream
-						.flat_map(|(_block, changes)| {
-							let mut transactions: Vec<ethereum::Transaction> = vec![];
-							let storage: Vec<Option<StorageData>> = changes.iter()
-								.filter_map(|(o_sk, _k, v)| {
-									if o_sk.is_none() {
-										Some(v.cloned())
-									} else { None }
-								}).collect();
-							for change in storage {
-								if let Some(data) = change {
-									let storage: Vec<(
-										ethereum::Transaction,
-										TransactionStatus,
-										ethereum::Receipt
-									)> = Decode::decode(&mut &data.0[..]).unwrap();
-									let tmp: Vec<ethereum::Transaction> =
-										storage.iter().map(|x| x.0.clone()).collect();
-									transactions.extend(tmp);
-								}
-							}
-							futures::stream::iter(transactions)
-						})
-						.map(|transaction| {
-							return Ok::<Result<
-								PubSubResult,
-								jsonrpc_core::types::error::Error
-							>, ()>(Ok(
-								PubSubResult::TransactionHash(H256::from_slice(
-									Keccak256::digest(
-										&rlp::encode(&transaction)
-									).as_slice()
-								))
-							));
-						})
-						.compat();
-
-						sink
-							.sink_map_err(|e| warn!(
-								"Error sending notifications: {:?}", e
+				let full = params == Some(Params::Bool(true));
+				let stream = ready_transactions_stream(client, self.pool.clone());
+				self.subscriptions.add(subscriber, move |sink| {
+					let stream = stream
+					.map(move |transaction| {
+						let result = if full {
+							PubSubResult::Transaction(Box::new(
+								pending_transaction_build(transaction)
 							))
-							.send_all(stream)
-							.map(|_| ())
-					});
-				}
+						} else {
+							PubSubResult::TransactionHash(H256::from_slice(
+								Keccak256::digest(
+									&rlp::encode(&transaction)
+								).as_slice()
+							))
+						};
+						return Ok::<Result<
+							PubSubResult,
+							jsonrpc_core::types::error::Error
+						>, ()>(Ok(result));
+					})
+					.compat();
+
+					sink
+						.sink_map_err(|e| warn!(
+							"Error sending notifications: {:?}", e
+						))
+						.send_all(stream)
+						.map(|_| ())
+				});
 			},
 			Kind::Syncing => {
 				if let Some(stream) = stream_build!(
@@ -373,6 +358,7 @@ impl<B: BlockT, P, C, BE, H: ExHashT> EthPubSubApiT for EthPubSubApi<B, P, C, BE
 				) {
 					self.subscriptions.add(subscriber, |sink| {
 						let mut previous_syncing = network.is_major_syncing();
+						let sync_progress = self.sync_progress.clone();
 						let stream = stream
 						.filter_map(move |(_, _)| {
 							let syncing = network.is_major_syncing();
@@ -383,13 +369,20 @@ impl<B: BlockT, P, C, BE, H: ExHashT> EthPubSubApiT for EthPubSubApi<B, P, C, BE
 								futures::future::ready(None)
 							}
 						})
-						.map(|syncing| {
+						.map(move |syncing| {
+							let status = if syncing {
+								let block_number = client.info().best_number.unique_saturated_into();
+								Some(sync_progress.sync_info(block_number))
+							} else {
+								None
+							};
 							return Ok::<Result<
 								PubSubResult,
 								jsonrpc_core::types::error::Error
 							>, ()>(Ok(
 								PubSubResult::SyncState(PubSubSyncStatus {
-									syncing: syncing
+									syncing,
+									status,
 								})
 							));
 						})
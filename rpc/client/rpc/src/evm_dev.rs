@@ -0,0 +1,129 @@
+use std::{marker::PhantomData, sync::{Arc, Mutex, atomic::{AtomicU64, Ordering}}};
+use ethereum_types::{H256, U256};
+use futures::{channel::{mpsc, oneshot}, FutureExt as _, SinkExt as _, TryFutureExt as _};
+use jsonrpc_core::{BoxFuture, Result};
+use sha3::{Digest, Keccak256};
+use sp_api::{BlockId, ProvideRuntimeApi};
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+use sp_blockchain::HeaderBackend;
+use sp_transaction_pool::{InPoolTransaction, TransactionPool};
+use sc_client_api::backend::{AuxStore, Backend};
+use sc_consensus_manual_seal::EngineCommand;
+use fc_consensus::revert_block_mappings;
+use fc_rpc_core::EvmDevApi as EvmDevApiT;
+use fp_rpc::EthereumRuntimeRPCApi;
+use crate::internal_err;
+
+pub use fc_rpc_core::EvmDevApiServer;
+
+/// State of a manually sealed chain, shared by the rpc servers and the block authorship.
+#[derive(Clone, Default)]
+pub struct DevState {
+	/// Seconds added to the timestamps of the sealed blocks.
+	pub time_offset: Arc<AtomicU64>,
+	/// Best block numbers at the snapshots, the id of a snapshot is its index plus one.
+	/// Reverting to a snapshot also drops the pending transactions and the ethereum mappings of the reverted blocks.
+	snapshots: Arc<Mutex<Vec<u64>>>,
+}
+
+pub struct EvmDevApi<B: BlockT, C, BE, P> {
+	client: Arc<C>,
+	backend: Arc<BE>,
+	pool: Arc<P>,
+	command_sink: mpsc::Sender<EngineCommand<B::Hash>>,
+	state: DevState,
+	_marker: PhantomData<B>,
+}
+
+impl<B: BlockT, C, BE, P> EvmDevApi<B, C, BE, P> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		pool: Arc<P>,
+		command_sink: mpsc::Sender<EngineCommand<B::Hash>>,
+		state: DevState,
+	) -> Self {
+		Self { client, backend, pool, command_sink, state, _marker: PhantomData }
+	}
+}
+
+impl<B, C, BE, P> EvmDevApiT for EvmDevApi<B, C, BE, P> where
+	B: BlockT<Hash=H256> + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + AuxStore + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	BE: Backend<B> + 'static,
+	P: TransactionPool<Block=B> + Send + Sync + 'static,
+{
+	fn mine(&self) -> BoxFuture<H256> {
+		let mut sink = self.command_sink.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			sink.send(EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize: false,
+				parent_hash: None,
+				sender: Some(sender),
+			}).await.map_err(|err| internal_err(format!("send seal command failed: {:?}", err)))?;
+
+			receiver.await
+				.map_err(|err| internal_err(format!("seal block failed: {:?}", err)))?
+				.map(|block| block.hash)
+				.map_err(|err| internal_err(format!("seal block failed: {}", err)))
+		};
+		Box::new(future.boxed().compat())
+	}
+
+	fn increase_time(&self, seconds: u64) -> Result<u64> {
+		Ok(self.state.time_offset.fetch_add(seconds, Ordering::SeqCst).saturating_add(seconds))
+	}
+
+	fn snapshot(&self) -> Result<U256> {
+		let mut snapshots = self.state.snapshots.lock().map_err(|err| internal_err(format!("{:?}", err)))?;
+		snapshots.push(UniqueSaturatedInto::<u64>::unique_saturated_into(self.client.info().best_number));
+		Ok(U256::from(snapshots.len()))
+	}
+
+	fn revert(&self, id: U256) -> Result<bool> {
+		let mut snapshots = self.state.snapshots.lock().map_err(|err| internal_err(format!("{:?}", err)))?;
+		if id.is_zero() || id > U256::from(snapshots.len()) {
+			return Ok(false);
+		}
+		let index = id.as_usize() - 1;
+		let number = snapshots[index];
+		snapshots.truncate(index);
+
+		let best: u64 = self.client.info().best_number.unique_saturated_into();
+		if best > number {
+			// the ethereum blocks of the reverted blocks, their mappings are removed after reverting
+			let mut reverted = Vec::new();
+			for reverted_number in (number + 1)..=best {
+				let id = BlockId::Number(reverted_number.unique_saturated_into());
+				let hash = self.client.hash(reverted_number.unique_saturated_into())
+					.map_err(|err| internal_err(format!("fetch block hash failed: {:?}", err)))?;
+				let block = self.client.runtime_api().current_block(&id)
+					.map_err(|err| internal_err(format!("fetch runtime block failed: {:?}", err)))?;
+				if let (Some(hash), Some(block)) = (hash, block) {
+					reverted.push((hash, block));
+				}
+			}
+
+			self.backend.revert((best - number).unique_saturated_into(), false)
+				.map_err(|err| internal_err(format!("revert blocks failed: {:?}", err)))?;
+
+			for (hash, block) in reverted {
+				let ethereum_hash = H256::from_slice(Keccak256::digest(&rlp::encode(&block.header)).as_slice());
+				let transaction_hashes: Vec<H256> = block.transactions.iter()
+					.map(|transaction| H256::from_slice(Keccak256::digest(&rlp::encode(transaction)).as_slice()))
+					.collect();
+				revert_block_mappings(self.client.as_ref(), ethereum_hash, hash, &transaction_hashes)
+					.map_err(|err| internal_err(format!("revert ethereum mappings failed: {:?}", err)))?;
+			}
+		}
+
+		// the transactions sent after the snapshot are dropped with the blocks,
+		// the pending ones were validated against the reverted state
+		let pending: Vec<_> = self.pool.ready().map(|in_pool_tx| in_pool_tx.hash().clone()).collect();
+		self.pool.remove_invalid(&pending);
+		Ok(true)
+	}
+}
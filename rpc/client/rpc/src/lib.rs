@@ -1,14 +1,27 @@
+mod eip712;
 mod eth;
 mod eth_pubsub;
-
-pub use eth::{EthApi, EthApiServer, NetApi, NetApiServer, Web3Api, Web3ApiServer};
+mod evm_dev;
+mod signer;
+mod sync;
+mod txpool;
+
+pub use eth::{
+	EthApi, EthApiServer, EthFilterApi, EthFilterApiServer, FilterPool, FilterPoolItem, FilterType, Filters,
+	NetApi, NetApiServer, PersonalApiServer, Web3Api, Web3ApiServer,
+	extrinsics_transactions, pending_transaction_build, pending_transaction_filters_task, pool_transactions,
+	ready_transactions_stream,
+};
 pub use eth_pubsub::{EthPubSubApi, EthPubSubApiServer, HexEncodedIdProvider};
+pub use evm_dev::{DevState, EvmDevApi, EvmDevApiServer};
+pub use signer::{EthSigner, EthDevSigner, EthKeystoreSigner, ETH_KEY_TYPE};
+pub use sync::SyncProgress;
+pub use txpool::{TxPoolApi, TxPoolApiServer};
 
-use ethereum_types::{H160, H256};
 use jsonrpc_core::{ErrorCode, Error, Value};
 use rustc_hex::ToHex;
-use clover_evm::ExitReason;
-use sha3::{Digest, Keccak256};
+use ethereum_types::U256;
+use clover_evm::{ExitError, ExitReason};
 
 pub fn internal_err<T: ToString>(message: T) -> Error {
 	Error {
@@ -18,128 +31,189 @@ pub fn internal_err<T: ToString>(message: T) -> Error {
 	}
 }
 
+/// JSON-RPC error code of a reverted execution.
+pub const EXECUTION_REVERTED: i64 = 3;
+
+/// Selector of `Error(string)`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Decodes the reason of `Error(string)` and `Panic(uint256)` revert data, custom errors
+/// are left to the clients which know their abi.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+	if data.len() < 36 {
+		return None;
+	}
+	let (selector, body) = data.split_at(4);
+	if selector == PANIC_SELECTOR {
+		return Some(format!("panic: 0x{:02x}", U256::from_big_endian(&body[0..32])));
+	}
+	if selector != ERROR_SELECTOR {
+		return None;
+	}
+	let offset = U256::from_big_endian(&body[0..32]);
+	if offset > U256::from(body.len()) {
+		return None;
+	}
+	let offset = offset.as_usize();
+	let len = U256::from_big_endian(body.get(offset..offset + 32)?);
+	if len > U256::from(body.len()) {
+		return None;
+	}
+	let start = offset + 32;
+	let reason = body.get(start..start + len.as_usize())?;
+	std::str::from_utf8(reason).ok().map(|reason| reason.to_string())
+}
+
+/// Reason of the executions denied by the evm policies, reported like a revert.
+fn policy_reason(error: &ExitError) -> Option<&'static str> {
+	match error {
+		ExitError::DeployerNotAllowed => Some("deployer not allowed"),
+		ExitError::ContractPaused => Some("contract paused"),
+		ExitError::CallerNotAllowed => Some("caller not allowed"),
+		_ => None,
+	}
+}
+
+/// Message of a failed execution, `None` if it succeeded.
+pub fn exit_reason_message(reason: &ExitReason, data: &[u8]) -> Option<String> {
+	match reason {
+		ExitReason::Succeed(_) => None,
+		ExitReason::Error(e) => Some(match policy_reason(e) {
+			Some(reason) => format!("execution reverted: {}", reason),
+			None => format!("evm error: {:?}", e),
+		}),
+		ExitReason::Revert(_) => Some(match decode_revert_reason(data) {
+			Some(reason) => format!("execution reverted: {}", reason),
+			None => "execution reverted".to_string(),
+		}),
+		ExitReason::Fatal(e) => Some(format!("evm fatal: {:?}", e)),
+	}
+}
+
 pub fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> Result<(), Error> {
+	let message = match exit_reason_message(reason, data) {
+		Some(message) => message,
+		None => return Ok(()),
+	};
 	match reason {
-		ExitReason::Succeed(_) => Ok(()),
-		ExitReason::Error(e) => {
+		ExitReason::Error(e) if policy_reason(e).is_some() => {
 			Err(Error {
-				code: ErrorCode::InternalError,
-				message: format!("evm error: {:?}", e),
+				code: ErrorCode::ServerError(EXECUTION_REVERTED),
+				message,
 				data: Some(Value::String("0x".to_string()))
 			})
 		},
 		ExitReason::Revert(_) => {
-			let mut message = "VM Exception while processing transaction: revert".to_string();
-			// A minimum size of error function selector (4) + offset (32) + string length (32)
-			// should contain a utf-8 encoded revert reason.
-			if data.len() > 68 {
-				let message_len = data[36..68].iter().sum::<u8>();
-				let body: &[u8] = &data[68..68 + message_len as usize];
-				if let Ok(reason) = std::str::from_utf8(body) {
-					message = format!("{} {}", message, reason.to_string());
-				}
-			}
 			Err(Error {
-				code: ErrorCode::InternalError,
+				code: ErrorCode::ServerError(EXECUTION_REVERTED),
 				message,
-				data: Some(Value::String(data.to_hex()))
+				data: Some(Value::String(format!("0x{}", data.to_hex::<String>())))
 			})
 		},
-		ExitReason::Fatal(e) => {
+		_ => {
 			Err(Error {
 				code: ErrorCode::InternalError,
-				message: format!("evm fatal: {:?}", e),
+				message,
 				data: Some(Value::String("0x".to_string()))
 			})
 		},
 	}
 }
 
-/// A generic Ethereum signer.
-pub trait EthSigner: Send + Sync {
-	/// Available accounts from this signer.
-	fn accounts(&self) -> Vec<H160>;
-	/// Sign a transaction message using the given account in message.
-	fn sign(
-		&self,
-		message: ethereum::TransactionMessage,
-		address: &H160,
-	) -> Result<ethereum::Transaction, Error>;
-}
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clover_evm::{ExitRevert, ExitSucceed};
+
+	/// `Error(string)` revert data with the abi encoded `reason`.
+	fn error_data(reason: &[u8]) -> Vec<u8> {
+		let mut data = ERROR_SELECTOR.to_vec();
+		data.extend_from_slice(&[0u8; 31]);
+		data.push(0x20);
+		data.extend_from_slice(&[0u8; 31]);
+		data.push(reason.len() as u8);
+		data.extend_from_slice(reason);
+		data.resize(data.len() + (32 - reason.len() % 32) % 32, 0);
+		data
+	}
 
-pub struct EthDevSigner {
-	keys: Vec<secp256k1::SecretKey>,
-}
+	#[test]
+	fn error_reasons_are_decoded() {
+		assert_eq!(decode_revert_reason(&error_data(b"Not enough balance")), Some("Not enough balance".to_string()));
+		assert_eq!(decode_revert_reason(&error_data(b"")), Some(String::new()));
 
-impl EthDevSigner {
-	pub fn new() -> Self {
-		Self {
-			keys: vec![
-				secp256k1::SecretKey::parse(&[
-					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
-					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
-					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
-					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
-				]).expect("Test key is valid; qed"),
-			],
-		}
+		let long_reason = [b'a'; 40];
+		assert_eq!(decode_revert_reason(&error_data(&long_reason)), Some("a".repeat(40)));
+	}
+
+	#[test]
+	fn panic_codes_are_decoded() {
+		let mut data = PANIC_SELECTOR.to_vec();
+		data.extend_from_slice(&[0u8; 31]);
+		data.push(0x11);
+		assert_eq!(decode_revert_reason(&data), Some("panic: 0x11".to_string()));
+	}
+
+	#[test]
+	fn truncated_data_is_not_decoded() {
+		assert_eq!(decode_revert_reason(&[]), None);
+		assert_eq!(decode_revert_reason(&ERROR_SELECTOR), None);
+		assert_eq!(decode_revert_reason(&PANIC_SELECTOR), None);
+
+		// the reason is shorter than its length
+		let data = error_data(b"Not enough balance");
+		assert_eq!(decode_revert_reason(&data[..4 + 64 + 10]), None);
+		// the length is missing
+		assert_eq!(decode_revert_reason(&data[..4 + 32]), None);
+	}
+
+	#[test]
+	fn malformed_data_is_not_decoded() {
+		// custom errors are left to the clients
+		let mut data = error_data(b"reason");
+		data[0..4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+		assert_eq!(decode_revert_reason(&data), None);
+
+		// the offset is out of the data
+		let mut data = error_data(b"reason");
+		data[4 + 31] = 0xff;
+		assert_eq!(decode_revert_reason(&data), None);
+		let mut data = error_data(b"reason");
+		data[4..36].copy_from_slice(&[0xff; 32]);
+		assert_eq!(decode_revert_reason(&data), None);
+
+		// the length is out of the data
+		let mut data = error_data(b"reason");
+		data[4 + 32..4 + 64].copy_from_slice(&[0xff; 32]);
+		assert_eq!(decode_revert_reason(&data), None);
+
+		// the reason isn't utf-8
+		assert_eq!(decode_revert_reason(&error_data(&[0xff, 0xfe])), None);
+	}
+
+	#[test]
+	fn policy_errors_are_reported_as_reverts() {
+		let error = error_on_execution_failure(&ExitReason::Error(ExitError::ContractPaused), &[]).unwrap_err();
+		assert_eq!(error.code, ErrorCode::ServerError(EXECUTION_REVERTED));
+		assert_eq!(error.message, "execution reverted: contract paused");
+		assert_eq!(
+			exit_reason_message(&ExitReason::Error(ExitError::DeployerNotAllowed), &[]),
+			Some("execution reverted: deployer not allowed".to_string())
+		);
+		assert_eq!(
+			exit_reason_message(&ExitReason::Error(ExitError::CallerNotAllowed), &[]),
+			Some("execution reverted: caller not allowed".to_string())
+		);
 	}
-}
 
-impl EthSigner for EthDevSigner {
-	fn accounts(&self) -> Vec<H160> {
-		self.keys.iter().map(|secret| {
-			let public = secp256k1::PublicKey::from_secret_key(secret);
-			let mut res = [0u8; 64];
-			res.copy_from_slice(&public.serialize()[1..65]);
-
-			H160::from(H256::from_slice(Keccak256::digest(&res).as_slice()))
-		}).collect()
-	}
-
-	fn sign(
-		&self,
-		message: ethereum::TransactionMessage,
-		address: &H160,
-	) -> Result<ethereum::Transaction, Error> {
-		let mut transaction = None;
-
-		for secret in &self.keys {
-			let key_address = {
-				let public = secp256k1::PublicKey::from_secret_key(secret);
-				let mut res = [0u8; 64];
-				res.copy_from_slice(&public.serialize()[1..65]);
-				H160::from(H256::from_slice(Keccak256::digest(&res).as_slice()))
-			};
-
-			if &key_address == address {
-				let signing_message = secp256k1::Message::parse_slice(&message.hash()[..])
-					.map_err(|_| internal_err("invalid signing message"))?;
-				let (signature, recid) = secp256k1::sign(&signing_message, secret);
-
-				let v = match message.chain_id {
-					None => 27 + recid.serialize() as u64,
-					Some(chain_id) => 2 * chain_id + 35 + recid.serialize() as u64,
-				};
-				let rs = signature.serialize();
-				let r = H256::from_slice(&rs[0..32]);
-				let s = H256::from_slice(&rs[32..64]);
-
-				transaction = Some(ethereum::Transaction {
-					nonce: message.nonce,
-					gas_price: message.gas_price,
-					gas_limit: message.gas_limit,
-					action: message.action,
-					value: message.value,
-					input: message.input.clone(),
-					signature: ethereum::TransactionSignature::new(v, r, s)
-						.ok_or(internal_err("signer generated invalid signature"))?,
-				});
-
-				break
-			}
-		}
-
-		transaction.ok_or(internal_err("signer not available"))
+	#[test]
+	fn other_errors_are_internal_errors() {
+		let error = error_on_execution_failure(&ExitReason::Error(ExitError::OutOfGas), &[]).unwrap_err();
+		assert_eq!(error.code, ErrorCode::InternalError);
+		assert_eq!(error.message, "evm error: OutOfGas");
+		assert!(error_on_execution_failure(&ExitReason::Succeed(ExitSucceed::Stopped), &[]).is_ok());
+		assert_eq!(exit_reason_message(&ExitReason::Revert(ExitRevert::Reverted), &[]), Some("execution reverted".to_string()));
 	}
 }
@@ -0,0 +1,191 @@
+use ethereum_types::{H160, H256};
+use jsonrpc_core::Error;
+use sha3::{Digest, Keccak256};
+use sp_core::{crypto::{KeyTypeId, Pair as _}, ecdsa};
+use sc_keystore::KeyStorePtr;
+use crate::internal_err;
+
+/// Key type of the ethereum accounts in the keystore.
+pub const ETH_KEY_TYPE: KeyTypeId = KeyTypeId(*b"ethk");
+
+/// A generic Ethereum signer.
+pub trait EthSigner: Send + Sync {
+	/// Available accounts from this signer.
+	fn accounts(&self) -> Vec<H160>;
+	/// Sign a transaction message using the given account in message.
+	fn sign(
+		&self,
+		message: ethereum::TransactionMessage,
+		address: &H160,
+	) -> Result<ethereum::Transaction, Error>;
+	/// Sign a hash using the given account, returns the `r ++ s ++ v` signature.
+	fn sign_hash(&self, hash: H256, address: &H160) -> Result<[u8; 65], Error>;
+	/// Create a new account, `None` if the signer can't store keys.
+	fn new_account(&self) -> Option<Result<H160, Error>> {
+		None
+	}
+	/// Import the given secret key, `None` if the signer can't store keys.
+	fn import_raw_key(&self, _secret: &[u8]) -> Option<Result<H160, Error>> {
+		None
+	}
+}
+
+fn secret_address(secret: &secp256k1::SecretKey) -> H160 {
+	let public = secp256k1::PublicKey::from_secret_key(secret);
+	let mut res = [0u8; 64];
+	res.copy_from_slice(&public.serialize()[1..65]);
+
+	H160::from(H256::from_slice(Keccak256::digest(&res).as_slice()))
+}
+
+fn sign_transaction(
+	secret: &secp256k1::SecretKey,
+	message: ethereum::TransactionMessage,
+) -> Result<ethereum::Transaction, Error> {
+	let signing_message = secp256k1::Message::parse_slice(&message.hash()[..])
+		.map_err(|_| internal_err("invalid signing message"))?;
+	let (signature, recid) = secp256k1::sign(&signing_message, secret);
+
+	let v = match message.chain_id {
+		None => 27 + recid.serialize() as u64,
+		Some(chain_id) => 2 * chain_id + 35 + recid.serialize() as u64,
+	};
+	let rs = signature.serialize();
+	let r = H256::from_slice(&rs[0..32]);
+	let s = H256::from_slice(&rs[32..64]);
+
+	Ok(ethereum::Transaction {
+		nonce: message.nonce,
+		gas_price: message.gas_price,
+		gas_limit: message.gas_limit,
+		action: message.action,
+		value: message.value,
+		input: message.input.clone(),
+		signature: ethereum::TransactionSignature::new(v, r, s)
+			.ok_or(internal_err("signer generated invalid signature"))?,
+	})
+}
+
+fn sign_hash(secret: &secp256k1::SecretKey, hash: H256) -> Result<[u8; 65], Error> {
+	let signing_message = secp256k1::Message::parse_slice(hash.as_bytes())
+		.map_err(|_| internal_err("invalid signing message"))?;
+	let (signature, recid) = secp256k1::sign(&signing_message, secret);
+
+	let mut res = [0u8; 65];
+	res[0..64].copy_from_slice(&signature.serialize()[..]);
+	res[64] = 27 + recid.serialize();
+	Ok(res)
+}
+
+fn find_secret<'a>(
+	secrets: &'a [secp256k1::SecretKey],
+	address: &H160,
+) -> Result<&'a secp256k1::SecretKey, Error> {
+	secrets.iter()
+		.find(|secret| &secret_address(secret) == address)
+		.ok_or(internal_err("signer not available"))
+}
+
+/// Signer of the well known development account, only meant for `--dev` chains.
+pub struct EthDevSigner {
+	keys: Vec<secp256k1::SecretKey>,
+}
+
+impl EthDevSigner {
+	pub fn new() -> Self {
+		Self {
+			keys: vec![
+				secp256k1::SecretKey::parse(&[
+					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+					0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+				]).expect("Test key is valid; qed"),
+			],
+		}
+	}
+}
+
+impl EthSigner for EthDevSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.keys.iter().map(secret_address).collect()
+	}
+
+	fn sign(
+		&self,
+		message: ethereum::TransactionMessage,
+		address: &H160,
+	) -> Result<ethereum::Transaction, Error> {
+		sign_transaction(find_secret(&self.keys, address)?, message)
+	}
+
+	fn sign_hash(&self, hash: H256, address: &H160) -> Result<[u8; 65], Error> {
+		sign_hash(find_secret(&self.keys, address)?, hash)
+	}
+}
+
+/// Signer of the secp256k1 keys stored in the node keystore under `ETH_KEY_TYPE`.
+#[derive(Clone)]
+pub struct EthKeystoreSigner {
+	keystore: KeyStorePtr,
+}
+
+impl EthKeystoreSigner {
+	pub fn new(keystore: KeyStorePtr) -> Self {
+		Self { keystore }
+	}
+
+	fn secrets(&self) -> Vec<secp256k1::SecretKey> {
+		let keystore = self.keystore.read();
+		keystore.public_keys_by_type::<ecdsa::Public>(ETH_KEY_TYPE)
+			.unwrap_or_default()
+			.iter()
+			.filter_map(|public| keystore.key_pair_by_type::<ecdsa::Pair>(public, ETH_KEY_TYPE).ok())
+			.filter_map(|pair| secp256k1::SecretKey::parse_slice(&pair.to_raw_vec()).ok())
+			.collect()
+	}
+
+	fn pair_address(pair: ecdsa::Pair) -> Result<H160, Error> {
+		secp256k1::SecretKey::parse_slice(&pair.to_raw_vec())
+			.map(|secret| secret_address(&secret))
+			.map_err(|_| internal_err("keystore returned an invalid key"))
+	}
+}
+
+impl EthSigner for EthKeystoreSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.secrets().iter().map(secret_address).collect()
+	}
+
+	fn sign(
+		&self,
+		message: ethereum::TransactionMessage,
+		address: &H160,
+	) -> Result<ethereum::Transaction, Error> {
+		sign_transaction(find_secret(&self.secrets(), address)?, message)
+	}
+
+	fn sign_hash(&self, hash: H256, address: &H160) -> Result<[u8; 65], Error> {
+		sign_hash(find_secret(&self.secrets(), address)?, hash)
+	}
+
+	fn new_account(&self) -> Option<Result<H160, Error>> {
+		Some(
+			self.keystore.write().generate_by_type::<ecdsa::Pair>(ETH_KEY_TYPE)
+				.map_err(|err| internal_err(format!("generate key failed: {:?}", err)))
+				.and_then(Self::pair_address)
+		)
+	}
+
+	fn import_raw_key(&self, secret: &[u8]) -> Option<Result<H160, Error>> {
+		if secp256k1::SecretKey::parse_slice(secret).is_err() {
+			return Some(Err(internal_err("invalid secret key")));
+		}
+		let suri = format!("0x{}", rustc_hex::ToHex::to_hex::<String>(secret));
+		Some(
+			self.keystore.write().insert_by_type::<ecdsa::Pair>(ETH_KEY_TYPE, &suri)
+				.map_err(|err| internal_err(format!("import key failed: {:?}", err)))
+				.and_then(Self::pair_address)
+		)
+	}
+}
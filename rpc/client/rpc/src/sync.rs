@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+use ethereum_types::U256;
+use fc_rpc_core::types::SyncInfo;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct SyncBlocks {
+	starting_block: Option<u64>,
+	highest_block: Option<u64>,
+}
+
+/// Progress of the major sync, updated from the network status and shared by the rpc servers.
+#[derive(Clone, Debug, Default)]
+pub struct SyncProgress(Arc<Mutex<SyncBlocks>>);
+
+impl SyncProgress {
+	/// Records a network status, the best block is kept as the starting block
+	/// until the major sync is over.
+	pub fn update(&self, is_major_syncing: bool, best_block: u64, best_seen_block: Option<u64>) {
+		if let Ok(mut blocks) = self.0.lock() {
+			blocks.starting_block = if is_major_syncing {
+				blocks.starting_block.or(Some(best_block))
+			} else {
+				None
+			};
+			blocks.highest_block = best_seen_block;
+		}
+	}
+
+	/// Sync info at the given current block, the highest block is never below the current one.
+	pub fn sync_info(&self, current_block: u64) -> SyncInfo {
+		let blocks = self.0.lock().map(|blocks| *blocks).unwrap_or_default();
+		SyncInfo {
+			starting_block: U256::from(blocks.starting_block.unwrap_or(current_block)),
+			current_block: U256::from(current_block),
+			highest_block: U256::from(blocks.highest_block.unwrap_or(current_block).max(current_block)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sync_info(starting_block: u64, current_block: u64, highest_block: u64) -> SyncInfo {
+		SyncInfo {
+			starting_block: U256::from(starting_block),
+			current_block: U256::from(current_block),
+			highest_block: U256::from(highest_block),
+		}
+	}
+
+	#[test]
+	fn starting_block_is_latched_during_the_major_sync() {
+		let progress = SyncProgress::default();
+		progress.update(true, 10, Some(100));
+		assert_eq!(progress.sync_info(10), sync_info(10, 10, 100));
+
+		// later statuses of the same sync keep the first best block
+		progress.update(true, 50, Some(120));
+		assert_eq!(progress.sync_info(50), sync_info(10, 50, 120));
+
+		// the next major sync starts from its own best block
+		progress.update(false, 120, Some(120));
+		progress.update(true, 130, Some(200));
+		assert_eq!(progress.sync_info(130), sync_info(130, 130, 200));
+	}
+
+	#[test]
+	fn current_block_is_used_without_a_major_sync() {
+		let progress = SyncProgress::default();
+		assert_eq!(progress.sync_info(7), sync_info(7, 7, 7));
+
+		progress.update(false, 120, None);
+		assert_eq!(progress.sync_info(120), sync_info(120, 120, 120));
+	}
+
+	#[test]
+	fn highest_block_is_never_below_the_current_block() {
+		let progress = SyncProgress::default();
+		progress.update(true, 10, Some(100));
+		// imported past the last seen best block
+		assert_eq!(progress.sync_info(150), sync_info(10, 150, 150));
+	}
+}
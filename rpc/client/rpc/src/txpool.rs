@@ -0,0 +1,71 @@
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use ethereum_types::U256;
+use jsonrpc_core::Result;
+use sp_runtime::traits::Block as BlockT;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_transaction_pool::TransactionPool;
+use fc_rpc_core::TxPoolApi as TxPoolApiT;
+use fc_rpc_core::types::{Transaction, TransactionMap, TxPoolResult};
+use fp_rpc::EthereumRuntimeRPCApi;
+
+use crate::{pending_transaction_build, pool_transactions};
+
+pub use fc_rpc_core::TxPoolApiServer;
+
+/// The ethereum transactions of the transaction pool.
+///
+/// The pool only exposes the transactions ready to be included, transactions waiting
+/// for an earlier nonce are never reported and `queued` is always empty.
+pub struct TxPoolApi<B, C, P> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C, P> TxPoolApi<B, C, P> {
+	pub fn new(client: Arc<C>, pool: Arc<P>) -> Self {
+		Self { client, pool, _marker: PhantomData }
+	}
+}
+
+impl<B, C, P> TxPoolApi<B, C, P> where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block=B>,
+{
+	fn pending(&self) -> Result<Vec<Transaction>> {
+		Ok(pool_transactions(self.client.as_ref(), self.pool.as_ref())?
+			.into_iter()
+			.map(pending_transaction_build)
+			.collect())
+	}
+}
+
+impl<B, C, P> TxPoolApiT for TxPoolApi<B, C, P> where
+	B: BlockT + Send + Sync + 'static,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block=B> + Send + Sync + 'static,
+{
+	fn content(&self) -> Result<TxPoolResult<TransactionMap<Transaction>>> {
+		let mut pending = TransactionMap::new();
+		for transaction in self.pending()? {
+			pending.entry(transaction.from)
+				.or_insert_with(HashMap::new)
+				.insert(transaction.nonce, transaction);
+		}
+		Ok(TxPoolResult {
+			pending,
+			queued: TransactionMap::new(),
+		})
+	}
+
+	fn status(&self) -> Result<TxPoolResult<U256>> {
+		Ok(TxPoolResult {
+			pending: U256::from(self.pending()?.len()),
+			queued: U256::zero(),
+		})
+	}
+}
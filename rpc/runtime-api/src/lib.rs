@@ -13,6 +13,7 @@ sp_api::decl_runtime_apis! {
 		fn currency_pair() -> sp_std::vec::Vec<(primitives::CurrencyId, primitives::CurrencyId)>;
 	}
 
+	#[api_version(2)]
 	pub trait CurrencyExchangeApi<AccountId, CurrencyId, Balance, Rate, Share> where
 		AccountId: codec::Codec,
 		CurrencyId: codec::Codec,
@@ -25,14 +26,78 @@ sp_api::decl_runtime_apis! {
 		fn get_liquidity(account: Option<AccountId>) -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Balance, Balance, Balance, Balance, Balance)>;
 		fn get_exchange_rate() -> Rate;
 		fn to_add_liquidity(source: CurrencyId, target: CurrencyId, source_amount: Balance, target_amount: Balance) -> (Share, Share);
-    fn get_staking_info(account: AccountId, currency_first: CurrencyId, currency_second: CurrencyId) -> (Share, Balance);
+		// (staked shares, claimable native currency rewards)
+		#[changed_in(2)]
+		fn get_staking_info(account: AccountId, currency_first: CurrencyId, currency_second: CurrencyId) -> (Share, Balance);
+		// (staked shares, claimable rewards of each currency)
+		fn get_staking_info(account: AccountId, currency_first: CurrencyId, currency_second: CurrencyId) -> (Share, sp_std::vec::Vec<(CurrencyId, Balance)>);
 	}
 
-  pub trait IncentivePoolApi<AccountId, CurrencyId, Balance, Share> where
-    AccountId: codec::Codec,
-    CurrencyId: codec::Codec,
-    Balance: codec::Codec,
-    Share: codec::Codec, {
-    fn get_all_incentive_pools() -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Share, Balance)>;
-  }
+	#[api_version(2)]
+	pub trait IncentivePoolApi<AccountId, CurrencyId, Balance, Share> where
+		AccountId: codec::Codec,
+		CurrencyId: codec::Codec,
+		Balance: codec::Codec,
+		Share: codec::Codec, {
+		// (currency_left, currency_right, total_shares, useable native currency rewards)
+		#[changed_in(2)]
+		fn get_all_incentive_pools() -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Share, Balance)>;
+		// (currency_left, currency_right, total_shares, useable rewards of each currency)
+		fn get_all_incentive_pools() -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Share, sp_std::vec::Vec<(CurrencyId, Balance)>)>;
+	}
+
+	pub trait IncentiveProgramApi<CurrencyId, Balance, BlockNumber> where
+		CurrencyId: codec::Codec,
+		Balance: codec::Codec,
+		BlockNumber: codec::Codec, {
+		// Get the incentive programs which haven't ended,
+		// (currency_left, currency_right, reward_currency, current_reward_per_block, start, end, halving_period)
+		fn get_active_incentive_programs() -> sp_std::vec::Vec<(CurrencyId, CurrencyId, CurrencyId, Balance, BlockNumber, Option<BlockNumber>, Option<BlockNumber>)>;
+	}
+
+	pub trait IncentiveRewardApi<AccountId, PoolId, CurrencyId, Balance, Share, Rate> where
+		AccountId: codec::Codec,
+		PoolId: codec::Codec,
+		CurrencyId: codec::Codec,
+		Balance: codec::Codec,
+		Share: codec::Codec,
+		Rate: codec::Codec, {
+		// Get the rewards the account could claim in the next block from every pool it has shares in,
+		// (pool_id, shares, claimable rewards of each currency)
+		fn get_pending_rewards(account: AccountId) -> sp_std::vec::Vec<(PoolId, Share, sp_std::vec::Vec<(CurrencyId, Balance)>)>;
+		// Get the estimated annual percentage rate of the pools with active incentive programs,
+		// None if the pool has no shares or the price of the shares or a reward is unknown
+		fn get_pool_aprs() -> sp_std::vec::Vec<(PoolId, Option<Rate>)>;
+		// Get the annualized rate of the rewards the programs of the pools paid in the last 30 days,
+		// None if the pool has no shares or the price of the shares or a reward is unknown
+		fn get_historical_pool_aprs() -> sp_std::vec::Vec<(PoolId, Option<Rate>)>;
+	}
+
+	pub trait StakeLockApi<AccountId, CurrencyId, Share, Rate, BlockNumber> where
+		AccountId: codec::Codec,
+		CurrencyId: codec::Codec,
+		Share: codec::Codec,
+		Rate: codec::Codec,
+		BlockNumber: codec::Codec, {
+		// Get the staked shares and stake locks of the account in every pool,
+		// (currency_left, currency_right, staked_shares, effective_multiplier, locks of (amount, multiplier, unlock_at))
+		fn get_stake_locks(account: AccountId) -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Share, Rate, sp_std::vec::Vec<(Share, Rate, BlockNumber)>)>;
+	}
+
+	pub trait ContractVerificationApi<Address, AccountId, Hash, BlockNumber> where
+		Address: codec::Codec,
+		AccountId: codec::Codec,
+		Hash: codec::Codec,
+		BlockNumber: codec::Codec, {
+		// Get the verification metadata of a contract,
+		// (registrant, compiler_version, settings, abi, source_hash, code_hash, registered_at, code_unchanged)
+		fn get_contract_metadata(contract: Address) -> Option<(AccountId, sp_std::vec::Vec<u8>, sp_std::vec::Vec<u8>, sp_std::vec::Vec<u8>, Hash, Hash, BlockNumber, bool)>;
+	}
+
+	pub trait CurrencyFeeApi<CurrencyId, Balance> where
+		CurrencyId: codec::Codec,
+		Balance: codec::Codec, {
+		// Get the fee currency and the fee amount in that currency of an extrinsic
+		fn query_fee_info(uxt: Block::Extrinsic, len: u32) -> (CurrencyId, Balance);
+	}
 }
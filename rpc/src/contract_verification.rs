@@ -0,0 +1,95 @@
+use super::*;
+
+use codec::Codec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use clover_rpc_runtime_api::ContractVerificationApi as ContractVerificationRuntimeApi;
+
+#[derive(Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ContractMetadata<AccountId, Hash, BlockNumber> {
+  registrant: AccountId,
+  compiler_version: String,
+  /// json compiler settings, a string if the registered settings are not valid json
+  settings: Value,
+  /// json abi, a string if the registered abi is not valid json
+  abi: Value,
+  source_hash: Hash,
+  code_hash: Hash,
+  registered_at: BlockNumber,
+  /// whether the code of the contract still has the code hash, the sources are verified by compiling
+  /// them off chain
+  code_unchanged: bool,
+}
+
+#[rpc]
+pub trait ContractVerificationRpc<BlockHash, Address, AccountId, Hash, BlockNumber> {
+  /// Registered verification metadata of a contract.
+  #[rpc(name = "clover_getContractMetadata")]
+  fn get_contract_metadata(&self, contract: Address, at: Option<BlockHash>) -> Result<Option<ContractMetadata<AccountId, Hash, BlockNumber>>>;
+
+  /// Registered abi of a contract, for decoding its calls and logs.
+  #[rpc(name = "clover_getContractAbi")]
+  fn get_contract_abi(&self, contract: Address, at: Option<BlockHash>) -> Result<Option<Value>>;
+}
+
+pub struct ContractVerification<C, B> {
+  client: Arc<C>,
+  _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> ContractVerification<C, B> {
+  pub fn new(client: Arc<C>) -> Self {
+    ContractVerification {
+      client,
+      _marker: Default::default(),
+    }
+  }
+}
+
+fn json_value(bytes: Vec<u8>) -> Value {
+  serde_json::from_slice(&bytes)
+    .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+impl<C, Block, Address, AccountId, Hash, BlockNumber> ContractVerificationRpc<<Block as BlockT>::Hash, Address, AccountId, Hash, BlockNumber> for ContractVerification<C, Block>
+where
+  Block: BlockT,
+  C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+  C::Api: ContractVerificationRuntimeApi<Block, Address, AccountId, Hash, BlockNumber>,
+  Address: Codec,
+  AccountId: Codec,
+  Hash: Codec,
+  BlockNumber: Codec,
+{
+  fn get_contract_metadata(&self, contract: Address, at: Option<<Block as BlockT>::Hash>) -> Result<Option<ContractMetadata<AccountId, Hash, BlockNumber>>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+    api.get_contract_metadata(&at, contract).map_err(|e| RpcError {
+      code: ErrorCode::ServerError(Error::RuntimeError.into()),
+      message: "Unable to get value.".into(),
+      data: Some(format!("{:?}", e).into()),
+    }).map(|metadata| metadata.map(
+      |(registrant, compiler_version, settings, abi, source_hash, code_hash, registered_at, code_unchanged)| {
+        ContractMetadata {
+          registrant,
+          compiler_version: String::from_utf8_lossy(&compiler_version).into_owned(),
+          settings: json_value(settings),
+          abi: json_value(abi),
+          source_hash,
+          code_hash,
+          registered_at,
+          code_unchanged,
+        }
+      }))
+  }
+
+  fn get_contract_abi(&self, contract: Address, at: Option<<Block as BlockT>::Hash>) -> Result<Option<Value>> {
+    self.get_contract_metadata(contract, at).map(|metadata| metadata.map(|metadata| metadata.abi))
+  }
+}
@@ -4,9 +4,7 @@ use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use clover_primitives::CurrencyId;
-use strum::IntoEnumIterator;
 use std::string::ToString;
-use int_enum::IntEnum;
 
 pub struct Currency;
 
@@ -26,9 +24,9 @@ pub trait CurrencyRpc {
 impl CurrencyRpc for Currency {
     fn get_currencies(&self) -> Result<Vec<CurrencyInfo>> {
         let mut v: Vec<CurrencyInfo> = Vec::new();
-        for item in CurrencyId::iter() {
+        for item in CurrencyId::TOKENS.iter() {
             v.push(CurrencyInfo  {
-                id: item.int_value(),
+                id: item.token_id().unwrap_or_default(),
                 name: item.to_string()
             });
         }
@@ -1,6 +1,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use super::*;
 use std::fmt::Display;
+use sp_api::ApiExt;
 
 use codec::{Codec, Decode, Encode};
 
@@ -38,7 +39,7 @@ pub trait CurrencyExchangeRpc<BlockHash, AccountId, CurrencyId, Balance, Rate, S
                               account: AccountId,
                               currency_first: CurrencyId,
                               currency_second: CurrencyId,
-                              at: Option<BlockHash>) -> Result<(String, String)>;
+                              at: Option<BlockHash>) -> Result<(String, Vec<(CurrencyId, String)>)>;
 }
 
 pub struct CurrencyExchange<C, M> {
@@ -58,7 +59,7 @@ where
   C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
     C::Api: CurrencyExchangeRuntimeApi<Block, AccountId, CurrencyId, Balance, Rate, Share>,
     AccountId: Codec,
-    CurrencyId: Codec,
+    CurrencyId: Codec + From<clover_primitives::CurrencyId>,
   Balance: Codec + Display,
     Rate: Codec,
     Share: Codec + Display,
@@ -142,18 +143,30 @@ where
   fn get_account_staking_info(&self, account: AccountId,
                               currency_left: CurrencyId,
                               currency_right: CurrencyId,
-                              at: Option<<Block as BlockT>::Hash>) -> Result<(String, String)> {
+                              at: Option<<Block as BlockT>::Hash>) -> Result<(String, Vec<(CurrencyId, String)>)> {
     let api = self.client.runtime_api();
     let at = BlockId::hash(at.unwrap_or_else(||
                                              // If the block hash is not supplied assume the best block.
                                              self.client.info().best_hash));
 
-    api.get_staking_info(&at, account, currency_left, currency_right).map_err(|e| RpcError {
+    let runtime_error = |e| RpcError {
       code: ErrorCode::ServerError(Error::RuntimeError.into()),
       message: "Unable to get value.".into(),
       data: Some(format!("{:?}", e).into()),
-    }).map(|(s1, s2)| {
-      (format!("{}", s1), format!("{}", s2))
-    })
+    };
+    let version = api.has_api_with::<dyn CurrencyExchangeRuntimeApi<Block, AccountId, CurrencyId, Balance, Rate, Share>, _>(
+      &at, |v| v >= 2).map_err(runtime_error)?;
+    let (shares, rewards) = if version {
+      api.get_staking_info(&at, account, currency_left, currency_right).map_err(runtime_error)?
+    } else {
+      // older runtimes only reward the native currency
+      #[allow(deprecated)]
+      let (shares, reward) = api.get_staking_info_before_version_2(&at, account, currency_left, currency_right)
+        .map_err(runtime_error)?;
+      (shares, vec![(clover_primitives::CurrencyId::CLV.into(), reward)])
+    };
+
+    Ok((format!("{}", shares),
+        rewards.into_iter().map(|(currency_id, reward)| (currency_id, format!("{}", reward))).collect()))
   }
 }
@@ -0,0 +1,72 @@
+use super::*;
+
+use std::fmt::Display;
+use codec::{Codec, Decode};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+
+pub use clover_rpc_runtime_api::CurrencyFeeApi as CurrencyFeeRuntimeApi;
+
+#[derive(Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct FeeInfo<CurrencyId> {
+  currency_id: CurrencyId,
+  partial_fee: String,
+}
+
+#[rpc]
+pub trait CurrencyFeeRpc<BlockHash, CurrencyId, Balance> {
+  /// Fee of an encoded extrinsic, in the fee currency chosen by the extrinsic.
+  #[rpc(name = "clover_queryFeeInfo")]
+  fn query_fee_info(&self, encoded_xt: Bytes, at: Option<BlockHash>) -> Result<FeeInfo<CurrencyId>>;
+}
+
+pub struct CurrencyFee<C, B> {
+  client: Arc<C>,
+  _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> CurrencyFee<C, B> {
+  pub fn new(client: Arc<C>) -> Self {
+    CurrencyFee {
+      client,
+      _marker: Default::default(),
+    }
+  }
+}
+
+impl<C, Block, CurrencyId, Balance> CurrencyFeeRpc<<Block as BlockT>::Hash, CurrencyId, Balance> for CurrencyFee<C, Block>
+where
+  Block: BlockT,
+  C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+  C::Api: CurrencyFeeRuntimeApi<Block, CurrencyId, Balance>,
+  CurrencyId: Codec,
+  Balance: Codec + Display,
+{
+  fn query_fee_info(&self, encoded_xt: Bytes, at: Option<<Block as BlockT>::Hash>) -> Result<FeeInfo<CurrencyId>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or_else(||
+      // If the block hash is not supplied assume the best block.
+      self.client.info().best_hash));
+
+    let encoded_len = encoded_xt.len() as u32;
+    let uxt: Block::Extrinsic = Decode::decode(&mut &*encoded_xt).map_err(|e| RpcError {
+      code: ErrorCode::InvalidParams,
+      message: "Unable to decode extrinsic.".into(),
+      data: Some(format!("{:?}", e).into()),
+    })?;
+
+    api.query_fee_info(&at, uxt, encoded_len).map_err(|e| RpcError {
+      code: ErrorCode::ServerError(Error::RuntimeError.into()),
+      message: "Unable to get value.".into(),
+      data: Some(format!("{:?}", e).into()),
+    }).map(|(currency_id, fee)| {
+      FeeInfo {
+        currency_id,
+        partial_fee: format!("{}", fee),
+      }
+    })
+  }
+}
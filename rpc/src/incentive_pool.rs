@@ -2,6 +2,7 @@
 use super::*;
 use codec::{Codec, };
 use std::fmt::Display;
+use sp_api::ApiExt;
 
 pub use clover_rpc_runtime_api::IncentivePoolApi as IncentivePoolRuntimeApi;
 
@@ -22,7 +23,7 @@ impl<C, B> IncentivePool<C, B> {
 #[rpc]
 pub trait IncentivePoolRpc<BlockHash, AccountId, CurrencyId, Balance, Share> {
   #[rpc(name = "incentive_getAllPools")]
-  fn get_all_incentive_pools(&self, at: Option<BlockHash>) -> Result<Vec<(CurrencyId, CurrencyId, String, String)>>;
+  fn get_all_incentive_pools(&self, at: Option<BlockHash>) -> Result<Vec<(CurrencyId, CurrencyId, String, Vec<(CurrencyId, String)>)>>;
 }
 
 impl<C, Block, AccountId, CurrencyId, Balance, Share> IncentivePoolRpc<<Block as BlockT>::Hash, AccountId, CurrencyId, Balance, Share> for IncentivePool<C, Block>
@@ -31,23 +32,38 @@ where
   C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
   C::Api: IncentivePoolRuntimeApi<Block, AccountId, CurrencyId, Balance, Share>,
   AccountId: Codec,
-  CurrencyId: Codec,
+  CurrencyId: Codec + From<clover_primitives::CurrencyId>,
   Balance: Codec + Display,
   Share: Codec + Display, {
   fn get_all_incentive_pools(&self,
-                             at: Option<<Block as BlockT>::Hash>) -> Result<Vec<(CurrencyId, CurrencyId, String, String)>> {
+                             at: Option<<Block as BlockT>::Hash>) -> Result<Vec<(CurrencyId, CurrencyId, String, Vec<(CurrencyId, String)>)>> {
     let api = self.client.runtime_api();
     let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
 
-    api.get_all_incentive_pools(&at).map_err(|e| RpcError {
+    let runtime_error = |e| RpcError {
       code: ErrorCode::ServerError(Error::RuntimeError.into()),
       message: "Unable to get value.".into(),
       data: Some(format!("{:?}", e).into()),
-    }).map(|data|
-           data.into_iter().map(|(c1, c2, share, balance)| {
-             (c1, c2, format!("{}", share), format!("{}", balance))
-           })
-           .collect())
+    };
+    let version = api.has_api_with::<dyn IncentivePoolRuntimeApi<Block, AccountId, CurrencyId, Balance, Share>, _>(
+      &at, |v| v >= 2).map_err(runtime_error)?;
+    let pools = if version {
+      api.get_all_incentive_pools(&at).map_err(runtime_error)?
+    } else {
+      // older runtimes only reward the native currency
+      #[allow(deprecated)]
+      let pools = api.get_all_incentive_pools_before_version_2(&at).map_err(runtime_error)?;
+      pools.into_iter()
+        .map(|(c1, c2, share, reward)| (c1, c2, share, vec![(clover_primitives::CurrencyId::CLV.into(), reward)]))
+        .collect()
+    };
+
+    Ok(pools.into_iter().map(|(c1, c2, share, rewards)| {
+      let rewards = rewards.into_iter()
+        .map(|(currency_id, balance)| (currency_id, format!("{}", balance)))
+        .collect();
+      (c1, c2, format!("{}", share), rewards)
+    })
+    .collect())
   }
 }
-
@@ -0,0 +1,76 @@
+use super::*;
+
+use std::fmt::Display;
+use codec::Codec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+pub use clover_rpc_runtime_api::IncentiveProgramApi as IncentiveProgramRuntimeApi;
+
+#[derive(Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct IncentiveProgramInfo<CurrencyId, BlockNumber> {
+  currency_left: CurrencyId,
+  currency_right: CurrencyId,
+  reward_currency: CurrencyId,
+  /// reward per block of the next block
+  reward_per_block: String,
+  start: BlockNumber,
+  end: Option<BlockNumber>,
+  halving_period: Option<BlockNumber>,
+}
+
+#[rpc]
+pub trait IncentiveProgramRpc<BlockHash, CurrencyId, Balance, BlockNumber> {
+  /// Incentive programs which haven't started yet or still pay rewards.
+  #[rpc(name = "incentive_getActivePrograms")]
+  fn get_active_incentive_programs(&self, at: Option<BlockHash>) -> Result<Vec<IncentiveProgramInfo<CurrencyId, BlockNumber>>>;
+}
+
+pub struct IncentiveProgram<C, B> {
+  client: Arc<C>,
+  _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> IncentiveProgram<C, B> {
+  pub fn new(client: Arc<C>) -> Self {
+    IncentiveProgram {
+      client,
+      _marker: Default::default(),
+    }
+  }
+}
+
+impl<C, Block, CurrencyId, Balance, BlockNumber> IncentiveProgramRpc<<Block as BlockT>::Hash, CurrencyId, Balance, BlockNumber> for IncentiveProgram<C, Block>
+where
+  Block: BlockT,
+  C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+  C::Api: IncentiveProgramRuntimeApi<Block, CurrencyId, Balance, BlockNumber>,
+  CurrencyId: Codec,
+  Balance: Codec + Display,
+  BlockNumber: Codec,
+{
+  fn get_active_incentive_programs(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<IncentiveProgramInfo<CurrencyId, BlockNumber>>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+    api.get_active_incentive_programs(&at).map_err(|e| RpcError {
+      code: ErrorCode::ServerError(Error::RuntimeError.into()),
+      message: "Unable to get value.".into(),
+      data: Some(format!("{:?}", e).into()),
+    }).map(|programs| programs.into_iter()
+           .map(|(currency_left, currency_right, reward_currency, reward_per_block, start, end, halving_period)| {
+             IncentiveProgramInfo {
+               currency_left,
+               currency_right,
+               reward_currency,
+               reward_per_block: format!("{}", reward_per_block),
+               start,
+               end,
+               halving_period,
+             }
+           })
+           .collect())
+  }
+}
@@ -0,0 +1,113 @@
+use super::*;
+
+use std::fmt::Display;
+use codec::Codec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+pub use clover_rpc_runtime_api::IncentiveRewardApi as IncentiveRewardRuntimeApi;
+
+#[derive(Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PendingRewards<PoolId, CurrencyId> {
+  pool_id: PoolId,
+  shares: String,
+  /// rewards of each currency the account could claim in the next block
+  rewards: Vec<(CurrencyId, String)>,
+}
+
+#[derive(Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PoolApr<PoolId, Rate> {
+  pool_id: PoolId,
+  /// None if the pool has no shares or the price of the shares or a reward is unknown
+  apr: Option<Rate>,
+}
+
+#[rpc]
+pub trait IncentiveRewardRpc<BlockHash, AccountId, PoolId, CurrencyId, Balance, Share, Rate> {
+  /// Rewards the account could claim in the next block from every pool it has shares in.
+  #[rpc(name = "incentive_getPendingRewards")]
+  fn get_pending_rewards(&self, account: AccountId, at: Option<BlockHash>) -> Result<Vec<PendingRewards<PoolId, CurrencyId>>>;
+
+  /// Estimated annual percentage rate of the pools with active incentive programs.
+  #[rpc(name = "incentive_getPoolAprs")]
+  fn get_pool_aprs(&self, at: Option<BlockHash>) -> Result<Vec<PoolApr<PoolId, Rate>>>;
+
+  /// Annualized rate of the rewards the incentive programs of the pools paid in the last 30 days.
+  #[rpc(name = "incentive_getHistoricalPoolAprs")]
+  fn get_historical_pool_aprs(&self, at: Option<BlockHash>) -> Result<Vec<PoolApr<PoolId, Rate>>>;
+}
+
+pub struct IncentiveReward<C, B> {
+  client: Arc<C>,
+  _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> IncentiveReward<C, B> {
+  pub fn new(client: Arc<C>) -> Self {
+    IncentiveReward {
+      client,
+      _marker: Default::default(),
+    }
+  }
+}
+
+impl<C, Block, AccountId, PoolId, CurrencyId, Balance, Share, Rate> IncentiveRewardRpc<<Block as BlockT>::Hash, AccountId, PoolId, CurrencyId, Balance, Share, Rate> for IncentiveReward<C, Block>
+where
+  Block: BlockT,
+  C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+  C::Api: IncentiveRewardRuntimeApi<Block, AccountId, PoolId, CurrencyId, Balance, Share, Rate>,
+  AccountId: Codec,
+  PoolId: Codec,
+  CurrencyId: Codec,
+  Balance: Codec + Display,
+  Share: Codec + Display,
+  Rate: Codec,
+{
+  fn get_pending_rewards(&self, account: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<PendingRewards<PoolId, CurrencyId>>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+    api.get_pending_rewards(&at, account).map_err(|e| RpcError {
+      code: ErrorCode::ServerError(Error::RuntimeError.into()),
+      message: "Unable to get value.".into(),
+      data: Some(format!("{:?}", e).into()),
+    }).map(|pools| pools.into_iter()
+           .map(|(pool_id, shares, rewards)| PendingRewards {
+             pool_id,
+             shares: format!("{}", shares),
+             rewards: rewards.into_iter()
+               .map(|(currency_id, balance)| (currency_id, format!("{}", balance)))
+               .collect(),
+           })
+           .collect())
+  }
+
+  fn get_pool_aprs(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<PoolApr<PoolId, Rate>>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+    api.get_pool_aprs(&at).map_err(|e| RpcError {
+      code: ErrorCode::ServerError(Error::RuntimeError.into()),
+      message: "Unable to get value.".into(),
+      data: Some(format!("{:?}", e).into()),
+    }).map(|pools| pools.into_iter()
+           .map(|(pool_id, apr)| PoolApr { pool_id, apr })
+           .collect())
+  }
+
+  fn get_historical_pool_aprs(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<PoolApr<PoolId, Rate>>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+    api.get_historical_pool_aprs(&at).map_err(|e| RpcError {
+      code: ErrorCode::ServerError(Error::RuntimeError.into()),
+      message: "Unable to get value.".into(),
+      data: Some(format!("{:?}", e).into()),
+    }).map(|pools| pools.into_iter()
+           .map(|(pool_id, apr)| PoolApr { pool_id, apr })
+           .collect())
+  }
+}
@@ -11,6 +11,11 @@ pub mod pair;
 pub mod balance;
 pub mod exchange;
 pub mod incentive_pool;
+pub mod incentive_program;
+pub mod incentive_reward;
+pub mod stake_lock;
+pub mod fee;
+pub mod contract_verification;
 
 pub enum Error {
   RuntimeError,
@@ -0,0 +1,88 @@
+use super::*;
+
+use std::fmt::Display;
+use codec::Codec;
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+pub use clover_rpc_runtime_api::StakeLockApi as StakeLockRuntimeApi;
+
+#[derive(Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StakeLockInfo<Rate, BlockNumber> {
+  amount: String,
+  multiplier: Rate,
+  unlock_at: BlockNumber,
+}
+
+#[derive(Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct PoolStakeLocks<CurrencyId, Rate, BlockNumber> {
+  currency_left: CurrencyId,
+  currency_right: CurrencyId,
+  staked_shares: String,
+  /// reward pool shares divided by the staked shares
+  effective_multiplier: Rate,
+  locks: Vec<StakeLockInfo<Rate, BlockNumber>>,
+}
+
+#[rpc]
+pub trait StakeLockRpc<BlockHash, AccountId, CurrencyId, Share, Rate, BlockNumber> {
+  /// Staked shares, stake locks and the effective reward multiplier of the account in every pool.
+  #[rpc(name = "clover_getStakeLocks")]
+  fn get_stake_locks(&self, account: AccountId, at: Option<BlockHash>) -> Result<Vec<PoolStakeLocks<CurrencyId, Rate, BlockNumber>>>;
+}
+
+pub struct StakeLock<C, B> {
+  client: Arc<C>,
+  _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> StakeLock<C, B> {
+  pub fn new(client: Arc<C>) -> Self {
+    StakeLock {
+      client,
+      _marker: Default::default(),
+    }
+  }
+}
+
+impl<C, Block, AccountId, CurrencyId, Share, Rate, BlockNumber> StakeLockRpc<<Block as BlockT>::Hash, AccountId, CurrencyId, Share, Rate, BlockNumber> for StakeLock<C, Block>
+where
+  Block: BlockT,
+  C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+  C::Api: StakeLockRuntimeApi<Block, AccountId, CurrencyId, Share, Rate, BlockNumber>,
+  AccountId: Codec,
+  CurrencyId: Codec,
+  Share: Codec + Display,
+  Rate: Codec,
+  BlockNumber: Codec,
+{
+  fn get_stake_locks(&self, account: AccountId, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<PoolStakeLocks<CurrencyId, Rate, BlockNumber>>> {
+    let api = self.client.runtime_api();
+    let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+    api.get_stake_locks(&at, account).map_err(|e| RpcError {
+      code: ErrorCode::ServerError(Error::RuntimeError.into()),
+      message: "Unable to get value.".into(),
+      data: Some(format!("{:?}", e).into()),
+    }).map(|pools| pools.into_iter()
+           .map(|(currency_left, currency_right, staked_shares, effective_multiplier, locks)| {
+             PoolStakeLocks {
+               currency_left,
+               currency_right,
+               staked_shares: format!("{}", staked_shares),
+               effective_multiplier,
+               locks: locks.into_iter()
+                 .map(|(amount, multiplier, unlock_at)| StakeLockInfo {
+                   amount: format!("{}", amount),
+                   multiplier,
+                   unlock_at,
+                 })
+                 .collect(),
+             }
+           })
+           .collect())
+  }
+}
@@ -0,0 +1,278 @@
+use crate::{
+  AccountId, Balance, CloverDex, Currencies, CurrencyId, MaxStakeLockDuration, MaxStakeLocks, Rate, RewardPool, Runtime,
+  System,
+};
+
+use clover_incentives::{IncentiveProgram, IncentivePrograms, PairKey, PoolId};
+use frame_benchmarking::account;
+use frame_support::StorageDoubleMap;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::MultiCurrency;
+use primitives::currency::DOLLARS;
+use sp_runtime::FixedPointNumber;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+// pairs of all the currencies, the first three form the route CLV -> CUSDT -> CETH -> DOT
+const PAIRS: [(CurrencyId, CurrencyId); 6] = [
+  (CurrencyId::CLV, CurrencyId::CUSDT),
+  (CurrencyId::CUSDT, CurrencyId::CETH),
+  (CurrencyId::CETH, CurrencyId::DOT),
+  (CurrencyId::CLV, CurrencyId::CETH),
+  (CurrencyId::CUSDT, CurrencyId::DOT),
+  (CurrencyId::CLV, CurrencyId::DOT),
+];
+const ROUTE: [CurrencyId; 3] = [CurrencyId::CUSDT, CurrencyId::CETH, CurrencyId::DOT];
+
+fn funded_account(name: &'static str, index: u32) -> AccountId {
+  let who: AccountId = account(name, index, SEED);
+  for currency_id in &[CurrencyId::CLV, CurrencyId::CUSDT, CurrencyId::DOT, CurrencyId::CETH] {
+    let _ = <Currencies as MultiCurrency<_>>::deposit(*currency_id, &who, 1_000_000 * DOLLARS);
+  }
+  who
+}
+
+fn create_pools(who: &AccountId, pairs: u32) -> Result<(), &'static str> {
+  for (first, second) in PAIRS.iter().take(pairs as usize) {
+    CloverDex::add_liquidity(RawOrigin::Signed(who.clone()).into(), *first, *second, 1_000 * DOLLARS, 1_000 * DOLLARS)?;
+  }
+  Ok(())
+}
+
+fn stake_shares(who: &AccountId, first: CurrencyId, second: CurrencyId) -> Result<(), &'static str> {
+  let shares = CloverDex::shares(CloverDex::get_pair_key(&first, &second), who);
+  CloverDex::stake_pool_shares(RawOrigin::Signed(who.clone()).into(), first, second, shares)?;
+  Ok(())
+}
+
+fn lock_shares(who: &AccountId, first: CurrencyId, second: CurrencyId, locks: u32) -> Result<(), &'static str> {
+  for _ in 0 .. locks {
+    CloverDex::lock_pool_shares(RawOrigin::Signed(who.clone()).into(), first, second, DOLLARS, MaxStakeLockDuration::get())?;
+  }
+  Ok(())
+}
+
+runtime_benchmarks! {
+  { Runtime, cloverdex }
+
+  _ {}
+
+  set_exchange_rate {
+  }: _(RawOrigin::Root, Rate::saturating_from_rational(3, 1000))
+
+  set_max_route_hops {
+  }: _(RawOrigin::Root, cloverdex::MAX_ROUTE_HOPS_LIMIT)
+
+  // worst case: create a pool, which updates the currency graph
+  add_liquidity {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 5)?;
+    let (first, second) = PAIRS[5];
+  }: _(RawOrigin::Signed(caller), first, second, 100 * DOLLARS, 100 * DOLLARS)
+
+  withdraw_liquidity {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 1)?;
+    let (first, second) = PAIRS[0];
+    let shares = CloverDex::shares(CloverDex::get_pair_key(&first, &second), &caller);
+  }: _(RawOrigin::Signed(caller), first, second, shares / 2)
+
+  // swap along a route with `r` hops
+  swap_currency {
+    let r in 1 .. (ROUTE.len() as u32);
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 3)?;
+    let route = ROUTE[..r as usize].to_vec();
+    let target = ROUTE[r as usize - 1];
+    let amount: Balance = DOLLARS;
+  }: _(RawOrigin::Signed(caller), CurrencyId::CLV, amount, target, 0, route)
+
+  stake_pool_shares {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 1)?;
+    let (first, second) = PAIRS[0];
+    let shares = CloverDex::shares(CloverDex::get_pair_key(&first, &second), &caller);
+  }: _(RawOrigin::Signed(caller), first, second, shares)
+
+  unstake_pool_shares {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 1)?;
+    let (first, second) = PAIRS[0];
+    stake_shares(&caller, first, second)?;
+    let shares = CloverDex::shares(CloverDex::get_pair_key(&first, &second), &caller);
+  }: _(RawOrigin::Signed(caller), first, second, shares)
+
+  // worst case: the account has the max number of locks minus one
+  lock_pool_shares {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 1)?;
+    let (first, second) = PAIRS[0];
+    stake_shares(&caller, first, second)?;
+    lock_shares(&caller, first, second, MaxStakeLocks::get() - 1)?;
+  }: _(RawOrigin::Signed(caller), first, second, DOLLARS, MaxStakeLockDuration::get())
+
+  // worst case: the lock is removed early, a penalty is paid
+  unlock_pool_shares {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 1)?;
+    let (first, second) = PAIRS[0];
+    stake_shares(&caller, first, second)?;
+    lock_shares(&caller, first, second, MaxStakeLocks::get())?;
+  }: _(RawOrigin::Signed(caller), first, second, 0)
+
+  // worst case: all the locks expired
+  release_pool_share_locks {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 1)?;
+    let (first, second) = PAIRS[0];
+    stake_shares(&caller, first, second)?;
+    lock_shares(&caller, first, second, MaxStakeLocks::get())?;
+    System::set_block_number(System::block_number() + MaxStakeLockDuration::get());
+  }: _(RawOrigin::Signed(caller.clone()), caller.clone(), first, second)
+
+  // worst case: rewards are paid to the account
+  withdraw_rewards {
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, 1)?;
+    let (first, second) = PAIRS[0];
+    let pair_key = PairKey::try_from(first, second).ok_or("invalid pair")?;
+    // pays rewards in two currencies from the budgets
+    for reward_currency_id in &[CurrencyId::CLV, CurrencyId::DOT] {
+      IncentivePrograms::<Runtime>::insert(PoolId::Dex(pair_key), reward_currency_id, IncentiveProgram {
+        reward_per_block: DOLLARS,
+        start: 0,
+        end: None,
+        halving_period: None,
+      });
+      RewardPool::deposit_rewards(RawOrigin::Signed(caller.clone()).into(), PoolId::Dex(pair_key), *reward_currency_id, 1_000 * DOLLARS)?;
+    }
+    stake_shares(&caller, first, second)?;
+    System::set_block_number(System::block_number() + 10);
+  }: _(RawOrigin::Signed(caller), first, second)
+
+  // route finding reads at most `MAX_CANDIDATE_ROUTES * max_route_hops` pools whatever the pool count `p`
+  get_supply_amount_needed {
+    let p in 1 .. (PAIRS.len() as u32);
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, p)?;
+  }: {
+    CloverDex::get_supply_amount_needed(CurrencyId::CLV, CurrencyId::DOT, DOLLARS);
+  }
+
+  // `on_initialize` adds the price of every pool `p` to its accumulator
+  accumulate_prices {
+    let p in 1 .. (PAIRS.len() as u32);
+    let caller = funded_account("caller", 0);
+    create_pools(&caller, p)?;
+    CloverDex::accumulate_prices(System::block_number());
+    System::set_block_number(System::block_number() + 1);
+  }: {
+    CloverDex::accumulate_prices(System::block_number());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_set_exchange_rate() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_set_exchange_rate());
+    });
+  }
+
+  #[test]
+  fn test_set_max_route_hops() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_set_max_route_hops());
+    });
+  }
+
+  #[test]
+  fn test_add_liquidity() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_add_liquidity());
+    });
+  }
+
+  #[test]
+  fn test_withdraw_liquidity() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_withdraw_liquidity());
+    });
+  }
+
+  #[test]
+  fn test_stake_pool_shares() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_stake_pool_shares());
+    });
+  }
+
+  #[test]
+  fn test_unstake_pool_shares() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_unstake_pool_shares());
+    });
+  }
+
+  #[test]
+  fn test_lock_pool_shares() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_lock_pool_shares());
+    });
+  }
+
+  #[test]
+  fn test_unlock_pool_shares() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_unlock_pool_shares());
+    });
+  }
+
+  #[test]
+  fn test_release_pool_share_locks() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_release_pool_share_locks());
+    });
+  }
+
+  #[test]
+  fn test_withdraw_rewards() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_withdraw_rewards());
+    });
+  }
+
+  #[test]
+  fn test_get_supply_amount_needed() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_get_supply_amount_needed());
+    });
+  }
+
+  #[test]
+  fn test_accumulate_prices() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_accumulate_prices());
+    });
+  }
+
+  #[test]
+  fn test_swap_currency() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_swap_currency());
+    });
+  }
+}
@@ -0,0 +1,142 @@
+use crate::{AccountId, Balances, ContractVerification, MaxContractMetadataLength, Runtime};
+
+use clover_evm::{AddressMapping, Runner};
+use frame_support::traits::{Currency, Get};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use primitives::currency::DOLLARS;
+use sp_core::{H160, H256, U256};
+use sp_std::prelude::*;
+
+/// Init code of a contract which stores the second word of the input at the slot of the first word.
+const STORE_CONTRACT: [u8; 20] = [
+  0x60, 0x08, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x08, 0x60, 0x00, 0xf3,
+  0x60, 0x20, 0x35, 0x60, 0x00, 0x35, 0x55, 0x00,
+];
+
+/// The account signing for `deployer` with the `CallOrigin` of the evm module.
+fn truncated_account(deployer: &H160) -> AccountId {
+  let mut data = [0u8; 32];
+  data[0..20].copy_from_slice(deployer.as_bytes());
+  AccountId::from(data)
+}
+
+/// Deploy a contract from a funded deployer, returns the deployer and the contract.
+fn deploy_contract() -> Result<(H160, H160), &'static str> {
+  let deployer = H160::repeat_byte(1);
+  let account = <Runtime as clover_evm::Trait>::AddressMapping::into_account_id(&deployer);
+  Balances::make_free_balance_be(&account, 1_000 * DOLLARS);
+  let (info, _) = <Runtime as clover_evm::Trait>::Runner::create(
+    deployer,
+    STORE_CONTRACT.to_vec(),
+    U256::zero(),
+    1_000_000,
+    None,
+    None,
+    <Runtime as clover_evm::Trait>::config(),
+  ).map_err(|_| "deployment failed")?;
+  if !info.exit_reason.is_succeed() {
+    return Err("deployment failed");
+  }
+  Ok((deployer, info.value))
+}
+
+runtime_benchmarks! {
+  { Runtime, clover_contract_verification }
+
+  _ {}
+
+  // the metadata of `b` bytes, with a compiler version of 7 bytes, is stored and its deposit reserved
+  register {
+    let b in 7 .. MaxContractMetadataLength::get();
+    let (deployer, contract) = deploy_contract()?;
+    let caller = <Runtime as clover_evm::Trait>::AddressMapping::into_account_id(&deployer);
+  }: _(RawOrigin::Signed(caller.clone()), contract, b"v0.6.12".to_vec(), vec![], vec![0u8; b as usize - 7], H256::zero())
+  verify {
+    assert_eq!(ContractVerification::contracts(&contract).map(|metadata| metadata.registrant), Some(caller));
+  }
+
+  register_from_evm {
+    let b in 7 .. MaxContractMetadataLength::get();
+    let (deployer, contract) = deploy_contract()?;
+  }: _(RawOrigin::Signed(truncated_account(&deployer)), contract, b"v0.6.12".to_vec(), vec![], vec![0u8; b as usize - 7], H256::zero())
+  verify {
+    assert!(ContractVerification::contracts(&contract).is_some());
+  }
+
+  force_register {
+    let b in 7 .. MaxContractMetadataLength::get();
+    let (deployer, contract) = deploy_contract()?;
+    let registrant = <Runtime as clover_evm::Trait>::AddressMapping::into_account_id(&deployer);
+  }: _(RawOrigin::Root, contract, registrant, b"v0.6.12".to_vec(), vec![], vec![0u8; b as usize - 7], H256::zero())
+  verify {
+    assert_eq!(ContractVerification::contracts(&contract).map(|metadata| metadata.deposit), Some(0));
+  }
+
+  unregister {
+    let (deployer, contract) = deploy_contract()?;
+    let caller = <Runtime as clover_evm::Trait>::AddressMapping::into_account_id(&deployer);
+    let abi = vec![0u8; MaxContractMetadataLength::get() as usize - 7];
+    ContractVerification::register(RawOrigin::Signed(caller.clone()).into(), contract, b"v0.6.12".to_vec(), vec![], abi, H256::zero())?;
+  }: _(RawOrigin::Signed(caller), contract)
+  verify {
+    assert_eq!(ContractVerification::contracts(&contract), None);
+  }
+
+  unregister_from_evm {
+    let (deployer, contract) = deploy_contract()?;
+    let abi = vec![0u8; MaxContractMetadataLength::get() as usize - 7];
+    ContractVerification::register_from_evm(RawOrigin::Signed(truncated_account(&deployer)).into(), contract, b"v0.6.12".to_vec(), vec![], abi, H256::zero())?;
+  }: _(RawOrigin::Signed(truncated_account(&deployer)), contract)
+  verify {
+    assert_eq!(ContractVerification::contracts(&contract), None);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_register() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_register());
+    });
+  }
+
+  #[test]
+  fn test_register_from_evm() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_register_from_evm());
+    });
+  }
+
+  #[test]
+  fn test_force_register() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_force_register());
+    });
+  }
+
+  #[test]
+  fn test_unregister() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_unregister());
+    });
+  }
+
+  #[test]
+  fn test_unregister_from_evm() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_unregister_from_evm());
+    });
+  }
+}
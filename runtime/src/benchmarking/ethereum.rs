@@ -0,0 +1,122 @@
+use crate::{AccountId, Ethereum, Runtime};
+
+use clover_ethereum::{SponsorLimit, SponsorLimits, SponsoredAccountCounts, SponsoredAccounts};
+use clover_evm::AddressMapping;
+use frame_support::{StorageDoubleMap, StorageMap};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_core::{H160, U256};
+use sp_std::prelude::*;
+
+const MAX_ACCOUNTS: u32 = 1_000;
+
+fn sponsor() -> (H160, AccountId) {
+  let sponsor = H160::repeat_byte(1);
+  (sponsor, <Runtime as clover_evm::Trait>::AddressMapping::into_account_id(&sponsor))
+}
+
+fn limit() -> SponsorLimit {
+  SponsorLimit {
+    max_gas_price: U256::from(1_000_000_000u64),
+    max_gas_limit: U256::from(1_000_000u64),
+    remaining_fee: U256::from(1_000_000_000_000u64),
+  }
+}
+
+fn accounts(count: u32) -> Vec<H160> {
+  (0 .. count).map(|i| H160::from_low_u64_be(i as u64 + 1)).collect()
+}
+
+/// Set up a sponsor of `count` accounts.
+fn sponsored_accounts(sponsor: &H160, count: u32) -> Vec<H160> {
+  SponsorLimits::insert(sponsor, limit());
+  let accounts = accounts(count);
+  for account in &accounts {
+    SponsoredAccounts::insert(sponsor, account, true);
+  }
+  SponsoredAccountCounts::insert(sponsor, count);
+  accounts
+}
+
+runtime_benchmarks! {
+  { Runtime, clover_ethereum }
+
+  _ {}
+
+  set_sponsor_limit {
+    let (sponsor, caller) = sponsor();
+  }: _(RawOrigin::Signed(caller), limit())
+  verify {
+    assert_eq!(Ethereum::sponsor_limits(&sponsor), Some(limit()));
+  }
+
+  // all the `a` sponsored accounts are removed
+  remove_sponsor {
+    let a in 0 .. MAX_ACCOUNTS;
+    let (sponsor, caller) = sponsor();
+    sponsored_accounts(&sponsor, a);
+  }: _(RawOrigin::Signed(caller), a)
+  verify {
+    assert_eq!(Ethereum::sponsor_limits(&sponsor), None);
+    assert_eq!(Ethereum::sponsored_account_counts(&sponsor), 0);
+  }
+
+  add_sponsored_accounts {
+    let a in 1 .. MAX_ACCOUNTS;
+    let (sponsor, caller) = sponsor();
+    sponsored_accounts(&sponsor, 0);
+  }: _(RawOrigin::Signed(caller), accounts(a))
+  verify {
+    assert_eq!(Ethereum::sponsored_account_counts(&sponsor), a);
+  }
+
+  remove_sponsored_accounts {
+    let a in 1 .. MAX_ACCOUNTS;
+    let (sponsor, caller) = sponsor();
+    let accounts = sponsored_accounts(&sponsor, a);
+  }: _(RawOrigin::Signed(caller), accounts)
+  verify {
+    assert_eq!(Ethereum::sponsored_account_counts(&sponsor), 0);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_set_sponsor_limit() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_set_sponsor_limit());
+    });
+  }
+
+  #[test]
+  fn test_remove_sponsor() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_remove_sponsor());
+    });
+  }
+
+  #[test]
+  fn test_add_sponsored_accounts() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_add_sponsored_accounts());
+    });
+  }
+
+  #[test]
+  fn test_remove_sponsored_accounts() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_remove_sponsored_accounts());
+    });
+  }
+}
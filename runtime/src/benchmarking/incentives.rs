@@ -0,0 +1,69 @@
+use crate::{CurrencyId, Incentives, Runtime, System};
+
+use clover_incentives::{PairKey, PoolId};
+
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use primitives::currency::DOLLARS;
+use sp_std::prelude::*;
+
+fn pool_id() -> PoolId {
+  PoolId::Dex(PairKey::try_from(CurrencyId::CLV, CurrencyId::DOT).unwrap())
+}
+
+fn create_program() -> Result<(), &'static str> {
+  Incentives::create_program(RawOrigin::Root.into(), pool_id(), CurrencyId::CLV, DOLLARS, 0, None, Some(100))?;
+  System::set_block_number(10);
+  Ok(())
+}
+
+runtime_benchmarks! {
+  { Runtime, clover_incentives }
+
+  _ {}
+
+  create_program {
+  }: _(RawOrigin::Root, pool_id(), CurrencyId::CLV, DOLLARS, 0, Some(1000), Some(100))
+
+  update_program {
+    create_program()?;
+  }: _(RawOrigin::Root, pool_id(), CurrencyId::CLV, 2 * DOLLARS, Some(1000), Some(200))
+
+  end_program {
+    create_program()?;
+  }: _(RawOrigin::Root, pool_id(), CurrencyId::CLV)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_create_program() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_create_program());
+    });
+  }
+
+  #[test]
+  fn test_update_program() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_update_program());
+    });
+  }
+
+  #[test]
+  fn test_end_program() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_end_program());
+    });
+  }
+}
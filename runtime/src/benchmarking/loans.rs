@@ -0,0 +1,38 @@
+use crate::{CurrencyId, Loans, Rate, Runtime};
+
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::FixedPointNumber;
+use sp_std::prelude::*;
+
+runtime_benchmarks! {
+  { Runtime, clover_loans }
+
+  _ {}
+
+  set_debit_exchange_rate {
+  }: _(RawOrigin::Root, CurrencyId::DOT, Some(Rate::saturating_from_integer(2)))
+  verify {
+    assert_eq!(Loans::debit_exchange_rate(CurrencyId::DOT), Some(Rate::saturating_from_integer(2)));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_set_debit_exchange_rate() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_set_debit_exchange_rate());
+    });
+  }
+}
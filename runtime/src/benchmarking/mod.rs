@@ -0,0 +1,11 @@
+//! Benchmarks of the clover modules, using the runtime as config.
+
+pub mod cloverdex;
+pub mod contract_verification;
+pub mod ethereum;
+pub mod incentives;
+pub mod loans;
+pub mod oracle_staking;
+pub mod prices;
+pub mod reward_pool;
+pub mod vaults;
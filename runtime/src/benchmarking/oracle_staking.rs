@@ -0,0 +1,118 @@
+use crate::{
+  AccountId, Balances, CloverOracle, CurrencyId, MaxOracleOperators, MinOperatorBond, OracleStaking,
+  OracleUnbondingPeriod, Price, Runtime, System,
+};
+
+use frame_benchmarking::account;
+use frame_support::traits::{Currency, Get, OnInitialize};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use primitives::currency::DOLLARS;
+use sp_runtime::FixedPointNumber;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+fn funded_account(name: &'static str, index: u32) -> AccountId {
+  let who: AccountId = account(name, index, SEED);
+  Balances::make_free_balance_be(&who, MinOperatorBond::get() * 10);
+  who
+}
+
+fn bonded_operators(count: u32) -> Result<Vec<AccountId>, &'static str> {
+  let mut operators = vec![];
+  for i in 0 .. count {
+    let who = funded_account("operator", i);
+    OracleStaking::bond(RawOrigin::Signed(who.clone()).into(), MinOperatorBond::get())?;
+    operators.push(who);
+  }
+  Ok(operators)
+}
+
+runtime_benchmarks! {
+  { Runtime, clover_oracle_staking }
+
+  _ {}
+
+  // the members are recomputed from all the operators `o`
+  bond {
+    let o in 1 .. MaxOracleOperators::get();
+    bonded_operators(o - 1)?;
+    let caller = funded_account("caller", 0);
+  }: _(RawOrigin::Signed(caller.clone()), MinOperatorBond::get() * 2)
+  verify {
+    assert!(OracleStaking::members().contains(&caller));
+  }
+
+  unbond {
+    let o in 1 .. MaxOracleOperators::get();
+    bonded_operators(o - 1)?;
+    let caller = funded_account("caller", 0);
+    OracleStaking::bond(RawOrigin::Signed(caller.clone()).into(), MinOperatorBond::get())?;
+  }: _(RawOrigin::Signed(caller.clone()), MinOperatorBond::get())
+  verify {
+    assert!(!OracleStaking::members().contains(&caller));
+  }
+
+  withdraw_unbonded {
+    let caller = funded_account("caller", 0);
+    OracleStaking::bond(RawOrigin::Signed(caller.clone()).into(), MinOperatorBond::get())?;
+    OracleStaking::unbond(RawOrigin::Signed(caller.clone()).into(), MinOperatorBond::get())?;
+    System::set_block_number(System::block_number() + OracleUnbondingPeriod::get());
+  }: _(RawOrigin::Signed(caller.clone()))
+  verify {
+    assert_eq!(OracleStaking::operators(&caller), None);
+  }
+
+  // every feed `f` is rewarded
+  check_feeds {
+    let f in 1 .. MaxOracleOperators::get();
+    Balances::make_free_balance_be(&OracleStaking::account_id(), 1_000 * DOLLARS);
+    for who in bonded_operators(f)? {
+      CloverOracle::feed_values(RawOrigin::Signed(who).into(), vec![(CurrencyId::DOT, Price::saturating_from_integer(10))])?;
+    }
+  }: {
+    OracleStaking::on_initialize(System::block_number() + 1);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_bond() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_bond());
+    });
+  }
+
+  #[test]
+  fn test_unbond() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_unbond());
+    });
+  }
+
+  #[test]
+  fn test_withdraw_unbonded() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_withdraw_unbonded());
+    });
+  }
+
+  #[test]
+  fn test_check_feeds() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_check_feeds());
+    });
+  }
+}
@@ -0,0 +1,110 @@
+use crate::{CloverOracle, CurrencyId, FeedCurrencies, Price, Prices, Runtime, System};
+
+use clover_prices::PriceFeedParams;
+use clover_traits::PriceProvider;
+use frame_support::traits::{Get, OnFinalize};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use sp_runtime::FixedPointNumber;
+use sp_std::prelude::*;
+
+fn feed_price(currency_id: CurrencyId, price: Price) -> Result<(), &'static str> {
+  CloverOracle::feed_values(RawOrigin::Root.into(), vec![(currency_id, price)])?;
+  // allow feeding again in the same block
+  CloverOracle::on_finalize(0);
+  Ok(())
+}
+
+fn feed_valid_price(currency_id: CurrencyId, price: Price) -> Result<(), &'static str> {
+  feed_price(currency_id, price)?;
+  Prices::on_finalize(System::block_number());
+  Ok(())
+}
+
+runtime_benchmarks! {
+  { Runtime, clover_prices }
+
+  _ {}
+
+  lock_price {
+    feed_valid_price(CurrencyId::DOT, Price::saturating_from_integer(10))?;
+  }: _(RawOrigin::Root, CurrencyId::DOT)
+  verify {
+    assert_eq!(Prices::locked_price(CurrencyId::DOT), Some(Price::saturating_from_integer(10)));
+  }
+
+  unlock_price {
+    feed_valid_price(CurrencyId::DOT, Price::saturating_from_integer(10))?;
+    <Prices as PriceProvider<CurrencyId, Price>>::lock_price(CurrencyId::DOT);
+  }: _(RawOrigin::Root, CurrencyId::DOT)
+  verify {
+    assert_eq!(Prices::locked_price(CurrencyId::DOT), None);
+  }
+
+  set_feed_params {
+    let params = PriceFeedParams {
+      max_age: 60_000,
+      max_deviation: Price::saturating_from_rational(1, 10),
+    };
+  }: _(RawOrigin::Root, CurrencyId::DOT, Some(params))
+  verify {
+    assert_eq!(Prices::feed_params(CurrencyId::DOT), Some(params));
+  }
+
+  // the last valid price of each currency is set, so the deviation is checked for all of them
+  update_prices {
+    let c in 1 .. (FeedCurrencies::get().len() as u32);
+    let currencies = FeedCurrencies::get();
+    for currency_id in currencies.iter().take(c as usize) {
+      feed_valid_price(*currency_id, Price::saturating_from_integer(10))?;
+      feed_price(*currency_id, Price::saturating_from_integer(10))?;
+    }
+    System::set_block_number(System::block_number() + 1);
+  }: {
+    Prices::on_finalize(System::block_number());
+  }
+  verify {
+    assert_eq!(Prices::valid_prices(currencies[0]).map(|valid| valid.updated_at), Some(System::block_number()));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_lock_price() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_lock_price());
+    });
+  }
+
+  #[test]
+  fn test_unlock_price() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_unlock_price());
+    });
+  }
+
+  #[test]
+  fn test_set_feed_params() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_set_feed_params());
+    });
+  }
+
+  #[test]
+  fn test_update_prices() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_update_prices());
+    });
+  }
+}
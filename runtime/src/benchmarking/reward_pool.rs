@@ -0,0 +1,79 @@
+use crate::{AccountId, Currencies, CurrencyId, RewardPool, Runtime};
+
+use clover_incentives::{IncentiveProgram, IncentivePrograms, PairKey, PoolId};
+use frame_benchmarking::account;
+use frame_support::StorageDoubleMap;
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::MultiCurrency;
+use primitives::currency::DOLLARS;
+use reward_pool::RewardBudgets;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+fn funded_account(name: &'static str, index: u32) -> AccountId {
+  let who: AccountId = account(name, index, SEED);
+  let _ = <Currencies as MultiCurrency<_>>::deposit(CurrencyId::DOT, &who, 1_000_000 * DOLLARS);
+  who
+}
+
+fn dex_pool() -> Result<PoolId, &'static str> {
+  let pair_key = PairKey::try_from(CurrencyId::CLV, CurrencyId::DOT).ok_or("invalid pair")?;
+  Ok(PoolId::Dex(pair_key))
+}
+
+runtime_benchmarks! {
+  { Runtime, reward_pool }
+
+  _ {}
+
+  deposit_rewards {
+    let caller = funded_account("caller", 0);
+    let pool_id = dex_pool()?;
+    IncentivePrograms::<Runtime>::insert(pool_id, CurrencyId::DOT, IncentiveProgram {
+      reward_per_block: DOLLARS,
+      start: 0,
+      end: None,
+      halving_period: None,
+    });
+  }: _(RawOrigin::Signed(caller), pool_id, CurrencyId::DOT, 1_000 * DOLLARS)
+
+  // the budget of a pool without a program in the currency is refunded
+  refund_rewards {
+    let pool_id = dex_pool()?;
+    let dest: AccountId = account("dest", 0, SEED);
+    <Currencies as MultiCurrency<_>>::deposit(CurrencyId::DOT, &RewardPool::account_id(), 1_000 * DOLLARS)?;
+    RewardBudgets::<Runtime>::insert(pool_id, CurrencyId::DOT, 1_000 * DOLLARS);
+  }: _(RawOrigin::Root, pool_id, CurrencyId::DOT, dest.clone())
+  verify {
+    assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(CurrencyId::DOT, &dest), 1_000 * DOLLARS);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_deposit_rewards() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_deposit_rewards());
+    });
+  }
+
+  #[test]
+  fn test_refund_rewards() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_refund_rewards());
+    });
+  }
+}
@@ -0,0 +1,127 @@
+use crate::{AccountId, CloverDex, Currencies, CurrencyId, RewardPool, Runtime, System, VaultCompoundPeriod, Vaults};
+
+use clover_incentives::{IncentiveProgram, IncentivePrograms, PairKey, PoolId};
+use frame_benchmarking::account;
+use frame_support::{traits::OnInitialize, StorageDoubleMap};
+use frame_system::RawOrigin;
+use orml_benchmarking::runtime_benchmarks;
+use orml_traits::MultiCurrency;
+use primitives::currency::DOLLARS;
+use sp_std::prelude::*;
+
+const SEED: u32 = 0;
+
+// the vault pair, and the pools to swap the CLV and DOT rewards to it
+const VAULT_PAIR: (CurrencyId, CurrencyId) = (CurrencyId::CUSDT, CurrencyId::CETH);
+const PAIRS: [(CurrencyId, CurrencyId); 4] = [
+  (CurrencyId::CUSDT, CurrencyId::CETH),
+  (CurrencyId::CLV, CurrencyId::CUSDT),
+  (CurrencyId::CLV, CurrencyId::CETH),
+  (CurrencyId::CETH, CurrencyId::DOT),
+];
+
+fn funded_account(name: &'static str, index: u32) -> AccountId {
+  let who: AccountId = account(name, index, SEED);
+  for currency_id in &[CurrencyId::CLV, CurrencyId::CUSDT, CurrencyId::DOT, CurrencyId::CETH] {
+    let _ = <Currencies as MultiCurrency<_>>::deposit(*currency_id, &who, 1_000_000 * DOLLARS);
+  }
+  who
+}
+
+fn create_vault(who: &AccountId) -> Result<(), &'static str> {
+  for (first, second) in PAIRS.iter() {
+    CloverDex::add_liquidity(RawOrigin::Signed(who.clone()).into(), *first, *second, 1_000 * DOLLARS, 1_000 * DOLLARS)?;
+  }
+  let (first, second) = VAULT_PAIR;
+  Vaults::create_vault(RawOrigin::Root.into(), first, second)?;
+  Ok(())
+}
+
+runtime_benchmarks! {
+  { Runtime, clover_vaults }
+
+  _ {}
+
+  create_vault {
+    let caller = funded_account("caller", 0);
+    let (first, second) = VAULT_PAIR;
+    CloverDex::add_liquidity(RawOrigin::Signed(caller).into(), first, second, 1_000 * DOLLARS, 1_000 * DOLLARS)?;
+  }: _(RawOrigin::Root, first, second)
+
+  deposit {
+    let caller = funded_account("caller", 0);
+    create_vault(&caller)?;
+    let (first, second) = VAULT_PAIR;
+    Vaults::deposit(RawOrigin::Signed(caller.clone()).into(), first, second, 100 * DOLLARS)?;
+  }: _(RawOrigin::Signed(caller), first, second, 100 * DOLLARS)
+
+  withdraw {
+    let caller = funded_account("caller", 0);
+    create_vault(&caller)?;
+    let (first, second) = VAULT_PAIR;
+    Vaults::deposit(RawOrigin::Signed(caller.clone()).into(), first, second, 100 * DOLLARS)?;
+  }: _(RawOrigin::Signed(caller), first, second, 50 * DOLLARS)
+
+  // worst case: rewards in two currencies, none of them in the pool, one swapped with two hops
+  compound {
+    let caller = funded_account("caller", 0);
+    create_vault(&caller)?;
+    let (first, second) = VAULT_PAIR;
+    let pair_key = PairKey::try_from(first, second).ok_or("invalid pair")?;
+    for reward_currency_id in &[CurrencyId::CLV, CurrencyId::DOT] {
+      IncentivePrograms::<Runtime>::insert(PoolId::Dex(pair_key), reward_currency_id, IncentiveProgram {
+        reward_per_block: DOLLARS,
+        start: 0,
+        end: None,
+        halving_period: None,
+      });
+      RewardPool::deposit_rewards(RawOrigin::Signed(caller.clone()).into(), PoolId::Dex(pair_key), *reward_currency_id, 1_000 * DOLLARS)?;
+    }
+    Vaults::deposit(RawOrigin::Signed(caller).into(), first, second, 100 * DOLLARS)?;
+    let now = System::block_number() + VaultCompoundPeriod::get();
+    System::set_block_number(now);
+  }: {
+    Vaults::on_initialize(now);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use frame_support::assert_ok;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    frame_system::GenesisConfig::default()
+      .build_storage::<Runtime>()
+      .unwrap()
+      .into()
+  }
+
+  #[test]
+  fn test_create_vault() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_create_vault());
+    });
+  }
+
+  #[test]
+  fn test_deposit() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_deposit());
+    });
+  }
+
+  #[test]
+  fn test_withdraw() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_withdraw());
+    });
+  }
+
+  #[test]
+  fn test_compound() {
+    new_test_ext().execute_with(|| {
+      assert_ok!(test_benchmark_compound());
+    });
+  }
+}
@@ -0,0 +1,134 @@
+//! Annual percentage rate estimates of the incentive pools.
+//!
+//! The rewards the programs of a pool pay in the coming year, limited by the reward budgets of the pool,
+//! are valued with `clover_prices` and divided by the value of the staked shares in the pool.
+//! Shares of a dex pool are its staked lp tokens, valued by their part of the pool liquidity,
+//! the boost of stake locks adds reward shares which aren't backed by lp tokens and is left out.
+//! Shares of loans pools are debits, valued in the stable currency with the debit exchange rate,
+//! shares of loans deposit and single asset pools are amounts of the pool currency,
+//! shares of the evm usage pool are gas, which has no price.
+//!
+//! The historical APR annualizes the rewards `reward_pool` recorded as paid in the last `PaidRewardsHistory` periods instead.
+//! Rewards are paid, and recorded, when a pool is updated, the rewards of a pool nobody updated since are left out.
+
+use frame_support::{storage::IterableStorageDoubleMap, traits::Get};
+use sp_runtime::{
+  helpers_128bit::multiply_by_rational,
+  traits::{Saturating, Zero},
+  FixedPointNumber,
+};
+use sp_std::prelude::*;
+use clover_traits::PriceProvider;
+use primitives::{Balance, BlockNumber, CurrencyId, PoolId, Price, Rate, Share};
+
+use crate::{
+  CloverDex, DAYS, GetStableCurrencyId, Incentives, Loans, PaidRewardsHistory, PaidRewardsPeriod, Prices, RewardPool,
+  Runtime, System,
+};
+
+pub const BLOCKS_PER_YEAR: BlockNumber = 365 * DAYS;
+
+/// Value of a share of the pool.
+fn share_price(pool_id: &PoolId) -> Option<Price> {
+  match pool_id {
+    PoolId::Dex(pair_key) => {
+      let (left, right) = (pair_key.left(), pair_key.right());
+      let (balance_left, balance_right) = CloverDex::get_pool_info(left, right).ok()?;
+      let total_shares = CloverDex::total_shares(CloverDex::get_pair_key(&left, &right));
+      let liquidity = Prices::get_price(left)?.saturating_mul_int(balance_left)
+        .saturating_add(Prices::get_price(right)?.saturating_mul_int(balance_right));
+      Price::checked_from_rational(liquidity, total_shares)
+    },
+    PoolId::Loans(currency_id) => Prices::get_price(GetStableCurrencyId::get())?
+      .checked_mul(&Loans::get_debit_exchange_rate(*currency_id)),
+    PoolId::Single(currency_id) | PoolId::LoansDeposit(currency_id) => Prices::get_price(*currency_id),
+    PoolId::EvmUsage => None,
+  }
+}
+
+/// Shares of the pool backed by value, the reward shares without the boost of stake locks.
+fn staked_shares(pool_id: &PoolId) -> Share {
+  let total_shares = RewardPool::get_pool_info(pool_id).total_shares;
+  match pool_id {
+    PoolId::Dex(pair_key) =>
+      total_shares.saturating_sub(CloverDex::get_total_stake_boost(pair_key.left(), pair_key.right())),
+    _ => total_shares,
+  }
+}
+
+/// Value of the yearly rewards of the pool divided by the value of its staked shares,
+/// `None` if the pool has no shares or a price is unknown.
+fn pool_apr(pool_id: &PoolId, rewards: Vec<(CurrencyId, Balance)>) -> Option<Rate> {
+  let shares = staked_shares(pool_id);
+  if shares.is_zero() {
+    return None;
+  }
+  let shares_value = share_price(pool_id)?.saturating_mul_int(shares);
+
+  let mut rewards_value: Balance = Zero::zero();
+  for (currency_id, reward) in rewards {
+    rewards_value = rewards_value.saturating_add(Prices::get_price(currency_id)?.saturating_mul_int(reward));
+  }
+  Rate::checked_from_rational(rewards_value, shares_value)
+}
+
+/// Estimated APR of every pool with an active incentive program.
+pub fn get_pool_aprs() -> Vec<(PoolId, Option<Rate>)> {
+  let now = System::block_number();
+  let mut pool_ids: Vec<PoolId> = Vec::new();
+  for (pool_id, ..) in Incentives::get_active_programs() {
+    if !pool_ids.contains(&pool_id) {
+      pool_ids.push(pool_id);
+    }
+  }
+
+  pool_ids.into_iter()
+    .map(|pool_id| {
+      let rewards = Incentives::get_program_rewards(&pool_id, now, now.saturating_add(BLOCKS_PER_YEAR))
+        .into_iter()
+        .map(|(currency_id, reward)| (currency_id, reward.min(RewardPool::reward_budgets(&pool_id, currency_id))))
+        .collect();
+      (pool_id, pool_apr(&pool_id, rewards))
+    })
+    .collect()
+}
+
+/// APR of every pool paid rewards in the kept periods of `reward_pool::PaidRewards`,
+/// the rewards paid since the start of the first kept period of the pool are annualized.
+pub fn get_historical_pool_aprs() -> Vec<(PoolId, Option<Rate>)> {
+  let now = System::block_number();
+  let period = PaidRewardsPeriod::get();
+  // periods before are only left until their pool is paid again
+  let oldest_kept = (now - now % period).saturating_sub(period.saturating_mul(PaidRewardsHistory::get().saturating_sub(1)));
+  let mut pool_rewards: Vec<(PoolId, BlockNumber, Vec<(CurrencyId, Balance)>)> = Vec::new();
+  for (pool_id, period_start, paid_rewards) in reward_pool::PaidRewards::<Runtime>::iter() {
+    if period_start < oldest_kept {
+      continue;
+    }
+    match pool_rewards.iter_mut().find(|(id, ..)| *id == pool_id) {
+      Some((_, from, rewards)) => {
+        *from = period_start.min(*from);
+        for (currency_id, paid) in paid_rewards {
+          match rewards.iter_mut().find(|(id, _)| *id == currency_id) {
+            Some((_, reward)) => *reward = reward.saturating_add(paid),
+            None => rewards.push((currency_id, paid)),
+          }
+        }
+      },
+      None => pool_rewards.push((pool_id, period_start, paid_rewards)),
+    }
+  }
+
+  pool_rewards.into_iter()
+    .map(|(pool_id, from, rewards)| {
+      let elapsed = now.saturating_sub(from).max(1);
+      let yearly_rewards = rewards.into_iter()
+        .map(|(currency_id, reward)| {
+          let yearly = multiply_by_rational(reward, BLOCKS_PER_YEAR.into(), elapsed.into()).unwrap_or_else(|_| Balance::max_value());
+          (currency_id, yearly)
+        })
+        .collect();
+      (pool_id, pool_apr(&pool_id, yearly_rewards))
+    })
+    .collect()
+}
@@ -15,14 +15,13 @@ use sp_core::{
 use sp_runtime::{
   ApplyExtrinsicResult, generic, create_runtime_str, FixedPointNumber, impl_opaque_keys, Percent,
   ModuleId, transaction_validity::{TransactionPriority, TransactionValidity, TransactionSource},
-  DispatchResult, OpaqueExtrinsic
+  OpaqueExtrinsic
 };
 use sp_runtime::traits::{
-  BlakeTwo256, Block as BlockT, Convert, NumberFor, OpaqueKeys, SaturatedConversion, Saturating,
+  AccountIdConversion, BlakeTwo256, Block as BlockT, Convert, NumberFor, OpaqueKeys, SaturatedConversion, Saturating,
   StaticLookup,
 };
 use sp_runtime::curve::PiecewiseLinear;
-use enum_iterator::IntoEnumIterator;
 
 use sp_api::impl_runtime_apis;
 pub use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
@@ -39,7 +38,7 @@ use sp_core::{u32_trait::{_1, _2, _4, _5}};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
 
-use orml_traits::{create_median_value_data_provider, MultiCurrency, DataFeeder};
+use orml_traits::MultiCurrency;
 use orml_currencies::{BasicCurrencyAdapter};
 
 pub use pallet_staking::StakerStatus;
@@ -73,13 +72,22 @@ pub use primitives::{
 };
 
 pub use constants::{time::*, };
+pub use signature::{ExtrinsicSignature, ethereum_signing_message};
+pub use payment::ChargeMultiCurrencyPayment;
+pub use clover_incentives::{PairKey, PoolId as IncentivePoolId};
 
 use clover_traits::incentive_ops::IncentiveOps;
 
 mod weights;
 mod constants;
+mod signature;
+mod payment;
+mod incentive_apr;
+mod precompiles;
 mod mock;
 mod tests;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
@@ -110,10 +118,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
   spec_name: create_runtime_str!("clover"),
   impl_name: create_runtime_str!("clover"),
   authoring_version: 1,
-  spec_version: 3,
+  spec_version: 4,
   impl_version: 1,
   apis: RUNTIME_API_VERSIONS,
-  transaction_version: 1,
+  transaction_version: 2,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -319,6 +327,21 @@ impl FeeCalculator for FixedGasPrice {
 
 parameter_types! {
 	pub const ChainId: u64 = 1337;
+  pub const DepositPerStorageSlot: Balance = 20 * MILLICENTS;
+  pub const DepositPerCodeByte: Balance = 4 * MILLICENTS;
+}
+
+parameter_types! {
+  pub const ContractMetadataDepositPerByte: Balance = 10 * MILLICENTS;
+  pub const MaxContractMetadataLength: u32 = 256 * 1024;
+}
+
+impl clover_contract_verification::Trait for Runtime {
+  type Event = Event;
+  type DepositPerByte = ContractMetadataDepositPerByte;
+  type MaxMetadataLength = MaxContractMetadataLength;
+  type ForceOrigin = EnsureRootOrHalfGeneralCouncil;
+  type WeightInfo = weights::clover_contract_verification::WeightInfo<Runtime>;
 }
 
 impl clover_evm::Trait for Runtime {
@@ -329,14 +352,13 @@ impl clover_evm::Trait for Runtime {
   type AddressMapping = EvmAddressMapping<Runtime>;
   type Currency = Balances;
   type MergeAccount = Currencies;
+  type DefaultDepositPerStorageSlot = DepositPerStorageSlot;
+  type DefaultDepositPerCodeByte = DepositPerCodeByte;
+  type DepositOrigin = EnsureRootOrHalfGeneralCouncil;
+  type PermissionOrigin = EnsureRootOrHalfGeneralCouncil;
   type Event = Event;
   type Runner = clover_evm::runner::stack::Runner<Self>;
-  type Precompiles = (
-    clover_evm::precompiles::ECRecover,
-    clover_evm::precompiles::Sha256,
-    clover_evm::precompiles::Ripemd160,
-    clover_evm::precompiles::Identity,
-  );
+  type Precompiles = precompiles::CloverPrecompiles;
   type ChainId = ChainId;
 }
 
@@ -357,6 +379,8 @@ impl<F: FindAuthor<u32>> FindAuthor<H160> for EthereumFindAuthor<F>
 impl clover_ethereum::Trait for Runtime {
   type Event = Event;
   type FindAuthor = EthereumFindAuthor<Babe>;
+  type OnTransactionExecuted = clover_incentives::OnEvmUsage<Runtime>;
+  type WeightInfo = weights::clover_ethereum::WeightInfo<Runtime>;
 }
 
 pub struct TransactionConverter;
@@ -719,6 +743,8 @@ impl pallet_treasury::Trait for Runtime {
 
 parameter_types! {
   pub const TransactionByteFee: Balance = 1;
+  /// Max slippage when swapping the fee currency to CLV.
+  pub MaxFeeSwapSlippage: primitives::Ratio = primitives::Ratio::saturating_from_rational(5, 100);
 }
 
 impl pallet_transaction_payment::Trait for Runtime {
@@ -777,7 +803,7 @@ where
       frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
       frame_system::CheckNonce::<Runtime>::from(nonce),
       frame_system::CheckWeight::<Runtime>::new(),
-      pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+      ChargeMultiCurrencyPayment::from(tip),
     );
     let raw_payload = SignedPayload::new(call, extra)
       .map_err(|e| {
@@ -787,7 +813,7 @@ where
     let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
     let address = Indices::unlookup(account);
     let (call, extra, _) = raw_payload.deconstruct();
-    Some((call, (address, signature, extra)))
+    Some((call, (address, signature.into(), extra)))
   }
 }
 
@@ -835,6 +861,9 @@ impl orml_currencies::Config for Runtime {
 parameter_types! {
   pub const RewardModuleId: ModuleId = ModuleId(*b"clv/repm");
   pub const ExistentialReward: u128 = 100;
+  pub const MigratedRewardPeriod: BlockNumber = 30 * DAYS;
+  pub const PaidRewardsPeriod: BlockNumber = DAYS;
+  pub const PaidRewardsHistory: u32 = 30;
 }
 
 impl reward_pool::Trait for Runtime {
@@ -844,16 +873,37 @@ impl reward_pool::Trait for Runtime {
   type Currency = Currencies;
   type GetNativeCurrencyId = GetNativeCurrencyId;
   type ExistentialReward = ExistentialReward;
+  type MigratedRewardPeriod = MigratedRewardPeriod;
+  type MigratedRewardSource = TreasuryAccount;
+  type RefundOrigin = EnsureRootOrHalfGeneralCouncil;
+  type PaidRewardsPeriod = PaidRewardsPeriod;
+  type PaidRewardsHistory = PaidRewardsHistory;
   type Handler = Incentives;
+  type WeightInfo = weights::reward_pool::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+  pub const EvmUsageWindow: BlockNumber = DAYS;
 }
 
 impl clover_incentives::Trait for Runtime {
+  type Event = Event;
   type RewardPool = RewardPool;
+  type GetNativeCurrencyId = GetNativeCurrencyId;
+  type UpdateOrigin = EnsureRootOrHalfGeneralCouncil;
+  type EvmUsageWindow = EvmUsageWindow;
+  type WeightInfo = weights::clover_incentives::WeightInfo<Runtime>;
 }
 
 parameter_types! {
   pub GetExchangeFee: Rate = Rate::saturating_from_rational(1, 1000);
   pub const CloverdexModuleId: ModuleId = ModuleId(*b"clv/dexm");
+  pub const MaxStakeLockDuration: BlockNumber = 4 * 52 * 7 * DAYS;
+  pub MaxStakeBoost: Rate = Rate::saturating_from_rational(5, 2);
+  pub EarlyUnlockPenalty: primitives::Ratio = primitives::Ratio::saturating_from_rational(1, 10);
+  pub const MaxStakeLocks: u32 = 8;
+  pub const DexTwapWindow: BlockNumber = 30 * MINUTES;
+  pub TreasuryAccount: AccountId = TreasuryModuleId::get().into_account();
 }
 
 impl cloverdex::Trait for Runtime {
@@ -864,23 +914,53 @@ impl cloverdex::Trait for Runtime {
   type ModuleId = CloverdexModuleId;
   type OnAddLiquidity = ();
   type OnRemoveLiquidity = ();
+  type MaxStakeLockDuration = MaxStakeLockDuration;
+  type MaxStakeBoost = MaxStakeBoost;
+  type EarlyUnlockPenalty = EarlyUnlockPenalty;
+  type MaxStakeLocks = MaxStakeLocks;
+  type TreasuryAccount = TreasuryAccount;
+  type TwapWindow = DexTwapWindow;
   type IncentiveOps = Incentives;
+  type WeightInfo = weights::cloverdex::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+  pub const VaultsModuleId: ModuleId = ModuleId(*b"clv/valt");
+  pub const VaultCompoundPeriod: BlockNumber = 1 * HOURS;
+  pub MaxVaultCompoundWeight: Weight = MaximumBlockWeight::get() / 10;
+  pub MaxVaultSwapSlippage: primitives::Ratio = primitives::Ratio::saturating_from_rational(3, 100);
+}
+
+impl clover_vaults::Trait for Runtime {
+  type Event = Event;
+  type VaultModuleId = VaultsModuleId;
+  type CompoundPeriod = VaultCompoundPeriod;
+  type MaxCompoundWeight = MaxVaultCompoundWeight;
+  type MaxSwapSlippage = MaxVaultSwapSlippage;
+  type UpdateOrigin = EnsureRootOrHalfGeneralCouncil;
+  type WeightInfo = weights::clover_vaults::WeightInfo<Runtime>;
 }
 
 parameter_types! {
   pub const LoansModuleId: ModuleId = ModuleId(*b"clv/loan");
+  pub DefaultDebitExchangeRate: Rate = Rate::saturating_from_rational(1, 1);
 }
 
 impl clover_loans::Trait for Runtime {
   type Event = Event;
   type Currency = Currencies;
   type ModuleId = LoansModuleId;
+  type OnUpdateLoan = clover_incentives::OnUpdateLoan<Runtime>;
+  type OnUpdateCollateral = clover_incentives::OnUpdateCollateral<Runtime>;
+  type GetStableCurrencyId = GetStableCurrencyId;
+  type DefaultDebitExchangeRate = DefaultDebitExchangeRate;
+  type WeightInfo = weights::clover_loans::WeightInfo<Runtime>;
 }
 
 type CloverDataProvider = orml_oracle::Instance1;
 impl orml_oracle::Config<CloverDataProvider> for Runtime {
   type Event = Event;
-  type OnNewData = ();
+  type OnNewData = OracleStaking;
   type CombineData = orml_oracle::DefaultCombineData<Runtime, MinimumCount, ExpiresIn, CloverDataProvider>;
   type Time = Timestamp;
   type OracleKey = CurrencyId;
@@ -889,6 +969,49 @@ impl orml_oracle::Config<CloverDataProvider> for Runtime {
   type WeightInfo = ();
 }
 
+parameter_types! {
+  pub const OracleStakingModuleId: ModuleId = ModuleId(*b"clv/orcl");
+  pub const MinOperatorBond: Balance = 10_000 * DOLLARS;
+  pub const MaxOracleOperators: u32 = 21;
+  pub const OracleFeedReward: Balance = 10 * CENTS;
+  pub MaxOracleFeedDeviation: primitives::Ratio = primitives::Ratio::saturating_from_rational(5, 100);
+  pub const OracleStrikeThreshold: u32 = 10;
+  pub const OracleSlashRatio: Perbill = Perbill::from_percent(10);
+  pub const OracleUnbondingPeriod: BlockNumber = 7 * DAYS;
+}
+
+impl clover_oracle_staking::Trait for Runtime {
+  type Event = Event;
+  type Currency = Balances;
+  type Oracle = CloverOracle;
+  type OnMembersChanged = CloverOracle;
+  type ModuleId = OracleStakingModuleId;
+  type MinOperatorBond = MinOperatorBond;
+  type MaxOperators = MaxOracleOperators;
+  type FeedReward = OracleFeedReward;
+  type MaxFeedDeviation = MaxOracleFeedDeviation;
+  type StrikeThreshold = OracleStrikeThreshold;
+  type SlashRatio = OracleSlashRatio;
+  type Slash = clover_oracle_staking::RewardPot<Runtime>;
+  type UnbondingPeriod = OracleUnbondingPeriod;
+  type OnBond = clover_incentives::OnBondNative<Runtime>;
+  type OnUnbond = clover_incentives::OnUnbondNative<Runtime>;
+  type WeightInfo = weights::clover_oracle_staking::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+  pub const PriceFeedInterval: BlockNumber = 5;
+  pub MaxPriceSourceDeviation: primitives::Ratio = primitives::Ratio::saturating_from_rational(3, 100);
+  pub const MinPriceSources: u32 = 1;
+}
+
+impl clover_price_feeder::Trait for Runtime {
+  type AuthorityId = clover_price_feeder::crypto::OracleAuthId;
+  type FeedInterval = PriceFeedInterval;
+  type MaxSourceDeviation = MaxPriceSourceDeviation;
+  type MinSources = MinPriceSources;
+}
+
 type BandDataProvider = orml_oracle::Instance2;
 impl orml_oracle::Config<BandDataProvider> for Runtime {
   type Event = Event;
@@ -901,21 +1024,6 @@ impl orml_oracle::Config<BandDataProvider> for Runtime {
   type WeightInfo = ();
 }
 
-type TimeStampedPrice = orml_oracle::TimestampedValue<Price, primitives::Moment>;
-create_median_value_data_provider!(
-  AggregatedDataProvider,
-  CurrencyId,
-  Price,
-  TimeStampedPrice,
-  [CloverOracle, BandOracle]
-);
-// Aggregated data provider cannot feed.
-impl DataFeeder<CurrencyId, Price, AccountId> for AggregatedDataProvider {
-  fn feed_value(_: AccountId, _: CurrencyId, _: Price) -> DispatchResult {
-    Err("Not supported".into())
-  }
-}
-
 parameter_types! {
   pub const TombstoneDeposit: Balance = 16 * MILLICENTS;
   pub const RentByteFee: Balance = 4 * MILLICENTS;
@@ -948,6 +1056,9 @@ parameter_types! {
   pub const MinimumCount: u32 = 1;
   pub const ExpiresIn: Moment = 1000 * 60 * 60; // 60 mins
   pub ZeroAccountId: AccountId = AccountId::from([0u8; 32]);
+  pub FeedCurrencies: Vec<CurrencyId> = vec![CurrencyId::CLV, CurrencyId::DOT, CurrencyId::CETH];
+  pub const DefaultMaxPriceAge: Moment = 1000 * 60 * 10; // 10 mins
+  pub DefaultMaxPriceDeviation: primitives::Ratio = primitives::Ratio::saturating_from_rational(5, 100); // per block
 }
 
 type EnsureRootOrHalfGeneralCouncil = EnsureOneOf<
@@ -958,10 +1069,17 @@ type EnsureRootOrHalfGeneralCouncil = EnsureOneOf<
 
 impl clover_prices::Trait for Runtime {
   type Event = Event;
-  type Source = AggregatedDataProvider;
+  type CloverSource = CloverOracle;
+  type BandSource = BandOracle;
+  type DexSource = CloverDex;
+  type Time = Timestamp;
+  type FeedCurrencies = FeedCurrencies;
+  type DefaultMaxPriceAge = DefaultMaxPriceAge;
+  type DefaultMaxPriceDeviation = DefaultMaxPriceDeviation;
   type GetStableCurrencyId = GetStableCurrencyId;
   type StableCurrencyFixedPrice = StableCurrencyFixedPrice;
   type LockOrigin = EnsureRootOrHalfGeneralCouncil;
+  type WeightInfo = weights::clover_prices::WeightInfo<Runtime>;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -1000,19 +1118,23 @@ construct_runtime!(
 
     // Clover module
     CloverDex: cloverdex::{Module, Storage, Call, Event<T>, Config},
-    RewardPool: reward_pool::{Module, Storage, Call, Event<T>,},
-    Incentives: clover_incentives::{Module, Storage, Call, Config},
+    RewardPool: reward_pool::{Module, Storage, Call, Event<T>, Config<T>},
+    Incentives: clover_incentives::{Module, Storage, Call, Event<T>, Config},
+    Vaults: clover_vaults::{Module, Storage, Call, Event<T>},
     Prices: clover_prices::{Module, Storage, Call, Event},
     Loans: clover_loans::{Module, Storage, Call, Event<T>},
 
     // oracle
     CloverOracle: orml_oracle::<Instance1>::{Module, Storage, Call, Config<T>, Event<T>},
     BandOracle: orml_oracle::<Instance2>::{Module, Storage, Call, Config<T>, Event<T>},
+    PriceFeeder: clover_price_feeder::{Module},
+    OracleStaking: clover_oracle_staking::{Module, Storage, Call, Event<T>},
 
     // Smart contracts modules
     Contracts: pallet_contracts::{Module, Call, Config, Storage, Event<T>},
     EVM: clover_evm::{Module, Config, Call, Storage, Event<T>},
     Ethereum: clover_ethereum::{Module, Call, Storage, Event, Config, ValidateUnsigned},
+    ContractVerification: clover_contract_verification::{Module, Call, Storage, Event<T>},
 
     Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
 
@@ -1047,12 +1169,22 @@ pub type SignedExtra = (
   frame_system::CheckEra<Runtime>,
   frame_system::CheckNonce<Runtime>,
   frame_system::CheckWeight<Runtime>,
-  pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+  ChargeMultiCurrencyPayment,
 );
 /// Unchecked extrinsic type as expected by this runtime.
-pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
+/// Extrinsics can be signed with either a substrate or an ethereum key.
+pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, ExtrinsicSignature, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+/// Migrations which should run before the migrations of the modules.
+pub struct CustomOnRuntimeUpgrade;
+impl frame_support::traits::OnRuntimeUpgrade for CustomOnRuntimeUpgrade {
+  fn on_runtime_upgrade() -> frame_support::weights::Weight {
+    // the reward pool seeds the reward budgets from the migrated incentive programs
+    Incentives::migrate_fixed_rewards()
+  }
+}
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
   Runtime,
@@ -1060,6 +1192,7 @@ pub type Executive = frame_executive::Executive<
   frame_system::ChainContext<Runtime>,
   Runtime,
   AllModules,
+  CustomOnRuntimeUpgrade,
 >;
 
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -1258,7 +1391,7 @@ impl_runtime_apis! {
       let mut balances = sp_std::vec::Vec::new();
       match currency_id {
         None => {
-          for cid in CurrencyId::into_enum_iter() {
+          for cid in CurrencyId::TOKENS.iter().copied() {
             balances.push((cid, Currencies::total_balance(cid, &account)));
           }
         },
@@ -1301,15 +1434,89 @@ impl_runtime_apis! {
       result
     }
 
-    fn get_staking_info(account: AccountId, currency_first: CurrencyId, currency_second: CurrencyId) -> (Share, Balance) {
-      let result = Incentives::get_account_info(&account, &currency_first, &currency_second);
-      (result.shares, result.accumlated_rewards)
+    fn get_staking_info(account: AccountId, currency_first: CurrencyId, currency_second: CurrencyId) -> (Share, sp_std::vec::Vec<(CurrencyId, Balance)>) {
+      let accumlated_rewards = PairKey::try_from(currency_first, currency_second)
+        .map(|pair_key| Incentives::get_accumlated_rewards(&account, &IncentivePoolId::Dex(pair_key)))
+        .unwrap_or_default();
+      (CloverDex::get_staked_shares(&account, currency_first, currency_second), accumlated_rewards)
     }
   }
 
   impl clover_rpc_runtime_api::IncentivePoolApi<Block, AccountId, CurrencyId, Balance, Share> for Runtime {
-    fn get_all_incentive_pools() -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Share, Balance)> {
+    fn get_all_incentive_pools() -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Share, sp_std::vec::Vec<(CurrencyId, Balance)>)> {
       Incentives::get_all_incentive_pools()
+        .into_iter()
+        .filter_map(|(pool_id, shares, rewards)| {
+          pool_id.currency_pair().map(|(left, right)| (left, right, shares, rewards))
+        })
+        .collect()
+    }
+  }
+
+  impl clover_rpc_runtime_api::IncentiveProgramApi<Block, CurrencyId, Balance, BlockNumber> for Runtime {
+    fn get_active_incentive_programs() -> sp_std::vec::Vec<(CurrencyId, CurrencyId, CurrencyId, Balance, BlockNumber, Option<BlockNumber>, Option<BlockNumber>)> {
+      Incentives::get_active_programs()
+        .into_iter()
+        .filter_map(|(pool_id, reward_currency_id, reward_per_block, start, end, halving_period)| {
+          pool_id.currency_pair()
+            .map(|(left, right)| (left, right, reward_currency_id, reward_per_block, start, end, halving_period))
+        })
+        .collect()
+    }
+  }
+
+  impl clover_rpc_runtime_api::IncentiveRewardApi<Block, AccountId, IncentivePoolId, CurrencyId, Balance, Share, Rate> for Runtime {
+    fn get_pending_rewards(account: AccountId) -> sp_std::vec::Vec<(IncentivePoolId, Share, sp_std::vec::Vec<(CurrencyId, Balance)>)> {
+      RewardPool::get_account_pending_rewards(&account, System::block_number().saturating_add(1))
+    }
+
+    fn get_pool_aprs() -> sp_std::vec::Vec<(IncentivePoolId, Option<Rate>)> {
+      incentive_apr::get_pool_aprs()
+    }
+
+    fn get_historical_pool_aprs() -> sp_std::vec::Vec<(IncentivePoolId, Option<Rate>)> {
+      incentive_apr::get_historical_pool_aprs()
+    }
+  }
+
+  impl clover_rpc_runtime_api::StakeLockApi<Block, AccountId, CurrencyId, Share, Rate, BlockNumber> for Runtime {
+    fn get_stake_locks(account: AccountId) -> sp_std::vec::Vec<(CurrencyId, CurrencyId, Share, Rate, sp_std::vec::Vec<(Share, Rate, BlockNumber)>)> {
+      CloverDex::get_stake_locks(&account)
+        .into_iter()
+        .map(|(left, right, staked_shares, multiplier, locks)| {
+          let locks = locks.into_iter().map(|lock| (lock.amount, lock.multiplier, lock.unlock_at)).collect();
+          (left, right, staked_shares, multiplier, locks)
+        })
+        .collect()
+    }
+  }
+
+  impl clover_rpc_runtime_api::ContractVerificationApi<Block, H160, AccountId, H256, BlockNumber> for Runtime {
+    fn get_contract_metadata(contract: H160) -> Option<(AccountId, Vec<u8>, Vec<u8>, Vec<u8>, H256, H256, BlockNumber, bool)> {
+      ContractVerification::contracts(&contract).map(|metadata| (
+        metadata.registrant,
+        metadata.compiler_version,
+        metadata.settings,
+        metadata.abi,
+        metadata.source_hash,
+        metadata.code_hash,
+        metadata.registered_at,
+        ContractVerification::code_unchanged(&contract),
+      ))
+    }
+  }
+
+  impl clover_rpc_runtime_api::CurrencyFeeApi<Block, CurrencyId, Balance> for Runtime {
+    fn query_fee_info(uxt: <Block as BlockT>::Extrinsic, len: u32) -> (CurrencyId, Balance) {
+      use frame_support::weights::GetDispatchInfo;
+      use payment::FeePayment;
+
+      let info = uxt.get_dispatch_info();
+      let quote = uxt.signature.as_ref().and_then(|(address, _, extra)| {
+        let who = <Runtime as frame_system::Trait>::Lookup::lookup(address.clone()).ok()?;
+        extra.fee_payment().map(|payment| payment.quote_fee(&who, &info, len as usize))
+      });
+      quote.unwrap_or_else(|| (CurrencyId::CLV, TransactionPayment::query_info(uxt, len).partial_fee))
     }
   }
 
@@ -1395,7 +1602,7 @@ impl_runtime_apis! {
             gas_price,
             nonce,
             config.as_ref().unwrap_or(<Runtime as clover_evm::Trait>::config()),
-        ).map_err(|err| err.into())
+        ).map(|(info, _)| info).map_err(|err| err.into())
     }
 
     fn current_transaction_statuses() -> Option<Vec<TransactionStatus>> {
@@ -1421,5 +1628,56 @@ impl_runtime_apis! {
             Ethereum::current_transaction_statuses()
         )
     }
+
+    fn extrinsic_filter(xts: Vec<<Block as BlockT>::Extrinsic>) -> Vec<clover_ethereum::Transaction> {
+        xts.into_iter().filter_map(|xt| match xt.function {
+            Call::Ethereum(clover_ethereum::Call::transact(transaction)) |
+            Call::Ethereum(clover_ethereum::Call::transact_sponsored(transaction, _)) => Some(transaction),
+            _ => None,
+        }).collect()
+    }
+
+    fn current_exit_reasons() -> Option<Vec<(clover_evm::ExitReason, Vec<u8>)>> {
+        Ethereum::current_exit_reasons()
+    }
+  }
+
+  #[cfg(feature = "runtime-benchmarks")]
+  impl frame_benchmarking::Benchmark<Block> for Runtime {
+    fn dispatch_benchmark(
+      config: frame_benchmarking::BenchmarkConfig
+    ) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+      use frame_benchmarking::{BenchmarkBatch, TrackedStorageKey};
+      use orml_benchmarking::add_benchmark;
+
+      let whitelist: Vec<TrackedStorageKey> = vec![
+        // Block Number
+        hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec().into(),
+        // Total Issuance
+        hex_literal::hex!("c2261276cc9d1f8598ea4b6a74b15c2f57c875e4cff74148e4628f264b974c80").to_vec().into(),
+        // Execution Phase
+        hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a").to_vec().into(),
+        // Event Count
+        hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850").to_vec().into(),
+        // System Events
+        hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7").to_vec().into(),
+      ];
+
+      let mut batches = Vec::<BenchmarkBatch>::new();
+      let params = (&config, &whitelist);
+
+      add_benchmark!(params, batches, cloverdex, benchmarking::cloverdex);
+      add_benchmark!(params, batches, clover_contract_verification, benchmarking::contract_verification);
+      add_benchmark!(params, batches, clover_ethereum, benchmarking::ethereum);
+      add_benchmark!(params, batches, clover_incentives, benchmarking::incentives);
+      add_benchmark!(params, batches, clover_loans, benchmarking::loans);
+      add_benchmark!(params, batches, clover_oracle_staking, benchmarking::oracle_staking);
+      add_benchmark!(params, batches, clover_prices, benchmarking::prices);
+      add_benchmark!(params, batches, reward_pool, benchmarking::reward_pool);
+      add_benchmark!(params, batches, clover_vaults, benchmarking::vaults);
+
+      if batches.is_empty() { return Err("Benchmark not found for this module.".into()) }
+      Ok(batches)
+    }
   }
 }
@@ -0,0 +1,215 @@
+//! Transaction payment in any currency tradable on cloverdex.
+//!
+//! `ChargeMultiCurrencyPayment` wraps `pallet_transaction_payment::ChargeTransactionPayment`,
+//! when a fee currency is given, just enough of it is swapped to CLV before the fee is charged.
+//! The swap is only quoted when the transaction is validated and done in `pre_dispatch`,
+//! its weight is added to the block and charged with the fee.
+//! Refunds of unused weight are paid back in CLV.
+//! Calls which don't pay a fee never swap, their tip is charged in CLV.
+
+use codec::{Decode, Encode};
+use frame_support::{traits::Currency, weights::{DispatchInfo, Pays, PostDispatchInfo, Weight}};
+use orml_traits::MultiCurrency;
+use sp_runtime::{
+  traits::{DispatchInfoOf, One, PostDispatchInfoOf, SaturatedConversion, Saturating, SignedExtension, Zero},
+  FixedPointNumber,
+  transaction_validity::{
+    InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError, ValidTransaction,
+  },
+  DispatchResult, RuntimeDebug,
+};
+use primitives::{AccountId, Balance, CurrencyId, Ratio};
+
+use crate::{
+  Balances, Call, CloverDex, Currencies, ExistentialDeposit, MaxFeeSwapSlippage, MaximumBlockLength,
+  MaximumBlockWeight, Runtime, System, TransactionPayment,
+};
+
+type ChargeTransactionPayment = pallet_transaction_payment::ChargeTransactionPayment<Runtime>;
+
+/// Require the transactor pay for themselves, optionally in a currency other than CLV.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct ChargeMultiCurrencyPayment {
+  #[codec(compact)]
+  tip: Balance,
+  fee_currency: Option<CurrencyId>,
+}
+
+impl ChargeMultiCurrencyPayment {
+  /// Pay the fee and `tip` in `fee_currency`, CLV is used if `fee_currency` is `None`.
+  pub fn new(tip: Balance, fee_currency: Option<CurrencyId>) -> Self {
+    Self { tip, fee_currency }
+  }
+
+  pub fn fee_currency(&self) -> CurrencyId {
+    self.fee_currency.unwrap_or(CurrencyId::CLV)
+  }
+
+  /// CLV to swap from the fee currency for the fee of the transaction, including the swap weight,
+  /// `None` if no swap is needed.
+  fn fee_swap(&self, who: &AccountId, info: &DispatchInfo, len: usize) -> Option<(CurrencyId, Balance)> {
+    let currency_id = self.fee_currency();
+    if currency_id == CurrencyId::CLV || info.pays_fee == Pays::No {
+      return None;
+    }
+
+    let fee = TransactionPayment::compute_fee(len as u32, &with_swap_weight(info), self.tip);
+    // keep the account alive after the fee is withdrawn
+    let needed = fee
+      .saturating_add(ExistentialDeposit::get())
+      .saturating_sub(Balances::free_balance(who));
+    if needed.is_zero() {
+      None
+    } else {
+      Some((currency_id, needed))
+    }
+  }
+
+  /// Currency and amount `who` is expected to pay for the transaction, as `pre_dispatch` charges it:
+  /// the fee including the swap weight and the `ExistentialDeposit` top-up, without the swap slippage.
+  pub fn quote_fee(&self, who: &AccountId, info: &DispatchInfo, len: usize) -> (CurrencyId, Balance) {
+    match self.fee_swap(who, info, len) {
+      Some((currency_id, needed)) => {
+        let (supply_amount, _) = CloverDex::get_supply_amount_needed(currency_id, CurrencyId::CLV, needed);
+        (currency_id, supply_amount)
+      }
+      None => (CurrencyId::CLV, TransactionPayment::compute_fee(len as u32, info, self.tip)),
+    }
+  }
+}
+
+/// Finds the `ChargeMultiCurrencyPayment` of a signed extension tuple, whatever its position.
+pub trait FeePayment {
+  fn fee_payment(&self) -> Option<&ChargeMultiCurrencyPayment> {
+    None
+  }
+}
+
+impl FeePayment for ChargeMultiCurrencyPayment {
+  fn fee_payment(&self) -> Option<&ChargeMultiCurrencyPayment> {
+    Some(self)
+  }
+}
+
+impl<T: frame_system::Trait> FeePayment for frame_system::CheckSpecVersion<T> {}
+impl<T: frame_system::Trait> FeePayment for frame_system::CheckTxVersion<T> {}
+impl<T: frame_system::Trait> FeePayment for frame_system::CheckGenesis<T> {}
+impl<T: frame_system::Trait> FeePayment for frame_system::CheckEra<T> {}
+impl<T: frame_system::Trait> FeePayment for frame_system::CheckNonce<T> {}
+impl<T: frame_system::Trait> FeePayment for frame_system::CheckWeight<T> {}
+
+#[impl_trait_for_tuples::impl_for_tuples(12)]
+impl FeePayment for Tuple {
+  fn fee_payment(&self) -> Option<&ChargeMultiCurrencyPayment> {
+    for_tuples!( #( if let Some(payment) = Tuple.fee_payment() { return Some(payment); } )* );
+    None
+  }
+}
+
+/// Weight of swapping the fee currency to CLV.
+pub fn swap_weight() -> Weight {
+  CloverDex::swap_for_exact_target_weight()
+}
+
+fn with_swap_weight(info: &DispatchInfo) -> DispatchInfo {
+  DispatchInfo { weight: info.weight.saturating_add(swap_weight()), ..*info }
+}
+
+/// Priority of a transaction paying `fee`, the same as `ChargeTransactionPayment` gives.
+fn priority(len: usize, info: &DispatchInfo, fee: Balance) -> TransactionPriority {
+  let weight_saturation = MaximumBlockWeight::get() / info.weight.max(1);
+  let len_saturation = MaximumBlockLength::get() as u64 / (len as u64).max(1);
+  let coefficient: Balance = weight_saturation.min(len_saturation).saturated_into();
+  fee.saturating_mul(coefficient).saturated_into()
+}
+
+impl From<Balance> for ChargeMultiCurrencyPayment {
+  fn from(tip: Balance) -> Self {
+    Self::new(tip, None)
+  }
+}
+
+impl SignedExtension for ChargeMultiCurrencyPayment {
+  const IDENTIFIER: &'static str = "ChargeMultiCurrencyPayment";
+  type AccountId = AccountId;
+  type Call = Call;
+  type AdditionalSigned = ();
+  /// The pre dispatch data of `ChargeTransactionPayment` and the weight of the fee swap.
+  type Pre = (<ChargeTransactionPayment as SignedExtension>::Pre, Weight);
+
+  fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> { Ok(()) }
+
+  fn validate(
+    &self,
+    who: &Self::AccountId,
+    call: &Self::Call,
+    info: &DispatchInfoOf<Self::Call>,
+    len: usize,
+  ) -> TransactionValidity {
+    let (currency_id, needed) = match self.fee_swap(who, info, len) {
+      Some(swap) => swap,
+      None => return ChargeTransactionPayment::from(self.tip).validate(who, call, info, len),
+    };
+
+    // only quote the swap, it is done when the transaction is dispatched
+    let supply_amount = fee_in_currency(currency_id, needed);
+    if supply_amount.is_zero() || Currencies::free_balance(currency_id, who) < supply_amount {
+      return InvalidTransaction::Payment.into();
+    }
+    let info = with_swap_weight(info);
+    let fee = TransactionPayment::compute_fee(len as u32, &info, self.tip);
+    Ok(ValidTransaction {
+      priority: priority(len, &info, fee),
+      ..Default::default()
+    })
+  }
+
+  fn pre_dispatch(
+    self,
+    who: &Self::AccountId,
+    call: &Self::Call,
+    info: &DispatchInfoOf<Self::Call>,
+    len: usize,
+  ) -> Result<Self::Pre, TransactionValidityError> {
+    let (currency_id, needed) = match self.fee_swap(who, info, len) {
+      Some(swap) => swap,
+      None => return ChargeTransactionPayment::from(self.tip).pre_dispatch(who, call, info, len).map(|pre| (pre, 0)),
+    };
+
+    CloverDex::swap_for_exact_target(who, currency_id, CurrencyId::CLV, needed, MaxFeeSwapSlippage::get())
+      .map_err(|_| TransactionValidityError::from(InvalidTransaction::Payment))?;
+    let swap_weight = swap_weight();
+    System::register_extra_weight_unchecked(swap_weight, info.class);
+
+    ChargeTransactionPayment::from(self.tip)
+      .pre_dispatch(who, call, &with_swap_weight(info), len)
+      .map(|pre| (pre, swap_weight))
+  }
+
+  fn post_dispatch(
+    (pre, swap_weight): Self::Pre,
+    info: &DispatchInfoOf<Self::Call>,
+    post_info: &PostDispatchInfoOf<Self::Call>,
+    len: usize,
+    result: &DispatchResult,
+  ) -> Result<(), TransactionValidityError> {
+    // the swap weight is not refunded
+    let info = DispatchInfo { weight: info.weight.saturating_add(swap_weight), ..*info };
+    let post_info = PostDispatchInfo {
+      actual_weight: post_info.actual_weight.map(|weight| weight.saturating_add(swap_weight)),
+      pays_fee: post_info.pays_fee,
+    };
+    ChargeTransactionPayment::post_dispatch(pre, &info, &post_info, len, result)
+  }
+}
+
+/// Amount of `currency_id` needed to pay a CLV `fee`, including the max swap slippage.
+fn fee_in_currency(currency_id: CurrencyId, fee: Balance) -> Balance {
+  if currency_id == CurrencyId::CLV {
+    fee
+  } else {
+    let (amount, _) = CloverDex::get_supply_amount_needed(currency_id, CurrencyId::CLV, fee);
+    Ratio::one().saturating_add(MaxFeeSwapSlippage::get()).saturating_mul_int(amount)
+  }
+}
+
@@ -0,0 +1,92 @@
+//! Precompiles of the evm, the standard ones and the cloverdex quotes.
+//!
+//! The cloverdex precompile quotes the dex for evm contracts, the tokens are given by their
+//! `CurrencyId::evm_address`, so WCLV is quoted as CLV:
+//! `getAmountOut(address tokenIn, address tokenOut, uint256 amountIn)` returns the amount of `tokenOut` paid for `amountIn`,
+//! `getAmountIn(address tokenIn, address tokenOut, uint256 amountOut)` returns the amount of `tokenIn` needed for `amountOut`,
+//! zero if the tokens can't be exchanged.
+
+use clover_evm::{
+  precompiles::{ECRecover, Identity, Ripemd160, Sha256},
+  ExitError, ExitSucceed, Precompile, Precompiles,
+};
+use primitives::{Balance, CurrencyId};
+use sp_core::{H160, U256};
+use sp_std::vec::Vec;
+
+use crate::CloverDex;
+
+/// Address of the cloverdex precompile, next to WCLV
+pub const CLOVERDEX_PRECOMPILE_ADDRESS: H160 = H160([
+  0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x08, 0x03,
+]);
+
+/// Gas of a quote, the route finding reads at most `MAX_CANDIDATE_ROUTES * max_route_hops` pools
+const QUOTE_GAS: usize = 50_000;
+
+/// `getAmountOut(address,address,uint256)`
+const GET_AMOUNT_OUT: [u8; 4] = [0x4a, 0xa0, 0x66, 0x52];
+/// `getAmountIn(address,address,uint256)`
+const GET_AMOUNT_IN: [u8; 4] = [0x53, 0xb6, 0x09, 0xb5];
+
+/// The standard precompiles at the addresses 1 to 4, and the cloverdex precompile.
+pub struct CloverPrecompiles;
+
+impl Precompiles for CloverPrecompiles {
+  fn execute(
+    address: H160,
+    input: &[u8],
+    target_gas: Option<usize>,
+  ) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError>> {
+    if address == CLOVERDEX_PRECOMPILE_ADDRESS {
+      return Some(CloverDexPrecompile::execute(input, target_gas));
+    }
+    <(ECRecover, Sha256, Ripemd160, Identity) as Precompiles>::execute(address, input, target_gas)
+  }
+}
+
+/// Quotes of cloverdex in the evm.
+pub struct CloverDexPrecompile;
+
+impl CloverDexPrecompile {
+  /// The token of an abi encoded address.
+  fn token(word: &[u8]) -> Result<CurrencyId, ExitError> {
+    if word[..12].iter().any(|b| *b != 0) {
+      return Err(ExitError::Other("invalid address".into()));
+    }
+    CurrencyId::from_evm_address(&H160::from_slice(&word[12..])).ok_or_else(|| ExitError::Other("unknown token".into()))
+  }
+}
+
+impl Precompile for CloverDexPrecompile {
+  fn execute(
+    input: &[u8],
+    target_gas: Option<usize>,
+  ) -> core::result::Result<(ExitSucceed, Vec<u8>, usize), ExitError> {
+    if target_gas.map_or(false, |gas| gas < QUOTE_GAS) {
+      return Err(ExitError::OutOfGas);
+    }
+    if input.len() != 100 {
+      return Err(ExitError::Other("invalid input".into()));
+    }
+    let token_in = Self::token(&input[4..36])?;
+    let token_out = Self::token(&input[36..68])?;
+    let amount = U256::from_big_endian(&input[68..100]);
+    if amount > U256::from(Balance::max_value()) {
+      return Err(ExitError::Other("amount overflow".into()));
+    }
+    let amount = amount.low_u128();
+
+    let quote = if input[..4] == GET_AMOUNT_OUT {
+      CloverDex::get_target_amount_available(token_in, token_out, amount).0
+    } else if input[..4] == GET_AMOUNT_IN {
+      CloverDex::get_supply_amount_needed(token_in, token_out, amount).0
+    } else {
+      return Err(ExitError::Other("unknown function".into()));
+    };
+
+    let mut output = [0u8; 32];
+    U256::from(quote).to_big_endian(&mut output);
+    Ok((ExitSucceed::Returned, output.to_vec(), QUOTE_GAS))
+  }
+}
@@ -0,0 +1,60 @@
+//! Extrinsic signature which accepts both substrate keys and ethereum keys.
+//!
+//! An ethereum key signs the extrinsic signing payload with `personal_sign`,
+//! the recovered evm address is mapped to the substrate account using
+//! `EvmAddressMapping`, so nonce and fees are handled by the mapped account.
+
+use codec::{Decode, Encode};
+use sp_runtime::{
+  traits::{Lazy, Verify},
+  transaction_validity::TransactionValidityError,
+  RuntimeDebug,
+};
+use sp_std::prelude::*;
+use clover_evm::AddressMapping;
+use evm_accounts::{EcdsaSignature, EvmAddressMapping};
+
+use crate::{AccountId, Call, EvmAccounts, Runtime, Signature, SignedExtra, SignedPayload};
+
+#[derive(Eq, PartialEq, Clone, Encode, Decode, RuntimeDebug)]
+pub enum ExtrinsicSignature {
+  /// A substrate signature, sr25519, ed25519 or ecdsa.
+  Substrate(Signature),
+  /// A secp256k1 signature produced by an ethereum wallet's `personal_sign`.
+  Ethereum(EcdsaSignature),
+}
+
+impl Verify for ExtrinsicSignature {
+  type Signer = <Signature as Verify>::Signer;
+
+  fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId) -> bool {
+    match self {
+      ExtrinsicSignature::Substrate(sig) => sig.verify(msg, signer),
+      ExtrinsicSignature::Ethereum(sig) => {
+        match EvmAccounts::recover_extrinsic_signer(sig, msg.get()) {
+          Some(address) => &EvmAddressMapping::<Runtime>::into_account_id(&address) == signer,
+          None => false,
+        }
+      },
+    }
+  }
+}
+
+impl From<Signature> for ExtrinsicSignature {
+  fn from(sig: Signature) -> Self {
+    ExtrinsicSignature::Substrate(sig)
+  }
+}
+
+impl From<EcdsaSignature> for ExtrinsicSignature {
+  fn from(sig: EcdsaSignature) -> Self {
+    ExtrinsicSignature::Ethereum(sig)
+  }
+}
+
+/// Build the message an ethereum wallet should `personal_sign` to authorize `call`.
+/// The signer is the account `EvmAddressMapping` maps the wallet address to.
+pub fn ethereum_signing_message(call: Call, extra: SignedExtra) -> Result<Vec<u8>, TransactionValidityError> {
+  let raw_payload = SignedPayload::new(call, extra)?;
+  Ok(raw_payload.using_encoded(EvmAccounts::extrinsic_signing_message))
+}
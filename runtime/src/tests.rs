@@ -23,3 +23,409 @@ fn test_balance() {
       assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(CLV, &AccountId::from(ALICE)), 1500);
     });
 }
+
+#[test]
+fn test_ethereum_signed_extrinsic() {
+  use sp_runtime::traits::Verify;
+  use clover_evm::AddressMapping;
+
+  ExtBuilder::default().build().execute_with(|| {
+    let secret = secp256k1::SecretKey::parse(&sp_io::hashing::keccak_256(b"Alice")).unwrap();
+    let address = EvmAccounts::eth_address(&secret);
+    let signer = EvmAddressMapping::<Runtime>::into_account_id(&address);
+    let payload = b"clover extrinsic payload";
+
+    let signature = ExtrinsicSignature::from(EvmAccounts::eth_sign(&secret, payload, &[]));
+    assert!(signature.verify(&payload[..], &signer));
+    assert!(!signature.verify(&payload[..], &AccountId::from(ALICE)));
+    assert!(!signature.verify(&b"another payload"[..], &signer));
+  });
+}
+
+mod fee_payment {
+  use super::*;
+  use frame_support::{assert_ok, weights::{DispatchClass, DispatchInfo, PostDispatchInfo, Pays}};
+  use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+  use payment::FeePayment;
+
+  const FEE_WEIGHT: Weight = 1_000;
+  const LEN: usize = 100;
+
+  fn remark() -> Call {
+    Call::System(frame_system::Call::remark(vec![]))
+  }
+
+  fn info() -> DispatchInfo {
+    DispatchInfo { weight: FEE_WEIGHT, ..Default::default() }
+  }
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    let alice = AccountId::from(ALICE);
+    let mut ext = ExtBuilder::default()
+      .balances(vec![
+        (alice.clone(), CLV, 0),
+        (alice.clone(), CUSDT, 1_000 * DOLLARS),
+        (AccountId::from(BOB), CLV, 0),
+        (AccountId::from(BOB), CUSDT, 10 * DOLLARS),
+        (AccountId::from(DAVE), CLV, 0),
+        (AccountId::from(DAVE), CUSDT, 1),
+      ])
+      .build();
+    ext.execute_with(|| {
+      assert_ok!(<Currencies as MultiCurrencyExtended<_>>::update_balance(CLV, &alice, (1_000 * DOLLARS) as Amount));
+      assert_ok!(CloverDex::add_liquidity(Origin::signed(alice), CLV, CUSDT, 100 * DOLLARS, 100 * DOLLARS));
+    });
+    ext
+  }
+
+  #[test]
+  fn validate_only_quotes_the_fee_swap() {
+    new_test_ext().execute_with(|| {
+      let bob = AccountId::from(BOB);
+      let payment = ChargeMultiCurrencyPayment::new(0, Some(CUSDT));
+      assert_ok!(payment.validate(&bob, &remark(), &info(), LEN));
+      assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(CUSDT, &bob), 10 * DOLLARS);
+      assert_eq!(Balances::free_balance(&bob), ExistentialDeposit::get());
+
+      // not enough fee currency for the quoted swap
+      assert_eq!(
+        payment.validate(&AccountId::from(DAVE), &remark(), &info(), LEN),
+        Err(InvalidTransaction::Payment.into())
+      );
+    });
+  }
+
+  #[test]
+  fn pre_dispatch_swaps_and_charges_the_swap_weight() {
+    new_test_ext().execute_with(|| {
+      let bob = AccountId::from(BOB);
+      let swap_weight = payment::swap_weight();
+      let block_weight = System::block_weight().get(DispatchClass::Normal);
+
+      let pre = ChargeMultiCurrencyPayment::new(0, Some(CUSDT))
+        .pre_dispatch(&bob, &remark(), &info(), LEN)
+        .unwrap();
+      assert_eq!(pre.1, swap_weight);
+      assert_eq!(System::block_weight().get(DispatchClass::Normal), block_weight + swap_weight);
+      assert!(<Currencies as MultiCurrency<_>>::free_balance(CUSDT, &bob) < 10 * DOLLARS);
+      // the swapped CLV paid the fee including the swap weight
+      assert_eq!(Balances::free_balance(&bob), ExistentialDeposit::get());
+
+      // only the unused call weight is refunded
+      let post_info = PostDispatchInfo { actual_weight: Some(0), pays_fee: Pays::Yes };
+      assert_ok!(ChargeMultiCurrencyPayment::post_dispatch(pre, &info(), &post_info, LEN, &Ok(())));
+      assert_eq!(Balances::free_balance(&bob), ExistentialDeposit::get() + FEE_WEIGHT as Balance);
+    });
+  }
+
+  #[test]
+  fn quote_is_what_pre_dispatch_charges() {
+    new_test_ext().execute_with(|| {
+      let bob = AccountId::from(BOB);
+      let payment = ChargeMultiCurrencyPayment::new(0, Some(CUSDT));
+      let (currency_id, quote) = payment.quote_fee(&bob, &info(), LEN);
+      assert_eq!(currency_id, CUSDT);
+
+      assert_ok!(payment.pre_dispatch(&bob, &remark(), &info(), LEN));
+      assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(CUSDT, &bob), 10 * DOLLARS - quote);
+    });
+  }
+
+  #[test]
+  fn fee_payment_is_found_in_the_signed_extra() {
+    let extra: SignedExtra = (
+      frame_system::CheckSpecVersion::<Runtime>::new(),
+      frame_system::CheckTxVersion::<Runtime>::new(),
+      frame_system::CheckGenesis::<Runtime>::new(),
+      frame_system::CheckEra::<Runtime>::from(generic::Era::Immortal),
+      frame_system::CheckNonce::<Runtime>::from(0),
+      frame_system::CheckWeight::<Runtime>::new(),
+      ChargeMultiCurrencyPayment::new(0, Some(CUSDT)),
+    );
+    assert_eq!(extra.fee_payment().map(|payment| payment.fee_currency()), Some(CUSDT));
+  }
+
+  #[test]
+  fn free_calls_do_not_swap() {
+    new_test_ext().execute_with(|| {
+      let bob = AccountId::from(BOB);
+      let info = DispatchInfo { pays_fee: Pays::No, ..info() };
+      let payment = ChargeMultiCurrencyPayment::new(0, Some(CUSDT));
+      assert_eq!(payment.quote_fee(&bob, &info, LEN), (CLV, 0));
+
+      let pre = payment.pre_dispatch(&bob, &remark(), &info, LEN).unwrap();
+      assert_eq!(pre.1, 0);
+      assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(CUSDT, &bob), 10 * DOLLARS);
+      assert_eq!(Balances::free_balance(&bob), ExistentialDeposit::get());
+    });
+  }
+
+  #[test]
+  fn clv_fee_does_not_swap() {
+    new_test_ext().execute_with(|| {
+      let alice = AccountId::from(ALICE);
+      let clv_before = Balances::free_balance(&alice);
+      let pre = ChargeMultiCurrencyPayment::new(0, None)
+        .pre_dispatch(&alice, &remark(), &info(), LEN)
+        .unwrap();
+      assert_eq!(pre.1, 0);
+      assert_eq!(
+        Balances::free_balance(&alice),
+        clv_before - TransactionPayment::compute_fee(LEN as u32, &info(), 0)
+      );
+      assert_eq!(<Currencies as MultiCurrency<_>>::free_balance(CUSDT, &alice), 900 * DOLLARS);
+    });
+  }
+}
+
+mod incentive_apr {
+  use super::*;
+  use frame_support::{assert_ok, traits::{Get, OnFinalize}};
+  use clover_traits::RewardPoolOps;
+  use crate::incentive_apr::{get_historical_pool_aprs, get_pool_aprs, BLOCKS_PER_YEAR};
+
+  const REWARD_BUDGET: Balance = 10_000_000 * DOLLARS;
+
+  fn new_test_ext() -> sp_io::TestExternalities {
+    let alice = AccountId::from(ALICE);
+    let mut ext = ExtBuilder::default()
+      .balances(vec![
+        (alice.clone(), CLV, 0),
+        (alice.clone(), CUSDT, 2 * REWARD_BUDGET),
+      ])
+      .build();
+    ext.execute_with(|| {
+      System::set_block_number(1);
+      assert_ok!(<Currencies as MultiCurrencyExtended<_>>::update_balance(CLV, &alice, (1_000 * DOLLARS) as Amount));
+    });
+    ext
+  }
+
+  /// create a program paying a dollar of the stable currency every block to the pool
+  fn create_program(pool_id: IncentivePoolId) {
+    assert_ok!(Incentives::create_program(Origin::root(), pool_id, CUSDT, DOLLARS, 1, None, None));
+    assert_ok!(RewardPool::deposit_rewards(Origin::signed(AccountId::from(ALICE)), pool_id, CUSDT, REWARD_BUDGET));
+  }
+
+  #[test]
+  fn dex_apr_values_the_unboosted_staked_lp_tokens() {
+    new_test_ext().execute_with(|| {
+      let alice = AccountId::from(ALICE);
+      let pool_id = CloverDex::incentive_pool_id(CLV, CUSDT).unwrap();
+      // 200 lp tokens for 400 dollars of liquidity
+      assert_ok!(CloverDex::add_liquidity(Origin::signed(alice.clone()), CLV, CUSDT, 100 * DOLLARS, 200 * DOLLARS));
+      assert_ok!(CloverDex::stake_pool_shares(Origin::signed(alice.clone()), CLV, CUSDT, 200 * DOLLARS));
+      create_program(pool_id);
+
+      assert_ok!(CloverOracle::feed_values(Origin::root(), vec![(CLV, Price::saturating_from_integer(2))]));
+      Prices::on_finalize(1);
+
+      let apr = Rate::checked_from_rational(BLOCKS_PER_YEAR as Balance * DOLLARS, 400 * DOLLARS);
+      assert_eq!(get_pool_aprs(), vec![(pool_id, apr)]);
+
+      // the boost of the lock adds reward shares without adding liquidity
+      assert_ok!(CloverDex::lock_pool_shares(Origin::signed(alice), CLV, CUSDT, 100 * DOLLARS, MaxStakeLockDuration::get()));
+      assert!(RewardPool::get_pool_info(&pool_id).total_shares > 200 * DOLLARS);
+      assert_eq!(get_pool_aprs(), vec![(pool_id, apr)]);
+    });
+  }
+
+  #[test]
+  fn loans_apr_values_the_debits_with_the_debit_exchange_rate() {
+    new_test_ext().execute_with(|| {
+      let pool_id = IncentivePoolId::Loans(CLV);
+      assert_ok!(<Incentives as IncentiveOps<_, _, _, _, _>>::add_share(&AccountId::from(ALICE), &pool_id, &(100 * DOLLARS)));
+      create_program(pool_id);
+
+      let apr = Rate::checked_from_rational(BLOCKS_PER_YEAR as Balance * DOLLARS, 100 * DOLLARS);
+      assert_eq!(get_pool_aprs(), vec![(pool_id, apr)]);
+
+      // a debit is worth two dollars
+      assert_ok!(Loans::set_debit_exchange_rate(Origin::root(), CLV, Some(Rate::saturating_from_integer(2))));
+      let apr = Rate::checked_from_rational(BLOCKS_PER_YEAR as Balance * DOLLARS, 200 * DOLLARS);
+      assert_eq!(get_pool_aprs(), vec![(pool_id, apr)]);
+    });
+  }
+
+  #[test]
+  fn historical_apr_annualizes_the_paid_rewards() {
+    new_test_ext().execute_with(|| {
+      let alice = AccountId::from(ALICE);
+      let pool_id = IncentivePoolId::Loans(CLV);
+      assert_ok!(<Incentives as IncentiveOps<_, _, _, _, _>>::add_share(&alice, &pool_id, &(100 * DOLLARS)));
+      assert_ok!(Incentives::create_program(Origin::root(), pool_id, CUSDT, DOLLARS, 1, Some(11), None));
+      assert_ok!(RewardPool::deposit_rewards(Origin::signed(alice), pool_id, CUSDT, REWARD_BUDGET));
+      // nothing paid yet
+      assert_eq!(get_historical_pool_aprs(), vec![]);
+
+      // the rewards are only counted once they are paid
+      System::set_block_number(11);
+      assert_eq!(get_historical_pool_aprs(), vec![]);
+      assert_ok!(RewardPool::update_rewards(&pool_id));
+      // 10 dollars paid in the first 11 blocks of the period
+      let apr = Rate::checked_from_rational(10 * DOLLARS * BLOCKS_PER_YEAR as Balance / 11, 100 * DOLLARS);
+      assert_eq!(get_historical_pool_aprs(), vec![(pool_id, apr)]);
+
+      // the program ended, the 10 dollars were paid in 22 blocks
+      System::set_block_number(22);
+      let apr = Rate::checked_from_rational(10 * DOLLARS * BLOCKS_PER_YEAR as Balance / 22, 100 * DOLLARS);
+      assert_eq!(get_historical_pool_aprs(), vec![(pool_id, apr)]);
+
+      // the period of the rewards isn't kept anymore
+      System::set_block_number(PaidRewardsPeriod::get() * PaidRewardsHistory::get() as BlockNumber);
+      assert_eq!(get_historical_pool_aprs(), vec![]);
+    });
+  }
+}
+
+mod stake_locks {
+  use super::*;
+  use clover_traits::RewardPoolOps;
+  use frame_support::{assert_ok, traits::OnInitialize};
+
+  fn cusdt_rewards(who: &AccountId, pool_id: &IncentivePoolId, at: BlockNumber) -> Balance {
+    RewardPool::get_pending_rewards_at(who, pool_id, at)
+      .into_iter()
+      .find(|(currency_id, _)| *currency_id == CUSDT)
+      .map_or(0, |(_, amount)| amount)
+  }
+
+  #[test]
+  fn expired_locks_earn_unboosted_rewards() {
+    let alice = AccountId::from(ALICE);
+    let bob = AccountId::from(BOB);
+    let mut ext = ExtBuilder::default()
+      .balances(vec![
+        (alice.clone(), CLV, 0),
+        (alice.clone(), CUSDT, 10_000_000 * DOLLARS),
+      ])
+      .build();
+    ext.execute_with(|| {
+      System::set_block_number(1);
+      assert_ok!(<Currencies as MultiCurrencyExtended<_>>::update_balance(CLV, &alice, (1_000 * DOLLARS) as Amount));
+      let pool_id = CloverDex::incentive_pool_id(CLV, CUSDT).unwrap();
+      assert_ok!(CloverDex::add_liquidity(Origin::signed(alice.clone()), CLV, CUSDT, 100 * DOLLARS, 100 * DOLLARS));
+      let lp_token = CloverDex::lp_token(CloverDex::get_pair_key(&CLV, &CUSDT));
+      assert_ok!(<Currencies as MultiCurrency<_>>::transfer(lp_token, &alice, &bob, 50 * DOLLARS));
+      assert_ok!(CloverDex::stake_pool_shares(Origin::signed(alice.clone()), CLV, CUSDT, 50 * DOLLARS));
+      assert_ok!(CloverDex::stake_pool_shares(Origin::signed(bob.clone()), CLV, CUSDT, 50 * DOLLARS));
+      assert_ok!(Incentives::create_program(Origin::root(), pool_id, CUSDT, DOLLARS, 1, None, None));
+      assert_ok!(RewardPool::deposit_rewards(Origin::signed(alice.clone()), pool_id, CUSDT, 1_000_000 * DOLLARS));
+
+      assert_ok!(CloverDex::lock_pool_shares(Origin::signed(alice.clone()), CLV, CUSDT, 50 * DOLLARS, 100));
+      // boosted while locked
+      assert!(cusdt_rewards(&alice, &pool_id, 101) > cusdt_rewards(&bob, &pool_id, 101));
+
+      // the boost is removed at the start of the expiry block, nobody released the lock
+      System::set_block_number(101);
+      CloverDex::on_initialize(101);
+      assert_eq!(
+        RewardPool::get_account_shares(&alice, &pool_id),
+        RewardPool::get_account_shares(&bob, &pool_id)
+      );
+      let (alice_rewards, bob_rewards) = (cusdt_rewards(&alice, &pool_id, 101), cusdt_rewards(&bob, &pool_id, 101));
+      let alice_earned = cusdt_rewards(&alice, &pool_id, 111) - alice_rewards;
+      let bob_earned = cusdt_rewards(&bob, &pool_id, 111) - bob_rewards;
+      assert_eq!(alice_earned, 5 * DOLLARS);
+      assert_eq!(bob_earned, 5 * DOLLARS);
+    });
+  }
+}
+
+mod loans {
+  use super::*;
+  use clover_loans::Position;
+  use clover_traits::RewardPoolOps;
+  use frame_support::{assert_noop, assert_ok, StorageDoubleMap};
+
+  #[test]
+  fn failed_loan_transfers_change_nothing() {
+    let alice = AccountId::from(ALICE);
+    let bob = AccountId::from(BOB);
+    ExtBuilder::default().build().execute_with(|| {
+      assert_ok!(<Currencies as MultiCurrencyExtended<_>>::update_balance(CLV, &alice, (1_000 * DOLLARS) as Amount));
+      assert_ok!(Loans::adjust_position(&alice, CLV, (100 * DOLLARS) as Amount, (50 * DOLLARS) as Amount));
+      // bob can't take the debit of alice
+      let bob_position = Position { collateral: 0, debit: Balance::max_value() - DOLLARS };
+      clover_loans::Positions::<Runtime>::insert(CLV, &bob, bob_position);
+
+      assert_noop!(
+        Loans::transfer_loan(&alice, &bob, CLV),
+        clover_loans::Error::<Runtime>::DebitOverflow
+      );
+      assert_eq!(Loans::positions(CLV, &alice), Position { collateral: 100 * DOLLARS, debit: 50 * DOLLARS });
+      assert_eq!(Loans::positions(CLV, &bob), bob_position);
+      assert_eq!(RewardPool::get_account_shares(&alice, &IncentivePoolId::Loans(CLV)), 50 * DOLLARS);
+      assert_eq!(RewardPool::get_account_shares(&alice, &IncentivePoolId::LoansDeposit(CLV)), 100 * DOLLARS);
+      assert_eq!(RewardPool::get_account_shares(&bob, &IncentivePoolId::Loans(CLV)), 0);
+    });
+  }
+}
+
+mod precompiles {
+  use super::*;
+  use clover_evm::{ExitSucceed, Precompiles};
+  use crate::precompiles::{CloverPrecompiles, CLOVERDEX_PRECOMPILE_ADDRESS};
+  use frame_support::assert_ok;
+  use primitives::WCLV_ADDRESS;
+  use sp_core::{H160, U256};
+
+  fn quote_input(selector: [u8; 4], token_in: H160, token_out: H160, amount: Balance) -> Vec<u8> {
+    let mut input = selector.to_vec();
+    input.extend_from_slice(&[0u8; 12]);
+    input.extend_from_slice(token_in.as_bytes());
+    input.extend_from_slice(&[0u8; 12]);
+    input.extend_from_slice(token_out.as_bytes());
+    let mut amount_word = [0u8; 32];
+    U256::from(amount).to_big_endian(&mut amount_word);
+    input.extend_from_slice(&amount_word);
+    input
+  }
+
+  fn quote(input: Vec<u8>) -> Balance {
+    let (exit, output, _) = CloverPrecompiles::execute(CLOVERDEX_PRECOMPILE_ADDRESS, &input, None)
+      .expect("cloverdex precompile")
+      .expect("quote");
+    assert_eq!(exit, ExitSucceed::Returned);
+    U256::from_big_endian(&output).low_u128()
+  }
+
+  #[test]
+  fn wclv_is_clv_in_the_evm() {
+    assert_eq!(CurrencyId::CLV.evm_address(), Some(WCLV_ADDRESS));
+    assert_eq!(CurrencyId::from_evm_address(&WCLV_ADDRESS), Some(CurrencyId::CLV));
+    for token in CurrencyId::TOKENS.iter() {
+      assert_eq!(CurrencyId::from_evm_address(&token.evm_address().unwrap()), Some(*token));
+    }
+    assert_eq!(CurrencyId::LpToken(0).evm_address(), None);
+    assert_eq!(CurrencyId::from_evm_address(&H160::from_low_u64_be(1)), None);
+  }
+
+  #[test]
+  fn cloverdex_quotes_wclv_as_clv() {
+    let alice = AccountId::from(ALICE);
+    ExtBuilder::default().build().execute_with(|| {
+      assert_ok!(<Currencies as MultiCurrencyExtended<_>>::update_balance(CLV, &alice, (1_000 * DOLLARS) as Amount));
+      assert_ok!(<Currencies as MultiCurrencyExtended<_>>::update_balance(CUSDT, &alice, (1_000 * DOLLARS) as Amount));
+      assert_ok!(CloverDex::add_liquidity(Origin::signed(alice), CLV, CUSDT, 100 * DOLLARS, 200 * DOLLARS));
+      let cusdt = CurrencyId::CUSDT.evm_address().unwrap();
+
+      let amount_out = quote(quote_input([0x4a, 0xa0, 0x66, 0x52], WCLV_ADDRESS, cusdt, DOLLARS));
+      assert!(amount_out > 0);
+      assert_eq!(amount_out, CloverDex::get_target_amount_available(CLV, CUSDT, DOLLARS).0);
+
+      let amount_in = quote(quote_input([0x53, 0xb6, 0x09, 0xb5], cusdt, WCLV_ADDRESS, DOLLARS));
+      assert!(amount_in > 0);
+      assert_eq!(amount_in, CloverDex::get_supply_amount_needed(CUSDT, CLV, DOLLARS).0);
+
+      // no pool
+      let dot = CurrencyId::DOT.evm_address().unwrap();
+      assert_eq!(quote(quote_input([0x4a, 0xa0, 0x66, 0x52], WCLV_ADDRESS, dot, DOLLARS)), 0);
+      // not a token
+      let input = quote_input([0x4a, 0xa0, 0x66, 0x52], H160::from_low_u64_be(1), cusdt, DOLLARS);
+      assert!(CloverPrecompiles::execute(CLOVERDEX_PRECOMPILE_ADDRESS, &input, None).unwrap().is_err());
+      // the standard precompiles are kept
+      assert!(CloverPrecompiles::execute(H160::from_low_u64_be(4), &[1, 2, 3], None).is_some());
+    });
+  }
+}
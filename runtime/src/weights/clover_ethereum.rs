@@ -0,0 +1,39 @@
+//! Weights for clover_ethereum, estimated from the storage accesses of the sponsor calls.
+//! Regenerate with the `benchmark` subcommand of the node built with `--features runtime-benchmarks`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+use sp_std::marker::PhantomData;
+
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Trait> clover_ethereum::WeightInfo for WeightInfo<T> {
+	fn set_sponsor_limit() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_sponsor(a: u32, ) -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(a as Weight)))
+	}
+	fn add_sponsored_accounts(a: u32, ) -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add((8_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(a as Weight)))
+	}
+	fn remove_sponsored_accounts(a: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((8_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(DbWeight::get().writes((2 as Weight).saturating_mul(a as Weight)))
+	}
+}
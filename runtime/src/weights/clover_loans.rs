@@ -0,0 +1,17 @@
+//! Weights for clover_loans, estimated from the storage accesses of the governance calls.
+//! Regenerate with the `benchmark` subcommand of the node built with `--features runtime-benchmarks`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+use sp_std::marker::PhantomData;
+
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Trait> clover_loans::WeightInfo for WeightInfo<T> {
+	fn set_debit_exchange_rate() -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+}
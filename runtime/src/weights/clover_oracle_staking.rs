@@ -0,0 +1,40 @@
+//! Weights for clover_oracle_staking, estimated from similar calls of pallet_staking.
+//! Regenerate with the `benchmark` subcommand of the node built with `--features runtime-benchmarks`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+use sp_std::marker::PhantomData;
+
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Trait> clover_oracle_staking::WeightInfo for WeightInfo<T> {
+	fn bond(o: u32, ) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn unbond(o: u32, ) -> Weight {
+		(65_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(o as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(o as Weight)))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn withdraw_unbonded() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn check_feeds(f: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((60_000_000 as Weight).saturating_mul(f as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((6 as Weight).saturating_mul(f as Weight)))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+			.saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(f as Weight)))
+	}
+}
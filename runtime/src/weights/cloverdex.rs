@@ -0,0 +1,80 @@
+//! Weights for cloverdex, estimated from the previous hand-written weights.
+//! Regenerate with the `benchmark` subcommand of the node built with `--features runtime-benchmarks`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::weights::{constants::RocksDbWeight as DbWeight, Weight};
+
+use sp_std::marker::PhantomData;
+
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Trait> cloverdex::WeightInfo for WeightInfo<T> {
+	fn set_exchange_rate() -> Weight {
+		(12_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn set_max_route_hops() -> Weight {
+		(12_000_000 as Weight)
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn add_liquidity() -> Weight {
+		(206_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(10 as Weight))
+			.saturating_add(DbWeight::get().writes(9 as Weight))
+	}
+	fn withdraw_liquidity() -> Weight {
+		(248_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(11 as Weight))
+			.saturating_add(DbWeight::get().writes(10 as Weight))
+	}
+	fn swap_currency(r: u32, ) -> Weight {
+		(112_000_000 as Weight)
+			.saturating_add((88_000_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((9 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(DbWeight::get().writes((6 as Weight).saturating_mul(r as Weight)))
+	}
+	fn stake_pool_shares() -> Weight {
+		(206_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn unstake_pool_shares() -> Weight {
+		(206_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn withdraw_rewards() -> Weight {
+		(206_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(8 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn lock_pool_shares() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn unlock_pool_shares() -> Weight {
+		(230_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(10 as Weight))
+			.saturating_add(DbWeight::get().writes(8 as Weight))
+	}
+	fn release_pool_share_locks() -> Weight {
+		(120_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(6 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn get_supply_amount_needed(p: u32, ) -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add((1_200_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(DbWeight::get().reads(7 as Weight))
+	}
+	fn accumulate_prices(p: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((25_000_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(p as Weight)))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(p as Weight)))
+	}
+}
@@ -1,4 +1,13 @@
 //! A list of the different weight modules for our runtime.
 #![allow(clippy::unnecessary_cast)]
 
-pub mod evm_accounts;
\ No newline at end of file
+pub mod cloverdex;
+pub mod clover_contract_verification;
+pub mod clover_ethereum;
+pub mod clover_incentives;
+pub mod clover_loans;
+pub mod clover_oracle_staking;
+pub mod clover_prices;
+pub mod clover_vaults;
+pub mod evm_accounts;
+pub mod reward_pool;